@@ -1,8 +1,14 @@
-
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+use clap::builder::BoolishValueParser;
 use anyhow::Result;
 
 use crate::commands::*;
+use crate::auth;
+use crate::svn::{self, SvnOptions};
+use crate::util::{self, SvError::*};
+use crate::transcript;
+use crate::profile::{self, Profile};
+use std::path::PathBuf;
 
 pub(crate) const HELP_TEMPLATE: &str = "\
 {name}  v{version}
@@ -32,7 +38,198 @@ pub trait Run {
     infer_subcommands = true,
     version,
 )]
+pub struct App {
+    #[command(flatten)]
+    global: GlobalOptions,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+//  Options that apply to every command.
+//  These are mostly passed through to each svn command that we run.
+//  Each option may also be set with an environment variable.
+#[derive(Debug, Args)]
+struct GlobalOptions {
+    /// Accept server certificates that fail validation.
+    ///
+    /// A comma separated list of the failures to accept:
+    /// unknown-ca, cn-mismatch, expired, not-yet-valid, other
+    #[arg(
+        long,
+        global = true,
+        value_name = "FAILURES",
+        value_parser = parse_cert_failures,
+        env = "SVU_TRUST_SERVER_CERT_FAILURES",
+    )]
+    trust_server_cert_failures: Option<String>,
+
+    /// Proxy server to use for HTTP(S) connections.
+    #[arg(long, global = true, value_name = "HOST[:PORT]", env = "SVU_HTTP_PROXY")]
+    http_proxy: Option<String>,
+
+    /// Hosts that should be accessed without the proxy.
+    ///
+    /// A comma separated list of host name patterns.
+    #[arg(long, global = true, value_name = "HOSTS", env = "SVU_HTTP_PROXY_EXCEPTIONS")]
+    http_proxy_exceptions: Option<String>,
+
+    /// Use the svn configuration directory DIR.
+    ///
+    /// The directory holds the svn configuration files and its cache
+    /// of credentials.  Passed to svn with its --config-dir argument.
+    #[arg(long, global = true, value_name = "DIR", env = "SVU_CONFIG_DIR")]
+    config_dir: Option<PathBuf>,
+
+    /// Use the settings of the named profile.
+    ///
+    /// Profiles are defined in svu/profiles.json in the user's configuration
+    /// directory or in the file named by SVU_PROFILES.  Each profile may set
+    /// username, passwordEnv, configDir, httpProxy, httpProxyExceptions and
+    /// repoUrl.  Without this option the profile mapped to the current directory
+    /// in "directories", or the one whose repoUrl contains the repository of the
+    /// working copy, is used.  Options given explicitly take precedence.
+    #[arg(long, global = true, value_name = "NAME", env = "SVU_PROFILE")]
+    profile: Option<String>,
+
+    /// Set a subversion configuration option.
+    ///
+    /// The value is passed to svn with its --config-option argument
+    /// and may be given multiple times.
+    /// eg. servers:global:http-timeout=60
+    #[arg(
+        long = "config-option",
+        global = true,
+        value_name = "FILE:SECTION:OPTION=[VALUE]",
+        value_parser = parse_config_option,
+    )]
+    config_options: Vec<String>,
+
+    /// Read the repository password from file descriptor FD.
+    ///
+    /// The first line read from FD is used as the password for the
+    /// user named by the SVU_USERNAME environment variable.
+    #[arg(long, global = true, value_name = "FD")]
+    password_from_fd: Option<i32>,
+
+    /// Maximum number of svn commands to run concurrently.
+    ///
+    /// Commands such as filerevs, grep and graph run svn commands in
+    /// parallel.  Defaults to the number of CPUs.
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u16).range(1..),
+        env = "SVU_JOBS",
+    )]
+    jobs: Option<u16>,
+
+    /// Refuse to modify the working copy or the repository.
+    ///
+    /// Any command that would update, revert, patch, copy, delete
+    /// or set properties fails with an error.
+    #[arg(long, global = true, env = "SVU_READ_ONLY", value_parser = BoolishValueParser::new())]
+    read_only: bool,
+
+    /// Use only ASCII characters in output.
+    ///
+    /// Unicode characters such as the ellipsis are replaced with
+    /// ASCII equivalents for terminals that cannot display them.
+    #[arg(long, global = true, env = "SVU_ASCII", value_parser = BoolishValueParser::new())]
+    ascii: bool,
+
+    /// Append the command, its output and timing to a Markdown transcript.
+    ///
+    /// Set SVU_TRANSCRIPT to record every command of a session.
+    #[arg(long, global = true, value_name = "FILE", env = "SVU_TRANSCRIPT")]
+    transcript: Option<PathBuf>,
+}
+
+impl GlobalOptions {
+    //  Fill in the options that were not given explicitly from the profile.
+    fn apply_profile(&mut self, profile: &Profile) {
+        if self.config_dir.is_none() {
+            self.config_dir = profile.config_dir_path();
+        }
+        if self.http_proxy.is_none() {
+            self.http_proxy = profile.http_proxy.clone();
+        }
+        if self.http_proxy_exceptions.is_none() {
+            self.http_proxy_exceptions = profile.http_proxy_exceptions.clone();
+        }
+        auth::set_profile_credentials(profile.username.clone(), profile.password());
+    }
+
+    fn svn_options(&self) -> SvnOptions {
+        let mut config_options = Vec::<String>::new();
+
+        if let Some(proxy) = &self.http_proxy {
+            let (host, port) = split_proxy(proxy);
+            config_options.push(format!("servers:global:http-proxy-host={}", host));
+            if let Some(port) = port {
+                config_options.push(format!("servers:global:http-proxy-port={}", port));
+            }
+        }
+        if let Some(exceptions) = &self.http_proxy_exceptions {
+            config_options.push(format!("servers:global:http-proxy-exceptions={}", exceptions));
+        }
+        //  Explicit config options are added last so that they take precedence.
+        config_options.extend(self.config_options.iter().cloned());
+
+        SvnOptions {
+            trust_server_cert_failures: self.trust_server_cert_failures.clone(),
+            config_dir: self.config_dir.clone(),
+            config_options,
+            read_only: self.read_only,
+        }
+    }
+}
+
+//  Split a proxy into its host and port.  The proxy may be given as a URL
+//  such as http://proxy:3128 and an IPv6 address with a port is written
+//  in brackets, eg. [::1]:3128.
+fn split_proxy(proxy: &str) -> (&str, Option<&str>) {
+    let proxy = proxy.split_once("://").map(|(_, rest)| rest).unwrap_or(proxy);
+    let proxy = proxy.split('/').next().unwrap_or(proxy);
+    let is_port = |port: &str| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit());
+    if let Some(rest) = proxy.strip_prefix('[') {
+        if let Some((host, after)) = rest.split_once(']') {
+            return (host, after.strip_prefix(':').filter(|p| is_port(p)));
+        }
+    }
+    match proxy.split_once(':') {
+        //  A bare IPv6 address has more than one colon and no port.
+        Some((host, port)) if is_port(port) => (host, Some(port)),
+        _ => (proxy, None),
+    }
+}
+
+fn parse_cert_failures(arg: &str) -> Result<String> {
+    const FAILURES: [&str; 5] = ["unknown-ca", "cn-mismatch", "expired", "not-yet-valid", "other"];
+    for failure in arg.split(',') {
+        if !FAILURES.contains(&failure.trim()) {
+            let msg = format!("Invalid certificate failure '{}', must be one of: {}", failure, FAILURES.join(", "));
+            return Err(General(msg).into());
+        }
+    }
+    Ok(arg.split(',').map(|f| f.trim()).collect::<Vec<&str>>().join(","))
+}
 
+fn parse_config_option(arg: &str) -> Result<String> {
+    let valid = match arg.split_once('=') {
+        Some((key, _)) => key.split(':').filter(|s| !s.is_empty()).count() == 3,
+        None => false,
+    };
+    if valid {
+        Ok(arg.to_string())
+    } else {
+        Err(General("Config option must be of the form FILE:SECTION:OPTION=[VALUE]".to_string()).into())
+    }
+}
+
+#[derive(Debug, Subcommand)]
 pub enum Commands {
     Log(log::Log),
     Branch(branch::Branch),
@@ -42,11 +239,118 @@ pub enum Commands {
     Bisect(bisect::Bisect),
     Prefix(prefix::Prefix),
     Ignore(ignore::Ignore),
+    Pin(pin::Pin),
+    Audit(audit::Audit),
+    Graph(graph::Graph),
+    Bugreport(bugreport::Bugreport),
+    Grep(grep::Grep),
+    RevMath(rev_math::RevMath),
+    Mixed(mixed::Mixed),
+    DiffAcross(diff_across::DiffAcross),
+    Conflicts(conflicts::Conflicts),
+    Blame(blame::Blame),
+    Rollback(rollback::Rollback),
+    Switch(switch::Switch),
+    Notify(notify::Notify),
+    Tag(tag::Tag),
+    Merge(merge::Merge),
+    Cherry(cherry::Cherry),
+    Mergeinfo(mergeinfo::Mergeinfo),
+    Status(status::Status),
+    Commit(commit::Commit),
+    Diff(diff::Diff),
+    Doctor(doctor::Doctor),
+    Props(props::Props),
+    Lock(lock::Lock),
+    Unlock(unlock::Unlock),
+    Churn(churn::Churn),
+    Authors(authors::Authors),
+    ReleaseNotes(release_notes::ReleaseNotes),
+    Search(search::Search),
+    Cat(cat::Cat),
+    Revert(revert::Revert),
+    Up(up::Up),
+    Size(size::Size),
+    Compare(compare::Compare),
+    Pq(pq::Pq),
+    Snapshot(snapshot::Snapshot),
+    Config(config::Config),
+    Tui(tui::Tui),
+    Prompt(prompt::Prompt),
+    ExportGit(export_git::ExportGit),
+    Issues(issues::Issues),
+    Last(last::Last),
+    Relocate(relocate::Relocate),
+    Origin(origin::Origin),
+    Review(review::Review),
+    Verify(verify::Verify),
+    LintMessage(lint_message::LintMessage),
+    Undo(undo::Undo),
+    Shelve(shelve::Shelve),
+    Archive(archive::Archive),
+    Tree(tree::Tree),
+    Du(du::Du),
+    Bigfiles(bigfiles::Bigfiles),
+    AuditMime(audit_mime::AuditMime),
+    AuditEol(audit_eol::AuditEol),
+    Keywords(keywords::Keywords),
+    Who(who::Who),
+    Watch(watch::Watch),
+    Worklog(worklog::Worklog),
+    Template(template::Template),
+    Init(init::Init),
+    Mark(mark::Mark),
+    Marks(mark::Marks),
+    Continue(operation::Continue),
     Completions(completions::Completions),
 }
 
 use Commands::*;
 
+impl App {
+    //  The transcript file when the command should be recorded.
+    pub fn transcript(&self) -> Option<&PathBuf> {
+        self.global.transcript.as_ref().filter(|_| !transcript::is_child())
+    }
+
+    //  True if the output of the command may be sent to the pager.
+    pub fn is_pageable(&self) -> bool {
+        matches!(
+            self.command,
+            Log(_) | Show(_) | Blame(_) | Cat(_) | DiffAcross(_) | Compare(_) | Tree(_)
+        )
+    }
+}
+
+impl Run for App {
+    fn run(&mut self) -> Result<()> {
+        crate::config::load()?;
+        if let Some((_, profile)) = profile::select(self.global.profile.as_deref())? {
+            self.global.apply_profile(&profile);
+        }
+        svn::set_options(self.global.svn_options());
+        util::set_ascii(self.global.ascii || crate::config::get_bool("core.ascii").unwrap_or(false));
+        if let Some(format) = crate::config::get_str("date.format") {
+            util::set_date_format(format);
+        }
+        match crate::config::get_str("color.ui") {
+            Some("always") => colored::control::set_override(true),
+            Some("never")  => colored::control::set_override(false),
+            _              => (),
+        }
+        if let Some(fd) = self.global.password_from_fd {
+            auth::set_password_fd(fd);
+        }
+        let jobs = self.global.jobs.or(crate::config::get_int("core.jobs").map(|n| n as u16));
+        if let Some(jobs) = jobs {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs as usize)
+                .build_global()?;
+        }
+        self.command.run()
+    }
+}
+
 impl Run for Commands{
     fn run(&mut self) -> Result<()> {
         match self {
@@ -58,6 +362,69 @@ impl Run for Commands{
             Bisect(cmd) => cmd.run(),
             Prefix(cmd) => cmd.run(),
             Ignore(cmd) => cmd.run(),
+            Pin(cmd) => cmd.run(),
+            Audit(cmd) => cmd.run(),
+            Graph(cmd) => cmd.run(),
+            Bugreport(cmd) => cmd.run(),
+            Grep(cmd) => cmd.run(),
+            RevMath(cmd) => cmd.run(),
+            Mixed(cmd) => cmd.run(),
+            DiffAcross(cmd) => cmd.run(),
+            Conflicts(cmd) => cmd.run(),
+            Blame(cmd) => cmd.run(),
+            Rollback(cmd) => cmd.run(),
+            Switch(cmd) => cmd.run(),
+            Notify(cmd) => cmd.run(),
+            Tag(cmd) => cmd.run(),
+            Merge(cmd) => cmd.run(),
+            Cherry(cmd) => cmd.run(),
+            Mergeinfo(cmd) => cmd.run(),
+            Status(cmd) => cmd.run(),
+            Commit(cmd) => cmd.run(),
+            Diff(cmd) => cmd.run(),
+            Doctor(cmd) => cmd.run(),
+            Props(cmd) => cmd.run(),
+            Lock(cmd) => cmd.run(),
+            Unlock(cmd) => cmd.run(),
+            Churn(cmd) => cmd.run(),
+            Authors(cmd) => cmd.run(),
+            ReleaseNotes(cmd) => cmd.run(),
+            Search(cmd) => cmd.run(),
+            Cat(cmd) => cmd.run(),
+            Revert(cmd) => cmd.run(),
+            Up(cmd) => cmd.run(),
+            Size(cmd) => cmd.run(),
+            Compare(cmd) => cmd.run(),
+            Pq(cmd) => cmd.run(),
+            Snapshot(cmd) => cmd.run(),
+            Config(cmd) => cmd.run(),
+            Tui(cmd) => cmd.run(),
+            Prompt(cmd) => cmd.run(),
+            ExportGit(cmd) => cmd.run(),
+            Issues(cmd) => cmd.run(),
+            Last(cmd) => cmd.run(),
+            Relocate(cmd) => cmd.run(),
+            Origin(cmd) => cmd.run(),
+            Review(cmd) => cmd.run(),
+            Verify(cmd) => cmd.run(),
+            LintMessage(cmd) => cmd.run(),
+            Undo(cmd) => cmd.run(),
+            Shelve(cmd) => cmd.run(),
+            Archive(cmd) => cmd.run(),
+            Tree(cmd) => cmd.run(),
+            Du(cmd) => cmd.run(),
+            Bigfiles(cmd) => cmd.run(),
+            AuditMime(cmd) => cmd.run(),
+            AuditEol(cmd) => cmd.run(),
+            Keywords(cmd) => cmd.run(),
+            Who(cmd) => cmd.run(),
+            Watch(cmd) => cmd.run(),
+            Worklog(cmd) => cmd.run(),
+            Template(cmd) => cmd.run(),
+            Init(cmd) => cmd.run(),
+            Mark(cmd) => cmd.run(),
+            Marks(cmd) => cmd.run(),
+            Continue(cmd) => cmd.run(),
             Completions(cmd) => cmd.run(),
         }
     }
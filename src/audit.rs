@@ -0,0 +1,100 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use crate::svn;
+use crate::util::{self, datetime_serializer};
+
+//  The audit log is opt-in and is enabled by setting the
+//  SVU_AUDIT_LOG environment variable.
+//  If the value is `wc` then the log is written to .svu/audit.log
+//  in the working copy, otherwise the value is the path to a log file.
+//
+//  Each line of the log is a JSON object describing one invocation of svu.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(with = "datetime_serializer")]
+    pub date: DateTime<Local>,
+    pub user: String,
+    pub args: Vec<String>,
+    #[serde(rename(serialize = "workingCopy", deserialize = "workingCopy"))]
+    pub working_copy: Option<String>,
+    pub revisions: Vec<String>,
+    #[serde(rename(serialize = "exitStatus", deserialize = "exitStatus"))]
+    pub exit_status: i32,
+}
+
+fn resolved_revisions() -> &'static Mutex<Vec<String>> {
+    static REVISIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    &REVISIONS
+}
+
+//  Called whenever a revision entered by the user is resolved
+//  so that it can be included in the audit log.
+pub fn note_revision(rev: &str, resolved: &str) {
+    if let Ok(mut revisions) = resolved_revisions().lock() {
+        revisions.push(format!("{}={}", rev, resolved));
+    }
+}
+
+pub fn is_enabled() -> bool {
+    env::var("SVU_AUDIT_LOG").map(|v| !v.is_empty()).unwrap_or(false)
+}
+
+//  Returns the path to the audit log file or None if
+//  auditing is not enabled.
+pub fn audit_log_file() -> Result<Option<PathBuf>> {
+    match env::var("SVU_AUDIT_LOG") {
+        Ok(value) if value == "wc" => Ok(Some(util::data_directory()?.join("audit.log"))),
+        Ok(value) if !value.is_empty() => Ok(Some(PathBuf::from(value))),
+        _ => Ok(None),
+    }
+}
+
+//  Append an entry for this invocation to the audit log.
+//  Failure to write the audit log does not affect the command.
+pub fn record(args: &[String], exit_status: i32) {
+    if is_enabled() {
+        let _ = write_entry(args, exit_status);
+    }
+}
+
+fn write_entry(args: &[String], exit_status: i32) -> Result<()> {
+    if let Some(path) = audit_log_file()? {
+        let entry = AuditEntry {
+            date: Local::now(),
+            user: env::var("USER").or(env::var("USERNAME")).unwrap_or("n/a".to_string()),
+            args: args.to_vec(),
+            working_copy: svn::workingcopy_info().ok().and_then(|info| info.wc_path),
+            revisions: resolved_revisions().lock().map(|r| r.clone()).unwrap_or_default(),
+            exit_status,
+        };
+        let mut writer = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?;
+        writer.write_all((serde_json::to_string(&entry)? + "\n").as_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn load_entries() -> Result<Vec<AuditEntry>> {
+    let mut entries = Vec::<AuditEntry>::new();
+    if let Some(path) = audit_log_file()? {
+        if path.is_file() {
+            let reader = BufReader::new(File::open(path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    entries.push(serde_json::from_str(&line)?);
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
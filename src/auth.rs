@@ -1,9 +1,13 @@
 
 use anyhow::Result;
 use std::env;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::sync::OnceLock;
 use crate::util::SvError::*;
 use std::path::{Path, PathBuf};
+use std::fs::{read_dir, read_to_string};
+use std::collections::HashMap;
+use regex::Regex;
 use crate::svn;
 
 #[derive(Debug, Clone)]
@@ -12,7 +16,10 @@ pub struct Credentials(pub String, pub String);   // username and password
 //  In order to support subversions repositories that require authentication
 //  we do the following in order:
 //  First, if the SVU_USERNAME and SVU_PASSWORD environment variables are set
-//  then we use those values for authentication.
+//  then we use those values for authentication.  The password may instead be
+//  read from a file descriptor given with the --password-from-fd option.
+//  The username and password of the selected profile are used for any
+//  value that is not given this way.
 //
 //  If that is not the case then we attempt to access the repository without
 //  to see if authentication is necessary.  If this access if it succeeds
@@ -20,65 +27,129 @@ pub struct Credentials(pub String, pub String);   // username and password
 //  the user has cached subversion credentials.
 //
 //  Finally, if authentication is needed, we prompt the user for their credentials.
+//  The user is given several attempts.  If stdin is not a terminal we fail
+//  rather than waiting for input that will never arrive.
+//  The username that the svn client has cached for the repository realm
+//  is offered as the default.
 
+const MAX_ATTEMPTS: u32 = 3;
+
+//  The result of attempting to access the repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Granted,
+    //  The username or password is not valid
+    AuthenticationFailed,
+    //  The user is known but is not permitted to access the repository
+    AuthorizationDenied,
+}
+
+impl Access {
+    fn message(&self, username: &str) -> String {
+        match self {
+            Access::Granted              => "Access granted".to_string(),
+            Access::AuthenticationFailed => "Authentication failed: invalid username or password.".to_string(),
+            Access::AuthorizationDenied  => format!("Authorization denied: '{}' does not have access to the repository.", username),
+        }
+    }
+}
+
+fn password_fd() -> &'static OnceLock<i32> {
+    static PASSWORD_FD: OnceLock<i32> = OnceLock::new();
+    &PASSWORD_FD
+}
+
+fn profile_credentials() -> &'static OnceLock<(Option<String>, Option<String>)> {
+    static PROFILE_CREDENTIALS: OnceLock<(Option<String>, Option<String>)> = OnceLock::new();
+    &PROFILE_CREDENTIALS
+}
+
+//  Set the username and password given by the selected profile.
+pub fn set_profile_credentials(username: Option<String>, password: Option<String>) {
+    let _ = profile_credentials().set((username, password));
+}
+
+fn profile_username() -> Option<String> {
+    profile_credentials().get().and_then(|(username, _)| username.clone())
+}
+
+fn profile_password() -> Option<String> {
+    profile_credentials().get().and_then(|(_, password)| password.clone())
+}
+
+//  Set the file descriptor from which the password is read.
+pub fn set_password_fd(fd: i32) {
+    let _ = password_fd().set(fd);
+}
 
 pub fn get_credentials() -> Result<Option<Credentials>> {
     let wc_info = svn::workingcopy_info()?;  // Ensure we are in working copy directory
     let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
 
-    let username = env::var("SVU_USERNAME").ok();
-    let password = env::var("SVU_PASSWORD").ok();
+    let username = env::var("SVU_USERNAME").ok().or_else(profile_username);
+    let password = match password_fd().get() {
+        Some(fd) => Some(fd_password(*fd)?),
+        None     => env::var("SVU_PASSWORD").ok().or_else(profile_password),
+    };
 
     match (username, password) {
         (Some(u), Some(p)) => {
-            if access_repo(Some(Credentials(u.clone(), p.clone())), &wc_root)? {
-                Ok(Some(Credentials(u, p)))
-            } else {
-                Err(General("Not a valid SVU_USERNAME/SVU_PASSWORD.".to_string()).into())
+            match access_repo(Some(Credentials(u.clone(), p.clone())), &wc_root)? {
+                Access::Granted => Ok(Some(Credentials(u, p))),
+                failure         => Err(General(failure.message(&u)).into()),
             }
         }
         (None, Some(_)) => {
             Err(General(
-                "SVU_USERNAME enviromnet variable must be set if using SVU_PASSWORD".to_string()
+                "SVU_USERNAME enviromnet variable must be set if using SVU_PASSWORD or --password-from-fd".to_string()
             )
             .into())
         }
         _ => {
 
             //  First attempt to access the repo without credentials
-            if access_repo(None,&wc_root)? {
+            if access_repo(None,&wc_root)? == Access::Granted {
                 Ok(None)  // No credentials needed
+            } else if !std::io::stdin().is_terminal() {
+                Err(General(
+                    "Authentication is required but stdin is not a terminal.\n\
+                     Set SVU_USERNAME and SVU_PASSWORD or use --password-from-fd".to_string()
+                )
+                .into())
             } else {
-                //  Prompt for username and password.
-                let mut username: Option<String> = None;
-                let mut password: Option<String> = None;
-
-                while username.is_none() && password.is_none() {
-                    let u = prompt_for_username()?;
-                    if u.is_empty() {
-                        continue;
-                    }
-                    let p = prompt_for_password()?;
-                    if p.is_empty() {
-                        continue;
-                    }
-
-                    if access_repo(Some(Credentials(u.clone(), p.clone())), &wc_root)? {
-                        username = Some(u);
-                        password = Some(p);
-                    } else {
-                        return Err(General("Not a valid username/password.".to_string()).into())
-                    }
-                }
-                Ok(Some(Credentials(username.unwrap(), password.unwrap())))
+                let default_username = profile_username().or_else(|| cached_username(&wc_info.root_url));
+                prompt_for_credentials(&wc_root, default_username)
             }
         }
     }
 }
 
+//  Prompt for username and password.
+fn prompt_for_credentials(wc_root: &Path, default_username: Option<String>) -> Result<Option<Credentials>> {
+    let mut attempts = 0;
+    while attempts < MAX_ATTEMPTS {
+        let u = prompt_for_username(default_username.as_deref())?;
+        if u.is_empty() {
+            continue;
+        }
+        let p = prompt_for_password()?;
+        if p.is_empty() {
+            continue;
+        }
+
+        attempts += 1;
+        match access_repo(Some(Credentials(u.clone(), p.clone())), wc_root)? {
+            Access::Granted => return Ok(Some(Credentials(u, p))),
+            failure         => eprintln!("{}", failure.message(&u)),
+        }
+    }
+    let msg = format!("Unable to access the repository after {} attempts.", MAX_ATTEMPTS);
+    Err(General(msg).into())
+}
+
 //  Check to see if we can access the repository by
 //  running svn info ^/
-fn access_repo(credentials: Option<Credentials>, wc_root: &Path) -> Result<bool> {
+fn access_repo(credentials: Option<Credentials>, wc_root: &Path) -> Result<Access> {
     let output = svn::SvnCmd::new("info")
         .with_creds(&credentials)
         .with_cwd(Some(wc_root))
@@ -86,24 +157,74 @@ fn access_repo(credentials: Option<Credentials>, wc_root: &Path) -> Result<bool>
         .run()?;
 
     if output.status.success() {
-        Ok(true)
+        Ok(Access::Granted)
     } else {
         let text = String::from_utf8_lossy(&output.stderr);
-        if text.contains("Authentication failed") {
-            Ok(false)
+        if text.contains("Authentication failed") || text.contains("Authentication required") || text.contains("E215004") {
+            Ok(Access::AuthenticationFailed)
+        } else if text.contains("Authorization failed") || text.contains("forbidden") || text.contains("E175013") {
+            Ok(Access::AuthorizationDenied)
         } else {
             Err(SvnError(output).into())
         }
     }
 }
 
-fn prompt_for_username() -> Result<String> {
+//  The password can only be read from the descriptor once
+//  so we save it in case the credentials are needed again.
+fn fd_password(fd: i32) -> Result<String> {
+    static PASSWORD: OnceLock<String> = OnceLock::new();
+    match PASSWORD.get() {
+        Some(password) => Ok(password.clone()),
+        None => {
+            let password = read_password_from_fd(fd)?;
+            Ok(PASSWORD.get_or_init(|| password).clone())
+        }
+    }
+}
+
+//  Read the password from the first line of the given file descriptor.
+//  The descriptor is duplicated so that the one given is never closed,
+//  which matters when it is one of the standard streams.
+#[cfg(unix)]
+fn read_password_from_fd(fd: i32) -> Result<String> {
+    use std::io::{BufRead, BufReader};
+    use std::os::fd::FromRawFd;
+    let invalid = || General(format!("--password-from-fd {} is not an open file descriptor", fd));
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(invalid().into());
+    }
+    let dup_fd = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+    if dup_fd == -1 {
+        return Err(invalid().into());
+    }
+    //  SAFETY: dup_fd is a new open descriptor that nothing else owns.
+    let file = unsafe { std::fs::File::from_raw_fd(dup_fd) };
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+#[cfg(not(unix))]
+fn read_password_from_fd(_fd: i32) -> Result<String> {
+    Err(General("--password-from-fd is not supported on this platform".to_string()).into())
+}
+
+fn prompt_for_username(default: Option<&str>) -> Result<String> {
     let mut line = String::new();
 
-    print!("Enter username for the subversion repo: ");
+    match default {
+        Some(name) => print!("Enter username for the subversion repo [{}]: ", name),
+        None       => print!("Enter username for the subversion repo: "),
+    }
     std::io::stdout().flush()?;
-    std::io::stdin().read_line(&mut line)?;
-    Ok(line.trim().to_owned())
+    if std::io::stdin().read_line(&mut line)? == 0 {
+        return Err(General("No username entered".to_string()).into());
+    }
+    match (line.trim(), default) {
+        ("", Some(name)) => Ok(name.to_owned()),
+        (name, _)        => Ok(name.to_owned()),
+    }
 }
 
 fn prompt_for_password() -> Result<String> {
@@ -113,3 +234,78 @@ fn prompt_for_password() -> Result<String> {
     Ok(line.trim().to_owned())
 }
 
+//  The name of the current user for filters such as log --mine.
+//  This is the username used to access the repository if we have one,
+//  otherwise the username cached by the svn client for the repository,
+//  and finally the name of the logged in user.
+pub fn current_username(creds: &Option<Credentials>, root_url: &str) -> Option<String> {
+    match creds {
+        Some(Credentials(username, _)) => Some(username.clone()),
+        None => env::var("SVU_USERNAME").ok()
+            .or_else(profile_username)
+            .or_else(|| cached_username(root_url))
+            .or_else(|| env::var("USER").ok())
+            .or_else(|| env::var("USERNAME").ok()),
+    }
+}
+
+//  The svn client caches the credentials for each realm in the
+//  auth/svn.simple directory of its configuration area.  Each file
+//  is in the svn hash format and contains the realm string, which
+//  starts with the <scheme://host:port> of the server, and the username.
+fn svn_auth_dir() -> Option<PathBuf> {
+    if let Some(dir) = svn::config_dir() {
+        Some(dir.join("auth").join("svn.simple"))
+    } else if cfg!(windows) {
+        env::var("APPDATA").ok().map(|d| PathBuf::from(d).join("Subversion").join("auth").join("svn.simple"))
+    } else {
+        env::var("HOME").ok().map(|d| PathBuf::from(d).join(".subversion").join("auth").join("svn.simple"))
+    }
+}
+
+//  Return the <scheme://host:port> that begins the realm string for a repository url.
+fn realm_prefix(root_url: &str) -> Option<String> {
+    let re = Regex::new(r"^(https?|svn)://(?:[^@/]*@)?([^/:]+)(?::(\d+))?").ok()?;
+    let caps = re.captures(root_url)?;
+    let scheme = caps[1].to_lowercase();
+    let port = match (caps.get(3), scheme.as_str()) {
+        (Some(port), _) => port.as_str(),
+        (None, "http")  => "80",
+        (None, "https") => "443",
+        (None, _)       => "3690",
+    };
+    Some(format!("<{}://{}:{}>", scheme, caps[2].to_lowercase(), port))
+}
+
+//  Return the username that the svn client has cached for the
+//  repository, if any.
+pub fn cached_username(root_url: &str) -> Option<String> {
+    let prefix = realm_prefix(root_url)?;
+    let mut files: Vec<PathBuf> = read_dir(svn_auth_dir()?).ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    files.sort();
+    files.iter()
+        .filter_map(|file| read_to_string(file).ok())
+        .map(|text| parse_svn_hash(&text))
+        .find(|hash| hash.get("svn:realmstring").is_some_and(|realm| realm.starts_with(&prefix)))
+        .and_then(|mut hash| hash.remove("username"))
+}
+
+//  Parse a file in the svn hash format:
+//  K <len>\n<key>\nV <len>\n<value>\n ... END
+fn parse_svn_hash(text: &str) -> HashMap<String, String> {
+    let mut hash = HashMap::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        if line == "END" {
+            break;
+        }
+        if line.starts_with("K ") {
+            if let (Some(key), Some(_), Some(value)) = (lines.next(), lines.next(), lines.next()) {
+                hash.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    hash
+}
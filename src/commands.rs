@@ -8,4 +8,66 @@ pub(crate) mod stash;
 pub(crate) mod bisect;
 pub(crate) mod prefix;
 pub(crate) mod ignore;
+pub(crate) mod pin;
+pub(crate) mod audit;
+pub(crate) mod graph;
+pub(crate) mod bugreport;
+pub(crate) mod grep;
+pub(crate) mod rev_math;
+pub(crate) mod mixed;
+pub(crate) mod diff_across;
+pub(crate) mod conflicts;
+pub(crate) mod blame;
+pub(crate) mod rollback;
+pub(crate) mod switch;
+pub(crate) mod notify;
+pub(crate) mod tag;
+pub(crate) mod merge;
+pub(crate) mod cherry;
+pub(crate) mod mergeinfo;
+pub(crate) mod status;
+pub(crate) mod commit;
+pub(crate) mod diff;
+pub(crate) mod doctor;
+pub(crate) mod props;
+pub(crate) mod lock;
+pub(crate) mod unlock;
+pub(crate) mod churn;
+pub(crate) mod authors;
+pub(crate) mod release_notes;
+pub(crate) mod search;
+pub(crate) mod cat;
+pub(crate) mod revert;
+pub(crate) mod up;
+pub(crate) mod size;
+pub(crate) mod compare;
+pub(crate) mod pq;
+pub(crate) mod snapshot;
+pub(crate) mod config;
+pub(crate) mod tui;
+pub(crate) mod prompt;
+pub(crate) mod export_git;
+pub(crate) mod issues;
+pub(crate) mod last;
+pub(crate) mod relocate;
+pub(crate) mod origin;
+pub(crate) mod review;
+pub(crate) mod verify;
+pub(crate) mod lint_message;
+pub(crate) mod undo;
+pub(crate) mod shelve;
+pub(crate) mod archive;
+pub(crate) mod tree;
+pub(crate) mod du;
+pub(crate) mod bigfiles;
+pub(crate) mod audit_mime;
+pub(crate) mod audit_eol;
+pub(crate) mod keywords;
+pub(crate) mod who;
+pub(crate) mod watch;
+pub(crate) mod worklog;
+pub(crate) mod template;
+pub(crate) mod init;
+pub(crate) mod mark;
+pub(crate) mod operation;
 pub(crate) mod completions;
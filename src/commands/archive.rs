@@ -0,0 +1,182 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::*;
+use crate::config;
+use crate::svn;
+use crate::util::{join_paths, SvError::*};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Export a branch, tag or revision as a tarball or zip file.
+///
+/// The tree is exported with `svn export` into a temporary directory and
+/// packaged in an archive whose files are within a single top level
+/// directory named after the archive.
+///
+/// The archive is named using the archive.name setting or --name, where
+/// {name} is the last component of the branch or tag, {location} is the
+/// branch or tag with slashes replaced by dashes and {rev} is the revision
+/// of the last commit to the branch or tag.  The default is {name}-r{rev}.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A SOURCE can be the trunk prefix, a full location such as tags/8.1.0\n\
+    or just the name of a branch or tag.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes.\n\
+    If SOURCE is a revision the current branch is archived at that revision.\n\n\
+    The format is taken from the extension of --output when it is given.\n\
+    The tar and zip commands are used to create the archive."
+)]
+pub struct Archive {
+    /// The branch, tag or revision to archive.  Defaults to the current branch.
+    #[arg(value_name = "SOURCE")]
+    source: Option<String>,
+
+    /// Archive the branch or tag as it was at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// The archive file to create.
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Archive format.
+    #[arg(short, long, value_enum)]
+    format: Option<Format>,
+
+    /// Template for the archive name.  Overrides the archive.name setting.
+    #[arg(short, long, value_name = "TEMPLATE")]
+    name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// gzip compressed tarball
+    #[value(name = "tar.gz")]
+    TarGz,
+    /// bzip2 compressed tarball
+    #[value(name = "tar.bz2")]
+    TarBz2,
+    /// Uncompressed tarball
+    Tar,
+    /// Zip file
+    Zip,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::TarGz  => ".tar.gz",
+            Format::TarBz2 => ".tar.bz2",
+            Format::Tar    => ".tar",
+            Format::Zip    => ".zip",
+        }
+    }
+
+    fn from_file_name(name: &str) -> Option<Format> {
+        match name {
+            n if n.ends_with(".tar.gz") || n.ends_with(".tgz") => Some(Format::TarGz),
+            n if n.ends_with(".tar.bz2") || n.ends_with(".tbz2") => Some(Format::TarBz2),
+            n if n.ends_with(".tar") => Some(Format::Tar),
+            n if n.ends_with(".zip") => Some(Format::Zip),
+            _ => None,
+        }
+    }
+}
+
+const DEFAULT_NAME: &str = "{name}-r{rev}";
+
+impl Archive {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let prefixes = svn::load_prefixes()?;
+
+        let (location, revision) = match self.source.as_deref() {
+            Some(source) if svn::looks_like_revision(source) && self.revision.is_none() => {
+                (svn::containing_location(&wc_info.rel_url, &prefixes)?, Some(source.to_string()))
+            }
+            Some(source) => (svn::resolve_location(&creds, &wc_info.root_url, source, &prefixes)?, self.revision.clone()),
+            None => (svn::containing_location(&wc_info.rel_url, &prefixes)?, self.revision.clone()),
+        };
+        let url = join_paths(&wc_info.root_url, &location);
+        let revision = match revision {
+            Some(rev) => Some(svn::resolve_revision(&creds, &rev, &url)?),
+            None => None,
+        };
+        let commit_rev = svn::info(&creds, &url, revision.as_deref())?.commit_rev;
+
+        let template = self.name.as_deref()
+            .or(config::get_str("archive.name"))
+            .unwrap_or(DEFAULT_NAME);
+        let name = location.rsplit('/').next().unwrap_or(&location);
+        let base_name = template
+            .replace("{name}", name)
+            .replace("{location}", &location.replace('/', "-"))
+            .replace("{rev}", &commit_rev);
+
+        let output_name = self.output.as_ref().map(|p| p.to_string_lossy().to_string());
+        let format = self.format
+            .or(output_name.as_deref().and_then(Format::from_file_name))
+            .unwrap_or(Format::TarGz);
+        let output = match &self.output {
+            Some(path) => path.clone(),
+            None => PathBuf::from(format!("{}{}", base_name, format.extension())),
+        };
+        if output.exists() {
+            return Err(General(format!("{} already exists", output.display())).into());
+        }
+
+        let temp_dir = env::temp_dir().join(format!("svu-archive-{}", std::process::id()));
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir)?;
+        }
+        fs::create_dir_all(&temp_dir)?;
+        println!("Exporting {}", svn::peg_path(&format!("^/{}", location), revision.as_deref()).green());
+        let result = svn::export(&creds, &url, revision.as_deref(), &temp_dir.join(&base_name))
+            .and_then(|_| package(&temp_dir, &base_name, &output, format));
+        fs::remove_dir_all(&temp_dir)?;
+        result?;
+        println!("Created {}", output.display().to_string().blue());
+        Ok(())
+    }
+}
+
+//  Package the directory dir_name within parent as the output file.
+fn package(parent: &Path, dir_name: &str, output: &Path, format: Format) -> Result<()> {
+    //  The command runs in parent, so the output path must be absolute.
+    let output = if output.is_absolute() { output.to_path_buf() } else { env::current_dir()?.join(output) };
+    let mut cmd = match format {
+        Format::Zip => {
+            let mut cmd = Command::new("zip");
+            cmd.arg("-qr").arg(&output).arg(dir_name);
+            cmd
+        }
+        _ => {
+            let flags = match format {
+                Format::TarGz  => "-czf",
+                Format::TarBz2 => "-cjf",
+                _              => "-cf",
+            };
+            let mut cmd = Command::new("tar");
+            cmd.arg(flags).arg(&output).arg(dir_name);
+            cmd
+        }
+    };
+    let program = cmd.get_program().to_string_lossy().to_string();
+    match cmd.current_dir(parent).output() {
+        Ok(result) if result.status.success() => Ok(()),
+        Ok(result) => {
+            let msg = format!("{} failed: {}", program, String::from_utf8_lossy(&result.stderr).trim());
+            Err(General(msg).into())
+        }
+        Err(e) => Err(General(format!("Cannot run {}: {}", program, e)).into()),
+    }
+}
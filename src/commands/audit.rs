@@ -0,0 +1,46 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use crate::audit;
+use crate::util::SvError::*;
+
+mod show;
+mod clear;
+
+/// Review the audit log of svu commands.
+///
+/// The audit log is disabled by default.  To enable it set the SVU_AUDIT_LOG
+/// environment variable.  If its value is `wc` each working copy keeps its own
+/// log in .svu/audit.log, otherwise the value is used as the path to a log file
+/// shared by all working copies.
+///
+/// Each entry records the date, user, command line arguments, working copy,
+/// resolved revisions and exit status of an svu invocation.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+#[command(flatten_help = false)]
+pub struct Audit {
+    #[command(subcommand)]
+    command: AuditCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum AuditCommands {
+    Show(show::Show),
+    Clear(clear::Clear),
+}
+use AuditCommands::*;
+
+impl Audit {
+    pub fn run(&mut self) -> Result<()> {
+        if !audit::is_enabled() {
+            return Err(General("The audit log is not enabled. (see SVU_AUDIT_LOG)".to_string()).into());
+        }
+        match &mut self.command {
+            Show(cmd)  => cmd.run(),
+            Clear(cmd) => cmd.run(),
+        }
+    }
+}
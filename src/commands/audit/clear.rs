@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::audit;
+use std::fs::remove_file;
+
+/// Remove all entries from the audit log.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Clear;
+
+impl Clear {
+    pub fn run(&mut self) -> Result<()> {
+        if let Some(path) = audit::audit_log_file()? {
+            if path.is_file() {
+                remove_file(&path)?;
+            }
+            println!("Cleared audit log: {}", path.to_string_lossy().blue());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::audit;
+use crate::util;
+
+/// Display the entries in the audit log.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Show {
+    /// Limit the number of entries displayed (most recent first).
+    #[arg(short, long, value_name = "NUM")]
+    limit: Option<usize>,
+}
+
+impl Show {
+    pub fn run(&mut self) -> Result<()> {
+        let mut entries = audit::load_entries()?;
+        entries.reverse();
+        if let Some(limit) = self.limit {
+            entries.truncate(limit);
+        }
+
+        for entry in &entries {
+            let status = if entry.exit_status == 0 {
+                entry.exit_status.to_string().green()
+            } else {
+                entry.exit_status.to_string().red()
+            };
+            println!(
+                "{} {} [{}] {}",
+                util::display_svn_datetime(&entry.date).magenta(),
+                entry.user.cyan(),
+                status,
+                entry.args.join(" ")
+            );
+            if let Some(wc) = &entry.working_copy {
+                println!("    working copy: {}", wc.blue());
+            }
+            if !entry.revisions.is_empty() {
+                println!("    revisions   : {}", entry.revisions.join(", ").yellow());
+            }
+        }
+        Ok(())
+    }
+}
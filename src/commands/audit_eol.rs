@@ -0,0 +1,262 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::*;
+use crate::commands::audit_mime::{has_binary_extension, is_text_mime_type};
+use crate::svn;
+use crate::util::SvError::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Find text files with inconsistent line endings or no svn:eol-style.
+///
+/// The text files in the working copy are checked for a mix of line
+/// endings, for line endings that do not match their svn:eol-style and
+/// for a missing svn:eol-style property.  Binary files are skipped, as
+/// are files that contain a NUL byte or are not valid UTF-8 and files
+/// with the extension of a known binary format.
+/// The problems are listed grouped by directory.
+///
+/// With --fix the line endings of each of those files are converted to
+/// the given style and svn:eol-style is set to it.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    svn refuses to set svn:eol-style on a file with mixed line endings, so\n\
+    --fix converts the file first.  Each converted file is written to a\n\
+    temporary file that then replaces the original.\n\
+    The changes made by --fix are left in the working copy to be committed."
+)]
+pub struct AuditEol {
+    /// Convert the files and set svn:eol-style to this style.
+    #[arg(long, value_enum, value_name = "STYLE", ignore_case = true)]
+    fix: Option<EolStyle>,
+
+    /// Path to working copy directory or file.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum EolStyle {
+    /// The line ending of the platform where the working copy is checked out
+    #[value(name = "native")]
+    Native,
+    /// Unix line endings
+    #[value(name = "LF")]
+    Lf,
+    /// Windows line endings
+    #[value(name = "CRLF")]
+    Crlf,
+}
+
+impl EolStyle {
+    fn property(&self) -> &'static str {
+        match self {
+            EolStyle::Native => "native",
+            EolStyle::Lf     => "LF",
+            EolStyle::Crlf   => "CRLF",
+        }
+    }
+
+    fn line_ending(&self) -> &'static [u8] {
+        match self {
+            EolStyle::Native if cfg!(windows) => b"\r\n",
+            EolStyle::Native | EolStyle::Lf   => b"\n",
+            EolStyle::Crlf                    => b"\r\n",
+        }
+    }
+}
+
+const EOL_STYLE: &str = "svn:eol-style";
+const MIME_TYPE: &str = "svn:mime-type";
+
+//  Number of paths given to each svn propset command.
+const BATCH_SIZE: usize = 100;
+
+//  The number of each kind of line ending in a file.
+#[derive(Debug, Default)]
+struct Endings {
+    lf:   usize,
+    crlf: usize,
+    cr:   usize,
+}
+
+impl Endings {
+    fn count(content: &[u8]) -> Self {
+        let mut endings = Endings::default();
+        let mut index = 0;
+        while index < content.len() {
+            match content[index] {
+                b'\r' if content.get(index + 1) == Some(&b'\n') => {
+                    endings.crlf += 1;
+                    index += 1;
+                }
+                b'\r' => endings.cr += 1,
+                b'\n' => endings.lf += 1,
+                _ => (),
+            }
+            index += 1;
+        }
+        endings
+    }
+
+    fn describe(&self) -> String {
+        [(self.lf, "LF"), (self.crlf, "CRLF"), (self.cr, "CR")]
+            .iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, name)| format!("{} {}", n, name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn kinds(&self) -> usize {
+        [self.lf, self.crlf, self.cr].iter().filter(|n| **n > 0).count()
+    }
+
+    //  True if every line ending is the given one.
+    fn only(&self, line_ending: &[u8]) -> bool {
+        match line_ending {
+            b"\r\n" => self.lf == 0 && self.cr == 0,
+            b"\r"   => self.lf == 0 && self.crlf == 0,
+            _       => self.crlf == 0 && self.cr == 0,
+        }
+    }
+}
+
+impl AuditEol {
+    pub fn run(&mut self) -> Result<()> {
+        if self.fix.is_some() {
+            svn::ensure_writable("convert line endings")?;
+        }
+        let info = svn::info(&None, &self.path, None)?;
+        if info.wc_path.is_none() || self.path.contains("://") || self.path.starts_with('^') {
+            return Err(General("audit-eol must be run on a working copy path".to_string()).into());
+        }
+        let files: Vec<String> = svn::info_recursive(&self.path)?
+            .into_iter()
+            .filter(|i| i.kind == "file")
+            .map(|i| i.path)
+            .collect();
+        let props: Props = svn::proplist(&None, &self.path, true)?
+            .into_iter()
+            .map(|t| (t.path, t.props))
+            .collect();
+
+        let problems = check_files(&files, &props);
+        if problems.is_empty() {
+            println!("Checked {} files, the line endings are consistent", files.len());
+            return Ok(());
+        }
+
+        let mut by_dir = BTreeMap::<&str, Vec<(&str, &str)>>::new();
+        for (path, description) in &problems {
+            let (dir, name) = path.rsplit_once('/').unwrap_or((".", path.as_str()));
+            by_dir.entry(dir).or_default().push((name, description.as_str()));
+        }
+        for (dir, entries) in &by_dir {
+            println!("{}", format!("{}/", dir).blue());
+            for (name, description) in entries {
+                println!("  {}: {}", name.green(), description);
+            }
+        }
+        println!("Checked {} files, found {} {}", files.len(), problems.len(), if problems.len() == 1 { "problem" } else { "problems" });
+
+        match self.fix {
+            Some(style) => {
+                let paths: Vec<&str> = problems.keys().map(|p| p.as_str()).collect();
+                for path in &paths {
+                    convert(Path::new(path), style.line_ending())?;
+                }
+                for chunk in paths.chunks(BATCH_SIZE) {
+                    svn::propset(EOL_STYLE, style.property(), chunk)?;
+                }
+                println!("Fixed {} {}", paths.len(), if paths.len() == 1 { "file" } else { "files" });
+                Ok(())
+            }
+            None => Err(General("Use --fix STYLE to correct the line endings".to_string()).into()),
+        }
+    }
+}
+
+//  The properties of each path.
+type Props = HashMap<String, Vec<(String, String)>>;
+
+fn prop<'a>(props: &'a Props, path: &str, name: &str) -> Option<&'a str> {
+    props.get(path).and_then(|p| p.iter().find(|(n, _)| n == name)).map(|(_, v)| v.as_str())
+}
+
+//  Return a description of the problems of each text file keyed by path.
+fn check_files(files: &[String], props: &Props) -> BTreeMap<String, String> {
+    use rayon::prelude::*;
+
+    files
+        .par_iter()
+        .filter_map(|path| {
+            if prop(props, path, MIME_TYPE).is_some_and(|m| !is_text_mime_type(m)) || has_binary_extension(path) {
+                return None;
+            }
+            //  The whole file is checked since --fix rewrites every line
+            //  ending and compressed data may look like text at the start.
+            let content = fs::read(path).ok()?;
+            if content.contains(&0) || std::str::from_utf8(&content).is_err() {
+                return None;
+            }
+            let endings = Endings::count(&content);
+            let mut problems = Vec::new();
+            if endings.kinds() > 1 {
+                problems.push(format!("mixed line endings ({})", endings.describe()));
+            }
+            match prop(props, path, EOL_STYLE) {
+                None => problems.push(format!("no {}", EOL_STYLE)),
+                Some(style) => {
+                    let expected = match style {
+                        "native" => Some(EolStyle::Native.line_ending()),
+                        "LF"     => Some(&b"\n"[..]),
+                        "CRLF"   => Some(&b"\r\n"[..]),
+                        "CR"     => Some(&b"\r"[..]),
+                        _        => None,
+                    };
+                    if endings.kinds() == 1 && expected.is_some_and(|e| !endings.only(e)) {
+                        problems.push(format!("{} is {} but the file has {}", EOL_STYLE, style, endings.describe()));
+                    }
+                }
+            }
+            if problems.is_empty() {
+                None
+            } else {
+                Some((path.clone(), problems.join(", ")))
+            }
+        })
+        .collect()
+}
+
+//  Convert every line ending of a file.  The converted contents are
+//  written to a temporary file that replaces the original, keeping
+//  its permissions.
+fn convert(path: &Path, line_ending: &[u8]) -> Result<()> {
+    let content = fs::read(path)?;
+    let mut converted = Vec::with_capacity(content.len());
+    let mut index = 0;
+    while index < content.len() {
+        match content[index] {
+            b'\r' if content.get(index + 1) == Some(&b'\n') => {
+                converted.extend_from_slice(line_ending);
+                index += 1;
+            }
+            b'\r' | b'\n' => converted.extend_from_slice(line_ending),
+            byte => converted.push(byte),
+        }
+        index += 1;
+    }
+    if converted != content {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(".{}.svu-eol", file_name));
+        fs::write(&temp, &converted)?;
+        fs::set_permissions(&temp, fs::metadata(path)?.permissions())?;
+        fs::rename(&temp, path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn;
+use crate::util::{join_paths, SvError::*};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+
+/// Find files whose svn:mime-type does not match their contents.
+///
+/// Each file is checked to see whether its contents are binary, using a
+/// test similar to the one svn uses when a file is added.  Binary files
+/// that have no svn:mime-type property are reported, as are text files
+/// whose svn:mime-type marks them as binary, which keeps svn from showing
+/// diffs or merging changes to them.
+///
+/// With --fix a mime type based on the file extension is set on binary
+/// files and the svn:mime-type property is removed from text files.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    svn treats a file as text if its mime type starts with text/ and as binary\n\
+    otherwise.  When PATH is a URL the contents of each file are fetched from\n\
+    the repository, so checking a large tree can take a while.\n\
+    The changes made by --fix are left in the working copy to be committed."
+)]
+pub struct AuditMime {
+    /// Set or remove svn:mime-type to fix the problems.
+    #[arg(long)]
+    fix: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+const MIME_TYPE: &str = "svn:mime-type";
+
+//  Number of paths given to each svn propset or propdel command.
+const BATCH_SIZE: usize = 100;
+
+//  The number of bytes examined at the start of each file.
+const SAMPLE_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Fix {
+    Set(String),
+    Remove,
+}
+
+impl AuditMime {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let info = svn::info(&creds, &self.path, None)?;
+        let is_wc = info.wc_path.is_some() && !self.path.contains("://") && !self.path.starts_with('^');
+        if self.fix {
+            if !is_wc {
+                return Err(General("--fix can only be used with a working copy path".to_string()).into());
+            }
+            svn::ensure_writable("set svn:mime-type properties")?;
+        }
+
+        let files = list_files(&creds, &self.path, &info.url, info.kind == "dir", is_wc)?;
+        let mime_types: HashMap<String, String> = svn::proplist(&creds, &self.path, true)?
+            .into_iter()
+            .filter_map(|t| {
+                let mime_type = t.props.into_iter().find(|(name, _)| name == MIME_TYPE)?.1;
+                Some((t.path, mime_type))
+            })
+            .collect();
+
+        let problems = self.check_files(&creds, &files, &mime_types, is_wc);
+        for (path, (description, _)) in &problems {
+            println!("{}: {}", path.green(), description);
+        }
+        if problems.is_empty() {
+            println!("Checked {} files, the mime types are consistent", files.len());
+            return Ok(());
+        }
+        println!("Checked {} files, found {} {}", files.len(), problems.len(), if problems.len() == 1 { "problem" } else { "problems" });
+
+        if self.fix {
+            //  Group the paths by the fix so that each svn command changes many paths.
+            let mut fixes = BTreeMap::<&Fix, Vec<&str>>::new();
+            for (path, (_, fix)) in &problems {
+                fixes.entry(fix).or_default().push(path.as_str());
+            }
+            for (fix, paths) in fixes {
+                for chunk in paths.chunks(BATCH_SIZE) {
+                    match fix {
+                        Fix::Set(mime_type) => svn::propset(MIME_TYPE, mime_type, chunk)?,
+                        Fix::Remove => svn::propdel(MIME_TYPE, chunk)?,
+                    }
+                }
+            }
+            println!("Fixed {} {}", problems.len(), if problems.len() == 1 { "file" } else { "files" });
+            Ok(())
+        } else {
+            Err(General("Use --fix to correct the svn:mime-type properties".to_string()).into())
+        }
+    }
+
+    //  Return a description of the problem and its fix keyed by path.
+    fn check_files(
+        &self,
+        creds: &Option<Credentials>,
+        files: &[(String, String)],
+        mime_types: &HashMap<String, String>,
+        is_wc: bool,
+    ) -> BTreeMap<String, (String, Fix)> {
+        use rayon::prelude::*;
+
+        files
+            .par_iter()
+            .filter_map(|(path, url)| {
+                let sample = if is_wc { read_sample(path)? } else { svn::cat(creds, url, None).ok()? };
+                let binary = is_binary(&sample[..sample.len().min(SAMPLE_SIZE)]);
+                match (mime_types.get(path), binary) {
+                    (None, true) => {
+                        let mime_type = mime_type_for(path);
+                        let desc = format!("binary file has no {}, suggest {}", MIME_TYPE, mime_type.cyan());
+                        Some((path.clone(), (desc, Fix::Set(mime_type.to_string()))))
+                    }
+                    (Some(mime_type), false) if !is_text_mime_type(mime_type) => {
+                        let desc = format!("text file has the binary {} {}", MIME_TYPE, mime_type.yellow());
+                        Some((path.clone(), (desc, Fix::Remove)))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
+
+//  Return the path and URL of each file.  The paths match those
+//  reported by svn proplist, which uses URLs for repository targets.
+fn list_files(creds: &Option<Credentials>, path: &str, url: &str, is_dir: bool, is_wc: bool) -> Result<Vec<(String, String)>> {
+    if !is_dir {
+        let path = if is_wc { path } else { url };
+        return Ok(vec![(path.to_string(), url.to_string())]);
+    }
+    if is_wc {
+        Ok(svn::info_recursive(path)?
+            .into_iter()
+            .filter(|i| i.kind == "file")
+            .map(|i| (i.path, i.url))
+            .collect())
+    } else {
+        Ok(svn::path_list_recursive(creds, url, None)?
+            .entries
+            .into_iter()
+            .filter(|e| e.kind == "file")
+            .map(|e| (join_paths(url, &e.name), join_paths(url, &e.name)))
+            .collect())
+    }
+}
+
+//  Read the start of a working copy file.
+//  Files that are missing or cannot be read are skipped.
+fn read_sample(path: &str) -> Option<Vec<u8>> {
+    let mut sample = Vec::with_capacity(SAMPLE_SIZE);
+    File::open(path).ok()?.take(SAMPLE_SIZE as u64).read_to_end(&mut sample).ok()?;
+    Some(sample)
+}
+
+//  A file is binary if it contains a NUL byte or if it is not
+//  UTF-8 and more than 15% of it is control characters.
+fn is_binary(sample: &[u8]) -> bool {
+    if sample.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => false,
+        //  A character may be cut off at the end of the sample.
+        Err(e) if e.error_len().is_none() => false,
+        Err(_) => {
+            let control = sample
+                .iter()
+                .filter(|b| **b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | b'\x0c' | b'\x1b'))
+                .count();
+            control * 100 > sample.len() * 15
+        }
+    }
+}
+
+//  svn treats these mime types as text and all others as binary.
+pub(crate) fn is_text_mime_type(mime_type: &str) -> bool {
+    mime_type.starts_with("text/") || mime_type == "image/x-xbitmap" || mime_type == "image/x-xpixmap"
+}
+
+//  True if the extension of the file is that of a known binary format.
+pub(crate) fn has_binary_extension(path: &str) -> bool {
+    mime_type_for(path) != "application/octet-stream"
+}
+
+fn mime_type_for(path: &str) -> &'static str {
+    let extension = path.rsplit_once('.').map(|(_, e)| e.to_lowercase()).unwrap_or_default();
+    match extension.as_str() {
+        "png"           => "image/png",
+        "jpg" | "jpeg"  => "image/jpeg",
+        "gif"           => "image/gif",
+        "bmp"           => "image/bmp",
+        "ico"           => "image/x-icon",
+        "tif" | "tiff"  => "image/tiff",
+        "webp"          => "image/webp",
+        "pdf"           => "application/pdf",
+        "zip"           => "application/zip",
+        "gz" | "tgz"    => "application/gzip",
+        "jar"           => "application/java-archive",
+        "class"         => "application/java-vm",
+        "woff"          => "font/woff",
+        "woff2"         => "font/woff2",
+        "ttf"           => "font/ttf",
+        "otf"           => "font/otf",
+        "mp3"           => "audio/mpeg",
+        "wav"           => "audio/wav",
+        "mp4"           => "video/mp4",
+        _               => "application/octet-stream",
+    }
+}
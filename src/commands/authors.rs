@@ -0,0 +1,172 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::display_svn_date;
+use std::collections::{HashMap, HashSet};
+
+/// Summarize the commits made by each author.
+///
+/// For each author the number of commits, the number of distinct files
+/// touched and the dates of the first and last commits are shown.
+/// Only the commits that affect the path are included.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Dates are given in a form accepted by svn such as 2024-03-01 or\n\
+    \"2024-03-01 13:00\" and refer to the start of that day or time."
+)]
+pub struct Authors {
+    /// The revision or range of revisions to examine.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    /// A range is specified by separating two revisions with a colon eg: HEAD-100:HEAD
+    #[arg(short, long, value_name = "REV[:REV]", conflicts_with_all = ["since", "until"])]
+    revision: Option<String>,
+
+    /// Include only commits made on or after this date.
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+
+    /// Include only commits made before this date.
+    #[arg(long, value_name = "DATE")]
+    until: Option<String>,
+
+    /// Write the summary as comma separated values.
+    #[arg(long)]
+    csv: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug)]
+struct AuthorStats {
+    author: String,
+    commits: usize,
+    files: HashSet<String>,
+    first: DateTime<Local>,
+    last: DateTime<Local>,
+}
+
+impl Authors {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revisions = match (&self.revision, &self.since, &self.until) {
+            (Some(rev), _, _) => vec![svn::resolve_revision_range(&creds, rev, &self.path)?],
+            (None, None, None) => vec![],
+            (None, since, until) => {
+                let start = since.as_ref().map(|d| format!("{{{}}}", d)).unwrap_or("1".to_string());
+                let end = until.as_ref().map(|d| format!("{{{}}}", d)).unwrap_or("HEAD".to_string());
+                vec![format!("{}:{}", start, end)]
+            }
+        };
+        //  The log paths are relative to the repository root.
+        let info = svn::info(&creds, &self.path, None)?;
+        let base = info.rel_url.trim_start_matches('^').trim_end_matches('/').to_string();
+        let entries = svn::log(&creds, std::slice::from_ref(&self.path), &revisions, false, None, false, true)?;
+
+        let since_date = self.since.as_deref().and_then(parse_date);
+        let mut authors = HashMap::<String, AuthorStats>::new();
+        for entry in &entries {
+            //  svn includes the revision that was current at the start
+            //  date, which was committed before it.
+            if since_date.is_some_and(|since| entry.date < since) {
+                continue;
+            }
+            let stats = authors.entry(entry.author.clone()).or_insert_with(|| AuthorStats {
+                author: entry.author.clone(),
+                commits: 0,
+                files: HashSet::new(),
+                first: entry.date,
+                last: entry.date,
+            });
+            stats.commits += 1;
+            stats.first = stats.first.min(entry.date);
+            stats.last = stats.last.max(entry.date);
+            let files = entry.paths
+                .iter()
+                .filter(|p| p.kind != "dir")
+                .filter(|p| base.is_empty() || p.path == base || p.path.starts_with(&format!("{}/", base)))
+                .map(|p| p.path.clone());
+            stats.files.extend(files);
+        }
+
+        let mut authors: Vec<AuthorStats> = authors.into_values().collect();
+        authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.author.cmp(&b.author)));
+        if self.csv {
+            show_csv(&authors);
+        } else if authors.is_empty() {
+            println!("No commits found");
+        } else {
+            show_table(&authors);
+        }
+        Ok(())
+    }
+}
+
+//  Parse the common forms of an svn date in local time.
+pub(crate) fn parse_date(text: &str) -> Option<DateTime<Local>> {
+    let datetime = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))?;
+    datetime.and_local_timezone(Local).earliest()
+}
+
+//  Quote a CSV field if it contains a separator, quote or newline.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn show_csv(authors: &[AuthorStats]) {
+    println!("author,commits,files,first,last");
+    for stats in authors {
+        println!(
+            "{},{},{},{},{}",
+            csv_field(&stats.author),
+            stats.commits,
+            stats.files.len(),
+            display_svn_date(&stats.first),
+            display_svn_date(&stats.last)
+        );
+    }
+}
+
+fn show_table(authors: &[AuthorStats]) {
+    let author_width = authors.iter().map(|a| a.author.chars().count()).max().unwrap_or(0).max(6);
+    let commits_width = authors.iter().map(|a| a.commits.to_string().len()).max().unwrap_or(0).max(7);
+    let files_width = authors.iter().map(|a| a.files.len().to_string().len()).max().unwrap_or(0).max(5);
+    let date_width = 10;
+    println!(
+        "{:<author_width$}  {:>commits_width$}  {:>files_width$}  {:<date_width$}  {:<date_width$}",
+        "Author", "Commits", "Files", "First", "Last",
+    );
+    println!(
+        "{}  {}  {}  {}  {}",
+        "-".repeat(author_width),
+        "-".repeat(commits_width),
+        "-".repeat(files_width),
+        "-".repeat(date_width),
+        "-".repeat(date_width)
+    );
+    for stats in authors {
+        println!(
+            "{:<author_width$}  {:>commits_width$}  {:>files_width$}  {:<date_width$}  {:<date_width$}",
+            stats.author.cyan(),
+            stats.commits.to_string().yellow(),
+            stats.files.len(),
+            display_svn_date(&stats.first).magenta(),
+            display_svn_date(&stats.last).magenta(),
+        );
+    }
+}
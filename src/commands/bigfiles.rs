@@ -0,0 +1,181 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn::{self, LogEntry};
+use crate::util::{self, join_paths};
+use std::collections::HashMap;
+
+/// Find the largest files committed in the history of a path.
+///
+/// The log of PATH is scanned for files that were added, replaced or
+/// modified below it, and the size of each committed version of those
+/// files is fetched with `svn list`.  The largest files are listed with
+/// the revision, author and date of their largest version.
+///
+/// Files that were later deleted are marked since they still take up
+/// space in the repository, which makes them candidates for cleanup or
+/// for moving to an svn:externals definition.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Scanning a long history runs many svn commands, use --revision to limit it.\n\
+    The number of svn commands run at once is limited by --jobs."
+)]
+pub struct Bigfiles {
+    /// The range of revisions to scan.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long = "revision", value_name = "RANGE", default_value = "1:HEAD")]
+    range: String,
+
+    /// Number of files to report.
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 20)]
+    top: usize,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+//  The largest version of a file.
+struct BigFile<'a> {
+    path:    String,
+    size:    u64,
+    entry:   &'a LogEntry,
+    deleted: bool,
+}
+
+//  Number of files listed by each svn command.
+const BATCH_SIZE: usize = 50;
+
+impl Bigfiles {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let info = svn::info(&creds, &self.path, None)?;
+        let prefix = info.rel_url.trim_start_matches('^').to_string();
+        let under_prefix = |path: &str| prefix == "/" || path == prefix || path.starts_with(&format!("{}/", prefix));
+        let range = svn::resolve_revision_range(&creds, &self.range, &self.path)?;
+        let mut entries = svn::log(&creds, &[info.url.as_str()], &[range.as_str()], false, None, false, true)?;
+        entries.sort_by_key(|e| e.revision.parse::<u64>().unwrap_or(0));
+
+        //  The files committed in each revision.
+        let mut wanted: Vec<(&LogEntry, Vec<String>)> = Vec::new();
+        for entry in &entries {
+            let paths: Vec<String> = entry.paths
+                .iter()
+                .filter(|p| matches!(p.action.as_str(), "A" | "R" | "M") && p.kind != "dir" && under_prefix(&p.path))
+                .map(|p| p.path.clone())
+                .collect();
+            if !paths.is_empty() {
+                wanted.push((entry, paths));
+            }
+        }
+
+        let sizes = fetch_sizes(&creds, &info.root_url, &wanted)?;
+
+        //  Keep the largest version of each file.
+        let mut largest = HashMap::<&str, BigFile>::new();
+        for (path, size, entry) in &sizes {
+            match largest.get(path.as_str()) {
+                Some(big) if big.size >= *size => (),
+                _ => {
+                    let big = BigFile { path: path.clone(), size: *size, entry, deleted: false };
+                    largest.insert(path.as_str(), big);
+                }
+            }
+        }
+
+        //  A file is deleted if it, or a directory containing it, was
+        //  deleted or replaced after its largest version was committed
+        //  and the file was not added again.
+        let mut files: Vec<BigFile> = largest.into_values().collect();
+        for file in files.iter_mut() {
+            let committed = file.entry.revision.parse::<u64>().unwrap_or(0);
+            for entry in entries.iter().filter(|e| e.revision.parse::<u64>().unwrap_or(0) > committed) {
+                for p in &entry.paths {
+                    if p.path == file.path {
+                        file.deleted = p.action == "D";
+                    } else if file.path.starts_with(&format!("{}/", p.path)) && matches!(p.action.as_str(), "D" | "R") {
+                        file.deleted = true;
+                    }
+                }
+            }
+        }
+        files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        files.truncate(self.top);
+
+        if files.is_empty() {
+            println!("No files were committed in {}", self.range);
+            return Ok(());
+        }
+        let sizes: Vec<String> = files.iter().map(|f| util::display_size(f.size)).collect();
+        let size_len = sizes.iter().map(|s| s.len()).max().unwrap_or(0);
+        let rev_len = files.iter().map(|f| f.entry.revision.len()).max().unwrap_or(0);
+        let author_len = files.iter().map(|f| f.entry.author.len()).max().unwrap_or(0);
+        for (file, size) in files.iter().zip(&sizes) {
+            println!(
+                "{} {} {} {} {}{}",
+                format!("{:>w$}", size, w = size_len).yellow(),
+                format!("{:>w$}", file.entry.revision, w = rev_len + 1).yellow(),
+                format!("{:w$}", file.entry.author, w = author_len).cyan(),
+                util::display_svn_date(&file.entry.date).magenta(),
+                file.path,
+                if file.deleted { format!(" ({})", "deleted".red()) } else { String::new() }
+            );
+        }
+        Ok(())
+    }
+}
+
+//  List the files of each revision in batches, in parallel, and return
+//  the size of each.  When a batch cannot be listed its files are listed
+//  one at a time so that only the files that are not found are left out.
+fn fetch_sizes<'a>(
+    creds: &Option<Credentials>,
+    root_url: &str,
+    wanted: &[(&'a LogEntry, Vec<String>)],
+) -> Result<Vec<(String, u64, &'a LogEntry)>> {
+    use rayon::prelude::*;
+
+    let batches: Vec<(&LogEntry, &[String])> = wanted
+        .iter()
+        .flat_map(|(entry, paths)| paths.chunks(BATCH_SIZE).map(move |chunk| (*entry, chunk)))
+        .collect();
+
+    let sizes = batches
+        .par_iter()
+        .map(|(entry, paths)| {
+            let targets: Vec<String> = paths
+                .iter()
+                .map(|p| svn::peg_path(&join_paths(root_url, p), Some(&entry.revision)))
+                .collect();
+            let lists = match svn::path_lists(creds, &targets) {
+                Ok(lists) => lists.into_iter().map(Some).collect(),
+                Err(_) => targets
+                    .iter()
+                    .map(|target| match svn::path_list(creds, target) {
+                        Ok(list) => Ok(Some(list)),
+                        Err(e) if svn::is_not_found_error(&e) => Ok(None),
+                        Err(e) => Err(e),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            };
+            Ok(paths
+                .iter()
+                .zip(lists)
+                .filter_map(|(path, list)| {
+                    let name = path.rsplit('/').next().unwrap_or(path);
+                    list?.entries
+                        .iter()
+                        .find(|e| e.kind == "file" && e.name == name)
+                        .map(|e| (path.clone(), e.size.unwrap_or(0), *entry))
+                })
+                .collect::<Vec<_>>())
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(sizes.into_iter().flatten().collect())
+}
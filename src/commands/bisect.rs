@@ -3,12 +3,16 @@ use chrono::Local;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use crate::auth::Credentials;
-use crate::util::{SvError::*, show_commit};
+use crate::util::{SvError::*, MessageStyle, show_commit};
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs::File;
 use crate::svn::{self, LogEntry};
 use crate::util;
+use crate::migrate;
+use crate::interrupt;
+use crate::hooks;
+use crate::commands::snapshot;
 use std::fs::OpenOptions;
 use serde::{Deserialize, Serialize};
 use regex::Regex;
@@ -26,6 +30,7 @@ mod log;
 mod run;
 mod replay;
 mod reset;
+mod import_results;
 
 /// Use a binary search to find the commit that introduced a bug.
 ///
@@ -63,6 +68,7 @@ enum BisectCommands {
     Run(run::Run),
     Replay(replay::Replay),
     Reset(reset::Reset),
+    ImportResults(import_results::ImportResults),
 }
 use BisectCommands::*;
 
@@ -80,6 +86,7 @@ impl Bisect {
             Run(cmd)    => cmd.run(),
             Replay(cmd) => cmd.run(),
             Reset(cmd)  => cmd.run(),
+            ImportResults(cmd) => cmd.run(),
         }
     }
 }
@@ -87,7 +94,8 @@ impl Bisect {
 
 fn parse_term(arg: &str) -> Result<String> {
     let commands = HashSet::from([
-        "start", "bad", "good", "terms", "skip", "unskip", "log", "run", "replay", "reset"
+        "start", "bad", "good", "terms", "skip", "unskip", "log", "run", "replay", "reset",
+        "import-results"
     ]);
     let re = Regex::new(r"^[A-Za-z][-_A-Za-z]*$").unwrap();
     if !re.is_match(arg)  {
@@ -106,21 +114,24 @@ fn parse_term(arg: &str) -> Result<String> {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct BisectData {
-    #[serde(rename(serialize = "originalRev", deserialize = "originalRev"))]
+    #[serde(rename(serialize = "originalRev", deserialize = "originalRev"), alias = "original_rev")]
     original_rev: String,
-    #[serde(rename(serialize = "headRev", deserialize = "headRev"))]
+    #[serde(rename(serialize = "headRev", deserialize = "headRev"), alias = "head_rev")]
     head_rev: Option<String>,
-    #[serde(rename(serialize = "firstRev", deserialize = "firstRev"))]
+    #[serde(rename(serialize = "firstRev", deserialize = "firstRev"), alias = "first_rev")]
     first_rev: Option<String>,
-    #[serde(rename(serialize = "maxRev", deserialize = "maxRev"))]
+    #[serde(rename(serialize = "maxRev", deserialize = "maxRev"), alias = "max_rev")]
     max_rev: Option<String>,
-    #[serde(rename(serialize = "minRev", deserialize = "minRev"))]
+    #[serde(rename(serialize = "minRev", deserialize = "minRev"), alias = "min_rev")]
     min_rev: Option<String>,
     skipped: HashSet<String>,
-    #[serde(rename(serialize = "termGood", deserialize = "termGood"))]
+    #[serde(rename(serialize = "termGood", deserialize = "termGood"), alias = "term_good")]
     term_good: Option<String>,
-    #[serde(rename(serialize = "termBad", deserialize = "termBad"))]
+    #[serde(rename(serialize = "termBad", deserialize = "termBad"), alias = "term_bad")]
     term_bad: Option<String>,
+    //  The patch name of the stash entry created by --autostash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    autostash: Option<String>,
 }
 
 impl BisectData {
@@ -144,11 +155,19 @@ impl BisectData {
 }
 
 
+const BISECT_DATA_FILE: &str = "bisect_data.json";
+
 fn bisect_data_file() -> Result<PathBuf> {
-    Ok(util::data_directory()?.join("bisect_data.json"))
+    Ok(util::data_directory()?.join(BISECT_DATA_FILE))
 }
 
 
+//  True if a bisect session is in progress in a data directory.
+//  Used by `svu prompt` which must not run svn.
+pub(crate) fn in_progress(data_dir: &Path) -> bool {
+    data_dir.join(BISECT_DATA_FILE).is_file()
+}
+
 fn bisect_log_file() -> Result<PathBuf> {
     Ok(util::data_directory()?.join("bisect_log"))
 }
@@ -156,17 +175,67 @@ fn bisect_log_file() -> Result<PathBuf> {
 fn load_bisect_data() -> Result<Option<BisectData>> {
     let path = bisect_data_file()?;
     if path.is_file() {
-        let reader = File::open(path)?;
-        let data: BisectData = serde_json::from_reader(reader)?;
-        Ok(Some(data))
+        Ok(Some(migrate::load_json(&path)?))
     } else {
         Ok(None)
     }
 }
 
+//  Used by `svu doctor` to validate the data file.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(bisect_data_file()?, load_bisect_data().map(|_| ()))])
+}
+
+//  Used by `svu tui` to record the revisions marked in the interface.
+//  A session is started if one is not already in progress.
+pub(crate) fn mark_revisions(good: Option<&str>, bad: Option<&str>) -> Result<()> {
+    let cmd_name = std::env::args().next().unwrap_or("svu".to_string());
+    match load_bisect_data()? {
+        None => {
+            let mut args = vec!["start".to_string()];
+            if let Some(rev) = good {
+                args.extend(["--good".to_string(), rev.to_string()]);
+            }
+            if let Some(rev) = bad {
+                args.extend(["--bad".to_string(), rev.to_string()]);
+            }
+            let mut start = start::Start::try_parse_from(&args)?;
+            start.cmd_line = Some([vec![cmd_name, "bisect".to_string()], args].concat());
+            start.run()
+        }
+        Some(mut data) => {
+            let min_rev = good.map(|r| r.to_string()).or(data.min_rev.clone());
+            let max_rev = bad.map(|r| r.to_string()).or(data.max_rev.clone());
+            if let (Some(min), Some(max)) = (&min_rev, &max_rev) {
+                if to_rev_num(min) >= to_rev_num(max) {
+                    let msg = format!(
+                        "The '{}' revision must be older than the '{}' revision",
+                        data.good_name(),
+                        data.bad_name()
+                    );
+                    return Err(General(msg).into());
+                }
+            }
+            if let Some(rev) = bad {
+                record_bad_revision(&mut data, rev)?;
+                log_bisect_command(&[cmd_name.clone(), "bisect".to_string(), "bad".to_string(), rev.to_string()])?;
+            }
+            if let Some(rev) = good {
+                record_good_revision(&mut data, rev)?;
+                log_bisect_command(&[cmd_name.clone(), "bisect".to_string(), "good".to_string(), rev.to_string()])?;
+            }
+            if let Some(status) = get_waiting_status(&data) {
+                append_to_log(format!("# {}", status))?;
+                println!("{}", status);
+            }
+            continue_bisect(&data)?;
+            Ok(())
+        }
+    }
+}
+
 fn save_bisect_data(data: &BisectData) -> Result<()> {
-    let writer = File::create(bisect_data_file()?)?;
-    Ok(serde_json::to_writer_pretty(writer, data)?)
+    migrate::save_json(&bisect_data_file()?, data)
 }
 
 //  Load and return the bisect data or return a Generaal
@@ -288,41 +357,50 @@ fn get_log_entry(revision: &str, with_paths: bool) -> Result<Option<LogEntry>> {
     Ok(log.first().cloned())
 }
 
+//  Return the revisions that lie between the current good and bad
+//  bounds, along with the subset of those that have not been skipped.
+fn get_remaining_revisions(data: &BisectData) -> Result<(Vec<String>, Vec<String>)> {
+    let max_rev = data.max_rev.as_ref().unwrap();
+    let min_rev = data.min_rev.as_ref().unwrap();
+    let extant_revs = get_extant_revisions(max_rev, min_rev)?;
+    let candidate_revs = extant_revs[1..extant_revs.len() - 1].to_vec();
+    let non_skipped_revs = candidate_revs
+        .iter()
+        .filter(|r| !data.skipped.contains(*r))
+        .cloned()
+        .collect();
+    Ok((candidate_revs, non_skipped_revs))
+}
+
 fn perform_bisect(data: &BisectData) -> Result<bool> {
     if !data.is_ready() {
         return Err(General("fatal: peform_bisect() called when data not ready".to_string()).into());
     }
 
     let max_rev = data.max_rev.as_ref().unwrap();
-    let min_rev = data.min_rev.as_ref().unwrap();
-    let extant_revs = get_extant_revisions(max_rev, min_rev)?;
-    let candidate_revs = &extant_revs[1..extant_revs.len() - 1];
-    let non_skipped_revs: Vec<String> = candidate_revs
-        .iter()
-        .filter_map(|r| {
-            if data.skipped.contains(r) {
-                None
-            } else {
-                Some(r.clone())
-            }
-        })
-        .collect();
+    let (candidate_revs, non_skipped_revs) = get_remaining_revisions(data)?;
 
     if non_skipped_revs.is_empty() {
         if !candidate_revs.is_empty() {
             println!("\nThere are only skipped revisions left to test.");
             println!("The first {} commit could be any of:", data.bad_name());
             println!("{} {}", max_rev.yellow(), get_1st_log_message(max_rev)?);
-            for rev in candidate_revs {
+            for rev in &candidate_revs {
                 println!("{} {}", rev.yellow(), get_1st_log_message(rev)?);
             }
             println!("We cannot bisect more!");
+            hooks::run("post-bisect-complete", &[
+                ("REVISION", max_rev),
+                ("TERM_BAD", data.bad_name()),
+                ("CANDIDATES", &format!("{} {}", max_rev, candidate_revs.join(" "))),
+            ])?;
             Ok(true)
         } else {
             println!("\nThe first '{}' revision is: {}", data.bad_name(), max_rev.yellow());
             if let Some(log_entry) = get_log_entry(max_rev, true)? {
-                show_commit(&log_entry, true, true);
+                show_commit(&log_entry, MessageStyle::Full, true);
             }
+            hooks::run("post-bisect-complete", &[("REVISION", max_rev), ("TERM_BAD", data.bad_name())])?;
             Ok(true)
         }
     } else {
@@ -344,8 +422,15 @@ fn update_workingcopy(revision: &String) -> Result<()> {
     let msg = get_1st_log_message(revision)?;
     let wc_info = svn::workingcopy_info()?;
     let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+    snapshot::before("bisect")?;
     println!("Updating working copy: [{}] {}", revision.yellow(), msg);
+    let mut progress = interrupt::Progress::new();
+    progress.step_with_hint(
+        format!("Updating the working copy to revision {}", revision),
+        format!("The bisect session was saved.  Run 'svn update -r {}' to finish the update.", revision)
+    );
     svn::update(revision, "infinity", Some(&wc_root))?;
+    progress.finish();
     Ok(())
 }
 
@@ -355,15 +440,16 @@ fn update_workingcopy(revision: &String) -> Result<()> {
 //  to start performing bisects
 fn mark_good_revision(revision: &str) -> Result<bool> {
     let mut data = get_bisect_data()?;
+    record_good_revision(&mut data, revision)?;
+    continue_bisect(&data)
+}
+
+//  Record a good revision without updating the working copy.
+fn record_good_revision(data: &mut BisectData, revision: &str) -> Result<()> {
     data.skipped.remove(revision);
     data.min_rev = Some(revision.to_string());
-    save_bisect_data(&data)?;
-    log_bisect_revision(revision, data.good_name())?;
-    if data.is_ready() {
-        perform_bisect(&data)
-    } else {
-        Ok(false)
-    }
+    save_bisect_data(data)?;
+    log_bisect_revision(revision, data.good_name())
 }
 
 //  Returns true if perform_bisect() reports that that session
@@ -372,20 +458,31 @@ fn mark_good_revision(revision: &str) -> Result<bool> {
 //  to start performing bisects
 fn mark_bad_revision(revision: &str) -> Result<bool> {
     let mut data = get_bisect_data()?;
+    record_bad_revision(&mut data, revision)?;
+    continue_bisect(&data)
+}
+
+//  Record a bad revision without updating the working copy.
+fn record_bad_revision(data: &mut BisectData, revision: &str) -> Result<()> {
     data.skipped.remove(revision);
     data.max_rev = Some(revision.to_string());
-    save_bisect_data(&data)?;
-    log_bisect_revision(revision, data.bad_name())?;
-    if data.is_ready() {
-        perform_bisect(&data)
-    } else {
-        Ok(false)
-    }
+    save_bisect_data(data)?;
+    log_bisect_revision(revision, data.bad_name())
 }
 
 //  Returns true if the perform_bisect() reports that the session is complete
 fn mark_skipped_revisions(incoming_skipped: &HashSet<String>) -> Result<bool> {
     let mut data = get_bisect_data()?;
+    if record_skipped_revisions(&mut data, incoming_skipped)? {
+        continue_bisect(&data)
+    } else {
+        Ok(false)
+    }
+}
+
+//  Record skipped revisions without updating the working copy.
+//  Returns true if any of the revisions were not already skipped.
+fn record_skipped_revisions(data: &mut BisectData, incoming_skipped: &HashSet<String>) -> Result<bool> {
     let mut new_skipped: Vec<String> = incoming_skipped
         .difference(&data.skipped)
         .cloned()
@@ -394,22 +491,24 @@ fn mark_skipped_revisions(incoming_skipped: &HashSet<String>) -> Result<bool> {
     if new_skipped.is_empty() {
         Ok(false)
     } else {
-        let skipped: HashSet<String> = data.skipped
-            .union(incoming_skipped)
-            .cloned()
-            .collect();
-        data = BisectData { skipped, ..data };
-        save_bisect_data(&data)?;
+        data.skipped.extend(new_skipped.iter().cloned());
+        save_bisect_data(data)?;
         new_skipped.sort_by(|a, b| b.cmp(a)); // Sorted most recent first
         for rev in &new_skipped {
             log_bisect_revision(rev, "skip")?;
         }
+        Ok(true)
+    }
+}
 
-        if data.is_ready() {
-            perform_bisect(&data)
-        } else {
-            Ok(false)
-        }
+//  Once both a good and a bad revision are known, update the working
+//  copy to the next revision to test.
+//  Returns true if the perform_bisect() reports that the session is complete
+fn continue_bisect(data: &BisectData) -> Result<bool> {
+    if data.is_ready() {
+        perform_bisect(data)
+    } else {
+        Ok(false)
     }
 }
 
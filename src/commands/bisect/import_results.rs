@@ -0,0 +1,98 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Mark many revisions from a file of test results.
+///
+/// Each line of the file contains a revision followed by good, bad or skip
+/// (or the terms set with `svu bisect terms`).  The results are applied in
+/// order and the working copy is updated to the next revision to test only
+/// after all of them have been applied.  This allows results collected
+/// elsewhere, such as by nightly builds, to seed or even complete a bisect
+/// without testing those revisions again.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Blank lines and lines starting with '#' are ignored.\n\
+    Example:\n\
+    \n    # nightly results\n    \
+    7601 good\n    \
+    7650 skip\n    \
+    7702 bad"
+)]
+pub struct ImportResults {
+    /// Path to the results file.
+    #[arg(value_name = "FILE")]
+    results_file: String,
+}
+
+impl ImportResults {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?;  // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        let wc_root_str = wc_root.to_string_lossy();
+        let mut data = get_bisect_data()?;
+        let cmd_name = std::env::args().next().unwrap_or("svu".to_string());
+
+        let reader = BufReader::new(File::open(&self.results_file)?);
+        let mut applied = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (rev, result) = match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                [rev, result] => (rev, result),
+                _ => {
+                    let msg = format!("{}:{}: expected 'REV good|bad|skip'", self.results_file, index + 1);
+                    return Err(General(msg).into());
+                }
+            };
+            let revision = svn::resolve_revision(&creds, rev, &wc_root_str)?;
+
+            if result == "good" || result == data.good_name() {
+                if data.max_rev.is_some() && to_rev_num(&revision) >= to_rev_num(data.max_rev.as_ref().unwrap()) {
+                    println!(
+                        "Ignoring {}: the '{}' revision must be older than the '{}' revision",
+                        line, data.good_name(), data.bad_name()
+                    );
+                    continue;
+                }
+                record_good_revision(&mut data, &revision)?;
+                log_bisect_command(&[cmd_name.clone(), "bisect".to_string(), "good".to_string(), revision])?;
+            } else if result == "bad" || result == data.bad_name() {
+                if data.min_rev.is_some() && to_rev_num(&revision) <= to_rev_num(data.min_rev.as_ref().unwrap()) {
+                    println!(
+                        "Ignoring {}: the '{}' revision must be more recent than the '{}' revision",
+                        line, data.bad_name(), data.good_name()
+                    );
+                    continue;
+                }
+                record_bad_revision(&mut data, &revision)?;
+                log_bisect_command(&[cmd_name.clone(), "bisect".to_string(), "bad".to_string(), revision])?;
+            } else if result == "skip" {
+                record_skipped_revisions(&mut data, &HashSet::from([revision.clone()]))?;
+                log_bisect_command(&[cmd_name.clone(), "bisect".to_string(), "skip".to_string(), revision])?;
+            } else {
+                let msg = format!(
+                    "{}:{}: unknown result '{}', expected {}, {} or skip",
+                    self.results_file, index + 1, result, data.good_name(), data.bad_name()
+                );
+                return Err(General(msg).into());
+            }
+            applied += 1;
+        }
+        println!("Applied {} results from {}", applied, self.results_file);
+
+        continue_bisect(&data)?;
+        if let Some(status) = get_waiting_status(&data) {
+            append_to_log(format!("# {}", status))?;
+            println!("{}", status);
+        }
+        Ok(())
+    }
+}
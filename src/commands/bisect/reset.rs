@@ -3,6 +3,7 @@ use clap::Parser;
 use super::*;
 use anyhow::Result;
 use std::fs::remove_file;
+use crate::commands::operation::{self, Step};
 
 /// Clean up after a bisect session.
 #[derive(Debug, Parser)]
@@ -32,24 +33,48 @@ impl Reset {
         let wc_path = wc_root.to_string_lossy();
     
         if let Some(data) = load_bisect_data()? {
-            if !self.no_update {
-                let revision = self
+            let revision = if self.no_update {
+                None
+            } else {
+                Some(self
                     .revision
                     .as_ref()
                     .map(|rev| svn::resolve_revision(&creds, rev, &wc_path))
-                    .unwrap_or(Ok(data.original_rev))?;
-                update_workingcopy(&revision)?;
-            } else {
-                let revision = wc_info.commit_rev;
-                let msg      = get_1st_log_message(&revision)?;
-                println!("Working copy: [{}] {}", revision.yellow(), msg);
+                    .unwrap_or(Ok(data.original_rev))?)
+            };
+
+            //  The session ends here.  The stash is popped by
+            //  `svu continue` if the working copy cannot be updated.
+            if let Some(patch_name) = &data.autostash {
+                operation::ensure_none_in_progress()?;
+                operation::begin("bisect reset", vec![Step::PopStash { patch_name: patch_name.clone() }], vec![])?;
             }
-    
             remove_file(bisect_data_file()?)?;
             let path = bisect_log_file()?;
             if path.is_file() {
                 remove_file(path)?;
             }
+
+            match revision {
+                Some(revision) => {
+                    if let Err(e) = update_workingcopy(&revision) {
+                        if data.autostash.is_some() {
+                            operation::show_failed("bisect reset");
+                        }
+                        return Err(e);
+                    }
+                }
+                None => {
+                    let revision = wc_info.commit_rev;
+                    let msg      = get_1st_log_message(&revision)?;
+                    println!("Working copy: [{}] {}", revision.yellow(), msg);
+                }
+            }
+
+            if data.autostash.is_some() {
+                println!("Reapplying the changes stashed by bisect start");
+                operation::complete(&wc_root)?;
+            }
         }
         Ok(())
     }
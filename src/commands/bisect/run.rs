@@ -5,6 +5,12 @@ use anyhow::Result;
 use std::process;
 use std::collections::HashSet;
 
+const EXIT_FOUND: i32    = 0;
+const EXIT_SKIPPED: i32  = 2;
+const EXIT_ABORTED: i32  = 3;
+const EXIT_FAILED: i32   = 4;
+const EXIT_ERROR: i32    = 5;
+
 /// Automate the bisect session by running a script.
 #[derive(Debug, Parser)]
 #[command(
@@ -19,9 +25,21 @@ use std::collections::HashSet;
    exits with this code, the current revision will be skipped (see `git bisect skip`). 125 was chosen\n\
    as the highest sensible value to use for this purpose, because 126 and 127 are used by POSIX shells to\n\
    signal specific error status (127 is for command not found, 126 is for command found but not executable\n\
-   these details do not matter, as they are normal errors in the script, as far as bisect run is concerned)."
+   these details do not matter, as they are normal errors in the script, as far as bisect run is concerned).\n\n\
+   When bisect run finishes, svu exits with one of the following codes:\n\
+     0  The first bad revision was found.  It is printed on stdout as the last line of output.\n\
+     2  The first bad revision cannot be determined because of skipped revisions.\n\
+     3  The bisect was aborted because the script returned an unrecoverable exit code.\n\
+     4  The script could not be executed.\n\
+     5  An error occurred, such as an svn command that failed.\n\n\
+   With --porcelain the last line of output has the form '<code> <outcome> [<revision>...]'\n\
+   where outcome is one of: found, skipped, aborted, failed, error."
 )]
 pub struct Run {
+    /// Print the outcome in a stable, machine readable form.
+    #[arg(long)]
+    porcelain: bool,
+
     /// Name of a command (script) to run
     #[arg(value_name = "CMD", num_args = 1..=1, required = true)]
     cmd: String,
@@ -33,6 +51,17 @@ pub struct Run {
 
 impl Run {
     pub fn run(&mut self) -> Result<()> {
+        //  An error must not exit with 1, which would be mistaken
+        //  for the status of a script that found a bad revision.
+        match self.run_script() {
+            Err(e) if !matches!(e.downcast_ref::<util::SvError>(), Some(Exit(_)) | Some(Interrupted)) => {
+                self.finish(EXIT_ERROR, "error", &[], Some(format!("{:?}", e)))
+            }
+            result => result,
+        }
+    }
+
+    fn run_script(&self) -> Result<()> {
         let wc_info = svn::workingcopy_info()?;  // Make sure we are in a working copy.
         let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
         let data    = get_bisect_data()?;  // Make sure a bisect session has benn started
@@ -46,32 +75,34 @@ impl Run {
                 data.good_name(), data.bad_name());
             Err(General(msg).into())
         } else {
-            
             loop {
+                let wc_info = svn::workingcopy_info()?;
                 let data    = get_bisect_data()?;
                 let cmd     = process::Command::new(self.cmd.as_str())
                     .current_dir(&wc_root)
                     .args(self.args.iter())
                     .stdout(process::Stdio::inherit())
                     .stderr(process::Stdio::inherit())
-                    .output()?;
-        
-                let exit_code = match cmd.status.code() {
-                    Some(code) => code,
-                    None => {
+                    .output();
+
+                let exit_code = match cmd.map(|c| c.status.code()) {
+                    Ok(Some(code)) => code,
+                    Ok(None) => {
+                        let msg = format!("'bisect run' failed. Command '{}' was terminated by a signal", self.cmd);
+                        return self.finish(EXIT_ABORTED, "aborted", &[], Some(msg));
+                    }
+                    Err(_) => {
                         let msg = format!("Command '{}' failed to execute", self.cmd);
-                        return Err(General(msg).into());
+                        return self.finish(EXIT_FAILED, "failed", &[], Some(msg));
                     }
                 };
 
-                match exit_code {
+                let complete = match exit_code {
                     0 => {
                         display_command(data.good_name());
                         let complete = mark_good_revision(&wc_info.commit_rev)?;
                         log_command(data.good_name())?;
-                        if complete {
-                            break
-                        }
+                        complete
                     }
                     125 => {
                         display_command("skip");
@@ -79,32 +110,64 @@ impl Run {
                         revs.insert(wc_info.commit_rev.clone());
                         let complete = mark_skipped_revisions(&revs)?;
                         log_command("skip")?;
-                        if complete {
-                            break
-                        }
-    
+                        complete
                     },
                     code if code < 128 => {
                         display_command(data.bad_name());
                         let complete = mark_bad_revision(&wc_info.commit_rev)?;
                         log_command(data.bad_name())?;
-                        if complete {
-                            break
-                        }
+                        complete
                     }
                     code => {
-                        let msg = format!("'bisect run' failed. Command '{}' returned unrecoverable error coce ({})",
+                        let msg = format!("'bisect run' failed. Command '{}' returned unrecoverable error code ({})",
                         self.cmd, code);
-                        return Err(General(msg).into());
+                        return self.finish(EXIT_ABORTED, "aborted", &[], Some(msg));
                     }
+                };
+
+                if complete {
+                    break
                 }
             }
+
+            let data    = get_bisect_data()?;
+            let max_rev = data.max_rev.clone().unwrap();
+            let (candidate_revs, _) = get_remaining_revisions(&data)?;
+            if candidate_revs.is_empty() {
+                self.finish(EXIT_FOUND, "found", &[max_rev], None)
+            } else {
+                let mut revs = vec![max_rev];
+                revs.extend(candidate_revs);
+                self.finish(EXIT_SKIPPED, "skipped", &revs, None)
+            }
+        }
+    }
+
+    //  Report the outcome of the bisect run and exit with the
+    //  corresponding status code.
+    fn finish(&self, code: i32, outcome: &str, revisions: &[String], error: Option<String>) -> Result<()> {
+        if let Some(msg) = error {
+            eprintln!("{}", msg);
+        }
+        if self.porcelain {
+            let mut line = format!("{} {}", code, outcome);
+            for rev in revisions {
+                line.push(' ');
+                line.push_str(rev);
+            }
+            println!("{}", line);
+        } else if code == EXIT_FOUND {
+            println!("{}", revisions[0]);
+        }
+
+        if code == EXIT_FOUND {
             Ok(())
+        } else {
+            Err(Exit(code).into())
         }
     }
 }
 
-
 fn display_command(name: &str) {
     let cmd: String = std::env::args().take(1).collect();
     println!("{} bisect {}", cmd, name);
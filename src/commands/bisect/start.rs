@@ -3,6 +3,8 @@ use std::fs::remove_file;
 use std::collections::HashSet;
 use clap::Parser;
 use crate::util::display_svn_datetime;
+use crate::commands::mixed;
+use std::path::Path;
 
 use super::*;
 use anyhow::Result;
@@ -35,6 +37,19 @@ pub struct Start {
     /// Specify an alternate name for the `svu bisect bad` subcommand.
     #[arg(long, value_name = "TERM", value_parser = parse_term)]
     term_bad: Option<String>,
+
+    /// Stash local modifications before starting.
+    ///
+    /// The stash is reapplied when the session ends with `svu bisect reset`.
+    /// Without this option the local modifications are left in place and
+    /// are carried along as each revision is checked out.
+    #[arg(long)]
+    autostash: bool,
+
+    //  The command line recorded in the bisect log when the
+    //  session is started by another command.
+    #[arg(skip)]
+    pub(super) cmd_line: Option<Vec<String>>,
 }
 
 impl Start {
@@ -58,6 +73,7 @@ impl Start {
                 Err(General(msg).into())
             }
             None => {
+                mixed::warn_if_mixed(Path::new(wc_info.wc_path.as_ref().unwrap()))?;
                 let good = self
                     .good_rev
                     .as_ref()
@@ -90,6 +106,19 @@ impl Start {
                     ).into());
                 }
 
+                let wc_root = Path::new(wc_info.wc_path.as_ref().unwrap());
+                let autostash = if svn::local_changes(wc_root)?.is_empty() {
+                    None
+                } else if self.autostash {
+                    crate::commands::stash::push_working_copy(Some("bisect autostash".to_string()))?
+                } else {
+                    eprintln!(
+                        "{} The working copy has local modifications.  Use --autostash to stash them for the bisect session",
+                        "warning:".yellow()
+                    );
+                    None
+                };
+
                 let (head_rev, first_rev) = get_workingcopy_bounds()?;
                 let data = BisectData {
                     original_rev: wc_info.commit_rev.clone(),
@@ -100,6 +129,7 @@ impl Start {
                     skipped:      HashSet::new(),
                     term_good:    self.term_good.clone(),
                     term_bad:     self.term_bad.clone(),
+                    autostash,
                 };
 
                 save_bisect_data(&data)?;
@@ -135,7 +165,8 @@ impl Start {
                 if data.is_ready() {
                     perform_bisect(&data)?;
                 }
-                log_bisect_command(&std::env::args().collect::<Vec<String>>())?;
+                let cmd_line = self.cmd_line.clone().unwrap_or_else(|| std::env::args().collect());
+                log_bisect_command(&cmd_line)?;
                 Ok(())
             }
         }
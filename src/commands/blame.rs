@@ -0,0 +1,122 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::*;
+use crate::svn::{self, BlameLine};
+use crate::util;
+use std::collections::HashMap;
+use std::fs;
+
+/// Annotate each line of a file with the commit that last changed it.
+///
+/// Each line shows the revision, author and date of the commit.
+/// The revision and date fade as the commit gets older and each author
+/// is given a distinct color so that related changes stand out.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Without a revision a working copy file is annotated as it is on disk\n\
+    and lines with local modifications are shown with a '-' revision."
+)]
+pub struct Blame {
+    /// Annotate the file as it was at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4 to use
+    /// the fifth most recent commit that affected the file.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Display the line number of each line.
+    #[arg(short = 'n', long)]
+    line_numbers: bool,
+
+    /// Path to working copy file or URL.
+    ///
+    /// A peg revision may be given such as path@1200.
+    #[arg(value_name = "PATH")]
+    path: String,
+}
+
+//  Colors assigned to authors in the order that they first appear.
+const AUTHOR_COLORS: [Color; 6] = [
+    Color::Cyan, Color::Green, Color::Magenta, Color::Blue, Color::BrightCyan, Color::BrightGreen,
+];
+
+impl Blame {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let path = svn::resolve_peg_path(&creds, &self.path)?;
+        let (base_path, peg) = svn::split_peg(&path);
+        let peg = peg.filter(|p| !p.is_empty());
+
+        let revision = match &self.revision {
+            Some(rev) => Some(svn::resolve_revision(&creds, rev, &path)?),
+            None      => peg.map(|p| p.to_string()),
+        };
+
+        let annotations = svn::blame(&creds, base_path, revision.as_deref())?;
+        //  svn blames the working file of a working copy path when there is
+        //  no revision, so its lines must be read from the working file.
+        let is_working = revision.is_none() && !base_path.contains("://") && !base_path.starts_with('^');
+        let content = if is_working {
+            fs::read(base_path)?
+        } else {
+            svn::cat(&creds, base_path, revision.as_deref())?
+        };
+        let text = String::from_utf8_lossy(&content);
+
+        let max_rev_len = annotations.iter()
+            .map(|a| a.revision.as_ref().map(|r| r.len()).unwrap_or(1))
+            .max()
+            .unwrap_or(1);
+        let max_author_len = annotations.iter()
+            .map(|a| a.author.as_ref().map(|r| r.len()).unwrap_or(1))
+            .max()
+            .unwrap_or(1);
+        let max_line_len = annotations.len().to_string().len();
+
+        let mut author_colors = HashMap::<String, Color>::new();
+        let now = Local::now();
+        for (annotation, line) in annotations.iter().zip(text.lines()) {
+            let BlameLine { line_number, revision, author, date } = annotation;
+            let rev_str = format!("{:>width$}", revision.as_deref().unwrap_or("-"), width = max_rev_len);
+            let author_str = format!("{:width$}", author.as_deref().unwrap_or("-"), width = max_author_len);
+            let date_str = date.as_ref().map(util::display_svn_date).unwrap_or("-".repeat(10));
+
+            let author_color = match author {
+                Some(author) => {
+                    let next = AUTHOR_COLORS[author_colors.len() % AUTHOR_COLORS.len()];
+                    *author_colors.entry(author.clone()).or_insert(next)
+                }
+                None => Color::White,
+            };
+
+            if self.line_numbers {
+                print!("{:>width$} ", line_number, width = max_line_len);
+            }
+            println!(
+                "{} {} {} {}",
+                faded(rev_str, date.as_ref(), &now),
+                author_str.color(author_color),
+                faded(date_str, date.as_ref(), &now),
+                line
+            );
+        }
+        Ok(())
+    }
+}
+
+//  Color text according to the age of the commit.
+//  Recent commits are bright and older commits fade to gray.
+fn faded(text: String, date: Option<&DateTime<Local>>, now: &DateTime<Local>) -> ColoredString {
+    match date.map(|d| now.signed_duration_since(*d).num_days()) {
+        Some(days) if days < 7   => text.bright_yellow(),
+        Some(days) if days < 30  => text.yellow(),
+        Some(days) if days < 365 => text.white(),
+        Some(_)                  => text.bright_black(),
+        None                     => text.normal(),
+    }
+}
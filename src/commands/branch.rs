@@ -3,13 +3,22 @@ use std::path::Path;
 use std::env::current_dir;
 use regex::Regex;
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crate::auth::Credentials;
 use crate::util;
 use crate::util::SvError::*;
 use crate::svn;
 use colored::*;
 use std::fmt::Display;
+use std::fs::File;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+mod create;
+mod delete;
+mod policy;
+mod rename;
+mod stale;
 
 /// Display current branch or list branches and tags.
 ///
@@ -25,7 +34,12 @@ use std::fmt::Display;
     author,
     help_template = crate::app::HELP_TEMPLATE,
 )]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = false)]
 pub struct Branch {
+    #[command(subcommand)]
+    command: Option<BranchCommands>,
+
     /// Display branches that match <REGEX>.
     ///
     /// If multiple --branch options are given, then branches matching any
@@ -51,9 +65,17 @@ pub struct Branch {
     /// Path to working copy directory
     #[arg(default_value = ".")]
     path: String,
+}
 
-
+#[derive(Debug, Subcommand)]
+enum BranchCommands {
+    Create(create::Create),
+    Delete(delete::Delete),
+    Policy(policy::Policy),
+    Rename(rename::Rename),
+    Stale(stale::Stale),
 }
+use BranchCommands::*;
 
 impl Branch {
     fn no_arguments(&self) -> bool {
@@ -72,6 +94,15 @@ impl Branch {
     }
 
     pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Some(Create(cmd)) => return cmd.run(),
+            Some(Delete(cmd)) => return cmd.run(),
+            Some(Policy(cmd)) => return cmd.run(),
+            Some(Rename(cmd)) => return cmd.run(),
+            Some(Stale(cmd))  => return cmd.run(),
+            None              => (),
+        }
+
         if self.no_arguments() {
             self.show_current_branch()
         } else {
@@ -172,4 +203,167 @@ impl Branch {
 }
 
 
+// Common structures and functions used by the branch subcommands.
+
+//  The naming policy for new branches.
+//  The pattern is a regular expression that branch names must match.
+//  The template is used to build a name that follows the policy.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct BranchPolicy {
+    pattern: Option<String>,
+    template: Option<String>,
+}
+
+impl BranchPolicy {
+    fn is_empty(&self) -> bool {
+        self.pattern.is_none() && self.template.is_none()
+    }
+
+    fn pattern_regex(&self) -> Result<Option<Regex>> {
+        match &self.pattern {
+            Some(pattern) => Ok(Some(Regex::new(&format!("^(?:{})$", pattern))?)),
+            None          => Ok(None),
+        }
+    }
+
+    //  Return true if the name satisfies the policy.
+    //  When there is no pattern any name is accepted.
+    fn accepts(&self, name: &str) -> Result<bool> {
+        Ok(self.pattern_regex()?.map(|re| re.is_match(name)).unwrap_or(true))
+    }
+}
+
+//  Values used to fill in the placeholders of a branch name template.
+struct TemplateValues {
+    user: String,
+    ticket: Option<String>,
+    slug: Option<String>,
+}
+
+//  Expand a branch name template such as `feature/{user}/{ticket}-{slug}`.
+//  Returns an error naming the first placeholder that has no value.
+fn expand_template(template: &str, values: &TemplateValues) -> Result<String> {
+    let re = Regex::new(r"\{([a-z]+)\}")?;
+    let mut name = String::new();
+    let mut last = 0;
+    for caps in re.captures_iter(template) {
+        let whole = caps.get(0).unwrap();
+        let value = match &caps[1] {
+            "user"   => Some(values.user.clone()),
+            "ticket" => values.ticket.clone(),
+            "slug"   => values.slug.clone(),
+            "date"   => Some(chrono::Local::now().format("%Y%m%d").to_string()),
+            other    => {
+                let msg = format!("Unknown placeholder '{{{}}}' in branch name template", other);
+                return Err(General(msg).into());
+            }
+        };
+        match value {
+            Some(value) => {
+                name.push_str(&template[last..whole.start()]);
+                name.push_str(&value);
+                last = whole.end();
+            }
+            None => {
+                let msg = format!("A value for '{{{}}}' is required by the branch name template '{}'", &caps[1], template);
+                return Err(General(msg).into());
+            }
+        }
+    }
+    name.push_str(&template[last..]);
+    Ok(name)
+}
+
+//  Convert text into a form suitable for use in a branch name.
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for ch in text.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+//  The user name used for the {user} placeholder.
+fn current_user(creds: &Option<Credentials>, root_url: &str) -> String {
+    crate::auth::current_username(creds, root_url).unwrap_or("unknown".to_string())
+}
+
+fn branch_policy_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("branch_policy.json"))
+}
+
+fn load_branch_policy() -> Result<BranchPolicy> {
+    let path = branch_policy_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        let policy: BranchPolicy = serde_json::from_reader(reader)?;
+        Ok(policy)
+    } else {
+        Ok(BranchPolicy::default())
+    }
+}
+
+//  Used by `svu doctor` to validate the data file.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(branch_policy_file()?, load_branch_policy().map(|_| ()))])
+}
+
+fn save_branch_policy(policy: &BranchPolicy) -> Result<()> {
+    util::save_json(&branch_policy_file()?, policy)
+}
+
+//  Resolve a branch name to its location relative to the repository root.
+//  The name may include one of the branch prefixes or it may be the
+//  name of a branch under any of the branch prefixes.
+fn branch_location(creds: &Option<Credentials>, root_url: &str, name: &str) -> Result<String> {
+    let prefixes = svn::load_prefixes()?;
+    let name = name.trim_start_matches("^/").trim_matches('/');
+    if prefixes.branch_prefixes.iter().any(|p| name.starts_with(&format!("{}/", p))) {
+        return Ok(name.to_string());
+    }
+    for prefix in &prefixes.branch_prefixes {
+        let location = util::join_paths(prefix, name);
+        if svn::path_exists(creds, &util::join_paths(root_url, &location))? {
+            return Ok(location);
+        }
+    }
+    Err(General(format!("Cannot find a branch named {}", name)).into())
+}
 
+//  Determine the source of a copy for a new branch or tag.
+//  `from` may be a branch or tag name, a revision of the current branch
+//  or a branch at a specific revision such as trunk@1200.
+//  Returns the source for display and its URL including any peg revision.
+pub(crate) fn copy_source(creds: &Option<Credentials>, wc_info: &svn::SvnInfo, from: Option<&str>) -> Result<(String, String)> {
+    let (location, revision) = match from {
+        None => (wc_info.rel_url.trim_start_matches("^/").to_string(), None),
+        Some(from) if svn::looks_like_revision(from) => {
+            let rev = svn::resolve_revision(creds, from, &wc_info.url)?;
+            (wc_info.rel_url.trim_start_matches("^/").to_string(), Some(rev))
+        }
+        Some(from) => {
+            let prefixes = svn::load_prefixes()?;
+            let (branch, peg) = svn::split_peg(from);
+            let location = svn::resolve_location(creds, &wc_info.root_url, branch, &prefixes)?;
+            let url = util::join_paths(&wc_info.root_url, &location);
+            let rev = match peg.filter(|p| !p.is_empty()) {
+                Some(peg) => Some(svn::resolve_revision(creds, peg, &url)?),
+                None      => None,
+            };
+            (location, rev)
+        }
+    };
+
+    let url = util::join_paths(&wc_info.root_url, &location);
+    if !svn::path_exists(creds, &svn::peg_path(&url, revision.as_deref()))? {
+        return Err(General(format!("^/{} does not exist", location)).into());
+    }
+    match revision {
+        Some(rev) => Ok((format!("^/{}@{}", location, rev), svn::peg_path(&url, Some(&rev)))),
+        None      => Ok((format!("^/{}", location), url)),
+    }
+}
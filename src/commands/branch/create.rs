@@ -0,0 +1,108 @@
+
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Create a new branch from the current branch.
+///
+/// The new branch is created in the repository by copying the branch
+/// that is checked out to your working copy, or the branch given with
+/// --from.  It is placed under the first branch prefix (see the `prefix`
+/// command).
+///
+/// If a branch naming policy has been configured (see `svu branch policy`)
+/// the name must match its pattern.  When NAME does not match and the
+/// policy has a template, NAME is used as the {slug} and the full branch
+/// name is built from the template.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Create {
+    /// Name of the new branch relative to the branch prefix.
+    #[arg(value_name = "NAME")]
+    name: Option<String>,
+
+    /// Value of the {ticket} placeholder in the naming template.
+    #[arg(long, value_name = "TICKET")]
+    ticket: Option<String>,
+
+    /// The branch, tag or revision to create the branch from.
+    ///
+    /// This may be the name of a branch or tag, a revision of the current
+    /// branch such as HEAD-3, or a branch at a specific revision such as
+    /// trunk@1200.  Defaults to the current branch at HEAD.
+    #[arg(long, value_name = "BRANCH|REV")]
+    from: Option<String>,
+
+    /// Commit message used when creating the branch.
+    #[arg(short, long, value_name = "MSG")]
+    message: Option<String>,
+
+    /// Create the branch even if its name does not follow the naming policy.
+    #[arg(short, long)]
+    force: bool,
+
+    /// Show the branch that would be created but do not create it.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Create {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let policy = load_branch_policy()?;
+        let name = self.branch_name(&policy, &creds, &wc_info.root_url)?;
+
+        if !self.force && !policy.accepts(&name)? {
+            let msg = format!(
+                "Branch name '{}' does not match the naming policy '{}'\n\
+                 Use --force to create it anyway",
+                name,
+                policy.pattern.as_deref().unwrap_or("")
+            );
+            return Err(General(msg).into());
+        }
+
+        let prefixes = svn::load_prefixes()?;
+        let prefix = prefixes.branch_prefixes.first().map(|p| p.as_str()).unwrap_or("branches");
+        let (source, source_url) = copy_source(&creds, &wc_info, self.from.as_deref())?;
+        let target_url = util::join_paths(util::join_paths(&wc_info.root_url, prefix), &name);
+        let target = format!("^/{}/{}", prefix, name);
+
+        if svn::path_exists(&creds, &target_url)? {
+            return Err(General(format!("{} already exists", target)).into());
+        }
+
+        let message = self.message.clone().unwrap_or(format!("Create branch {} from {}", target, source));
+        if self.dry_run {
+            println!("Would create branch {} from {}", target.green(), source.green());
+        } else {
+            svn::copy(&creds, &source_url, &target_url, &message)?;
+            println!("Created branch {} from {}", target.green(), source.green());
+        }
+        Ok(())
+    }
+
+    //  Determine the name of the new branch.
+    //  If the given name does not follow the policy we try to build
+    //  one from the policy template.
+    fn branch_name(&self, policy: &BranchPolicy, creds: &Option<Credentials>, root_url: &str) -> Result<String> {
+        match (&self.name, &policy.template) {
+            (Some(name), _) if self.force || policy.accepts(name)? => Ok(name.clone()),
+            (name, Some(template)) => {
+                let values = TemplateValues {
+                    user:   current_user(creds, root_url),
+                    ticket: self.ticket.clone(),
+                    slug:   name.as_ref().map(|n| slugify(n)),
+                };
+                expand_template(template, &values)
+            }
+            (Some(name), None) => Ok(name.clone()),
+            (None, None) => Err(General("A branch name is required".to_string()).into()),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::impact::{self, Impact};
+
+/// Delete a branch from the repository.
+///
+/// Before the branch is deleted a summary of the commits made on the
+/// branch since it was created is displayed.  If the number of commits
+/// or affected paths exceeds the impact thresholds you must confirm the
+/// deletion or use --yes.
+///
+/// Commits on the branch that have not been merged to the trunk are
+/// listed and you must confirm the deletion or use --force.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The impact thresholds are set with the impact.revisions (default 5)\n\
+    and impact.paths (default 50) settings.  See `svu config`."
+)]
+pub struct Delete {
+    /// Name of the branch to delete.
+    ///
+    /// This may include the branch prefix, eg. branches/my-branch
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// Commit message used when deleting the branch.
+    #[arg(short, long, value_name = "MSG")]
+    message: Option<String>,
+
+    /// Do not ask for confirmation when the impact thresholds are exceeded.
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Delete the branch even if it has commits not merged to the trunk.
+    #[arg(short, long)]
+    force: bool,
+
+    /// Show the impact of deleting the branch but do not delete it.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Delete {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let location = branch_location(&creds, &wc_info.root_url, &self.name)?;
+        let branch = format!("^/{}", location);
+        let branch_url = util::join_paths(&wc_info.root_url, &location);
+
+        if wc_info.rel_url == branch || wc_info.rel_url.starts_with(&format!("{}/", branch)) {
+            let msg = format!("Cannot delete {} because it is checked out to the working copy", branch);
+            return Err(General(msg).into());
+        }
+
+        //  The commits made on the branch since it was created
+        //  not including the commit that created the branch.
+        let mut entries = svn::log(&creds, &[branch_url.as_str()], &[], false, None, true, true)?;
+        let branch_path = format!("/{}", location);
+        if entries.last().is_some_and(|e| e.paths.iter().any(|p| p.path == branch_path && p.from_path.is_some())) {
+            entries.pop();
+        }
+        let impact = Impact::from_log(&entries);
+        println!("Deleting branch {}", branch.green());
+        impact.show("discarded");
+
+        let unmerged = self.unmerged_commits(&creds, &wc_info.root_url, &branch_url)?;
+        if !unmerged.is_empty() {
+            println!();
+            println!("{}", format!("{} has commits that are not merged to the trunk:", branch).yellow());
+            for entry in &unmerged {
+                println!("{} {} {}", entry.revision.yellow(), entry.author.cyan(), entry.msg_1st());
+            }
+        }
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        //  The user is asked once even when both the unmerged
+        //  commits and the impact call for confirmation.
+        let confirm_unmerged = !unmerged.is_empty() && !self.force;
+        let proceed = if confirm_unmerged {
+            impact::needs_confirmation(&impact, self.yes)?;
+            util::confirm("Delete the branch and its unmerged commits?")?
+        } else {
+            impact::confirm(&impact, self.yes, &format!("Delete {}?", branch))?
+        };
+
+        if proceed {
+            let message = self.message.clone().unwrap_or(format!("Delete branch {}", branch));
+            svn::delete_url(&creds, &branch_url, &message)?;
+            println!("Deleted branch {}", branch.green());
+        }
+        Ok(())
+    }
+
+    //  Return the commits on the branch that are eligible to be merged
+    //  to the trunk.
+    fn unmerged_commits(&self, creds: &Option<Credentials>, root_url: &str, branch_url: &str) -> Result<Vec<svn::LogEntry>> {
+        let prefixes = svn::load_prefixes()?;
+        let trunk_url = util::join_paths(root_url, &prefixes.trunk_prefix);
+        let revisions = svn::mergeinfo_eligible(creds, branch_url, &trunk_url)?;
+        if revisions.is_empty() {
+            Ok(Vec::new())
+        } else {
+            svn::log(creds, &[branch_url.to_string()], &revisions, true, None, false, false)
+        }
+    }
+}
@@ -0,0 +1,61 @@
+
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Display or configure the naming policy for new branches.
+///
+/// The policy consists of a regular expression that new branch names must
+/// match and a template used to build names that follow the policy.
+/// The template may contain the placeholders {user}, {ticket}, {slug} and {date}.
+/// The policy is enforced by `svu branch create`.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    For example:\n\
+    svu branch policy --pattern 'feature/[a-z]+/[A-Z]+-\\d+-[-a-z0-9]+' --template 'feature/{user}/{ticket}-{slug}'"
+)]
+pub struct Policy {
+    /// Regular expression that new branch names must match.
+    #[arg(short, long, value_name = "REGEX")]
+    pattern: Option<String>,
+
+    /// Template used to build new branch names.
+    #[arg(short, long, value_name = "TEMPLATE")]
+    template: Option<String>,
+
+    /// Remove the naming policy.
+    #[arg(long, conflicts_with_all = ["pattern", "template"])]
+    clear: bool,
+}
+
+impl Policy {
+    pub fn run(&mut self) -> Result<()> {
+        svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let mut policy = load_branch_policy()?;
+
+        if self.clear {
+            policy = BranchPolicy::default();
+            save_branch_policy(&policy)?;
+        } else if self.pattern.is_some() || self.template.is_some() {
+            if let Some(pattern) = &self.pattern {
+                policy.pattern = Some(pattern.clone());
+                policy.pattern_regex()?;  // Make sure it is valid
+            }
+            if let Some(template) = &self.template {
+                policy.template = Some(template.clone());
+            }
+            save_branch_policy(&policy)?;
+        }
+
+        if policy.is_empty() {
+            println!("No branch naming policy has been configured");
+        } else {
+            println!("Pattern : {}", policy.pattern.as_deref().unwrap_or("").green());
+            println!("Template: {}", policy.template.as_deref().unwrap_or("").green());
+        }
+        Ok(())
+    }
+}
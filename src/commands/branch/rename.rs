@@ -0,0 +1,98 @@
+
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::commands::{notify, stash};
+
+/// Rename a branch in the repository.
+///
+/// The branch is moved on the server so its history is preserved.
+/// If the working copy has the branch checked out it is switched to
+/// the new location.  Stash entries and notify settings that refer to
+/// the old branch are updated to refer to the new one.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    If NEW does not include a branch prefix, the branch keeps the prefix\n\
+    of OLD.  For example 'svu branch rename branches/foo bar' renames\n\
+    ^/branches/foo to ^/branches/bar."
+)]
+pub struct Rename {
+    /// Name of the branch to rename.
+    ///
+    /// This may include the branch prefix, eg. branches/my-branch
+    #[arg(value_name = "OLD")]
+    old: String,
+
+    /// New name of the branch.
+    #[arg(value_name = "NEW")]
+    new: String,
+
+    /// Commit message used when renaming the branch.
+    #[arg(short, long, value_name = "MSG")]
+    message: Option<String>,
+
+    /// Do not switch the working copy to the renamed branch.
+    #[arg(long)]
+    no_switch: bool,
+
+    /// Show what would be renamed but do not rename it.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Rename {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.as_ref().unwrap());
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let prefixes = svn::load_prefixes()?;
+
+        let old_location = branch_location(&creds, &wc_info.root_url, &self.old)?;
+        let new_name = self.new.trim_start_matches("^/").trim_matches('/');
+        let new_location = if prefixes.branch_prefixes.iter().any(|p| new_name.starts_with(&format!("{}/", p))) {
+            new_name.to_string()
+        } else {
+            let parent = old_location.rsplit_once('/').map(|(p, _)| p).unwrap_or("");
+            util::join_paths(parent, new_name)
+        };
+        let old_branch = format!("^/{}", old_location);
+        let new_branch = format!("^/{}", new_location);
+        let old_url = util::join_paths(&wc_info.root_url, &old_location);
+        let new_url = util::join_paths(&wc_info.root_url, &new_location);
+
+        if svn::path_exists(&creds, &new_url)? {
+            return Err(General(format!("{} already exists", new_branch)).into());
+        }
+        let checked_out = wc_info.rel_url == old_branch || wc_info.rel_url.starts_with(&format!("{}/", old_branch));
+
+        println!("Renaming {} to {}", old_branch.green(), new_branch.green());
+        if self.dry_run {
+            if checked_out && !self.no_switch {
+                println!("The working copy would be switched to {}", new_branch.green());
+            }
+            return Ok(());
+        }
+
+        let message = self.message.clone().unwrap_or(format!("Rename branch {} to {}", old_branch, new_branch));
+        svn::move_url(&creds, &old_url, &new_url, &message)?;
+
+        if checked_out && !self.no_switch {
+            let switch_url = format!("{}{}", new_url, &wc_info.rel_url[old_branch.len()..]);
+            svn::switch(&creds, &switch_url, None, Some(&wc_root))?;
+            println!("Switched the working copy to {}", new_branch.green());
+        }
+
+        let stashes = stash::rename_branch(&old_branch, &new_branch)?;
+        if stashes > 0 {
+            println!("Updated {} stash {}", stashes, if stashes == 1 { "entry" } else { "entries" });
+        }
+        if notify::rename_branch(&old_location, &new_location)? {
+            println!("Updated the notify settings");
+        }
+        Ok(())
+    }
+}
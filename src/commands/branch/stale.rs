@@ -0,0 +1,117 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+
+/// List branches that have not been changed recently.
+///
+/// Each branch whose last commit is older than the threshold is shown
+/// with the revision, author and date of that commit and whether all of
+/// its changes have been merged to trunk.  Use this to find dead
+/// branches that can be deleted.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A branch is considered merged when `svn mergeinfo` reports no revisions\n\
+    on the branch that are eligible to be merged to trunk."
+)]
+pub struct Stale {
+    /// List branches with no commits more recent than AGE.
+    ///
+    /// A number followed by d, w, m or y (eg. 90d, 12w, 6m, 1y).
+    #[arg(long, value_name = "AGE", value_parser = util::parse_age, default_value = "6months")]
+    than: Duration,
+
+    /// Do not check whether each branch has been merged to trunk.
+    #[arg(long)]
+    no_merge_check: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+struct StaleBranch {
+    path: String,
+    revision: String,
+    author: String,
+    date: DateTime<Local>,
+    merged: Option<bool>,
+}
+
+impl Stale {
+    pub fn run(&mut self) -> Result<()> {
+        use rayon::prelude::*;
+
+        let creds = crate::auth::get_credentials()?;
+        let root_url = svn::info(&creds, &self.path, None)?.root_url;
+        let prefixes = svn::load_prefixes()?;
+        let mut all_prefixes = prefixes.branch_prefixes.clone();
+        all_prefixes.extend(prefixes.tag_prefixes.clone());
+        let cutoff = Local::now()
+            .checked_sub_signed(self.than)
+            .ok_or_else(|| General(format!("--than {} days is too large", self.than.num_days())))?;
+
+        //  The list of each prefix includes the last commit made within each branch.
+        let mut branches = Vec::<StaleBranch>::new();
+        let mut sorted_prefixes = prefixes.branch_prefixes.clone();
+        sorted_prefixes.sort();
+        for prefix in &sorted_prefixes {
+            let path_list = svn::path_list(&creds, &util::join_paths(&root_url, prefix))?;
+            for entry in path_list.entries {
+                let path = util::join_paths(prefix, &entry.name);
+                if entry.kind == "dir" && !all_prefixes.contains(&path) && entry.commit_date < cutoff {
+                    branches.push(StaleBranch {
+                        path,
+                        revision: entry.commit_rev,
+                        author: entry.commit_author,
+                        date: entry.commit_date,
+                        merged: None,
+                    });
+                }
+            }
+        }
+        if branches.is_empty() {
+            println!("No branches have been idle for longer than {} days", self.than.num_days());
+            return Ok(());
+        }
+
+        if !self.no_merge_check {
+            let trunk_url = util::join_paths(&root_url, &prefixes.trunk_prefix);
+            branches.par_iter_mut().for_each(|branch| {
+                let branch_url = util::join_paths(&root_url, &branch.path);
+                branch.merged = svn::mergeinfo_eligible(&creds, &branch_url, &trunk_url)
+                    .ok()
+                    .map(|eligible| eligible.is_empty());
+            });
+        }
+
+        branches.sort_by_key(|b| b.date);
+        let path_width = branches.iter().map(|b| b.path.len() + 2).max().unwrap_or(0);
+        let rev_width = branches.iter().map(|b| b.revision.len()).max().unwrap_or(0);
+        let author_width = branches.iter().map(|b| b.author.len()).max().unwrap_or(0);
+        for branch in &branches {
+            let merged = match branch.merged {
+                Some(true)  => "merged".green(),
+                Some(false) => "not merged".red(),
+                None if self.no_merge_check => "".normal(),
+                None        => "unknown".yellow(),
+            };
+            println!(
+                "{:path_width$} {:>rev_width$} {:author_width$} {} {:>10} {}",
+                format!("^/{}", branch.path).green(),
+                branch.revision.yellow(),
+                branch.author.cyan(),
+                util::display_svn_date(&branch.date).magenta(),
+                util::display_age(&branch.date),
+                merged,
+                path_width = path_width,
+                rev_width = rev_width,
+                author_width = author_width
+            );
+        }
+        Ok(())
+    }
+}
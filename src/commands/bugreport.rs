@@ -0,0 +1,142 @@
+use anyhow::Result;
+use chrono::Local;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::{self, SvError::*, scrub_secrets};
+use std::env;
+use std::fs::{self, File, create_dir_all};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Create a bug report bundle for the last failed command.
+///
+/// The bundle contains the command line and output of the last svn command
+/// that failed, the versions of svu and svn, and the svu state files from
+/// the working copy's .svu directory.  Passwords and credentials embedded
+/// in URLs are removed.  Stash patch files are not included as they may
+/// contain proprietary source code.
+///
+/// The bundle is written as a compressed tarball if the `tar` command is
+/// available, otherwise it is left as a directory.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Bugreport {
+    /// Directory where the bundle is written.  It is created if necessary.
+    #[arg(short, long, value_name = "DIR", default_value = ".")]
+    output: PathBuf,
+}
+
+//  State files in the .svu directory that are included in the bundle.
+const STATE_FILES: [&str; 6] = [
+    "prefixes.json",
+    "bisect_data.json",
+    "bisect_log",
+    "pins.toml",
+    "pins.json",
+    "stash/stash_entries.json",
+];
+
+impl Bugreport {
+    pub fn run(&mut self) -> Result<()> {
+        //  The output directory is created if it does not exist.
+        create_dir_all(&self.output)?;
+        let output_dir = self.output.canonicalize()?;
+        let name = format!("svu-bugreport-{}", Local::now().format("%Y%m%d-%H%M%S"));
+        let bundle_dir = env::temp_dir().join(&name);
+        create_dir_all(&bundle_dir)?;
+
+        match svn::load_last_failure()? {
+            Some(failure) => {
+                let mut writer = File::create(bundle_dir.join("command.txt"))?;
+                writeln!(writer, "date       : {}", util::display_svn_datetime(&failure.date))?;
+                writeln!(writer, "command    : {}", failure.command.join(" "))?;
+                writeln!(writer, "cwd        : {}", failure.cwd)?;
+                writeln!(writer, "exit status: {}", failure.exit_status.map(|c| c.to_string()).unwrap_or("n/a".to_string()))?;
+                fs::write(bundle_dir.join("stdout.txt"), &failure.stdout)?;
+                fs::write(bundle_dir.join("stderr.txt"), &failure.stderr)?;
+            }
+            None => println!("{}", "No failed svn command has been recorded.".yellow()),
+        }
+
+        write_versions(&bundle_dir.join("versions.txt"))?;
+        copy_state_files(&bundle_dir)?;
+
+        let tarball = output_dir.join(format!("{}.tar.gz", name));
+        let tar = Command::new("tar")
+            .arg("-czf")
+            .arg(&tarball)
+            .arg("-C")
+            .arg(env::temp_dir())
+            .arg(&name)
+            .output();
+
+        match tar {
+            Ok(output) if output.status.success() => {
+                fs::remove_dir_all(&bundle_dir)?;
+                println!("Bug report written to {}", tarball.to_string_lossy().blue());
+            }
+            _ => {
+                let dest = output_dir.join(&name);
+                copy_dir(&bundle_dir, &dest)?;
+                fs::remove_dir_all(&bundle_dir)?;
+                println!("Could not create tarball, bug report written to {}", dest.to_string_lossy().blue());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_versions(path: &Path) -> Result<()> {
+    let mut writer = File::create(path)?;
+    writeln!(writer, "svu: {}", env!("CARGO_PKG_VERSION"))?;
+    let svn_version = svn::SvnCmd::new("--version")
+        .arg("--quiet")
+        .run()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or("n/a".to_string());
+    writeln!(writer, "svn: {}", svn_version)?;
+    writeln!(writer, "os : {} {}", env::consts::OS, env::consts::ARCH)?;
+    Ok(())
+}
+
+//  Copy the svu state files if we are in a working copy.
+fn copy_state_files(bundle_dir: &Path) -> Result<()> {
+    if svn::workingcopy_info().is_ok() {
+        let data_dir = util::data_directory()?;
+        let state_dir = bundle_dir.join("state");
+        for name in STATE_FILES {
+            let path = data_dir.join(name);
+            if path.is_file() {
+                let dest = state_dir.join(name);
+                if let Some(parent) = dest.parent() {
+                    create_dir_all(parent)?;
+                }
+                fs::write(dest, scrub_secrets(&fs::read_to_string(path)?))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        let msg = format!("{} already exists", to.to_string_lossy());
+        return Err(General(msg).into());
+    }
+    create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
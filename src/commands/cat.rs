@@ -0,0 +1,78 @@
+use anyhow::Result;
+use clap::Parser;
+use crate::highlight::Highlighter;
+use crate::svn;
+use std::io::{IsTerminal, Write};
+
+/// Display the contents of a file at a revision.
+///
+/// The syntax of common programming languages is highlighted
+/// when the output is a terminal.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The revision defaults to the working copy BASE revision of the file.\n\
+    Binary files are written without line numbers or highlighting."
+)]
+pub struct Cat {
+    /// Display the file as it was at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4 to use
+    /// the fifth most recent commit that affected the file.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Display the line number of each line.
+    #[arg(short = 'n', long)]
+    line_numbers: bool,
+
+    /// Do not highlight the syntax of the file.
+    #[arg(long)]
+    no_highlight: bool,
+
+    /// Path to working copy file or URL.
+    ///
+    /// A peg revision may be given such as path@1200.
+    #[arg(value_name = "PATH")]
+    path: String,
+}
+
+impl Cat {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let path = svn::resolve_peg_path(&creds, &self.path)?;
+        let (base_path, peg) = svn::split_peg(&path);
+        let peg = peg.filter(|p| !p.is_empty());
+
+        let revision = match &self.revision {
+            Some(rev) => Some(svn::resolve_revision(&creds, rev, &path)?),
+            None      => peg.map(|p| p.to_string()),
+        };
+        let content = svn::cat(&creds, base_path, revision.as_deref())?;
+
+        if content.contains(&0) {
+            std::io::stdout().write_all(&content)?;
+            return Ok(());
+        }
+        let mut highlighter = if self.no_highlight || !std::io::stdout().is_terminal() {
+            None
+        } else {
+            Highlighter::for_path(base_path)
+        };
+        let text = String::from_utf8_lossy(&content);
+        let width = text.lines().count().to_string().len();
+        for (index, line) in text.lines().enumerate() {
+            if self.line_numbers {
+                print!("{:>width$} ", index + 1, width = width);
+            }
+            match highlighter.as_mut() {
+                Some(highlighter) => println!("{}", highlighter.line(line)),
+                None => println!("{}", line),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::commands::conflicts;
+use crate::svn::{self, LogEntry};
+use crate::util::{join_paths, SvError::*};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+
+/// Merge individual revisions from one branch to another.
+///
+/// The changes of each revision are merged with `svn merge --change`
+/// into a working copy of the target branch, oldest first.  Nothing is
+/// committed, so you can review the result and resolve any conflicts
+/// before committing.  A backport commit message referencing the
+/// original revisions is displayed when the merge completes.
+///
+/// If the current working copy is not on the target branch, the target
+/// branch is checked out to a separate directory.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A BRANCH can be the trunk prefix, a full location such as branches/8.1\n\
+    or just the name of a branch.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes.\n\n\
+    If --from is omitted the revisions are taken from the branch checked\n\
+    out to the current working copy.\n\n\
+    The default checkout directory is svu-cherry-<BRANCH> in the system\n\
+    temporary directory.  If it already contains a working copy of the\n\
+    target branch it is updated and reused."
+)]
+pub struct Cherry {
+    /// The revisions to merge.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(value_name = "REV", num_args = 1.., required = true)]
+    revisions: Vec<String>,
+
+    /// The branch to merge the revisions to.
+    #[arg(short, long, value_name = "BRANCH")]
+    to: String,
+
+    /// The branch where the revisions were committed.
+    #[arg(short, long, value_name = "BRANCH")]
+    from: Option<String>,
+
+    /// Directory used to check out the target branch.
+    #[arg(short, long, value_name = "DIR")]
+    checkout: Option<PathBuf>,
+
+    /// Show what would be merged but do not change the working copy.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Cherry {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.as_ref().unwrap());
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let prefixes = svn::load_prefixes()?;
+
+        let source = match &self.from {
+            Some(branch) => svn::resolve_location(&creds, &wc_info.root_url, branch, &prefixes)?,
+            None         => svn::containing_location(&wc_info.rel_url, &prefixes)?,
+        };
+        let target = svn::resolve_location(&creds, &wc_info.root_url, &self.to, &prefixes)?;
+        if source == target {
+            return Err(General(format!("The revisions are already on ^/{}", target)).into());
+        }
+        let source_url = join_paths(&wc_info.root_url, &source);
+        let target_url = join_paths(&wc_info.root_url, &target);
+
+        //  Collect the commits keyed by revision number
+        //  so that duplicates are removed and they are merged in order.
+        let mut commits = BTreeMap::<u64, LogEntry>::new();
+        for rev in &self.revisions {
+            let revision = svn::resolve_revision(&creds, rev, &source_url)?;
+            let range = format!("{}:{}", revision, revision);
+            match svn::log(&creds, &[source_url.as_str()], &[range.as_str()], true, None, false, false)?.pop() {
+                Some(entry) => { commits.insert(entry.revision.parse::<u64>()?, entry); }
+                None => {
+                    let msg = format!("Revision {} did not change ^/{}", revision, source);
+                    return Err(General(msg).into());
+                }
+            }
+        }
+
+        let target_wc = if wc_info.rel_url == format!("^/{}", target) {
+            wc_root
+        } else {
+            self.prepare_checkout(&creds, &target, &target_url)?
+        };
+
+        for (revision, entry) in &commits {
+            println!("Merging {} {} {}", entry.revision.yellow(), entry.author.cyan(), entry.msg_1st());
+            let output = svn::cherry_pick(&creds, &source_url, &revision.to_string(), self.dry_run, Some(&target_wc))?;
+            print!("{}", String::from_utf8_lossy(&output));
+        }
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let conflicted = conflicts::conflicted_entries(&target_wc)?;
+        if !conflicted.is_empty() {
+            println!();
+            println!("{}", "Conflicts:".red());
+            for entry in &conflicted {
+                println!("  {} ({})", entry.path.red(), conflicts::conflict_kind(entry));
+            }
+        }
+
+        let revs = commits.keys().map(|r| format!("r{}", r)).collect::<Vec<String>>().join(", ");
+        println!();
+        println!("Working copy of ^/{}: {}", target, target_wc.to_string_lossy().blue());
+        println!("Suggested commit message:");
+        println!("{}", "-".repeat(70));
+        println!("Backport {} from ^/{}", revs, source);
+        println!();
+        for entry in commits.values() {
+            println!("r{} {}: {}", entry.revision, entry.author, entry.msg_1st());
+        }
+        println!("{}", "-".repeat(70));
+        Ok(())
+    }
+
+    //  Check out the target branch or update an existing checkout.
+    fn prepare_checkout(&self, creds: &Option<Credentials>, target: &str, target_url: &str) -> Result<PathBuf> {
+        let dir = match &self.checkout {
+            Some(dir) => dir.clone(),
+            None      => env::temp_dir().join(format!("svu-cherry-{}", target.replace('/', "-"))),
+        };
+
+        if dir.exists() {
+            let info = svn::info(creds, &dir.to_string_lossy(), None)
+                .map_err(|_| General(format!("{} exists and is not a working copy", dir.to_string_lossy())))?;
+            if info.url != target_url {
+                let msg = format!("{} is a working copy of {}, not ^/{}", dir.to_string_lossy(), info.rel_url, target);
+                return Err(General(msg).into());
+            }
+            if !svn::local_changes(&dir)?.is_empty() {
+                let msg = format!("{} has local changes", dir.to_string_lossy());
+                return Err(General(msg).into());
+            }
+            println!("Updating {}", dir.to_string_lossy().blue());
+            svn::update("HEAD", "infinity", Some(&dir))?;
+        } else {
+            println!("Checking out ^/{} to {}", target, dir.to_string_lossy().blue());
+            svn::checkout(creds, target_url, &dir)?;
+        }
+        Ok(dir)
+    }
+}
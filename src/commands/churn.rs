@@ -0,0 +1,154 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use serde::Serialize;
+use crate::svn;
+use std::collections::{BTreeMap, HashMap};
+
+/// Report the files, directories and months with the most changes.
+///
+/// The log of the path is read with the paths changed by each commit.
+/// Only the changes to files beneath the path are counted.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The changes per directory count the changes to the files directly\n\
+    within each directory.  The commits per month include every commit\n\
+    in the log of the path."
+)]
+pub struct Churn {
+    /// The revision or range of revisions to examine.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    /// A range is specified by separating two revisions with a colon eg: HEAD-100:HEAD
+    #[arg(short, long, value_name = "REV[:REV]")]
+    revision: Option<String>,
+
+    /// Number of files and directories to report.
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+    top: usize,
+
+    /// Write the statistics as JSON.
+    #[arg(long)]
+    json: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PathCount {
+    path: String,
+    changes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct MonthCount {
+    month: String,
+    commits: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Statistics {
+    commits: usize,
+    files: Vec<PathCount>,
+    directories: Vec<PathCount>,
+    months: Vec<MonthCount>,
+}
+
+impl Churn {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revisions = match &self.revision {
+            Some(rev) => vec![svn::resolve_revision_range(&creds, rev, &self.path)?],
+            None      => vec![],
+        };
+        //  The log paths are relative to the repository root.
+        let info = svn::info(&creds, &self.path, None)?;
+        let base = info.rel_url.trim_start_matches('^').trim_end_matches('/').to_string();
+        let entries = svn::log(&creds, std::slice::from_ref(&self.path), &revisions, false, None, false, true)?;
+
+        let mut files = HashMap::<String, usize>::new();
+        let mut directories = HashMap::<String, usize>::new();
+        let mut months = BTreeMap::<String, usize>::new();
+        for entry in &entries {
+            *months.entry(entry.date.format("%Y-%m").to_string()).or_default() += 1;
+            let changed = entry.paths
+                .iter()
+                .filter(|p| p.kind != "dir")
+                .filter(|p| base.is_empty() || p.path == base || p.path.starts_with(&format!("{}/", base)));
+            for log_path in changed {
+                *files.entry(log_path.path.clone()).or_default() += 1;
+                let dir = match log_path.path.rsplit_once('/') {
+                    Some(("", _)) | None => "/".to_string(),
+                    Some((dir, _)) => dir.to_string(),
+                };
+                *directories.entry(dir).or_default() += 1;
+            }
+        }
+
+        let stats = Statistics {
+            commits: entries.len(),
+            files: top_counts(files, self.top),
+            directories: top_counts(directories, self.top),
+            months: months.into_iter().map(|(month, commits)| MonthCount { month, commits }).collect(),
+        };
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        } else {
+            show_statistics(&stats);
+        }
+        Ok(())
+    }
+}
+
+//  The paths with the most changes, ties are ordered by path.
+fn top_counts(counts: HashMap<String, usize>, top: usize) -> Vec<PathCount> {
+    let mut counts: Vec<PathCount> = counts
+        .into_iter()
+        .map(|(path, changes)| PathCount { path, changes })
+        .collect();
+    counts.sort_by(|a, b| b.changes.cmp(&a.changes).then_with(|| a.path.cmp(&b.path)));
+    counts.truncate(top);
+    counts
+}
+
+fn show_counts(heading: &str, counts: &[PathCount]) {
+    println!("{}", heading.bold());
+    let width = counts.iter().map(|c| c.changes.to_string().len()).max().unwrap_or(0);
+    for count in counts {
+        println!("  {:>width$}  ^{}", count.changes.to_string().yellow(), count.path, width = width);
+    }
+}
+
+fn show_statistics(stats: &Statistics) {
+    if stats.commits == 0 {
+        println!("No commits found");
+        return;
+    }
+    show_counts("Most frequently changed files", &stats.files);
+    println!();
+    show_counts("Changes per directory", &stats.directories);
+    println!();
+
+    println!("{}", "Commits per month".bold());
+    let max_commits = stats.months.iter().map(|m| m.commits).max().unwrap_or(0);
+    let width = max_commits.to_string().len();
+    let bar_width = crate::util::terminal_width().saturating_sub(width + 14).clamp(10, 50);
+    let scale = |n: usize| if max_commits > bar_width { (n * bar_width).div_ceil(max_commits) } else { n };
+    for month in &stats.months {
+        println!(
+            "  {}  {:>width$} {}",
+            month.month,
+            month.commits,
+            "#".repeat(scale(month.commits)).green(),
+            width = width
+        );
+    }
+    println!();
+    println!("{} commits", stats.commits);
+}
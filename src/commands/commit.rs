@@ -0,0 +1,280 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::template::{expand_template, load_template, ticket_from_branch};
+use crate::hooks;
+use crate::svn::{self, StatusEntry};
+use crate::util::{self, SvError::*};
+use std::env::current_dir;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Commit changes from the working copy.
+///
+/// The changes beneath the current directory are listed and you can
+/// select which of them to commit.  Your editor is then opened with a
+/// commit message built from the message template.  Lines that start
+/// with '#' are removed from the message.  The commit is abandoned if
+/// the message is empty.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The message template is read from .svu/commit-template.txt if it\n\
+    exists.  See `svu template` for its placeholders and use\n\
+    `svu template render` to see the message that it produces.\n\n\
+    If the commit fails the message is saved to .svu/commit_message.txt\n\
+    and is used in place of the template the next time."
+)]
+pub struct Commit {
+    /// Use MSG as the commit message instead of opening the editor.
+    #[arg(short, long, value_name = "MSG")]
+    message: Option<String>,
+
+    /// Commit all of the changes without asking which to include.
+    #[arg(short, long)]
+    all: bool,
+
+    /// Value of the {ticket} placeholder in the message template.
+    #[arg(short, long, value_name = "ID")]
+    ticket: Option<String>,
+
+    /// Only commit items that belong to changelist NAME.
+    ///
+    /// May be given multiple times to include the items from several changelists.
+    #[arg(short, long = "changelist", value_name = "NAME")]
+    changelists: Vec<String>,
+}
+
+impl Commit {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("commit changes")?;
+        let creds = crate::auth::get_credentials()?;
+        let cwd = current_dir()?;
+        let (branch, _) = svn::current_branch(&cwd)?;
+
+        let mut entries = svn::local_changes_changelists(&cwd, &self.changelists)?;
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        if entries.is_empty() {
+            println!("No local changes to commit");
+            return Ok(());
+        }
+
+        let mut selected = if self.all {
+            vec![true; entries.len()]
+        } else {
+            match select_entries(&entries, vec![true; entries.len()])? {
+                Some(selected) => selected,
+                None => return Ok(()),
+            }
+        };
+        //  An added, deleted or replaced directory is committed along with
+        //  everything beneath it, so the commit must be recursive.
+        let trees: Vec<String> = entries.iter()
+            .zip(selected.iter())
+            .filter(|(e, s)| **s && is_tree_change(e, &entries))
+            .map(|(e, _)| e.path.clone())
+            .collect();
+        for (entry, s) in entries.iter().zip(selected.iter_mut()) {
+            if trees.iter().any(|dir| is_beneath(&entry.path, dir)) {
+                *s = true;
+            }
+        }
+        let depth = if trees.is_empty() { "empty" } else { "infinity" };
+        if !trees.is_empty() {
+            //  A recursive commit would also include any unselected
+            //  changes beneath the other selected directories.
+            for (dir, _) in entries.iter().zip(selected.iter()).filter(|(_, s)| **s) {
+                let skipped = entries.iter()
+                    .zip(selected.iter())
+                    .find(|(e, s)| !**s && is_beneath(&e.path, &dir.path));
+                if let Some((skipped, _)) = skipped {
+                    let msg = format!(
+                        "{} cannot be committed without {} when {} is committed",
+                        dir.path, skipped.path, trees[0]
+                    );
+                    return Err(General(msg).into());
+                }
+            }
+        }
+        let paths: Vec<&str> = entries.iter()
+            .zip(selected.iter())
+            .filter(|(_, s)| **s)
+            .map(|(e, _)| e.path.as_str())
+            .collect();
+        if paths.is_empty() {
+            println!("Nothing selected, commit abandoned");
+            return Ok(());
+        }
+
+        let message_file = message_file()?;
+        let message = match &self.message {
+            Some(message) => message.clone(),
+            None => self.edit_message(&message_file, &branch, &entries, &selected)?,
+        };
+        if message.trim().is_empty() {
+            if message_file.is_file() {
+                fs::remove_file(&message_file)?;
+            }
+            println!("Empty commit message, commit abandoned");
+            return Ok(());
+        }
+
+        File::create(&message_file)?.write_all(message.as_bytes())?;
+        let pre_commit = hooks::run("pre-commit", &[
+            ("BRANCH", &branch),
+            ("MESSAGE_FILE", &message_file.to_string_lossy()),
+            ("PATHS", &paths.join("\n")),
+        ]);
+        match pre_commit.and_then(|_| svn::commit(&creds, &paths, depth, &self.changelists, &message_file, Some(&cwd))) {
+            Ok(output) => {
+                let output = String::from_utf8_lossy(&output);
+                print!("{}", output);
+                fs::remove_file(&message_file)?;
+                let revision = output
+                    .lines()
+                    .find_map(|l| l.strip_prefix("Committed revision "))
+                    .map(|r| r.trim_end_matches('.'))
+                    .unwrap_or_default();
+                hooks::run("post-commit", &[("BRANCH", &branch), ("REVISION", revision)])
+            }
+            Err(e) => {
+                eprintln!("The commit message was saved to {}", message_file.to_string_lossy().blue());
+                Err(e)
+            }
+        }
+    }
+
+    //  Open the editor on the message template and return the message
+    //  with the comment lines removed.
+    fn edit_message(&self, message_file: &Path, branch: &str, entries: &[StatusEntry], selected: &[bool]) -> Result<String> {
+        //  A message saved by a failed commit or editor is reused.
+        let initial = if message_file.is_file() {
+            fs::read_to_string(message_file)?
+                .lines()
+                .filter(|line| !line.starts_with('#'))
+                .collect::<Vec<&str>>()
+                .join("\n")
+        } else {
+            let ticket = match &self.ticket {
+                Some(ticket) => Some(ticket.clone()),
+                None => ticket_from_branch(branch),
+            };
+            let files: Vec<&StatusEntry> = entries.iter().zip(selected).filter(|(_, s)| **s).map(|(e, _)| e).collect();
+            expand_template(&load_template()?, branch, ticket.as_deref(), &files)
+        };
+
+        let mut text = initial.trim_end().to_string();
+        text.push_str("\n\n# Lines starting with '#' are ignored.\n");
+        text.push_str(&format!("# Branch: {}\n#\n# Changes to be committed:\n", branch));
+        for (entry, _) in entries.iter().zip(selected).filter(|(_, s)| **s) {
+            text.push_str(&format!("#   {} {}\n", status_letter(entry), entry.path));
+        }
+        File::create(message_file)?.write_all(text.as_bytes())?;
+        util::edit_file(message_file)?;
+
+        let edited = fs::read_to_string(message_file)?;
+        let message = edited.lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<&str>>()
+            .join("\n");
+        Ok(message.trim().to_string() + "\n")
+    }
+}
+
+pub(crate) fn message_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("commit_message.txt"))
+}
+
+//  True if the entry is a directory that was added, deleted or replaced.
+//  A deleted directory no longer exists but its children are listed.
+fn is_tree_change(entry: &StatusEntry, entries: &[StatusEntry]) -> bool {
+    matches!(entry.item_status.as_str(), "added" | "deleted" | "replaced")
+        && (Path::new(&entry.path).is_dir() || entries.iter().any(|e| is_beneath(&e.path, &entry.path)))
+}
+
+//  True if path is a descendant of the directory dir.
+fn is_beneath(path: &str, dir: &str) -> bool {
+    if dir == "." {
+        return path != ".";
+    }
+    path.strip_prefix(dir).is_some_and(|rest| rest.starts_with('/'))
+}
+
+pub(crate) fn status_letter(entry: &StatusEntry) -> &'static str {
+    match entry.item_status.as_str() {
+        "added"      => "A",
+        "deleted"    => "D",
+        "replaced"   => "R",
+        "conflicted" => "C",
+        "missing"    => "!",
+        _            => "M",
+    }
+}
+
+pub(crate) fn status_color(entry: &StatusEntry) -> &'static str {
+    match entry.item_status.as_str() {
+        "added"                              => "green",
+        "deleted" | "missing" | "conflicted" => "red",
+        _                                    => "magenta",
+    }
+}
+
+//  Let the user choose entries starting with those initially selected.
+//  Returns None if the user quits.
+pub(crate) fn select_entries(entries: &[StatusEntry], mut selected: Vec<bool>) -> Result<Option<Vec<bool>>> {
+    let width = entries.len().to_string().len();
+    loop {
+        println!();
+        for (i, entry) in entries.iter().enumerate() {
+            let mark = if selected[i] { "x".green() } else { " ".normal() };
+            let color = status_color(entry);
+            println!(
+                "{:>width$}. [{}] {} {}",
+                i + 1,
+                mark,
+                status_letter(entry).color(color),
+                entry.path.color(color),
+                width = width
+            );
+        }
+        println!();
+        print!("Toggle items (eg. 1 3-5), (a)ll, (n)one, (q)uit or Enter to continue: ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match line.trim() {
+            "" => return Ok(Some(selected)),
+            "q" => return Ok(None),
+            "a" => selected.iter_mut().for_each(|s| *s = true),
+            "n" => selected.iter_mut().for_each(|s| *s = false),
+            input => match parse_selection(input, entries.len()) {
+                Some(indexes) => indexes.into_iter().for_each(|i| selected[i] = !selected[i]),
+                None => println!("{}", format!("Invalid selection: {}", input).red()),
+            },
+        }
+    }
+}
+
+//  Parse item numbers and ranges such as "1 3-5" into zero based indexes.
+fn parse_selection(input: &str, count: usize) -> Option<Vec<usize>> {
+    let mut indexes = Vec::new();
+    for word in input.split([' ', ',']).filter(|w| !w.is_empty()) {
+        let (first, last) = match word.split_once('-') {
+            Some((a, b)) => (a.parse::<usize>().ok()?, b.parse::<usize>().ok()?),
+            None => {
+                let n = word.parse::<usize>().ok()?;
+                (n, n)
+            }
+        };
+        if first == 0 || last > count || first > last {
+            return None;
+        }
+        indexes.extend(first - 1..last);
+    }
+    Some(indexes)
+}
@@ -0,0 +1,176 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn;
+use crate::util::{self, join_paths, SvError::*};
+
+/// Summarize the differences between two branches.
+///
+/// The revisions on each branch that have not been merged to the other
+/// are listed followed by the number of lines added and removed in each
+/// file that differs between the branches.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A BRANCH can be the trunk prefix, a full location such as branches/8.1\n\
+    or just the name of a branch or tag.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes.\n\
+    The revisions unique to a branch are those that `svn mergeinfo` reports\n\
+    as eligible to be merged to the other branch."
+)]
+pub struct Compare {
+    /// The first branch.
+    #[arg(value_name = "BRANCH1")]
+    branch1: String,
+
+    /// The second branch.
+    #[arg(value_name = "BRANCH2")]
+    branch2: String,
+
+    /// Display the full diff after the summary.
+    #[arg(short, long)]
+    diff: bool,
+}
+
+//  The number of lines added and removed in a file.
+struct FileStat {
+    path: String,
+    added: usize,
+    removed: usize,
+}
+
+impl Compare {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?;
+        let prefixes = svn::load_prefixes()?;
+        let root_url = wc_info.root_url.as_str();
+
+        let branch1 = svn::resolve_location(&creds, root_url, &self.branch1, &prefixes)?;
+        let branch2 = svn::resolve_location(&creds, root_url, &self.branch2, &prefixes)?;
+        if branch1 == branch2 {
+            return Err(General(format!("Both branches are ^/{}", branch1)).into());
+        }
+        let url1 = join_paths(root_url, &branch1);
+        let url2 = join_paths(root_url, &branch2);
+
+        show_unique(&creds, &branch1, &url1, &url2)?;
+        println!();
+        show_unique(&creds, &branch2, &url2, &url1)?;
+        println!();
+
+        let lines = svn::url_diff(&creds, &url1, &url2, false)?;
+        let stats = diffstat(&lines);
+        println!(
+            "{} {} {} {}",
+            "Differences from".bold(),
+            format!("^/{}", branch1).green(),
+            "to".bold(),
+            format!("^/{}", branch2).green()
+        );
+        println!("{:->70}", "-");
+        if stats.is_empty() {
+            println!("No differences");
+        } else {
+            show_diffstat(&stats);
+        }
+
+        if self.diff && !stats.is_empty() {
+            println!();
+            println!("{} {}", "---".red(), url1.red());
+            println!("{} {}", "+++".green(), url2.green());
+            for line in &lines {
+                util::print_diff_line(line);
+            }
+        }
+        Ok(())
+    }
+}
+
+//  List the revisions on a branch that have not been merged to the other branch.
+fn show_unique(creds: &Option<Credentials>, branch: &str, url: &str, other_url: &str) -> Result<()> {
+    let revisions = svn::mergeinfo_eligible(creds, url, other_url)?;
+    println!(
+        "{} {} {}",
+        "Revisions only on".bold(),
+        format!("^/{}", branch).green(),
+        format!("({})", revisions.len()).yellow()
+    );
+    println!("{:->70}", "-");
+    if revisions.is_empty() {
+        println!("None");
+        return Ok(());
+    }
+    let entries = svn::log(creds, &[url.to_string()], &revisions, true, None, false, false)?;
+    let max_rev_len = entries.iter().map(|e| e.revision.len()).max().unwrap_or(0);
+    let max_author_len = entries.iter().map(|e| e.author.len()).max().unwrap_or(0);
+    for entry in &entries {
+        println!(
+            "{} {} {} {}",
+            format!("{:>width$}", entry.revision, width = max_rev_len).yellow(),
+            format!("{:<width$}", entry.author, width = max_author_len).cyan(),
+            util::display_svn_date(&entry.date).magenta(),
+            entry.msg_1st()
+        );
+    }
+    Ok(())
+}
+
+//  Count the lines added and removed in each file of a diff.
+//  Property changes are not counted.
+fn diffstat(lines: &[String]) -> Vec<FileStat> {
+    let mut stats = Vec::<FileStat>::new();
+    let mut in_hunks = false;
+    for line in lines {
+        if let Some(path) = line.strip_prefix("Index: ") {
+            stats.push(FileStat { path: path.to_string(), added: 0, removed: 0 });
+            in_hunks = false;
+        } else if line.starts_with("Property changes on:") {
+            in_hunks = false;
+        } else if line.starts_with("@@") {
+            in_hunks = true;
+        } else if let Some(stat) = stats.last_mut().filter(|_| in_hunks) {
+            if line.starts_with('+') {
+                stat.added += 1;
+            } else if line.starts_with('-') {
+                stat.removed += 1;
+            }
+        }
+    }
+    stats
+}
+
+fn show_diffstat(stats: &[FileStat]) {
+    const BAR_WIDTH: usize = 40;
+    let path_width = stats.iter().map(|s| s.path.len()).max().unwrap_or(0);
+    let max_changes = stats.iter().map(|s| s.added + s.removed).max().unwrap_or(0).max(1);
+    let count_width = max_changes.to_string().len();
+    for stat in stats {
+        let changes = stat.added + stat.removed;
+        let scale = |n: usize| if max_changes > BAR_WIDTH { (n * BAR_WIDTH).div_ceil(max_changes) } else { n };
+        println!(
+            " {:path_width$} | {:>count_width$} {}{}",
+            stat.path,
+            changes,
+            "+".repeat(scale(stat.added)).green(),
+            "-".repeat(scale(stat.removed)).red(),
+            path_width = path_width,
+            count_width = count_width
+        );
+    }
+    let added: usize = stats.iter().map(|s| s.added).sum();
+    let removed: usize = stats.iter().map(|s| s.removed).sum();
+    println!(
+        " {} {} changed, {} {}, {} {}",
+        stats.len(),
+        if stats.len() == 1 { "file" } else { "files" },
+        added.to_string().green(),
+        if added == 1 { "insertion" } else { "insertions" },
+        removed.to_string().red(),
+        if removed == 1 { "deletion" } else { "deletions" }
+    );
+}
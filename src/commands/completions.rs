@@ -4,6 +4,7 @@ use clap::CommandFactory;
 use clap::Parser;
 use crate::util::SvError::*;
 use clap_complete::{generate, shells::Shell};
+use std::io::Write;
 
 /// Generate shell completions and write them to stdout.
 #[derive(Debug, Parser)]
@@ -11,7 +12,6 @@ use clap_complete::{generate, shells::Shell};
     author,
     help_template = crate::app::HELP_TEMPLATE,
 )]
-
 pub struct Completions {
     /// Target shell for completions.  Omit to use current shell.
     #[arg()]
@@ -23,9 +23,13 @@ impl Completions {
         let shell = self.shell
             .or(Shell::from_env())
             .ok_or(General("Cannot determine shell".to_owned()))?;
-        let mut clap_cmd = crate::app::Commands::command();
-        let name = clap_cmd.get_name().to_owned();
-        generate(shell, &mut clap_cmd, name, &mut std::io::stdout());
+        write_completions(shell, &mut std::io::stdout());
         Ok(())
     }
 }
+
+pub fn write_completions(shell: Shell, writer: &mut dyn Write) {
+    let mut clap_cmd = crate::app::App::command();
+    let name = clap_cmd.get_name().to_owned();
+    generate(shell, &mut clap_cmd, name, writer);
+}
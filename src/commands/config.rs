@@ -0,0 +1,70 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::config::{self as settings, Value};
+use crate::util::{self, SvError::*};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+mod list;
+mod get;
+mod set;
+mod unset;
+
+/// View and change the svu settings.
+///
+/// Settings are read from svu/config.toml in your configuration directory
+/// and from .svu/config.toml in the working copy.  The settings in the
+/// working copy take precedence.  Options given on the command line take
+/// precedence over both.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Use `svu config list --all` to see every setting with its description.\n\
+    SVU_CONFIG may give the location of your config file."
+)]
+#[command(flatten_help = false)]
+pub struct Config {
+    #[command(subcommand)]
+    command: ConfigCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommands {
+    List(list::List),
+    Get(get::Get),
+    Set(set::Set),
+    Unset(unset::Unset),
+}
+use ConfigCommands::*;
+
+impl Config {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            List(cmd)  => cmd.run(),
+            Get(cmd)   => cmd.run(),
+            Set(cmd)   => cmd.run(),
+            Unset(cmd) => cmd.run(),
+        }
+    }
+}
+
+//  The config file changed by set and unset.
+fn target_file(global: bool) -> Result<PathBuf> {
+    if global {
+        settings::user_config_file()
+            .ok_or_else(|| General("Cannot determine your configuration directory, set SVU_CONFIG".to_string()).into())
+    } else {
+        Ok(util::data_directory()?.join("config.toml"))
+    }
+}
+
+fn load_target(path: &Path) -> Result<BTreeMap<String, Value>> {
+    if path.is_file() {
+        settings::load_file(path)
+    } else {
+        Ok(BTreeMap::new())
+    }
+}
@@ -0,0 +1,34 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Display the value of a setting.
+///
+/// Strings are displayed without quotes and the items of an
+/// array are displayed on separate lines.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Get {
+    /// Name of the setting such as log.limit.
+    #[arg(value_name = "KEY")]
+    key: String,
+}
+
+impl Get {
+    pub fn run(&mut self) -> Result<()> {
+        match settings::load()?.entries.get(&self.key) {
+            Some((Value::String(s), _)) => println!("{}", s),
+            Some((Value::Array(items), _)) => {
+                for item in items {
+                    println!("{}", item);
+                }
+            }
+            Some((value, _)) => println!("{}", value),
+            None => return Err(General(format!("{} is not set", self.key)).into()),
+        }
+        Ok(())
+    }
+}
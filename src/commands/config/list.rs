@@ -0,0 +1,51 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Display the settings and their values.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct List {
+    /// Show every setting svu knows about along with its description.
+    #[arg(short, long)]
+    all: bool,
+
+    /// Show the file that each value was read from.
+    #[arg(short = 'o', long)]
+    show_origin: bool,
+}
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        let config = settings::load()?;
+        let width = config.entries.keys()
+            .map(|k| k.len())
+            .chain(settings::SETTINGS.iter().map(|s| s.key.len()).filter(|_| self.all))
+            .max()
+            .unwrap_or(0);
+
+        if self.all {
+            for setting in &settings::SETTINGS {
+                let value = match config.entries.get(setting.key) {
+                    Some((value, _)) => value.to_string().yellow(),
+                    None => "(not set)".normal(),
+                };
+                println!("{:width$} {}", setting.key.green(), value, width = width);
+                println!("{:width$} {}", "", setting.description, width = width);
+            }
+            return Ok(());
+        }
+
+        for (key, (value, path)) in &config.entries {
+            if self.show_origin {
+                println!("{:width$} {} {}", key.green(), value.to_string().yellow(), path.display().to_string().blue(), width = width);
+            } else {
+                println!("{:width$} {}", key.green(), value.to_string().yellow(), width = width);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Change the value of a setting.
+///
+/// The setting is saved in the working copy unless --global is given.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The items of an array setting are given as separate values, or as one\n\
+    value with the items separated by commas.\n\
+    eg. svu config set prefixes.branches branches releases\n    \
+    svu config set prefixes.branches branches,releases"
+)]
+pub struct Set {
+    /// Save the setting in your config file rather than the working copy.
+    #[arg(short, long)]
+    global: bool,
+
+    /// Name of the setting such as log.limit.
+    #[arg(value_name = "KEY")]
+    key: String,
+
+    /// The new value.  An array setting may be given several values.
+    #[arg(value_name = "VALUE", required = true, num_args = 1..)]
+    values: Vec<String>,
+}
+
+impl Set {
+    pub fn run(&mut self) -> Result<()> {
+        if !self.key.contains('.') || self.key.starts_with('.') || self.key.ends_with('.') {
+            return Err(General("Settings are named TABLE.NAME, eg. log.limit".to_string()).into());
+        }
+        if settings::find_setting(&self.key).is_none() {
+            eprintln!("{} {} is not a setting used by svu", "warning:".yellow(), self.key);
+        }
+        let value = settings::parse_arg(&self.key, &self.values)?;
+        let path = target_file(self.global)?;
+        let mut entries = load_target(&path)?;
+        println!("{} = {} in {}", self.key.green(), value.to_string().yellow(), path.display().to_string().blue());
+        entries.insert(self.key.clone(), value);
+        settings::save_file(&path, &entries)
+    }
+}
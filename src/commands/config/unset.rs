@@ -0,0 +1,34 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Remove a setting.
+///
+/// The setting is removed from the working copy unless --global is given.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Unset {
+    /// Remove the setting from your config file rather than the working copy.
+    #[arg(short, long)]
+    global: bool,
+
+    /// Name of the setting such as log.limit.
+    #[arg(value_name = "KEY")]
+    key: String,
+}
+
+impl Unset {
+    pub fn run(&mut self) -> Result<()> {
+        let path = target_file(self.global)?;
+        let mut entries = load_target(&path)?;
+        if entries.remove(&self.key).is_none() {
+            return Err(General(format!("{} is not set in {}", self.key, path.display())).into());
+        }
+        settings::save_file(&path, &entries)?;
+        println!("Removed {} from {}", self.key.green(), path.display().to_string().blue());
+        Ok(())
+    }
+}
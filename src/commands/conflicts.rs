@@ -0,0 +1,72 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::svn::{self, StatusEntry};
+use std::path::{Path, PathBuf};
+
+mod walk;
+
+/// List and resolve conflicts in the working copy.
+///
+/// With no subcommand the conflicted paths are listed.
+/// Use `conflicts walk` to resolve them one at a time.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = false)]
+pub struct Conflicts {
+    #[command(subcommand)]
+    command: Option<ConflictsCommands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConflictsCommands {
+    Walk(walk::Walk),
+}
+use ConflictsCommands::*;
+
+impl Conflicts {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Some(Walk(cmd)) => cmd.run(),
+            None => {
+                let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+                let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+                let entries = conflicted_entries(&wc_root)?;
+                if entries.is_empty() {
+                    println!("There are no conflicts in the working copy");
+                }
+                for entry in &entries {
+                    println!("{} {}", format!("{:<9}", conflict_kind(entry)).red(), entry.path);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+//  Used by `svu doctor` to validate the data files.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    walk::check_data_files()
+}
+
+//  Return the status entries of items with text or property conflicts.
+//  The paths are relative to the working copy root.
+pub(crate) fn conflicted_entries(wc_root: &Path) -> Result<Vec<StatusEntry>> {
+    let status = svn::status(".", Some(wc_root))?;
+    Ok(status.entries
+        .into_iter()
+        .filter(|e| e.item_status == "conflicted" || e.props_status == "conflicted")
+        .collect())
+}
+
+pub(crate) fn conflict_kind(entry: &StatusEntry) -> &'static str {
+    match (entry.item_status == "conflicted", entry.props_status == "conflicted") {
+        (true, true)  => "text+prop",
+        (true, false) => "text",
+        _             => "prop",
+    }
+}
@@ -0,0 +1,331 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, remove_file};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use super::*;
+use crate::util::{self, SvError::*, datetime_serializer};
+
+/// Resolve conflicted files one at a time.
+///
+/// The conflicting hunks of each file are displayed with the lines
+/// from your working copy (mine), the common ancestor (base) and the
+/// incoming change (theirs) shown in different colors.  For each file
+/// you can accept one side, edit the file in your editor or open it in
+/// your merge tool.
+///
+/// Your decisions are recorded as you go.  If you quit part way through,
+/// running `conflicts walk` again resumes with the remaining files.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The editor is taken from $VISUAL or $EDITOR (default vi).\n\
+    The merge tool is taken from $SVU_MERGE_TOOL or $SVN_MERGE and is run\n\
+    with the arguments: BASE THEIRS MINE MERGED WCPATH"
+)]
+pub struct Walk {
+    /// Discard the decisions of a previous session and start over.
+    #[arg(long)]
+    restart: bool,
+}
+
+//  The action taken for a conflicted file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Decision {
+    Mine,
+    Theirs,
+    Base,
+    Edited,
+    Merged,
+    Skipped,
+}
+
+impl Decision {
+    fn display(&self) -> ColoredString {
+        match self {
+            Decision::Mine    => "accepted mine".green(),
+            Decision::Theirs  => "accepted theirs".blue(),
+            Decision::Base    => "accepted base".yellow(),
+            Decision::Edited  => "edited".green(),
+            Decision::Merged  => "merged".green(),
+            Decision::Skipped => "skipped".red(),
+        }
+    }
+}
+
+//  A walk through the conflicts saved to .svu/conflicts_walk.json
+#[derive(Debug, Serialize, Deserialize)]
+struct Session {
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    decisions: BTreeMap<String, Decision>,
+}
+
+fn session_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("conflicts_walk.json"))
+}
+
+fn load_session() -> Result<Option<Session>> {
+    let path = session_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(Some(serde_json::from_reader(reader)?))
+    } else {
+        Ok(None)
+    }
+}
+
+//  Used by `svu doctor` to validate the data file.
+pub(super) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(session_file()?, load_session().map(|_| ()))])
+}
+
+fn save_session(session: &Session) -> Result<()> {
+    util::save_json(&session_file()?, session)
+}
+
+fn remove_session() -> Result<()> {
+    let path = session_file()?;
+    if path.is_file() {
+        remove_file(path)?;
+    }
+    Ok(())
+}
+
+//  The lines between a set of conflict markers.
+#[derive(Debug, Default)]
+struct Hunk {
+    line: usize,
+    mine_label: String,
+    base_label: Option<String>,
+    theirs_label: String,
+    mine: Vec<String>,
+    base: Vec<String>,
+    theirs: Vec<String>,
+}
+
+impl Walk {
+    pub fn run(&mut self) -> Result<()> {
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        svn::ensure_writable("resolve conflicts")?;
+
+        if self.restart {
+            remove_session()?;
+        }
+
+        let entries = conflicted_entries(&wc_root)?;
+        if entries.is_empty() {
+            println!("There are no conflicts to resolve");
+            return remove_session();
+        }
+
+        let mut session = match load_session()? {
+            Some(session) => {
+                println!("Resuming the session started {}", util::display_svn_datetime(&session.date).magenta());
+                session
+            }
+            None => Session { date: Local::now(), decisions: BTreeMap::new() },
+        };
+
+        //  Files skipped in a previous session are visited last.
+        let (skipped, mut ordered): (Vec<StatusEntry>, Vec<StatusEntry>) = entries
+            .into_iter()
+            .partition(|e| session.decisions.get(&e.path) == Some(&Decision::Skipped));
+        ordered.extend(skipped);
+
+        let total = ordered.len();
+        for (i, entry) in ordered.iter().enumerate() {
+            println!("\n[{}/{}] {} ({} conflict)", i + 1, total, entry.path.yellow(), conflict_kind(entry));
+            match walk_entry(&wc_root, entry)? {
+                Some(decision) => {
+                    println!("{} {}", entry.path, decision.display());
+                    session.decisions.insert(entry.path.clone(), decision);
+                    save_session(&session)?;
+                }
+                None => {
+                    save_session(&session)?;
+                    println!("Run 'svu conflicts walk' again to resume");
+                    return Ok(());
+                }
+            }
+        }
+
+        let remaining = conflicted_entries(&wc_root)?;
+        if remaining.is_empty() {
+            remove_session()?;
+            println!("\nAll conflicts have been resolved");
+        } else {
+            println!("\n{} conflicted {} remain", remaining.len(), if remaining.len() == 1 { "file" } else { "files" });
+            println!("Run 'svu conflicts walk' again to resume");
+        }
+        Ok(())
+    }
+}
+
+//  Show the conflicts for a single entry and prompt for a decision.
+//  Returns None if the user chooses to quit.
+fn walk_entry(wc_root: &Path, entry: &StatusEntry) -> Result<Option<Decision>> {
+    let file = wc_root.join(&entry.path);
+    let text_conflict = entry.item_status == "conflicted";
+    if text_conflict {
+        show_hunks(&file)?;
+    } else {
+        println!("Property conflicts are described in {}.prej", entry.path);
+    }
+
+    loop {
+        let answer = prompt("(m)ine, (t)heirs, (b)ase, (e)dit, (l)aunch merge tool, (s)kip, (q)uit")?;
+        match answer.as_deref() {
+            Some("m") => return accept(wc_root, entry, "mine-full", Decision::Mine),
+            Some("t") => return accept(wc_root, entry, "theirs-full", Decision::Theirs),
+            Some("b") => return accept(wc_root, entry, "base", Decision::Base),
+            Some("e") if text_conflict => {
+                //  The conflict is only resolved after a successful edit.
+                if let Err(e) = util::edit_file(&file) {
+                    println!("{}", e.to_string().red());
+                    continue;
+                }
+                if let Some(decision) = accept_working(wc_root, entry, Decision::Edited)? {
+                    return Ok(Some(decision));
+                }
+            }
+            Some("l") if text_conflict => {
+                launch_merge_tool(wc_root, entry)?;
+                if let Some(decision) = accept_working(wc_root, entry, Decision::Merged)? {
+                    return Ok(Some(decision));
+                }
+            }
+            Some("s") => return Ok(Some(Decision::Skipped)),
+            Some("q") | None => return Ok(None),
+            _ => println!("Please choose one of the listed options"),
+        }
+    }
+}
+
+fn accept(wc_root: &Path, entry: &StatusEntry, accept: &str, decision: Decision) -> Result<Option<Decision>> {
+    svn::resolve(&entry.path, accept, Some(wc_root))?;
+    Ok(Some(decision))
+}
+
+//  After the file has been edited or merged, check that no conflict
+//  markers remain and confirm that it should be marked as resolved.
+fn accept_working(wc_root: &Path, entry: &StatusEntry, decision: Decision) -> Result<Option<Decision>> {
+    let file = wc_root.join(&entry.path);
+    let hunks = conflict_hunks(&read_lines(&file)?);
+    if !hunks.is_empty() {
+        println!("{} still contains {} conflicting {}", entry.path, hunks.len(), if hunks.len() == 1 { "hunk" } else { "hunks" });
+        Ok(None)
+    } else if util::confirm(&format!("Mark {} as resolved?", entry.path))? {
+        accept(wc_root, entry, "working", decision)
+    } else {
+        Ok(None)
+    }
+}
+
+fn show_hunks(file: &Path) -> Result<()> {
+    let hunks = conflict_hunks(&read_lines(file)?);
+    if hunks.is_empty() {
+        println!("No conflict markers found (the file may be binary)");
+    }
+    for (i, hunk) in hunks.iter().enumerate() {
+        println!("{}", format!("Hunk {} of {} at line {}", i + 1, hunks.len(), hunk.line).bold());
+        println!("{}", format!("<<<<<<< {}", hunk.mine_label).magenta());
+        for line in &hunk.mine {
+            println!("{}", line.green());
+        }
+        if let Some(label) = &hunk.base_label {
+            println!("{}", format!("||||||| {}", label).magenta());
+            for line in &hunk.base {
+                println!("{}", line.yellow());
+            }
+        }
+        println!("{}", "=======".magenta());
+        for line in &hunk.theirs {
+            println!("{}", line.blue());
+        }
+        println!("{}", format!(">>>>>>> {}", hunk.theirs_label).magenta());
+    }
+    Ok(())
+}
+
+fn read_lines(file: &Path) -> Result<Vec<String>> {
+    let bytes = std::fs::read(file)?;
+    Ok(String::from_utf8_lossy(&bytes).lines().map(|l| l.to_string()).collect())
+}
+
+//  Parse the conflict markers written by svn.
+//  The base section is only present when svn is configured
+//  to use the diff3 style of conflict markers.
+fn conflict_hunks(lines: &[String]) -> Vec<Hunk> {
+    enum Section { Outside, Mine, Base, Theirs }
+    let mut hunks = Vec::new();
+    let mut hunk = Hunk::default();
+    let mut section = Section::Outside;
+
+    for (i, line) in lines.iter().enumerate() {
+        match section {
+            Section::Outside => {
+                if let Some(label) = line.strip_prefix("<<<<<<<") {
+                    hunk = Hunk { line: i + 1, mine_label: label.trim().to_string(), ..Default::default() };
+                    section = Section::Mine;
+                }
+            }
+            Section::Mine | Section::Base if line == "=======" => section = Section::Theirs,
+            Section::Mine if line.starts_with("|||||||") => {
+                hunk.base_label = Some(line[7..].trim().to_string());
+                section = Section::Base;
+            }
+            Section::Mine => hunk.mine.push(line.clone()),
+            Section::Base => hunk.base.push(line.clone()),
+            Section::Theirs => {
+                if let Some(label) = line.strip_prefix(">>>>>>>") {
+                    hunk.theirs_label = label.trim().to_string();
+                    hunks.push(std::mem::take(&mut hunk));
+                    section = Section::Outside;
+                } else {
+                    hunk.theirs.push(line.clone());
+                }
+            }
+        }
+    }
+    hunks
+}
+
+//  Prompt for a single letter answer.
+//  Returns None at the end of input.
+fn prompt(text: &str) -> Result<Option<String>> {
+    let mut line = String::new();
+    print!("{}: ", text);
+    std::io::stdout().flush()?;
+    if std::io::stdin().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+fn launch_merge_tool(wc_root: &Path, entry: &StatusEntry) -> Result<()> {
+    let mut cmd = util::command_from_env(&["SVU_MERGE_TOOL", "SVN_MERGE"])
+        .ok_or(General("No merge tool is configured. (see SVU_MERGE_TOOL)".to_string()))?;
+    let files = svn::conflict_files(&entry.path, Some(wc_root))?
+        .ok_or(General(format!("Cannot find the conflict files for {}", entry.path)))?;
+    let merged = wc_root.join(&entry.path);
+    let status = cmd
+        .arg(&files.base)
+        .arg(&files.theirs)
+        .arg(&files.mine)
+        .arg(&merged)
+        .arg(&merged)
+        .status()?;
+    if !status.success() {
+        eprintln!("{}", "The merge tool exited with an error".red());
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util;
+
+/// Display the differences in the working copy or between revisions.
+///
+/// Changed lines are colored and when a group of removed lines is
+/// followed by the same number of added lines, the words that differ
+/// within each pair of lines are highlighted.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Without --revision the local changes in the working copy are shown.\n\
+    With a single revision the working copy is compared to that revision."
+)]
+pub struct Diff {
+    /// The revision or range of revisions to compare.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    /// A range is specified by separating two revisions with a colon eg: HEAD-3:HEAD
+    #[arg(short, long, value_name = "REV[:REV]")]
+    revision: Option<String>,
+
+    /// Display a summary of the insertions and deletions of each file.
+    #[arg(long)]
+    stat: bool,
+
+    /// Color only whole lines without highlighting the words that differ.
+    #[arg(long)]
+    no_word_diff: bool,
+
+    /// Only show items that belong to changelist NAME.
+    ///
+    /// May be given multiple times to include the items from several changelists.
+    #[arg(short, long = "changelist", value_name = "NAME")]
+    changelists: Vec<String>,
+
+    /// Paths to compare [default: .]
+    #[arg(value_name = "PATH", num_args = 0..)]
+    paths: Vec<String>,
+}
+
+//  Lines that have more tokens than this are not compared word by word.
+const MAX_WORD_DIFF_TOKENS: usize = 200;
+
+impl Diff {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        if self.paths.is_empty() {
+            self.paths.push(".".to_string());
+        }
+        let revision = match &self.revision {
+            Some(rev) => Some(svn::resolve_revision_range(&creds, rev, &self.paths[0])?),
+            None      => None,
+        };
+
+        let lines = svn::diff(&creds, &self.paths, revision.as_deref(), &self.changelists)?;
+        if self.stat {
+            show_stat(&lines);
+        } else if self.no_word_diff {
+            for line in &lines {
+                util::print_diff_line(line);
+            }
+        } else {
+            show_word_diff(&lines);
+        }
+        Ok(())
+    }
+}
+
+//  Print the diff, pairing runs of removed lines with the
+//  added lines that follow them.
+fn show_word_diff(lines: &[String]) {
+    let mut in_hunk = false;
+    let mut removed: Vec<&str> = Vec::new();
+    let mut added: Vec<&str> = Vec::new();
+
+    let flush = |removed: &mut Vec<&str>, added: &mut Vec<&str>| {
+        if !removed.is_empty() && removed.len() == added.len() {
+            let pairs: Vec<(String, String)> = removed.iter()
+                .zip(added.iter())
+                .map(|(old, new)| word_diff(&old[1..], &new[1..]))
+                .collect();
+            for (old, _) in &pairs {
+                println!("{}{}", "-".red(), old);
+            }
+            for (_, new) in &pairs {
+                println!("{}{}", "+".green(), new);
+            }
+        } else {
+            for line in removed.iter().chain(added.iter()) {
+                util::print_diff_line(line);
+            }
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for line in lines {
+        if line.starts_with("@@") {
+            flush(&mut removed, &mut added);
+            in_hunk = true;
+            util::print_diff_line(line);
+        } else if line.starts_with("Index:") || line.starts_with("Property changes on:") {
+            flush(&mut removed, &mut added);
+            in_hunk = false;
+            util::print_diff_line(line);
+        } else if in_hunk && line.starts_with('-') {
+            if !added.is_empty() {
+                flush(&mut removed, &mut added);
+            }
+            removed.push(line);
+        } else if in_hunk && line.starts_with('+') {
+            added.push(line);
+        } else {
+            flush(&mut removed, &mut added);
+            util::print_diff_line(line);
+        }
+    }
+    flush(&mut removed, &mut added);
+}
+
+//  Split a line into words, runs of whitespace and single punctuation characters.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev_class: Option<u8> = None;
+    for (i, ch) in line.char_indices() {
+        let class = if ch.is_alphanumeric() || ch == '_' { 0 } else if ch.is_whitespace() { 1 } else { 2 };
+        if let Some(prev) = prev_class {
+            if class != prev || class == 2 {
+                tokens.push(&line[start..i]);
+                start = i;
+            }
+        }
+        prev_class = Some(class);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+//  Return the old and new lines with the tokens that are not part
+//  of their longest common subsequence highlighted.
+fn word_diff(old: &str, new: &str) -> (String, String) {
+    let a = tokenize(old);
+    let b = tokenize(new);
+    if a.len() > MAX_WORD_DIFF_TOKENS || b.len() > MAX_WORD_DIFF_TOKENS {
+        return (old.red().to_string(), new.green().to_string());
+    }
+
+    //  lcs[i][j] is the length of the common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut old_text = String::new();
+    let mut new_text = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            old_text.push_str(&a[i].red().to_string());
+            new_text.push_str(&b[j].green().to_string());
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            new_text.push_str(&b[j].green().reversed().to_string());
+            j += 1;
+        } else {
+            old_text.push_str(&a[i].red().reversed().to_string());
+            i += 1;
+        }
+    }
+    (old_text, new_text)
+}
+
+//  Print the number of lines inserted and deleted in each file.
+fn show_stat(lines: &[String]) {
+    let mut files: Vec<(String, usize, usize)> = Vec::new();
+    let mut in_hunk = false;
+    for line in lines {
+        if let Some(path) = line.strip_prefix("Index: ") {
+            files.push((path.to_string(), 0, 0));
+            in_hunk = false;
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        } else if line.starts_with("Property changes on:") {
+            in_hunk = false;
+        } else if in_hunk {
+            if let Some((_, insertions, deletions)) = files.last_mut() {
+                if line.starts_with('+') {
+                    *insertions += 1;
+                } else if line.starts_with('-') {
+                    *deletions += 1;
+                }
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return;
+    }
+    let name_width = files.iter().map(|(path, _, _)| path.chars().count()).max().unwrap_or(0);
+    let max_changes = files.iter().map(|(_, i, d)| i + d).max().unwrap_or(0);
+    let count_width = max_changes.to_string().len();
+    let bar_width = util::terminal_width().saturating_sub(name_width + count_width + 5).clamp(10, 50);
+    let scale = |n: usize| if max_changes > bar_width { (n * bar_width).div_ceil(max_changes) } else { n };
+
+    let (mut total_insertions, mut total_deletions) = (0, 0);
+    for (path, insertions, deletions) in &files {
+        println!(
+            " {:<name_width$} | {:>count_width$} {}{}",
+            path,
+            insertions + deletions,
+            "+".repeat(scale(*insertions)).green(),
+            "-".repeat(scale(*deletions)).red(),
+            name_width = name_width,
+            count_width = count_width
+        );
+        total_insertions += insertions;
+        total_deletions += deletions;
+    }
+    println!(
+        " {} {} changed, {} {}(+), {} {}(-)",
+        files.len(),
+        if files.len() == 1 { "file" } else { "files" },
+        total_insertions,
+        if total_insertions == 1 { "insertion" } else { "insertions" },
+        total_deletions,
+        if total_deletions == 1 { "deletion" } else { "deletions" }
+    );
+}
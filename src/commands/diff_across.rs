@@ -0,0 +1,75 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::{self, join_paths, SvError::*};
+
+/// Show the differences in a path between two branches.
+///
+/// The path is located on each branch using its location relative
+/// to the branch that contains it, in the same way that the
+/// filerevs command locates a path on other branches and tags.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A BRANCH can be the trunk prefix, a full location such as branches/8.1\n\
+    or just the name of a branch or tag.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes.\n\
+    If --from is omitted it defaults to the branch containing PATH."
+)]
+pub struct DiffAcross {
+    /// The branch to compare from.
+    #[arg(short, long, value_name = "BRANCH")]
+    from: Option<String>,
+
+    /// The branch to compare to.
+    #[arg(short, long, value_name = "BRANCH")]
+    to: String,
+
+    /// Display only the paths that differ.
+    #[arg(short, long)]
+    summarize: bool,
+
+    /// Path to a working copy file or directory.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+impl DiffAcross {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let path_info = svn::info(&creds, &self.path, None)?;
+        let root_url = path_info.root_url.as_str();
+        let prefixes = svn::load_prefixes()?;
+
+        let current = svn::containing_location(&path_info.rel_url, &prefixes)?;
+        let rel_path = svn::prefix_relative_path(&path_info.rel_url, &[current.as_str()])?;
+
+        let from = match &self.from {
+            Some(branch) => svn::resolve_location(&creds, root_url, branch, &prefixes)?,
+            None         => current,
+        };
+        let to = svn::resolve_location(&creds, root_url, &self.to, &prefixes)?;
+
+        let from_url = join_paths(join_paths(root_url, &from), &rel_path);
+        let to_url = join_paths(join_paths(root_url, &to), &rel_path);
+        for url in [&from_url, &to_url] {
+            if !svn::path_exists(&creds, url)? {
+                return Err(General(format!("{} does not exist", url)).into());
+            }
+        }
+
+        println!("{} {}", "---".red(), from_url.red());
+        println!("{} {}", "+++".green(), to_url.green());
+        let lines = svn::url_diff(&creds, &from_url, &to_url, self.summarize)?;
+        if lines.iter().all(|l| l.trim().is_empty()) {
+            println!("No differences");
+        }
+        for line in &lines {
+            util::print_diff_line(line);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::{bisect, branch, conflicts, notify, operation, pin, pq, search, snapshot, stash};
+use crate::svn;
+use crate::util::{self, SvError::*};
+use std::env::current_dir;
+use std::fs::{remove_file, rename};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+/// Check the working copy for common problems.
+///
+/// Detects a locked working copy, an operation that was interrupted
+/// and needs `svn cleanup`, a working copy format that is too old for
+/// the svn client, damaged data files in the .svu directory and stash
+/// patch files that do not match the stash entries.
+/// You are asked before each problem is fixed.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A damaged data file is not deleted.  It is renamed with a .corrupt\n\
+    extension so that it can be inspected."
+)]
+pub struct Doctor {
+    /// Fix the problems without asking for confirmation.
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Report the problems but do not fix them.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+//  The action that fixes a problem.
+#[derive(Debug)]
+enum Fix {
+    Cleanup(PathBuf),
+    Upgrade(PathBuf),
+    SetAside(PathBuf),
+    RemovePatch(PathBuf),
+    DropStash(String),
+    Manual,
+}
+
+impl Fix {
+    fn description(&self) -> String {
+        match self {
+            Fix::Cleanup(path)     => format!("run 'svn cleanup {}'", path.to_string_lossy()),
+            Fix::Upgrade(path)     => format!("run 'svn upgrade {}'", path.to_string_lossy()),
+            Fix::SetAside(path)    => format!("rename {} to {}", path.to_string_lossy(), corrupt_path(path).to_string_lossy()),
+            Fix::RemovePatch(path) => format!("remove {}", path.to_string_lossy()),
+            Fix::DropStash(name)   => format!("drop the stash entry for {}", name),
+            Fix::Manual            => "this must be fixed manually".to_string(),
+        }
+    }
+
+    fn apply(&self) -> Result<()> {
+        match self {
+            Fix::Cleanup(path)     => { svn::cleanup(path)?; }
+            Fix::Upgrade(path)     => { svn::upgrade(path)?; }
+            Fix::SetAside(path)    => rename(path, corrupt_path(path))?,
+            Fix::RemovePatch(path) => remove_file(path)?,
+            Fix::DropStash(name)   => stash::drop_stash_entry(name)?,
+            Fix::Manual            => (),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Problem {
+    description: String,
+    fix: Fix,
+}
+
+impl Problem {
+    fn new(description: String, fix: Fix) -> Self {
+        Problem { description, fix }
+    }
+}
+
+impl Doctor {
+    pub fn run(&mut self) -> Result<()> {
+        let cwd = current_dir()?;
+        let mut found = 0;
+
+        //  Nothing else can be checked until svn is able to use the working copy.
+        let wc_root = match working_copy_root(&cwd)? {
+            Ok(wc_root) => wc_root,
+            Err(problem) => {
+                self.handle(&[problem])?;
+                return Ok(());
+            }
+        };
+
+        let problems = check_working_copy(&wc_root)?;
+        found += problems.len();
+        self.handle(&problems)?;
+
+        //  The stash patches are checked before a damaged stash entries
+        //  file is set aside, otherwise every patch would appear unused.
+        let mut problems = check_data_files()?;
+        problems.extend(check_stash_patches()?);
+        found += problems.len();
+        self.handle(&problems)?;
+
+        if found == 0 {
+            println!("No problems found in the working copy at {}", wc_root.to_string_lossy().green());
+        }
+        Ok(())
+    }
+
+    //  Report each problem and fix it if the user agrees.
+    fn handle(&self, problems: &[Problem]) -> Result<()> {
+        for problem in problems {
+            println!("{} {}", "problem:".red(), problem.description);
+            println!("    fix: {}", problem.fix.description());
+            if self.dry_run || matches!(problem.fix, Fix::Manual) {
+                continue;
+            }
+            if self.yes || util::confirm("Fix this problem?")? {
+                match problem.fix.apply() {
+                    Ok(_)  => println!("{}", "Fixed".green()),
+                    Err(e) => println!("{} {}", "The fix failed:".red(), e),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+//  Called when a command fails because the working copy was created by
+//  an older svn client.  Rather than showing the raw svn error we explain
+//  the problem and, when running interactively, offer to upgrade it.
+pub fn offer_upgrade() {
+    let cwd = current_dir().unwrap_or_default();
+    let root = upgrade_root(&cwd);
+    eprintln!("{}", "The working copy was created by an older svn client and must be upgraded".red());
+    eprintln!("before it can be used.  This is usually needed after installing a newer svn.");
+
+    if !std::io::stdin().is_terminal() {
+        eprintln!("Run 'svu doctor --yes' to upgrade it.");
+        return;
+    }
+    let prompt = format!("Run 'svn upgrade {}' now?", root.to_string_lossy());
+    match util::confirm(&prompt) {
+        Ok(true) => match svn::upgrade(&root) {
+            Ok(_)  => println!("The working copy has been upgraded, run the command again."),
+            Err(e) => eprintln!("{} {}", "The upgrade failed:".red(), e),
+        },
+        _ => eprintln!("Run 'svu doctor' when you are ready to upgrade it."),
+    }
+}
+
+//  svn cannot tell us the root of a working copy that it cannot read, so
+//  we look for the top most directory containing a .svn directory.
+//  Working copies created before svn 1.7 have a .svn in every directory.
+fn upgrade_root(cwd: &Path) -> PathBuf {
+    cwd.ancestors()
+        .take_while(|dir| dir.join(".svn").is_dir())
+        .last()
+        .unwrap_or(cwd)
+        .to_path_buf()
+}
+
+fn corrupt_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".corrupt");
+    path.with_file_name(name)
+}
+
+//  The first line of an svn error message.
+fn svn_error(stderr: &[u8]) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .find(|line| line.starts_with("svn: E"))
+        .unwrap_or("svn failed")
+        .to_string()
+}
+
+//  Return the root directory of the working copy or the problem
+//  that prevents svn from using it.
+fn working_copy_root(cwd: &Path) -> Result<Result<PathBuf, Problem>> {
+    let output = svn::SvnCmd::new("info")
+        .arg("--show-item=wc-root")
+        .arg(".")
+        .run()?;
+    if output.status.success() {
+        return Ok(Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim())));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("E155007") {
+        Err(General("This command must be run in a subversion working copy directory.".to_string()).into())
+    } else if stderr.contains("E155036") {
+        let desc = "The working copy format is too old for this svn client".to_string();
+        Ok(Err(Problem::new(desc, Fix::Upgrade(upgrade_root(cwd)))))
+    } else if stderr.contains("E155037") || stderr.contains("E155004") {
+        let desc = "The working copy is locked by an operation that did not finish".to_string();
+        Ok(Err(Problem::new(desc, Fix::Cleanup(cwd.to_path_buf()))))
+    } else {
+        let desc = format!("svn cannot read the working copy: {}", svn_error(&output.stderr));
+        Ok(Err(Problem::new(desc, Fix::Manual)))
+    }
+}
+
+//  Look for locks and interrupted operations.
+fn check_working_copy(wc_root: &Path) -> Result<Vec<Problem>> {
+    let output = svn::SvnCmd::new("status")
+        .with_cwd(Some(wc_root))
+        .arg("--quiet")
+        .arg("--ignore-externals")
+        .run()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("E155037") || stderr.contains("E155004") {
+        let desc = "The working copy has an operation that did not finish".to_string();
+        return Ok(vec![Problem::new(desc, Fix::Cleanup(wc_root.to_path_buf()))]);
+    } else if !output.status.success() {
+        let desc = format!("svn status failed: {}", svn_error(&output.stderr));
+        return Ok(vec![Problem::new(desc, Fix::Manual)]);
+    }
+
+    //  The third column of the status is 'L' for a locked directory.
+    let locked: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| line.chars().nth(2) == Some('L'))
+        .map(|line| line.get(8..).unwrap_or("").trim().to_string())
+        .collect();
+    if locked.is_empty() {
+        Ok(vec![])
+    } else {
+        let desc = format!("The working copy is locked: {}", locked.join(", "));
+        Ok(vec![Problem::new(desc, Fix::Cleanup(wc_root.to_path_buf()))])
+    }
+}
+
+//  Load each of the data files in the .svu directory.
+fn check_data_files() -> Result<Vec<Problem>> {
+    let mut files = svn::check_data_files()?;
+    files.extend(stash::check_data_files()?);
+    files.extend(bisect::check_data_files()?);
+    files.extend(branch::check_data_files()?);
+    files.extend(pin::check_data_files()?);
+    files.extend(operation::check_data_files()?);
+    files.extend(notify::check_data_files()?);
+    files.extend(conflicts::check_data_files()?);
+    files.extend(search::check_data_files()?);
+    files.extend(pq::check_data_files()?);
+    files.extend(snapshot::check_data_files()?);
+
+    let problems = files
+        .into_iter()
+        .filter(|(path, _)| path.is_file())
+        .filter_map(|(path, result)| result.err().map(|e| {
+            let desc = format!("The data file {} is damaged: {}", path.to_string_lossy(), e);
+            Problem::new(desc, Fix::SetAside(path))
+        }))
+        .collect();
+    Ok(problems)
+}
+
+//  Look for patch files that are not used by any stash entry
+//  and stash entries whose patch file is missing.
+fn check_stash_patches() -> Result<Vec<Problem>> {
+    //  If the stash entries cannot be loaded the problem has already been reported.
+    let (orphans, missing) = match stash::stale_patch_files() {
+        Ok(stale) => stale,
+        Err(_)    => return Ok(vec![]),
+    };
+
+    let mut problems = Vec::new();
+    for path in orphans {
+        let desc = format!("The patch file {} does not belong to a stash entry", path.to_string_lossy());
+        problems.push(Problem::new(desc, Fix::RemovePatch(path)));
+    }
+    for name in missing {
+        let desc = format!("The patch file {} of a stash entry is missing", name);
+        problems.push(Problem::new(desc, Fix::DropStash(name)));
+    }
+    Ok(problems)
+}
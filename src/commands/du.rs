@@ -0,0 +1,144 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::*;
+use crate::svn;
+use crate::util::{self, join_paths};
+use std::collections::BTreeMap;
+
+/// Summarize the size of each directory in the repository.
+///
+/// The files are listed with `svn list --recursive` and the size of each
+/// directory is the total size of all of the files beneath it.  As with
+/// du, each directory is listed after the directories that it contains
+/// and the last line is the total for PATH.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Sizes are those of the files in the repository, which does not\n\
+    reflect how the repository stores them.  A checkout also holds a\n\
+    pristine copy of each file so it needs about twice the space.\n\
+    See also `svu size` for the largest directories and files."
+)]
+pub struct Du {
+    /// Report the sizes at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// List directories only down to N levels below PATH.
+    ///
+    /// Directories below that level are still included in the totals.
+    /// A depth of 0 shows only the total for PATH.
+    #[arg(short = 'd', long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// List files as well as directories.
+    #[arg(short, long)]
+    all: bool,
+
+    /// Order of the entries.
+    #[arg(short, long, value_enum, default_value_t = Sort::Path)]
+    sort: Sort,
+
+    /// Show sizes in bytes.
+    #[arg(short, long)]
+    bytes: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Sort {
+    /// Each directory after its contents, as du does
+    Path,
+    /// Largest first
+    Size,
+    /// Alphabetically by name
+    Name,
+}
+
+//  The total size of a directory or file.
+struct Usage {
+    name:   String,
+    size:   u64,
+    is_dir: bool,
+}
+
+impl Du {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revision = self.revision
+            .as_ref()
+            .map(|r| svn::resolve_revision(&creds, r, &self.path))
+            .transpose()?;
+        let list = svn::path_list_recursive(&creds, &self.path, revision.as_deref())?;
+
+        //  The total of each directory keyed by its path relative to PATH.
+        //  The empty path is PATH itself.
+        let mut totals = BTreeMap::<String, u64>::new();
+        totals.insert(String::new(), 0);
+        let mut files = Vec::new();
+        for entry in &list.entries {
+            if entry.kind == "dir" {
+                totals.entry(entry.name.clone()).or_default();
+                continue;
+            }
+            let size = entry.size.unwrap_or(0);
+            let mut dir = entry.name.as_str();
+            while let Some((parent, _)) = dir.rsplit_once('/') {
+                *totals.entry(parent.to_string()).or_default() += size;
+                dir = parent;
+            }
+            *totals.entry(String::new()).or_default() += size;
+            files.push(Usage { name: entry.name.clone(), size, is_dir: false });
+        }
+
+        let depth = |name: &str| if name.is_empty() { 0 } else { name.split('/').count() };
+        let mut usages: Vec<Usage> = totals
+            .into_iter()
+            .map(|(name, size)| Usage { name, size, is_dir: true })
+            .collect();
+        if self.all {
+            usages.extend(files);
+        }
+        usages.retain(|u| self.max_depth.is_none_or(|max| depth(&u.name) <= max));
+
+        match self.sort {
+            Sort::Size => usages.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name))),
+            Sort::Name => usages.sort_by(|a, b| a.name.cmp(&b.name)),
+            //  Compare by path components so that the contents of a directory
+            //  sort before it and directly after any earlier sibling.
+            Sort::Path => usages.sort_by(|a, b| {
+                let mut a_parts = a.name.split('/').filter(|p| !p.is_empty());
+                let mut b_parts = b.name.split('/').filter(|p| !p.is_empty());
+                loop {
+                    match (a_parts.next(), b_parts.next()) {
+                        (Some(x), Some(y)) if x == y => continue,
+                        (Some(x), Some(y)) => return x.cmp(y),
+                        (Some(_), None) => return std::cmp::Ordering::Less,
+                        (None, Some(_)) => return std::cmp::Ordering::Greater,
+                        (None, None) => return std::cmp::Ordering::Equal,
+                    }
+                }
+            }),
+        }
+
+        let sizes: Vec<String> = usages
+            .iter()
+            .map(|u| if self.bytes { u.size.to_string() } else { util::display_size(u.size) })
+            .collect();
+        let width = sizes.iter().map(|s| s.len()).max().unwrap_or(0);
+        for (usage, size) in usages.iter().zip(&sizes) {
+            let path = if usage.name.is_empty() { self.path.clone() } else { join_paths(&self.path, &usage.name) };
+            let path = if usage.is_dir { path.blue() } else { path.normal() };
+            println!("{:>w$}  {}", size.yellow(), path, w = width);
+        }
+        Ok(())
+    }
+}
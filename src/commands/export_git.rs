@@ -0,0 +1,328 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn::{self, LogEntry};
+use crate::util::{join_paths, SvError::*};
+use std::collections::HashMap;
+use std::fs::{read_to_string, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Export the history of a branch as a `git fast-import` stream.
+///
+/// Each commit in the history of PATH becomes a git commit containing the
+/// files below PATH as they were after that commit.  The contents of changed
+/// files are fetched with `svn cat`, so no git-svn installation is needed.
+///
+/// The stream is written to standard output, or to FILE with --output.
+/// With --git-dir the stream is imported directly into the git repository
+/// at DIR, which is created if necessary.
+///
+/// History starts at the commit that created PATH.  Commits made before a
+/// branch was copied are not included.  When the range starts after that
+/// commit, the first commit contains every file below PATH as it was then.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    eg. svu export-git ^/trunk | git -C project fast-import\n\
+    \n\
+    The authors file uses the git-svn format, one author per line:\n\
+    \n    \
+    jsmith = Jane Smith <jane@example.com>\n\
+    \n\
+    When an authors file is given every author must appear in it.\n\
+    Otherwise the svn username is used as the name and email.\n\
+    The revision of each commit is recorded as its mark, eg. :1234."
+)]
+pub struct ExportGit {
+    /// The range of revisions to export.
+    ///
+    /// eg. -r 1000:HEAD
+    #[arg(short, long = "revision", value_name = "RANGE", default_value = "1:HEAD")]
+    range: String,
+
+    /// Map svn usernames to git authors using FILE.
+    #[arg(short = 'A', long, value_name = "FILE")]
+    authors: Option<PathBuf>,
+
+    /// Name of the git branch to create.
+    #[arg(short, long, value_name = "NAME", default_value = "master")]
+    branch: String,
+
+    /// Write the stream to FILE.
+    #[arg(short, long, value_name = "FILE", conflicts_with = "git_dir")]
+    output: Option<PathBuf>,
+
+    /// Import the history into the git repository at DIR.
+    #[arg(short, long, value_name = "DIR")]
+    git_dir: Option<PathBuf>,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+//  A file changed by a commit, relative to the exported path.
+enum Change {
+    Delete(String),
+    Modify(String, Vec<u8>),
+}
+
+impl ExportGit {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let authors = match &self.authors {
+            Some(path) => Some(load_authors(path)?),
+            None => None,
+        };
+        let info = svn::info(&creds, &self.path, None)?;
+        let range = svn::resolve_revision_range(&creds, &self.range, &self.path)?;
+        let mut entries = svn::log(&creds, std::slice::from_ref(&info.url), &[range], true, None, true, true)?;
+        entries.sort_by_key(|e| e.revision.parse::<u64>().unwrap_or(0));
+        if entries.is_empty() {
+            return Err(General(format!("No commits found for {}", self.path)).into());
+        }
+        //  Make sure every author is known before anything is written.
+        if let Some(authors) = &authors {
+            let mut missing: Vec<&str> = entries
+                .iter()
+                .map(|e| e.author.as_str())
+                .filter(|a| !authors.contains_key(*a))
+                .collect();
+            missing.sort();
+            missing.dedup();
+            if !missing.is_empty() {
+                let msg = format!("Authors missing from the authors file: {}", missing.join(", "));
+                return Err(General(msg).into());
+            }
+        }
+
+        let exporter = Exporter {
+            creds: &creds,
+            root_url: &info.root_url,
+            base: info.rel_url.trim_start_matches('^').trim_end_matches('/').to_string(),
+            branch: &self.branch,
+            authors: &authors,
+        };
+
+        match &self.git_dir {
+            Some(dir) => {
+                self.import(&exporter, &entries, dir)?;
+                println!(
+                    "Imported {} commits from {} into {} branch {}",
+                    entries.len(),
+                    info.rel_url.green(),
+                    dir.display().to_string().blue(),
+                    self.branch.green()
+                );
+            }
+            None => {
+                let out: Box<dyn Write> = match &self.output {
+                    Some(path) => Box::new(File::create(path)?),
+                    None => Box::new(io::stdout().lock()),
+                };
+                let mut out = BufWriter::new(out);
+                exporter.write_stream(&entries, &mut out)?;
+                out.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    //  Feed the stream to git fast-import in the repository at dir.
+    fn import(&self, exporter: &Exporter, entries: &[LogEntry], dir: &Path) -> Result<()> {
+        let created = !dir.join(".git").exists() && !dir.join("HEAD").is_file();
+        if created {
+            run_git(Command::new("git").arg("init").arg("--quiet").arg(dir))?;
+            let head = format!("refs/heads/{}", self.branch);
+            run_git(Command::new("git").arg("-C").arg(dir).args(["symbolic-ref", "HEAD", &head]))?;
+        }
+
+        let mut git = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["fast-import", "--quiet"])
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| General(format!("Cannot run git: {}", e)))?;
+        {
+            let mut out = BufWriter::new(git.stdin.take().unwrap());
+            exporter.write_stream(entries, &mut out)?;
+            out.flush()?;
+        }
+        if !git.wait()?.success() {
+            return Err(General("git fast-import failed".to_string()).into());
+        }
+
+        //  Populate the working tree of a new repository.
+        if created {
+            run_git(Command::new("git").arg("-C").arg(dir).args(["reset", "--hard", "--quiet"]))?;
+        }
+        Ok(())
+    }
+}
+
+struct Exporter<'a> {
+    creds:    &'a Option<Credentials>,
+    root_url: &'a str,
+    //  The exported path relative to the repository root, eg. /trunk
+    base:     String,
+    branch:   &'a str,
+    authors:  &'a Option<HashMap<String, String>>,
+}
+
+impl Exporter<'_> {
+    fn write_stream<W: Write>(&self, entries: &[LogEntry], out: &mut W) -> Result<()> {
+        for (index, entry) in entries.iter().enumerate() {
+            let changes = if index == 0 { self.snapshot(entry)? } else { self.changes(entry)? };
+            let message = entry.msg.join("\n");
+            let message = format!("{}\n", message.trim_end());
+            let date = format!("{} {}", entry.date.timestamp(), entry.date.format("%z"));
+
+            writeln!(out, "commit refs/heads/{}", self.branch)?;
+            writeln!(out, "mark :{}", entry.revision)?;
+            writeln!(out, "committer {} {}", self.author(&entry.author), date)?;
+            writeln!(out, "data {}", message.len())?;
+            out.write_all(message.as_bytes())?;
+            for change in changes {
+                match change {
+                    Change::Delete(path) if path.is_empty() => writeln!(out, "deleteall")?,
+                    Change::Delete(path) => writeln!(out, "D {}", quote_path(&path))?,
+                    Change::Modify(path, content) => {
+                        writeln!(out, "M 100644 inline {}", quote_path(&path))?;
+                        writeln!(out, "data {}", content.len())?;
+                        out.write_all(&content)?;
+                        writeln!(out)?;
+                    }
+                }
+            }
+            writeln!(out)?;
+        }
+        writeln!(out, "done")?;
+        Ok(())
+    }
+
+    fn author(&self, username: &str) -> String {
+        match self.authors.as_ref().and_then(|a| a.get(username)) {
+            Some(author) => author.clone(),
+            None => format!("{0} <{0}>", username),
+        }
+    }
+
+    //  The path relative to the exported path, or None if
+    //  the path is outside of it.
+    fn relative_path(&self, path: &str) -> Option<String> {
+        if path == self.base {
+            Some(String::new())
+        } else if self.base.is_empty() {
+            Some(path.trim_start_matches('/').to_string())
+        } else {
+            path.strip_prefix(&format!("{}/", self.base)).map(|p| p.to_string())
+        }
+    }
+
+    fn changes(&self, entry: &LogEntry) -> Result<Vec<Change>> {
+        let mut deleted = Vec::new();
+        let mut fetch = Vec::new();
+        for log_path in &entry.paths {
+            let Some(rel_path) = self.relative_path(&log_path.path) else { continue };
+            let url = join_paths(self.root_url, &log_path.path);
+            if log_path.action == "D" || log_path.action == "R" {
+                deleted.push(Change::Delete(rel_path.clone()));
+            }
+            if log_path.action == "D" {
+                continue;
+            }
+            if log_path.kind == "dir" {
+                //  A copied directory brings all of its files with it.
+                //  Other changes to a directory affect only its properties.
+                if log_path.from_path.is_some() || log_path.action == "R" {
+                    let list = svn::path_list_recursive(self.creds, &url, Some(&entry.revision))?;
+                    for file in list.entries.into_iter().filter(|e| e.kind == "file") {
+                        let name = if rel_path.is_empty() { file.name.clone() } else { join_paths(&rel_path, &file.name) };
+                        fetch.push((name, join_paths(&url, &file.name)));
+                    }
+                }
+            } else {
+                fetch.push((rel_path, url));
+            }
+        }
+
+        deleted.extend(self.fetch(entry, &fetch)?);
+        Ok(deleted)
+    }
+
+    //  Every file below the exported path as it was after a commit.
+    //  Used for the first commit so that it includes the files that
+    //  were not changed by that commit.
+    fn snapshot(&self, entry: &LogEntry) -> Result<Vec<Change>> {
+        let url = join_paths(self.root_url, &self.base);
+        let fetch: Vec<(String, String)> = svn::path_list_recursive(self.creds, &url, Some(&entry.revision))?
+            .entries
+            .into_iter()
+            .filter(|e| e.kind == "file")
+            .map(|e| (e.name.clone(), join_paths(&url, &e.name)))
+            .collect();
+        let mut changes = vec![Change::Delete(String::new())];
+        changes.extend(self.fetch(entry, &fetch)?);
+        Ok(changes)
+    }
+
+    //  Fetch the contents of each (path, url) as it was after a commit.
+    fn fetch(&self, entry: &LogEntry, files: &[(String, String)]) -> Result<Vec<Change>> {
+        use rayon::prelude::*;
+
+        files
+            .par_iter()
+            .map(|(path, url)| {
+                svn::cat(self.creds, url, Some(&entry.revision))
+                    .map(|content| Change::Modify(path.clone(), content))
+            })
+            .collect()
+    }
+}
+
+//  Read an authors file in the git-svn format:  username = Name <email>
+fn load_authors(path: &Path) -> Result<HashMap<String, String>> {
+    let text = read_to_string(path)?;
+    let mut authors = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((user, author)) if author.contains('<') && author.trim_end().ends_with('>') => {
+                authors.insert(user.trim().to_string(), author.trim().to_string());
+            }
+            _ => {
+                let msg = format!("{}:{}: expected 'username = Name <email>'", path.display(), index + 1);
+                return Err(General(msg).into());
+            }
+        }
+    }
+    Ok(authors)
+}
+
+//  Paths containing quotes or line breaks must be quoted in the stream.
+fn quote_path(path: &str) -> String {
+    if path.starts_with('"') || path.contains('\n') {
+        let escaped = path.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        format!("\"{}\"", escaped)
+    } else {
+        path.to_string()
+    }
+}
+
+fn run_git(cmd: &mut Command) -> Result<()> {
+    let status = cmd.status().map_err(|e| General(format!("Cannot run git: {}", e)))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(General("git command failed".to_string()).into())
+    }
+}
@@ -5,10 +5,9 @@ use anyhow::Result;
 use clap::Parser;
 use colored::*;
 use crate::auth::Credentials;
-use crate::util::{SvError::*, join_paths, display_svn_datetime};
+use crate::util::{self, SvError::*, join_paths, display_svn_datetime};
 use crate::svn::{self, Prefixes, SvnInfo};
 use chrono::Local;
-use std::fmt::Display;
 
 /// Display commit revisions of files across tags and branches.
 /// 
@@ -48,7 +47,17 @@ pub struct Filerevs {
     #[arg(short = 'T', long, conflicts_with = "tag_regexes")]
     all_tags: bool,
 
+    /// Display a single table with a row for each path.
+    ///
+    /// Each location is shown as a column containing just the revision
+    /// of the path, followed by the number of paths missing from each location.
+    #[arg(short, long)]
+    summary: bool,
+
     /// PATH or URL to target file
+    ///
+    /// A path may include a peg revision such as path@1200 to refer to
+    /// an item that has since been renamed or replaced.
     #[arg(num_args = 1..)]
     paths: Vec<String>,
 }
@@ -62,7 +71,11 @@ impl Filerevs {
         let creds = crate::auth::get_credentials()?;
 
         // First make sure all paths are rooted in the same repository
-        let path_list = svn::info_list(&creds, &self.paths, None::<String>)?;
+        let paths = self.paths
+            .iter()
+            .map(|p| svn::resolve_peg_path(&creds, p))
+            .collect::<Result<Vec<String>>>()?;
+        let path_list = svn::info_list(&creds, &paths, None::<String>)?;
         let repo_uuid = &path_list[0].repo_uuid;
         for item in &path_list[1..] {
             if &item.repo_uuid != repo_uuid {
@@ -96,8 +109,18 @@ impl Filerevs {
         let mut sorted_prefixes = prefixes.clone();
         sorted_prefixes.sort_by(|a, b| a.len().cmp(&b.len()).reverse()); // Sorteed by length longest first.
 
+        let mut results = Vec::new();
         for path_entry in &path_list {
-            show_path_result(&creds, root_url, path_entry, &prefixes, &sorted_prefixes)?;
+            let rel_path = svn::prefix_relative_path(&path_entry.rel_url, &sorted_prefixes)?;
+            let entries = get_path_result(&creds, root_url, &rel_path, &prefixes);
+            if self.summary {
+                results.push((rel_path, entries));
+            } else {
+                show_path_result(path_entry, &rel_path, &entries);
+            }
+        }
+        if self.summary {
+            show_summary(&prefixes, &results);
         }
         Ok(())
     }
@@ -168,55 +191,40 @@ impl Filerevs {
 }
 
 
-//  We must determine the path to the file relative
-//  to its subversion prefix.
-//  Find find the url entry for one of our prefixes
-//  so we can determine where the relative path starts.
-fn get_svn_rel_path<S>(rel_url: &str, sorted_prefixes: &[S]) -> Result<String>
-where
-    S: AsRef<str> + Display,
-{
-    // Skip the leading ^/
-    sorted_prefixes
-        .iter()
-        .find(|p| rel_url[2..].starts_with(p.as_ref()))
-        .map(|p| rel_url[p.as_ref().len() + 3..].to_string())
-        .ok_or(General(format!("Cannot determine relative path for {}", rel_url)).into())
-}
-
 fn max_width(label: &str, value_widths: impl Iterator<Item = usize>) -> usize {
     value_widths.fold(label.len(), |m, v| m.max(v))
 }
 
-// /this/is/the/users/path
-// Location        Revision  Author  Date         Size
-// --------------  --------  ------  -----------  ----------
-// trunk               7601
-// branches/8.1        7645
-// tags/8.1.1-GA       7625
-fn show_path_result(
+struct Entry(String, Option<Box<SvnInfo>>);
+
+//  Get the info for the path in each of the locations.
+fn get_path_result(
     creds: &Option<Credentials>,
     root_url: &str,
-    path_entry: &SvnInfo,
+    rel_path: &str,
     prefixes: &[String],
-    sorted_prefixes: &[String]
-) -> Result<()> {
+) -> Vec<Entry> {
     use rayon::prelude::*;
 
-    struct Entry(String, Option<Box<SvnInfo>>);
-
-    let rel_path = &get_svn_rel_path(&path_entry.rel_url, sorted_prefixes)?;
-    let results: Vec<_> = prefixes
+    prefixes
         .par_iter()
         .map(|prefix| {
-            let path = join_paths(join_paths(root_url, prefix.as_str()), rel_path.as_str());
+            let path = join_paths(join_paths(root_url, prefix.as_str()), rel_path);
             let info = svn::info(creds, path.as_str(), Some("HEAD"))
                 .ok()
                 .map(Box::new);
             Entry(prefix.clone(), info)
         })
-        .collect();
+        .collect()
+}
 
+// /this/is/the/users/path
+// Location        Revision  Author  Date         Size
+// --------------  --------  ------  -----------  ----------
+// trunk               7601
+// branches/8.1        7645
+// tags/8.1.1-GA       7625
+fn show_path_result(path_entry: &SvnInfo, rel_path: &str, results: &[Entry]) {
     const LOCATION: &str = "Location";
     const REVISION: &str = "Revision";
     const AUTHOR: &str   = "Author";
@@ -261,7 +269,7 @@ fn show_path_result(
     print!("{:->date_width$}{}", "-", col_sep);
     println!("{:->size_width$}{}", "-", col_sep);
 
-    for Entry(prefix, opt_info) in &results {
+    for Entry(prefix, opt_info) in results {
         let loc = "^/".to_string() + prefix;
         if let Some(info) = opt_info {
             let size = info
@@ -278,5 +286,72 @@ fn show_path_result(
             println!("{:location_width$}{}{}", loc.green(), col_sep, "<does not exist>".red());
         }
     }
-    Ok(())
+}
+
+// Path              ^/trunk  ^/branches/8.1  ^/tags/8.1.1-GA
+// ----------------  -------  --------------  ---------------
+// src/main.rs          7601            7645             7625
+// src/extra.rs         7602               x                x
+// ----------------  -------  --------------  ---------------
+// Missing                 0               1                1
+fn show_summary(prefixes: &[String], results: &[(String, Vec<Entry>)]) {
+    const PATH: &str    = "Path";
+    const MISSING: &str = "Missing";
+    let col_sep = "  ";
+
+    let locations: Vec<String> = prefixes.iter().map(|p| format!("^/{}", p)).collect();
+    let path_width = max_width(PATH, results.iter().map(|(path, _)| path.len())).max(MISSING.len());
+    let widths: Vec<usize> = locations
+        .iter()
+        .enumerate()
+        .map(|(i, loc)| {
+            max_width(loc, results.iter().map(|(_, entries)| match &entries[i].1 {
+                Some(info) => info.commit_rev.len(),
+                None => 1,
+            }))
+        })
+        .collect();
+
+    let separator = || {
+        print!("{:->path_width$}", "-");
+        for width in &widths {
+            print!("{}{:->width$}", col_sep, "-", width = width);
+        }
+        println!();
+    };
+
+    println!();
+    print!("{:path_width$}", PATH);
+    for (loc, width) in locations.iter().zip(&widths) {
+        print!("{}{:>width$}", col_sep, loc.green(), width = width);
+    }
+    println!();
+    separator();
+
+    let mut missing = vec![0; locations.len()];
+    for (path, entries) in results {
+        print!("{:path_width$}", path.blue());
+        for (i, Entry(_, opt_info)) in entries.iter().enumerate() {
+            match opt_info {
+                Some(info) => print!("{}{:>width$}", col_sep, info.commit_rev.yellow(), width = widths[i]),
+                None => {
+                    print!("{}{:>width$}", col_sep, util::missing_mark().red(), width = widths[i]);
+                    missing[i] += 1;
+                }
+            }
+        }
+        println!();
+    }
+
+    separator();
+    print!("{:path_width$}", MISSING);
+    for (count, width) in missing.iter().zip(&widths) {
+        let count = count.to_string();
+        if count == "0" {
+            print!("{}{:>width$}", col_sep, count, width = width);
+        } else {
+            print!("{}{:>width$}", col_sep, count.red(), width = width);
+        }
+    }
+    println!();
 }
\ No newline at end of file
@@ -0,0 +1,371 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn::{self, Prefixes};
+use crate::util::join_paths;
+
+/// Display the copy history of branches and tags.
+///
+/// For trunk and each branch (and optionally each tag) the revision where it was
+/// created and the location that it was copied from are determined.  This is based
+/// on the configured prefixes (see the `prefix` command).
+/// The ascii format also shows the merges between branches.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    With --format ascii the branch points and merges are drawn newest first\n\
+    with a column for each branch.  A '*' marks the commit that created a\n\
+    branch or merged into it and an 'o' marks the branch that was merged.\n\
+    \n\
+    Use --format dot to produce a Graphviz description that can be rendered with:\n\
+    svu graph --format dot | dot -Tsvg > branches.svg"
+)]
+pub struct Graph {
+    /// Include tags as well as branches.
+    #[arg(short = 'T', long)]
+    tags: bool,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// A list of branches and the location each was copied from
+    Text,
+    /// An ASCII graph of branch points and merges
+    Ascii,
+    /// Graphviz DOT
+    Dot,
+}
+
+//  A branch, tag or trunk and the copy operation that created it.
+#[derive(Debug, Clone)]
+struct Location {
+    path: String,  // Relative to the repository root with leading slash
+    is_tag: bool,
+    created_rev: Option<String>,
+    copied_from: Option<svn::FromPath>,
+}
+
+impl Location {
+    fn display_path(&self) -> String {
+        format!("^{}", self.path)
+    }
+
+    fn colored_path(&self) -> ColoredString {
+        if self.is_tag { self.display_path().magenta() } else { self.display_path().green() }
+    }
+
+    fn created_rev_num(&self) -> Option<u64> {
+        self.created_rev.as_ref().and_then(|r| r.parse().ok())
+    }
+}
+
+//  A commit on the target location that merged revisions from the source.
+//  Both are indexes into the list of locations.
+#[derive(Debug, Clone)]
+struct Merge {
+    revision: String,
+    target: usize,
+    source: usize,
+}
+
+impl Graph {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let root_url = svn::info(&creds, &self.path, None)?.root_url;
+        let prefixes = svn::load_prefixes()?;
+        let locations = get_locations(&creds, &root_url, &prefixes, self.tags)?;
+
+        match self.format {
+            Format::Text => show_text(&locations),
+            Format::Ascii => {
+                let merges = get_merges(&creds, &root_url, &locations)?;
+                show_ascii(&locations, &merges);
+            }
+            Format::Dot => show_dot(&locations),
+        }
+        Ok(())
+    }
+}
+
+fn get_locations(
+    creds: &Option<Credentials>,
+    root_url: &str,
+    prefixes: &Prefixes,
+    include_tags: bool,
+) -> Result<Vec<Location>> {
+    use rayon::prelude::*;
+
+    let mut all_prefixes = prefixes.branch_prefixes.clone();
+    all_prefixes.extend(prefixes.tag_prefixes.clone());
+
+    let mut paths = vec![(prefixes.trunk_prefix.clone(), false)];
+    for branch in svn::list_locations(creds, root_url, &prefixes.branch_prefixes, &all_prefixes)? {
+        paths.push((branch, false));
+    }
+    if include_tags {
+        for tag in svn::list_locations(creds, root_url, &prefixes.tag_prefixes, &all_prefixes)? {
+            paths.push((tag, true));
+        }
+    }
+
+    paths
+        .par_iter()
+        .map(|(path, is_tag)| get_location(creds, root_url, path, *is_tag))
+        .collect()
+}
+
+//  The first log entry on the branch (stopping on copy) is the
+//  commit that created the branch.  If the branch was created by a copy then
+//  that commit will contain the copyfrom information for the branch path.
+fn get_location(
+    creds: &Option<Credentials>,
+    root_url: &str,
+    path: &str,
+    is_tag: bool,
+) -> Result<Location> {
+    let url = join_paths(root_url, path);
+    let abs_path = format!("/{}", path);
+    let entries = svn::log(creds, &[url.as_str()], &["1:HEAD"], false, Some(1), true, true)?;
+    let (created_rev, copied_from) = match entries.first() {
+        Some(entry) => {
+            let from = entry.paths
+                .iter()
+                .find(|p| p.path == abs_path)
+                .and_then(|p| p.from_path.clone());
+            (Some(entry.revision.clone()), from)
+        }
+        None => (None, None),
+    };
+    Ok(Location { path: abs_path, is_tag, created_rev, copied_from })
+}
+
+//  Find the location that contains the given path.
+//  The copy source may be a subdirectory of a branch, so we choose
+//  the longest location that is a prefix of the path.
+fn find_source<'a>(locations: &'a [Location], path: &str) -> Option<&'a Location> {
+    source_index(locations, path).map(|index| &locations[index])
+}
+
+fn source_index(locations: &[Location], path: &str) -> Option<usize> {
+    locations
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| path == l.path || path.starts_with(&format!("{}/", l.path)))
+        .max_by_key(|(_, l)| l.path.len())
+        .map(|(index, _)| index)
+}
+
+//  Find the merges into each location using the merge history of its log.
+fn get_merges(creds: &Option<Credentials>, root_url: &str, locations: &[Location]) -> Result<Vec<Merge>> {
+    use rayon::prelude::*;
+
+    let merges: Vec<Vec<Merge>> = locations
+        .par_iter()
+        .enumerate()
+        .map(|(target, location)| {
+            let url = join_paths(root_url, &location.path);
+            let mut merges = Vec::new();
+            for commit in svn::merge_commits(creds, &url)? {
+                let mut sources: Vec<usize> = commit.merged_paths
+                    .iter()
+                    .filter_map(|path| source_index(locations, path))
+                    .filter(|source| *source != target)
+                    .collect();
+                sources.sort_unstable();
+                sources.dedup();
+                for source in sources {
+                    merges.push(Merge { revision: commit.revision.clone(), target, source });
+                }
+            }
+            Ok(merges)
+        })
+        .collect::<Result<_>>()?;
+    Ok(merges.into_iter().flatten().collect())
+}
+
+fn show_text(locations: &[Location]) {
+    let width = locations.iter().fold(0, |m, l| m.max(l.display_path().len()));
+    for location in locations {
+        let path = location.display_path();
+        let rev = location.created_rev.as_deref().unwrap_or("n/a");
+        let path = if location.is_tag { path.magenta() } else { path.green() };
+        match &location.copied_from {
+            Some(from) => println!(
+                "{:width$} [{}] from ^{}@{}",
+                path,
+                rev.yellow(),
+                from.path.blue(),
+                from.revision.yellow()
+            ),
+            None => println!("{:width$} [{}]", path, rev.yellow()),
+        }
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", dot_escape(text))
+}
+
+fn show_dot(locations: &[Location]) {
+    let mut orphans = Vec::<String>::new();
+
+    println!("digraph branches {{");
+    println!("    rankdir=LR;");
+    println!("    node [shape=box];");
+    for location in locations {
+        let rev = location.created_rev.as_deref().unwrap_or("n/a");
+        let shape = if location.is_tag { "note" } else { "box" };
+        println!(
+            "    {} [label=\"{}\\nr{}\", shape={}];",
+            dot_quote(&location.display_path()),
+            dot_escape(&location.display_path()),
+            rev,
+            shape
+        );
+    }
+    for location in locations {
+        if let Some(from) = &location.copied_from {
+            //  The copy source may no longer exist (a deleted branch for example)
+            //  in which case we add a dashed node for it.
+            let source = match find_source(locations, &from.path) {
+                Some(source) => source.display_path(),
+                None => {
+                    let source = format!("^{}", from.path);
+                    if !orphans.contains(&source) {
+                        println!("    {} [style=dashed];", dot_quote(&source));
+                        orphans.push(source.clone());
+                    }
+                    source
+                }
+            };
+            println!(
+                "    {} -> {} [label={}];",
+                dot_quote(&source),
+                dot_quote(&location.display_path()),
+                dot_quote(&format!("r{}", from.revision))
+            );
+        }
+    }
+    println!("}}");
+}
+
+//  A row of the ascii graph.
+#[derive(Debug)]
+enum Event<'a> {
+    Created(usize),
+    Merged(&'a Merge),
+}
+
+fn show_ascii(locations: &[Location], merges: &[Merge]) {
+    if locations.is_empty() {
+        return;
+    }
+    //  Each location gets a column, ordered by the revision that created it.
+    let mut order: Vec<usize> = (0..locations.len()).collect();
+    order.sort_by_key(|&index| locations[index].created_rev_num().unwrap_or(0));
+    let mut column = vec![0; locations.len()];
+    for (col, &index) in order.iter().enumerate() {
+        column[index] = col;
+    }
+
+    let mut events: Vec<(u64, Event)> = Vec::new();
+    for (index, location) in locations.iter().enumerate() {
+        if let Some(rev) = location.created_rev_num() {
+            events.push((rev, Event::Created(index)));
+        }
+    }
+    for merge in merges {
+        events.push((merge.revision.parse().unwrap_or(0), Event::Merged(merge)));
+    }
+    //  Newest first, and at the same revision merges before branch points.
+    events.sort_by_key(|(rev, event)| (std::cmp::Reverse(*rev), matches!(event, Event::Created(_))));
+
+    //  A location's column is drawn from the top down to the row that created it.
+    let is_active = |index: usize, rev: u64, inclusive: bool| match locations[index].created_rev_num() {
+        Some(created) => created < rev || (inclusive && created == rev),
+        None => true,
+    };
+    let lanes = |rev: u64, inclusive: bool| -> Vec<char> {
+        let mut chars = vec![' '; locations.len() * 2 - 1];
+        for index in 0..locations.len() {
+            if is_active(index, rev, inclusive) {
+                chars[column[index] * 2] = '|';
+            }
+        }
+        chars
+    };
+    //  Join two columns with a horizontal line, crossing the columns between them.
+    let join = |chars: &mut Vec<char>, from: usize, to: usize| {
+        let (left, right) = (from.min(to) * 2, from.max(to) * 2);
+        for ch in chars.iter_mut().take(right).skip(left + 1) {
+            *ch = if *ch == '|' { '+' } else { '-' };
+        }
+    };
+    let print_row = |chars: &[char], text: String| {
+        let graph: String = chars.iter().collect();
+        let graph = graph.replace('*', &"*".yellow().to_string());
+        if text.is_empty() {
+            println!("{}", graph.trim_end());
+        } else {
+            println!("{} {}", graph, text);
+        }
+    };
+
+    for (rev, event) in &events {
+        match event {
+            Event::Created(index) => {
+                let location = &locations[*index];
+                let mut chars = lanes(*rev, true);
+                chars[column[*index] * 2] = '*';
+                let source = location.copied_from.as_ref().and_then(|from| source_index(locations, &from.path));
+                let text = match &location.copied_from {
+                    Some(from) => format!(
+                        "{} {} from ^{}@{}",
+                        format!("r{}", rev).yellow(),
+                        location.colored_path(),
+                        from.path.blue(),
+                        from.revision.yellow()
+                    ),
+                    None => format!("{} {}", format!("r{}", rev).yellow(), location.colored_path()),
+                };
+                print_row(&chars, text);
+
+                //  Draw the line down to the column that the location was copied from.
+                if let Some(source) = source.filter(|s| column[*s] != column[*index]) {
+                    let mut chars = lanes(*rev, false);
+                    join(&mut chars, column[source], column[*index]);
+                    chars[column[*index] * 2] = if column[*index] > column[source] { '\'' } else { '`' };
+                    print_row(&chars, String::new());
+                }
+            }
+            Event::Merged(merge) => {
+                let mut chars = lanes(*rev, true);
+                join(&mut chars, column[merge.source], column[merge.target]);
+                chars[column[merge.target] * 2] = '*';
+                chars[column[merge.source] * 2] = 'o';
+                let text = format!(
+                    "{} merged {} into {}",
+                    format!("r{}", rev).yellow(),
+                    locations[merge.source].colored_path(),
+                    locations[merge.target].colored_path()
+                );
+                print_row(&chars, text);
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn;
+use crate::util::{SvError::*, display_svn_date, join_paths};
+use regex::{Regex, RegexBuilder};
+use std::fs::read;
+use std::path::Path;
+
+/// Search the contents of files in the repository.
+///
+/// The files below PATH are listed with `svn list` and the contents of each
+/// file are fetched with `svn cat` and searched for lines matching PATTERN.
+///
+/// Use --branches and/or --tags to run the same search across the matching
+/// branches and tags.  The path searched in each branch is the path of PATH
+/// relative to its own branch (see the `prefix` command).  Results are grouped
+/// by branch.
+///
+/// Use --working-copy to search the versioned files in the working copy,
+/// including any local changes, without contacting the repository.
+///
+/// Use --pickaxe to find the commit where a line matching PATTERN first
+/// appeared below PATH.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    With --pickaxe the diff of each commit in the history of PATH is searched\n\
+    for added lines that match PATTERN, starting with the oldest commit.\n\
+    With --revision only the history up to that revision is searched."
+)]
+pub struct Grep {
+    /// Search the files as they existed at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Ignore case when matching.
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Only display the names of files that contain matches.
+    #[arg(short = 'l', long)]
+    files_with_matches: bool,
+
+    /// Search the files in the working copy.
+    #[arg(short, long, conflicts_with_all = ["revision", "branch_regexes", "tag_regexes"])]
+    working_copy: bool,
+
+    /// Find the commit that first added a line matching PATTERN.
+    #[arg(
+        short = 'S',
+        long,
+        conflicts_with_all = ["files_with_matches", "working_copy", "branch_regexes", "tag_regexes"],
+    )]
+    pickaxe: bool,
+
+    /// Search in branches that match <REGEX>.
+    ///
+    /// If multiple --branches options are given, then branches matching any
+    /// one of the regular expressions are searched.
+    #[arg(short, long = "branches", value_name = "REGEX")]
+    branch_regexes: Vec<Regex>,
+
+    /// Search in tags that match <REGEX>.
+    ///
+    /// If multiple --tags options are given, then tags matching any
+    /// one of the regular expressions are searched.
+    #[arg(short, long = "tags", value_name = "REGEX")]
+    tag_regexes: Vec<Regex>,
+
+    /// Regular expression to search for.
+    #[arg(value_name = "PATTERN")]
+    pattern: String,
+
+    /// PATH or URL of the directory or file to search.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+struct Match {
+    path: String,
+    line_num: usize,
+    line: String,
+}
+
+//  The results of searching one location.
+//  The matches are `None` if the path does not exist at that location.
+struct LocationResult {
+    location: String,
+    matches: Option<Vec<Match>>,
+}
+
+impl Grep {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let regex = RegexBuilder::new(&self.pattern)
+            .case_insensitive(self.ignore_case)
+            .build()?;
+        if self.working_copy {
+            let result = search_working_copy(&self.path, &regex)?;
+            self.show_result(&result, false);
+            return Ok(());
+        }
+        let revision = self.revision
+            .as_ref()
+            .map(|r| svn::resolve_revision(&creds, r, &self.path))
+            .transpose()?;
+        let info = svn::info(&creds, &self.path, revision.as_deref())?;
+
+        if self.pickaxe {
+            pickaxe(&creds, &info.url, revision.as_deref(), &regex)?;
+        } else if self.branch_regexes.is_empty() && self.tag_regexes.is_empty() {
+            let result = search_location(&creds, &info.url, &info.rel_url, revision.as_deref(), &regex)?;
+            self.show_result(&result, false);
+        } else {
+            let urls = self.get_branch_urls(&creds, &info)?;
+            for result in search_locations(&creds, &urls, revision.as_deref(), &regex)? {
+                self.show_result(&result, true);
+            }
+        }
+        Ok(())
+    }
+
+    //  Returns a (location, url) pair for the path in each matching branch and tag.
+    fn get_branch_urls(&self, creds: &Option<Credentials>, info: &svn::SvnInfo) -> Result<Vec<(String, String)>> {
+        let prefixes = svn::load_prefixes()?;
+        let mut all_prefixes = vec![prefixes.trunk_prefix.clone()];
+        all_prefixes.extend(prefixes.branch_prefixes.clone());
+        all_prefixes.extend(prefixes.tag_prefixes.clone());
+        let mut sorted_prefixes = all_prefixes.clone();
+        sorted_prefixes.sort_by(|a, b| a.len().cmp(&b.len()).reverse()); // Sorted by length longest first.
+        let rel_path = svn::prefix_relative_path(&info.rel_url, &sorted_prefixes)?;
+
+        let mut locations = Vec::<String>::new();
+        let mut add_matching = |prefixes: &[String], regexes: &[Regex]| -> Result<()> {
+            if !regexes.is_empty() {
+                for location in svn::list_locations(creds, &info.root_url, prefixes, &all_prefixes)? {
+                    if regexes.iter().any(|r| r.is_match(&location)) {
+                        locations.push(location);
+                    }
+                }
+            }
+            Ok(())
+        };
+        add_matching(&prefixes.branch_prefixes, &self.branch_regexes)?;
+        add_matching(&prefixes.tag_prefixes, &self.tag_regexes)?;
+
+        if locations.is_empty() {
+            Err(General("No branches or tags matched".to_string()).into())
+        } else {
+            Ok(locations
+                .into_iter()
+                .map(|l| {
+                    let url = join_paths(join_paths(&info.root_url, &l), &rel_path);
+                    (format!("^/{}", l), url)
+                })
+                .collect())
+        }
+    }
+
+    fn show_result(&self, result: &LocationResult, show_location: bool) {
+        if show_location {
+            println!();
+            println!("{}", result.location.green());
+        }
+        match &result.matches {
+            None => println!("{}", "<does not exist>".red()),
+            Some(matches) if self.files_with_matches => {
+                let mut last_path = "";
+                for m in matches {
+                    if m.path != last_path {
+                        println!("{}", m.path.blue());
+                        last_path = &m.path;
+                    }
+                }
+            }
+            Some(matches) => {
+                for m in matches {
+                    println!("{}:{}: {}", m.path.blue(), m.line_num.to_string().yellow(), m.line);
+                }
+            }
+        }
+    }
+}
+
+//  Search several locations in parallel.
+fn search_locations(
+    creds: &Option<Credentials>,
+    urls: &[(String, String)],
+    revision: Option<&str>,
+    regex: &Regex,
+) -> Result<Vec<LocationResult>> {
+    use rayon::prelude::*;
+
+    urls
+        .par_iter()
+        .map(|(location, url)| search_location(creds, url, location, revision, regex))
+        .collect()
+}
+
+//  A location that does not exist has no matches.  Other svn
+//  failures, such as network errors, are returned as errors
+//  rather than being reported as finding no matches.
+fn search_location(
+    creds: &Option<Credentials>,
+    url: &str,
+    location: &str,
+    revision: Option<&str>,
+    regex: &Regex,
+) -> Result<LocationResult> {
+    use rayon::prelude::*;
+
+    let files: Vec<String> = match svn::path_list_recursive(creds, url, revision) {
+        Ok(list) => list.entries
+            .into_iter()
+            .filter(|e| e.kind == "file")
+            .map(|e| e.name)
+            .collect(),
+        Err(e) if svn::is_not_found_error(&e) => return Ok(LocationResult { location: location.to_string(), matches: None }),
+        Err(e) => return Err(e),
+    };
+
+    //  When the url refers to a file, the list contains a single entry
+    //  with the file's name.
+    let is_file = files.len() == 1 && url.ends_with(&format!("/{}", files[0]));
+    let found = files
+        .par_iter()
+        .map(|name| {
+            let file_url = if is_file { url.to_string() } else { join_paths(url, name) };
+            match svn::cat(creds, &file_url, revision) {
+                Ok(content) => Ok(search_content(name, &content, regex)),
+                Err(e) if svn::is_not_found_error(&e) => Ok(vec![]),
+                Err(e) => Err(e),
+            }
+        })
+        .collect::<Result<Vec<Vec<Match>>>>()?;
+    let mut matches: Vec<Match> = found.into_iter().flatten().collect();
+    matches.sort_by(|a, b| a.path.cmp(&b.path).then(a.line_num.cmp(&b.line_num)));
+    Ok(LocationResult { location: location.to_string(), matches: Some(matches) })
+}
+
+//  Search the versioned files of the working copy as they are on disk.
+fn search_working_copy(path: &str, regex: &Regex) -> Result<LocationResult> {
+    use rayon::prelude::*;
+
+    let files: Vec<String> = svn::status_verbose(path, None)?
+        .entries
+        .into_iter()
+        .filter(|e| !matches!(e.item_status.as_str(), "unversioned" | "ignored" | "external" | "deleted" | "missing"))
+        .map(|e| e.path)
+        .filter(|p| Path::new(p).is_file())
+        .collect();
+
+    let mut matches: Vec<Match> = files
+        .par_iter()
+        .flat_map(|name| match read(name) {
+            Ok(content) => search_content(name, &content, regex),
+            Err(_) => vec![],
+        })
+        .collect();
+    matches.sort_by(|a, b| a.path.cmp(&b.path).then(a.line_num.cmp(&b.line_num)));
+    Ok(LocationResult { location: path.to_string(), matches: Some(matches) })
+}
+
+//  Search the commits of the url oldest first for added lines that match.
+//  The diffs are fetched in parallel a batch at a time so that we
+//  can stop at the first batch containing a match.
+fn pickaxe(creds: &Option<Credentials>, url: &str, revision: Option<&str>, regex: &Regex) -> Result<()> {
+    use rayon::prelude::*;
+
+    let range = format!("1:{}", revision.unwrap_or("HEAD"));
+    let target = svn::peg_path(url, revision);
+    let entries = svn::log(creds, &[target.as_str()], &[range.as_str()], true, None, false, false)?;
+    let batch_size = rayon::current_num_threads().max(1) * 2;
+
+    for batch in entries.chunks(batch_size) {
+        let results: Vec<Vec<(String, String)>> = batch
+            .par_iter()
+            .map(|entry| {
+                let target = svn::peg_path(url, Some(&entry.revision));
+                //  Before a copy the path had another name, which does not
+                //  exist at the url, so those commits are skipped.
+                //  Any other failure, such as a network error, stops the search.
+                match svn::change_diff(creds, &[target.as_str()], &entry.revision) {
+                    Ok(lines) => Ok(added_matches(&lines, regex)),
+                    Err(e) if svn::is_not_found_error(&e) => Ok(vec![]),
+                    Err(e) => Err(e),
+                }
+            })
+            .collect::<Result<_>>()?;
+
+        if let Some((entry, added)) = batch.iter().zip(results.iter()).find(|(_, added)| !added.is_empty()) {
+            println!(
+                "{} {} {} {}",
+                entry.revision.yellow(),
+                entry.author.cyan(),
+                display_svn_date(&entry.date).magenta(),
+                entry.msg_1st()
+            );
+            for (path, line) in added {
+                println!("{}: {}", path.blue(), line);
+            }
+            return Ok(());
+        }
+    }
+    println!("No commit added a line matching the pattern");
+    Ok(())
+}
+
+//  The added lines of a diff that match the regex with the path of each.
+fn added_matches(lines: &[String], regex: &Regex) -> Vec<(String, String)> {
+    let mut path = "";
+    let mut matches = Vec::new();
+    for line in lines {
+        if let Some(index_path) = line.strip_prefix("Index: ") {
+            path = index_path;
+        } else if let Some(added) = line.strip_prefix('+').filter(|_| !line.starts_with("+++")) {
+            if regex.is_match(added) {
+                matches.push((path.to_string(), highlight_matches(added, regex)));
+            }
+        }
+    }
+    matches
+}
+
+fn search_content(path: &str, content: &[u8], regex: &Regex) -> Vec<Match> {
+    // Skip binary files
+    if content.contains(&0) {
+        return vec![];
+    }
+    String::from_utf8_lossy(content)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(index, line)| Match {
+            path: path.to_string(),
+            line_num: index + 1,
+            line: highlight_matches(line, regex),
+        })
+        .collect()
+}
+
+fn highlight_matches(line: &str, regex: &Regex) -> String {
+    let mut result = String::new();
+    let mut last = 0;
+    for m in regex.find_iter(line) {
+        result += &line[last..m.start()];
+        result += &m.as_str().red().bold().to_string();
+        last = m.end();
+    }
+    result += &line[last..];
+    result
+}
@@ -17,7 +17,6 @@ use std::fmt::Display;
     Recursively finds all svn:ignore and svn:global-ignores properties in the repository and \
     writes them to stdout in .gitignore format."
 )]
-
 pub struct Ignore {
     /// Path to working Working copy directory.
     #[arg(default_value = ".")]
@@ -80,7 +79,15 @@ impl Ignore {
             let msg = format!("{} is not a subversion working copy directory", self.path);
             Err(General(msg).into())
         } else {
-            svn_ignore(&creds, &self.path, prefix_len)
+            svn_ignore(&creds, &self.path, prefix_len)?;
+            //  svu's own data directory lives at the root of the working copy.
+            let info = svn::info(&creds, &self.path, None)?;
+            if let Some(wc_path) = info.wc_path {
+                if Path::new(&self.path).canonicalize()? == Path::new(&wc_path).canonicalize()? {
+                    println!("/.svu/");
+                }
+            }
+            Ok(())
         }
     }
 }
@@ -98,15 +105,5 @@ fn is_working_directory(creds: &Option<Credentials>, path: &str) -> Result<bool>
 }
 
 fn get_ignores(creds: &Option<Credentials>, path: &str, global: bool) -> Result<Option<String>> {
-    let output = svn::SvnCmd::new("pget")
-        .with_creds(creds)
-        .arg(if global { "svn:global-ignores" } else { "svn:ignore" })
-        .arg(path)
-        .run()?;
-
-        if output.status.success() {
-        Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
-    } else {
-        Ok(None)
-    }
+    svn::propget(creds, if global { "svn:global-ignores" } else { "svn:ignore" }, path)
 }
@@ -0,0 +1,131 @@
+use anyhow::Result;
+use clap::{CommandFactory, Parser};
+use clap_complete::shells::Shell;
+use colored::*;
+use crate::svn;
+use crate::util;
+use std::fs::{create_dir_all, File};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+/// Prepare a working copy for use with svu.
+///
+/// Creates the .svu directory at the root of the working copy,
+/// detects the repository prefixes for branches and tags, offers
+/// to install shell completions and prints a summary of the
+/// available commands.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Init {
+    /// Answer yes to all questions.
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl Init {
+    pub fn run(&mut self) -> Result<()> {
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        let existed = wc_root.join(".svu").is_dir();
+        let data_dir = util::data_directory()?;
+        if existed {
+            println!("Using existing {}", data_dir.to_string_lossy().green());
+        } else {
+            println!("Created {}", data_dir.to_string_lossy().green());
+        }
+
+        self.init_prefixes()?;
+        self.offer_completions()?;
+        show_orientation();
+        Ok(())
+    }
+
+    fn init_prefixes(&self) -> Result<()> {
+        if svn::prefixes_configured()? {
+            println!("Repository prefixes are already configured (see svu prefix)");
+            return Ok(());
+        }
+        let creds = crate::auth::get_credentials()?;
+        let root_url = svn::workingcopy_info()?.root_url;
+        let prefixes = svn::detect_prefixes(&creds, &root_url)?;
+        svn::save_prefixes(&prefixes)?;
+
+        let display = |prefixes: &[String]| {
+            prefixes.iter().map(|p| format!("^/{}", p)).collect::<Vec<_>>().join(", ")
+        };
+        println!("Detected repository prefixes:");
+        println!("  trunk   : {}", format!("^/{}", prefixes.trunk_prefix).green());
+        println!("  branches: {}", display(&prefixes.branch_prefixes).green());
+        println!("  tags    : {}", display(&prefixes.tag_prefixes).green());
+        Ok(())
+    }
+
+    fn ask(&self, prompt: &str) -> Result<bool> {
+        if self.yes {
+            Ok(true)
+        } else if std::io::stdin().is_terminal() {
+            util::confirm(prompt)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn offer_completions(&self) -> Result<()> {
+        let (shell, path) = match Shell::from_env().and_then(|s| completions_file(s).map(|p| (s, p))) {
+            Some(found) => found,
+            None => {
+                println!("Use svu completions to generate completions for your shell");
+                return Ok(());
+            }
+        };
+        if path.is_file() {
+            println!("Shell completions are installed in {}", path.to_string_lossy().green());
+            return Ok(());
+        }
+
+        let prompt = format!("Install {} completions in {}?", shell, path.to_string_lossy());
+        if self.ask(&prompt)? {
+            if let Some(dir) = path.parent() {
+                create_dir_all(dir)?;
+            }
+            let mut writer = File::create(&path)?;
+            crate::commands::completions::write_completions(shell, &mut writer);
+            println!("Installed completions in {}", path.to_string_lossy().green());
+            if shell == Shell::Zsh {
+                println!("Make sure that ~/.zfunc is in your fpath");
+            }
+        }
+        Ok(())
+    }
+}
+
+//  The standard location for user installed completions of each shell.
+fn completions_file(shell: Shell) -> Option<PathBuf> {
+    let home = PathBuf::from(std::env::var("HOME").ok()?);
+    match shell {
+        Shell::Bash => {
+            let data_home = std::env::var("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .unwrap_or(home.join(".local/share"));
+            Some(data_home.join("bash-completion/completions/svu"))
+        }
+        Shell::Zsh  => Some(home.join(".zfunc/_svu")),
+        Shell::Fish => Some(home.join(".config/fish/completions/svu.fish")),
+        _           => None,
+    }
+}
+
+fn show_orientation() {
+    println!();
+    println!("Available commands:");
+    let app = crate::app::App::command();
+    for cmd in app.get_subcommands() {
+        let about = cmd.get_about().map(|a| a.to_string()).unwrap_or_default();
+        println!("  {} {}", format!("{:<12}", cmd.get_name()).green(), about);
+    }
+    println!();
+    println!("Use svu help <command> for more information about a command.");
+}
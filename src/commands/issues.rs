@@ -0,0 +1,99 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::issues;
+use crate::svn;
+use crate::util::SvError::*;
+use regex::Regex;
+
+/// List the issues referenced by the commits in a range.
+///
+/// Issue IDs are found in the commit messages using the regular expression
+/// in the issues.pattern setting or given with --pattern.  Each issue is
+/// listed once with the revisions that refer to it, in the order that the
+/// issues were first referenced.
+///
+/// If the issues.url setting is present the URL of each issue is shown.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    eg. svu config set issues.pattern '[A-Z]+-[0-9]+'\n    \
+    svu config set issues.url 'https://issues.example.com/browse/%BUGID%'\n    \
+    svu issues -r 1200:HEAD"
+)]
+pub struct Issues {
+    /// The range of revisions to search.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long = "revision", value_name = "RANGE", default_value = "1:HEAD")]
+    range: String,
+
+    /// Regular expression that matches an issue ID.
+    ///
+    /// Overrides the issues.pattern setting.  If the expression has a
+    /// capture group then the group is used as the ID.
+    #[arg(short, long, value_name = "REGEX")]
+    pattern: Option<Regex>,
+
+    /// Display only the issue IDs.
+    #[arg(long)]
+    ids_only: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+impl Issues {
+    pub fn run(&mut self) -> Result<()> {
+        let pattern = match self.pattern.as_ref().or(issues::configured_pattern()) {
+            Some(pattern) => pattern,
+            None => {
+                let msg = "No issue pattern is configured.\n\
+                           Use `svu config set issues.pattern REGEX` or --pattern";
+                return Err(General(msg.to_string()).into());
+            }
+        };
+        let creds = crate::auth::get_credentials()?;
+        let range = svn::resolve_revision_range(&creds, &self.range, &self.path)?;
+        let mut entries = svn::log(&creds, &[self.path.as_str()], &[range.as_str()], true, None, false, false)?;
+        entries.sort_by_key(|e| e.revision.parse::<u64>().unwrap_or(0));
+
+        //  Each issue with the revisions that refer to it.
+        let mut found: Vec<(String, Vec<String>)> = Vec::new();
+        for entry in &entries {
+            for (_, id) in issues::find(pattern, &entry.msg.join("\n")) {
+                match found.iter_mut().find(|(found_id, _)| *found_id == id) {
+                    Some((_, revisions)) if revisions.contains(&entry.revision) => (),
+                    Some((_, revisions)) => revisions.push(entry.revision.clone()),
+                    None => found.push((id, vec![entry.revision.clone()])),
+                }
+            }
+        }
+
+        if self.ids_only {
+            for (id, _) in &found {
+                println!("{}", id);
+            }
+            return Ok(());
+        }
+        if found.is_empty() {
+            println!("No issues are referenced by the {} commits in {}", entries.len(), range.yellow());
+            return Ok(());
+        }
+
+        let id_len = found.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+        for (id, revisions) in &found {
+            let revisions = revisions.join(", ");
+            match issues::url(id) {
+                Some(url) => println!("{} {}  {}", format!("{:w$}", id, w = id_len).green(), url.blue(), revisions.yellow()),
+                None      => println!("{} {}", format!("{:w$}", id, w = id_len).green(), revisions.yellow()),
+            }
+        }
+        println!("\n{} issues referenced by {} commits", found.len(), entries.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use colored::*;
+use crate::svn;
+use crate::util::{self, SvError::*};
+use regex::Regex;
+use std::collections::HashMap;
+
+mod list;
+mod set;
+mod clear;
+
+/// View and edit the svn:keywords property of files.
+///
+/// svn expands the keywords named in the svn:keywords property of a
+/// file, such as $Id$ or $Revision$, when the file is checked out.
+/// These commands list, set and clear the property on many files at
+/// once.  With --recursive every file beneath a directory is included
+/// and --include and --exclude select files with glob patterns.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A pattern without a slash is matched against the file name, otherwise\n\
+    it is matched against the path.  '*' and '?' do not match a slash, and\n\
+    '**' matches any number of directories.\n\
+    eg. svu keywords set 'Id Revision' -R src --include '*.c' --include '*.h'"
+)]
+#[command(flatten_help = false)]
+pub struct Keywords {
+    #[command(subcommand)]
+    command: KeywordsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum KeywordsCommands {
+    List(list::List),
+    Set(set::Set),
+    Clear(clear::Clear),
+}
+use KeywordsCommands::*;
+
+impl Keywords {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            List(cmd)  => cmd.run(),
+            Set(cmd)   => cmd.run(),
+            Clear(cmd) => cmd.run(),
+        }
+    }
+}
+
+const KEYWORDS: &str = "svn:keywords";
+
+//  Number of paths given to each svn propset or propdel command.
+const BATCH_SIZE: usize = 100;
+
+//  The keywords that svn expands.  A custom keyword
+//  is given as NAME=FORMAT and is not checked.
+const KNOWN_KEYWORDS: [&str; 11] = [
+    "Date", "LastChangedDate", "Revision", "Rev", "LastChangedRevision",
+    "Author", "LastChangedBy", "HeadURL", "URL", "Id", "Header",
+];
+
+//  The files that a command applies to.
+#[derive(Debug, Args)]
+struct Targets {
+    /// Include every file beneath the directories.
+    ///
+    /// Otherwise only the files directly within a directory are included.
+    #[arg(short = 'R', long)]
+    recursive: bool,
+
+    /// Only include files that match this glob pattern.
+    ///
+    /// May be given multiple times.
+    #[arg(short, long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Leave out files that match this glob pattern.
+    ///
+    /// May be given multiple times.
+    #[arg(short, long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Working copy files and directories.
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+}
+
+impl Targets {
+    //  Return each selected file with its svn:keywords property value.
+    fn files(&self) -> Result<Vec<(String, Option<String>)>> {
+        let include = self.include.iter().map(|g| util::glob_regex(g)).collect::<Result<Vec<Regex>>>()?;
+        let exclude = self.exclude.iter().map(|g| util::glob_regex(g)).collect::<Result<Vec<Regex>>>()?;
+        let depth = if self.recursive { "infinity" } else { "files" };
+        let mut files = Vec::new();
+
+        for path in &self.paths {
+            let infos = svn::info_depth(path, depth)?;
+            if infos.first().is_some_and(|i| i.wc_path.is_none()) {
+                return Err(General(format!("{} is not a working copy path", path)).into());
+            }
+            let keywords: HashMap<String, String> = svn::proplist_depth(&None, path, depth)?
+                .into_iter()
+                .filter_map(|t| {
+                    let value = t.props.into_iter().find(|(name, _)| name == KEYWORDS)?.1;
+                    Some((t.path, value))
+                })
+                .collect();
+            for info in infos.into_iter().filter(|i| i.kind == "file") {
+                let name = info.path.replace('\\', "/");
+                if (include.is_empty() || include.iter().any(|r| r.is_match(&name)))
+                    && !exclude.iter().any(|r| r.is_match(&name))
+                {
+                    let value = keywords.get(&info.path).cloned();
+                    files.push((info.path, value));
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+//  Split a property value or argument into keywords.
+//  Keywords may be separated by spaces, tabs or commas.
+fn parse_keywords(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|k| !k.is_empty())
+        .map(|k| k.to_string())
+        .collect()
+}
+
+fn check_keywords(keywords: &[String]) -> Result<()> {
+    for keyword in keywords {
+        if !keyword.contains('=') && !KNOWN_KEYWORDS.contains(&keyword.as_str()) {
+            let msg = format!("Unknown keyword '{}', expected one of: {}", keyword, KNOWN_KEYWORDS.join(", "));
+            return Err(General(msg).into());
+        }
+    }
+    Ok(())
+}
+
+fn file_count(count: usize) -> String {
+    format!("{} {}", count, if count == 1 { "file" } else { "files" }).green().to_string()
+}
@@ -0,0 +1,31 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Remove svn:keywords from files.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Clear {
+    #[command(flatten)]
+    targets: Targets,
+}
+
+impl Clear {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("clear svn:keywords")?;
+        let paths: Vec<String> = self.targets
+            .files()?
+            .into_iter()
+            .filter(|(_, value)| value.is_some())
+            .map(|(path, _)| path)
+            .collect();
+        for chunk in paths.chunks(BATCH_SIZE) {
+            svn::propdel(KEYWORDS, chunk)?;
+        }
+        println!("Removed {} from {}", KEYWORDS.cyan(), file_count(paths.len()));
+        Ok(())
+    }
+}
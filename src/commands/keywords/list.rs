@@ -0,0 +1,36 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// List the keywords of each file.
+///
+/// Only the files that have svn:keywords are listed unless --all is given.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct List {
+    /// Also list the files that have no keywords.
+    #[arg(short, long)]
+    all: bool,
+
+    #[command(flatten)]
+    targets: Targets,
+}
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        let files: Vec<(String, Option<String>)> = self.targets
+            .files()?
+            .into_iter()
+            .filter(|(_, value)| self.all || value.is_some())
+            .collect();
+        let width = files.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+        for (path, value) in &files {
+            let keywords = value.as_deref().map(|v| parse_keywords(v).join(" ")).unwrap_or_default();
+            println!("{:w$}  {}", path, keywords.cyan(), w = width);
+        }
+        Ok(())
+    }
+}
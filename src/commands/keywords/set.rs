@@ -0,0 +1,71 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Set the keywords of files.
+///
+/// KEYWORDS is a list of keywords separated by spaces or commas.
+/// By default they replace the keywords of each file.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Set {
+    /// Add the keywords to those that each file already has.
+    #[arg(short, long, conflicts_with = "remove")]
+    add: bool,
+
+    /// Remove the keywords from those that each file has.
+    #[arg(short = 'd', long)]
+    remove: bool,
+
+    /// The keywords, eg. "Id Revision".
+    #[arg(value_name = "KEYWORDS")]
+    keywords: String,
+
+    #[command(flatten)]
+    targets: Targets,
+}
+
+impl Set {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("set svn:keywords")?;
+        let keywords = parse_keywords(&self.keywords);
+        check_keywords(&keywords)?;
+
+        //  Group the files by their new value so that each
+        //  svn command changes many files.  None removes it.
+        let mut changes = BTreeMap::<Option<String>, Vec<String>>::new();
+        for (path, value) in self.targets.files()? {
+            let current = value.as_deref().map(parse_keywords).unwrap_or_default();
+            let new: Vec<String> = if self.add {
+                let mut new = current.clone();
+                new.extend(keywords.iter().filter(|k| !current.contains(k)).cloned());
+                new
+            } else if self.remove {
+                current.iter().filter(|k| !keywords.contains(k)).cloned().collect()
+            } else {
+                keywords.clone()
+            };
+            if new != current {
+                let new_value = if new.is_empty() { None } else { Some(new.join(" ")) };
+                changes.entry(new_value).or_default().push(path);
+            }
+        }
+
+        let mut count = 0;
+        for (value, paths) in &changes {
+            for chunk in paths.chunks(BATCH_SIZE) {
+                match value {
+                    Some(value) => svn::propset(KEYWORDS, value, chunk)?,
+                    None => svn::propdel(KEYWORDS, chunk)?,
+                }
+            }
+            count += paths.len();
+        }
+        println!("Updated {} on {}", KEYWORDS.cyan(), file_count(count));
+        Ok(())
+    }
+}
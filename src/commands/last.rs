@@ -0,0 +1,120 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn;
+use crate::util::{self, join_paths};
+use std::collections::HashMap;
+
+/// Show the last commit that changed each entry of a directory.
+///
+/// Each file and directory is listed with the revision, author and date of
+/// the last commit that changed it, followed by the first line of that
+/// commit's message.  The last commit of the directory itself is shown first.
+/// The messages are fetched in parallel.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The number of svn commands run at once is limited by --jobs."
+)]
+pub struct Last {
+    /// List the directory as it was at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Show the age of each commit rather than its date.
+    #[arg(short, long)]
+    age: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+impl Last {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revision = match &self.revision {
+            Some(rev) => Some(svn::resolve_revision(&creds, rev, &self.path)?),
+            None => None,
+        };
+        let info = svn::info(&creds, &self.path, revision.as_deref())?;
+        let mut entries = svn::path_list(&creds, &svn::peg_path(&info.url, revision.as_deref()))?.entries;
+        //  Directories first, as in a source browser.
+        entries.sort_by(|a, b| (a.kind != "dir", &a.name).cmp(&(b.kind != "dir", &b.name)));
+
+        //  Fetch the message of each distinct revision once.
+        let mut wanted: Vec<(String, String)> = vec![(info.commit_rev.clone(), info.url.clone())];
+        for entry in &entries {
+            if !wanted.iter().any(|(rev, _)| *rev == entry.commit_rev) {
+                let url = if info.kind == "dir" { join_paths(&info.url, &entry.name) } else { info.url.clone() };
+                wanted.push((entry.commit_rev.clone(), url));
+            }
+        }
+        let messages = fetch_messages(&creds, &wanted, revision.as_deref());
+
+        let date = |d: &chrono::DateTime<chrono::Local>| if self.age { util::display_age(d) } else { util::display_svn_date(d) };
+        let subject = |rev: &str, width: usize| {
+            messages.get(rev).map(|msg| util::message_subject(msg, width)).unwrap_or_default()
+        };
+
+        let header = format!(
+            "{} {} {} {}",
+            "Last commit".bold(),
+            info.commit_rev.yellow(),
+            info.commit_author.cyan(),
+            date(&info.commit_date).magenta()
+        );
+        let width = util::terminal_width().saturating_sub(util::display_width(&header) + 1);
+        println!("{} {}", header, subject(&info.commit_rev, width));
+        println!();
+
+        let name_len = entries.iter().map(|e| e.name.len() + 1).max().unwrap_or(0);
+        let rev_len = entries.iter().map(|e| e.commit_rev.len()).max().unwrap_or(0);
+        let author_len = entries.iter().map(|e| e.commit_author.len()).max().unwrap_or(0);
+        let date_len = entries.iter().map(|e| date(&e.commit_date).len()).max().unwrap_or(0);
+        let width = util::terminal_width().saturating_sub(name_len + rev_len + author_len + date_len + 4);
+        for entry in &entries {
+            let name = if entry.kind == "dir" {
+                format!("{:w$}", format!("{}/", entry.name), w = name_len).blue()
+            } else {
+                format!("{:w$}", entry.name, w = name_len).normal()
+            };
+            println!(
+                "{} {} {} {} {}",
+                name,
+                format!("{:>w$}", entry.commit_rev, w = rev_len).yellow(),
+                format!("{:w$}", entry.commit_author, w = author_len).cyan(),
+                format!("{:w$}", date(&entry.commit_date), w = date_len).magenta(),
+                subject(&entry.commit_rev, width)
+            );
+        }
+        Ok(())
+    }
+}
+
+//  Run `svn log --limit 1` for each revision in parallel and
+//  return the message of each.  A failure leaves the message out.
+fn fetch_messages(
+    creds: &Option<Credentials>,
+    wanted: &[(String, String)],
+    peg_rev: Option<&str>,
+) -> HashMap<String, Vec<String>> {
+    use rayon::prelude::*;
+
+    wanted
+        .par_iter()
+        .filter_map(|(rev, url)| {
+            let path = svn::peg_path(url, peg_rev);
+            svn::log(creds, &[path.as_str()], &[rev.as_str()], true, Some(1), false, false)
+                .ok()
+                .and_then(|mut entries| entries.pop())
+                .map(|entry| (rev.clone(), entry.msg))
+        })
+        .collect()
+}
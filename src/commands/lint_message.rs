@@ -0,0 +1,177 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::config;
+use crate::issues;
+use crate::svn;
+use crate::util::SvError::*;
+use regex::Regex;
+use std::io::Read;
+
+/// Check commit messages against the rules in the lint settings.
+///
+/// The message is read from FILE, or from standard input when FILE is '-'.
+/// With --revision the messages of the commits in a range are checked.
+/// The rules are:
+///
+///   lint.subject-length   Maximum length of the first line
+///   lint.blank-line       A blank line must follow the first line
+///   lint.require-issue    An issue ID matching issues.pattern is required
+///   lint.subject-forbid   Regular expressions the first line must not match
+///
+/// An empty message is always a violation.  The command fails if any
+/// message breaks a rule so that it can be used in hooks and CI jobs.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    eg. svu config set lint.subject-length 72\n    \
+    svu config set lint.subject-forbid '^(Added|Fixed|Changed) ' '\\.$'\n\n\
+    To check messages before they are committed, add this to the\n\
+    .svu/hooks/pre-commit hook:\n    \
+    svu lint-message \"$SVU_MESSAGE_FILE\""
+)]
+pub struct LintMessage {
+    /// Check the messages of the commits in this range.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long = "revision", value_name = "RANGE", conflicts_with = "file")]
+    range: Option<String>,
+
+    /// Path to working copy directory or repository URL used with --revision.
+    #[arg(long, default_value = ".", requires = "range")]
+    path: String,
+
+    /// File containing the message to check.
+    #[arg(value_name = "FILE", required_unless_present = "range")]
+    file: Option<String>,
+}
+
+//  The rules read from the lint settings.
+struct Rules {
+    subject_length: Option<usize>,
+    blank_line:     bool,
+    issue_pattern:  Option<&'static Regex>,
+    subject_forbid: Vec<Regex>,
+}
+
+impl Rules {
+    fn load() -> Result<Rules> {
+        let issue_pattern = if config::get_bool("lint.require-issue").unwrap_or(false) {
+            match issues::configured_pattern() {
+                Some(pattern) => Some(pattern),
+                None => {
+                    let msg = "lint.require-issue is set but no issue pattern is configured.\n\
+                               Use `svu config set issues.pattern REGEX`";
+                    return Err(General(msg.to_string()).into());
+                }
+            }
+        } else {
+            None
+        };
+        Ok(Rules {
+            subject_length: config::get_int("lint.subject-length").map(|n| n as usize),
+            blank_line:     config::get_bool("lint.blank-line").unwrap_or(false),
+            issue_pattern,
+            //  Invalid expressions are rejected by `svu config set`.
+            subject_forbid: config::get_list("lint.subject-forbid")
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|p| Regex::new(p).ok())
+                .collect(),
+        })
+    }
+
+    //  Return a description of each rule that the message breaks.
+    fn check(&self, lines: &[String]) -> Vec<String> {
+        let lines: Vec<&str> = lines.iter().map(|l| l.trim_end()).collect();
+        let start = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+        let end = lines.iter().rposition(|l| !l.is_empty()).map(|i| i + 1).unwrap_or(start);
+        let lines = &lines[start..end];
+        let Some(subject) = lines.first() else {
+            return vec!["The message is empty".to_string()];
+        };
+
+        let mut problems = Vec::new();
+        let length = subject.chars().count();
+        match self.subject_length {
+            Some(max) if length > max => problems.push(format!("The subject is {} characters long, the limit is {}", length, max)),
+            _ => (),
+        }
+        if self.blank_line && lines.len() > 1 && !lines[1].is_empty() {
+            problems.push("The subject must be followed by a blank line".to_string());
+        }
+        for pattern in &self.subject_forbid {
+            if pattern.is_match(subject) {
+                problems.push(format!("The subject matches '{}'", pattern));
+            }
+        }
+        if let Some(pattern) = self.issue_pattern {
+            if issues::find(pattern, &lines.join("\n")).is_empty() {
+                problems.push("The message does not reference an issue".to_string());
+            }
+        }
+        problems
+    }
+}
+
+//  svn adds this line to the message in the editor and
+//  removes it, along with everything that follows it.
+const EDITOR_MARKER: &str = "--This line, and those below, will be ignored--";
+
+impl LintMessage {
+    pub fn run(&mut self) -> Result<()> {
+        let rules = Rules::load()?;
+        let messages = match (&self.range, &self.file) {
+            (Some(range), _) => {
+                let creds = crate::auth::get_credentials()?;
+                let range = svn::resolve_revision_range(&creds, range, &self.path)?;
+                svn::log(&creds, &[self.path.as_str()], &[range.as_str()], true, None, false, false)?
+                    .into_iter()
+                    .map(|entry| (Some(entry.revision), entry.msg))
+                    .collect()
+            }
+            (None, Some(file)) => vec![(None, read_message(file)?)],
+            (None, None) => unreachable!("clap requires a file or a range"),
+        };
+
+        let mut failed = 0;
+        for (revision, msg) in &messages {
+            let problems = rules.check(msg);
+            if problems.is_empty() {
+                continue;
+            }
+            failed += 1;
+            match revision {
+                Some(rev) => println!("{} {}", "Commit".bold(), rev.yellow()),
+                None => println!("{} {}", "Message".bold(), self.file.as_deref().unwrap_or_default().blue()),
+            }
+            for problem in problems {
+                println!("  {}", problem.red());
+            }
+        }
+
+        match failed {
+            0 => Ok(()),
+            1 if messages.len() == 1 => Err(General("The commit message breaks the lint rules".to_string()).into()),
+            n => Err(General(format!("{} of {} commit messages break the lint rules", n, messages.len())).into()),
+        }
+    }
+}
+
+fn read_message(file: &str) -> Result<Vec<String>> {
+    let mut text = String::new();
+    if file == "-" {
+        std::io::stdin().read_to_string(&mut text)?;
+    } else {
+        text = std::fs::read_to_string(file)
+            .map_err(|e| General(format!("Cannot read {}: {}", file, e)))?;
+    }
+    Ok(text
+        .lines()
+        .take_while(|line| line.trim() != EDITOR_MARKER)
+        .map(|line| line.to_string())
+        .collect())
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::svn;
+use crate::util;
+
+mod list;
+
+/// Lock files in the repository.
+///
+/// Locking a file prevents other users from committing changes to it
+/// until you commit or unlock it.  This is mostly used for binary files
+/// that cannot be merged.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Use `svu lock list` to see the locks held in the repository and\n\
+    `svu unlock` to release them."
+)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(subcommand_negates_reqs = true)]
+#[command(flatten_help = false)]
+pub struct Lock {
+    #[command(subcommand)]
+    command: Option<LockCommands>,
+
+    /// A comment describing why the files are locked.
+    #[arg(short, long, value_name = "MSG")]
+    message: Option<String>,
+
+    /// Take over any locks held by other users.
+    #[arg(long)]
+    steal: bool,
+
+    /// Working copy paths of the files to lock.
+    #[arg(value_name = "PATH", required = true)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum LockCommands {
+    List(list::List),
+}
+use LockCommands::*;
+
+impl Lock {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Some(List(cmd)) => cmd.run(),
+            None => {
+                svn::lock(&self.paths, self.message.as_deref(), self.steal, None)?;
+                for path in &self.paths {
+                    println!("Locked: {}", path.green());
+                }
+                Ok(())
+            }
+        }
+    }
+}
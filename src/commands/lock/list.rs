@@ -0,0 +1,61 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// List the locks held in the repository.
+///
+/// Shows the owner, date and comment of each locked file beneath
+/// a URL.  By default the branch checked out to the working copy is used.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Every file beneath the URL is examined so listing the locks\n\
+    of a large tree can take some time."
+)]
+pub struct List {
+    /// Only list the locks held by USER.
+    #[arg(short, long, value_name = "USER")]
+    user: Option<String>,
+
+    /// Path or URL of the tree to examine.
+    #[arg(value_name = "PATH|URL")]
+    path: Option<String>,
+}
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let url = match &self.path {
+            Some(path) => svn::info(&creds, path, None)?.url,
+            None => {
+                let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+                svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?.url
+            }
+        };
+
+        let mut locks: Vec<svn::RepoLock> = svn::repo_locks(&creds, &url)?
+            .into_iter()
+            .filter(|lock| self.user.as_ref().map(|u| u == &lock.owner).unwrap_or(true))
+            .collect();
+        if locks.is_empty() {
+            println!("No locks are held beneath {}", url.green());
+            return Ok(());
+        }
+        locks.sort_by(|a, b| a.rel_url.cmp(&b.rel_url));
+
+        let path_width = locks.iter().map(|l| l.rel_url.len()).max().unwrap_or(0);
+        let owner_width = locks.iter().map(|l| l.owner.len()).max().unwrap_or(0);
+        for lock in &locks {
+            println!(
+                "{} {} {} {}",
+                format!("{:<width$}", lock.rel_url, width = path_width).green(),
+                format!("{:<width$}", lock.owner, width = owner_width).cyan(),
+                util::display_svn_datetime(&lock.created).magenta(),
+                lock.comment.lines().next().unwrap_or("")
+            );
+        }
+        Ok(())
+    }
+}
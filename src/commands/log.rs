@@ -4,8 +4,11 @@ use regex::Regex;
 use anyhow::Result;
 use clap::Parser;
 use crate::auth::Credentials;
+use crate::commands::notify;
+use crate::config;
+use crate::issues;
 use crate::svn::{self, LogEntry};
-use crate::util;
+use crate::util::{self, MessageStyle, SvError::*};
 use colored::*;
 use chrono::{DateTime, Local};
 
@@ -44,6 +47,20 @@ pub struct Log {
     #[arg(short, long)]
     full: bool,
 
+    /// Reflow long message lines to fit within COLS columns.
+    ///
+    /// If COLS is omitted the width of the terminal is used.
+    /// The indentation of list items is preserved.
+    #[arg(long, value_name = "COLS", num_args = 0..=1, default_missing_value = "0")]
+    wrap: Option<usize>,
+
+    /// Display only the first line of each commit message.
+    ///
+    /// The line is truncated to fit the width given by --wrap (or the terminal)
+    /// and an ellipsis is added if any of the message has been omitted.
+    #[arg(short, long, conflicts_with_all = ["full", "verbose"])]
+    subject_only: bool,
+
     /// Shorthand for --author --time --full
     #[arg(short, long)]
     verbose: bool,
@@ -54,6 +71,13 @@ pub struct Log {
     #[arg(short, long)]
     incoming: bool,
 
+    /// Display commits made since the last time svu saw the HEAD revision
+    ///
+    /// The HEAD revision of each repository is recorded by --new and --watch
+    /// so only the revisions committed since then are requested from the server.
+    #[arg(short = 'N', long, conflicts_with_all = ["incoming", "revisions"])]
+    new: bool,
+
     /// Display the paths affected by each commit
     #[arg(short = 'p', long)]
     show_paths: bool,
@@ -75,6 +99,13 @@ pub struct Log {
     #[arg(short, long = "revision", value_name = "REV", num_args = 0.., conflicts_with = "incoming")]
     revisions: Vec<String>,
 
+    /// Display only commits made by you
+    ///
+    /// Your username is the one used to access the repository, or the one
+    /// cached by the svn client for the repository, or SVU_USERNAME.
+    #[arg(long)]
+    mine: bool,
+
     /// Disply only commits with a matching message
     ///
     /// If more than one --match argument is given then commits with messages that
@@ -82,14 +113,56 @@ pub struct Log {
     #[arg(short = 'm', long = "match", value_name = "REGEX", num_args = 0..)]
     regexes: Vec<Regex>,
 
+    /// Poll the repository and display new commits as they arrive
+    ///
+    /// The repository is checked every INTERVAL seconds (default 60).
+    /// Commits are displayed oldest first.  If --limit is given, then that
+    /// many of the most recent commits are displayed before watching begins.
+    #[arg(
+        short,
+        long,
+        value_name = "INTERVAL",
+        num_args = 0..=1,
+        default_missing_value = "60",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["incoming", "revisions", "reverse", "new"],
+    )]
+    watch: Option<u64>,
+
+    /// Run CMD for each new commit while watching
+    ///
+    /// CMD may include arguments of its own and is called with two more: a summary
+    /// containing the revision and author, and the first line of the commit message.  This is compatible with desktop
+    /// notifiers such as notify-send.  The environment variables SVU_REVISION,
+    /// SVU_AUTHOR, SVU_DATE and SVU_MESSAGE are also set.
+    #[arg(long, value_name = "CMD", requires = "watch")]
+    notify: Option<String>,
+
     /// Limit commits to specific paths [default: .]
+    ///
+    /// A path may include a peg revision such as path@1200 to refer to
+    /// an item that has since been renamed or replaced.
     #[arg(value_name = "PATH", num_args = 0..)]
     paths: Vec<String>,
+
+    //  The username used to filter commits with --mine
+    #[arg(skip)]
+    mine_user: Option<String>,
 }
 impl Log {
 
     pub fn run(&mut self) -> Result<()> {
 
+        //  Options that were not given default to the config settings.
+        if self.limit.is_none() && self.revisions.is_empty() && !self.incoming {
+            self.limit = config::get_int("log.limit").map(|n| n as u32);
+        }
+        self.author = self.author || config::get_bool("log.author").unwrap_or(false);
+        self.date = self.date || config::get_bool("log.date").unwrap_or(false);
+        self.time = self.time || config::get_bool("log.time").unwrap_or(false);
+        self.show_paths = self.show_paths || config::get_bool("log.paths").unwrap_or(false);
+        self.stop_on_copy = self.stop_on_copy || config::get_bool("log.stop-on-copy").unwrap_or(false);
+
         // The incoming flag is a shortcut for -rHEAD:BASE
         if self.incoming {
             self.revisions = vec!["HEAD:BASE".to_owned()];
@@ -104,7 +177,28 @@ impl Log {
             self.paths.push(".".to_string());
         }
 
-        self.show_results()
+        if self.mine {
+            //  The username is looked up for the repository of the first
+            //  path, which may be a URL when not in a working copy.
+            let creds = crate::auth::get_credentials()?;
+            let target = self.paths
+                .iter()
+                .find(|p| !svn::looks_like_revision_range(p))
+                .map(|p| svn::resolve_peg_path(&creds, p))
+                .transpose()?
+                .unwrap_or(".".to_string());
+            let root_url = svn::info(&creds, &target, None)?.root_url;
+            match crate::auth::current_username(&creds, &root_url) {
+                Some(username) => self.mine_user = Some(username),
+                None => return Err(General("Cannot determine your username, set SVU_USERNAME".to_string()).into()),
+            }
+        }
+
+        match self.watch {
+            Some(interval)      => self.watch_commits(interval),
+            None if self.new    => self.show_new(),
+            None                => self.show_results(),
+        }
     }
 
 
@@ -146,6 +240,44 @@ impl Log {
                 None
             };
 
+        if self.reverse {
+            entries.reverse();
+        }
+
+        self.print_entries(&entries, omit_rev.as_ref());
+        Ok(())
+    }
+
+    //  Show the commits made since the HEAD revision that was last
+    //  recorded for the repository and record the current HEAD.
+    fn show_new(&self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let path = self.paths[0].as_str();
+        let last_head = {
+            let path_info = svn::info(&creds, path, None)?;
+            svn::last_seen_head(&path_info.repo_uuid)?
+        };
+        let (repo_uuid, head) = svn::head_revision(&creds, path)?;
+
+        match last_head {
+            None => println!("Recorded HEAD revision {}, future commits will be shown by --new", head.to_string().yellow()),
+            Some(last) if last >= head => println!("No new commits since revision {}", last.to_string().yellow()),
+            Some(last) => {
+                let range = format!("{}:{}", head, last + 1);
+                let mut entries = svn::log(
+                    &creds, &self.paths, &[range], true, self.limit, self.stop_on_copy, self.show_paths
+                )?;
+                entries = self.filter_entries(entries);
+                if self.reverse {
+                    entries.reverse();
+                }
+                self.print_entries(&entries, None);
+            }
+        }
+        svn::record_head(&repo_uuid, head)
+    }
+
+    fn print_entries(&self, entries: &[LogEntry], omit_rev: Option<&String>) {
         //  Get the length of the longest revision string and author name
         let (max_rev_len, max_author_len) = entries.iter().fold((0, 0), |(max_r, max_a), e| {
             (max_r.max(e.revision.len()), max_a.max(e.author.len()))
@@ -170,22 +302,39 @@ impl Log {
             }
         };
 
-        if self.reverse {
-            entries.reverse();
-        }
+        let msg_style = MessageStyle::from_options(self.wrap, self.subject_only);
 
-        for LogEntry { revision, author, date, msg, paths } in &entries {
-            if Some(revision) != omit_rev.as_ref() {
+        for LogEntry { revision, author, date, msg, paths } in entries {
+            if Some(revision) != omit_rev {
                 let msg_1st = msg.first().map(|s| s.as_str()).unwrap_or("");
                 let prefix = build_prefix(revision, author, date);
 
                 if self.full {
                     println!("\n{}", prefix);
-                    for line in msg {
-                        println!("{}", line);
+                    for line in msg_style.render(msg) {
+                        println!("{}", issues::decorate(&line));
                     }
                 } else {
-                    println!("{} {}", prefix, msg_1st);
+                    //  The subject follows the prefix on the same line
+                    let prefix_width = util::display_width(&prefix) + 1;
+                    match msg_style {
+                        MessageStyle::Subject(width) => {
+                            let subject = util::message_subject(msg, width.saturating_sub(prefix_width));
+                            println!("{} {}", prefix, issues::decorate(&subject));
+                        }
+                        MessageStyle::Wrapped(width) => {
+                            let lines = util::wrap_message(&[msg_1st.to_string()], width.saturating_sub(prefix_width));
+                            let indent = " ".repeat(prefix_width);
+                            for (i, line) in lines.iter().enumerate() {
+                                if i == 0 {
+                                    println!("{} {}", prefix, issues::decorate(line));
+                                } else {
+                                    println!("{}{}", indent, issues::decorate(line));
+                                }
+                            }
+                        }
+                        _ => println!("{} {}", prefix, issues::decorate(msg_1st)),
+                    }
                 }
 
                 if self.show_paths {
@@ -195,8 +344,51 @@ impl Log {
                 }
             }
         }
+    }
 
-        Ok(())
+    //  Poll the repository for commits that are newer than the most recent
+    //  commit that we have seen.
+    fn watch_commits(&self, interval: u64) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let path = self.paths[0].as_str();
+        let (repo_uuid, mut last_rev) = svn::head_revision(&creds, path)?;
+        svn::record_head(&repo_uuid, last_rev)?;
+
+        if let Some(limit) = self.limit {
+            let range = format!("{}:0", last_rev);
+            let mut entries = svn::log(
+                &creds, &self.paths, &[range], true, Some(limit), self.stop_on_copy, self.show_paths
+            )?;
+            entries.reverse();
+            self.print_entries(&self.filter_entries(entries), None);
+        }
+
+        println!("Watching {} for new commits every {} seconds", self.paths.join(", ").blue(), interval);
+        util::poll(interval, || {
+            last_rev = self.check_commits(&creds, path, last_rev)?;
+            Ok(())
+        })
+    }
+
+    //  Display the commits made after last_rev and return the HEAD revision,
+    //  which is recorded for `log --new`.
+    fn check_commits(&self, creds: &Option<Credentials>, path: &str, last_rev: u64) -> Result<u64> {
+        let (repo_uuid, head_rev) = svn::head_revision(creds, path)?;
+        if head_rev > last_rev {
+            let range = format!("{}:{}", last_rev + 1, head_rev);
+            let entries = svn::log(
+                creds, &self.paths, &[range], true, None, self.stop_on_copy, self.show_paths
+            )?;
+            let entries = self.filter_entries(entries);
+            self.print_entries(&entries, None);
+            if let Some(cmd) = &self.notify {
+                for entry in &entries {
+                    notify::run_command(cmd, entry)?;
+                }
+            }
+        }
+        svn::record_head(&repo_uuid, head_rev)?;
+        Ok(head_rev.max(last_rev))
     }
 
     fn get_log_entries(&self, creds: &Option<Credentials>) -> Result<Vec<LogEntry>> {
@@ -205,12 +397,16 @@ impl Log {
 
         //  If no revisions are specified and the first 'path' looks like a revision
         //  then treat it as one, appending :0 if it does not have a range.
-        if revisions.is_empty()
-            && !paths.is_empty()
-            && svn::looks_like_revision_range(paths[0].as_str()) {
-            revisions = vec![paths.remove(0)];
+        if revisions.is_empty() && paths.first().is_some_and(|p| svn::looks_like_revision_range(p)) {
+            revisions = paths.drain(..1).collect();
         };
 
+        //  Validate any peg revisions and resolve those such as path@HEAD-2
+        let paths = paths
+            .iter()
+            .map(|p| svn::resolve_peg_path(creds, p))
+            .collect::<Result<Vec<String>>>()?;
+
         //  Resolve any revisions that contains names such as HEAD or
         // that contain rev-3 type expressions.
         let resolve_path = paths.first().map(|p| p.as_str()).unwrap_or(".");
@@ -233,17 +429,25 @@ impl Log {
             self.show_paths,
         )?;
 
-        //  Check any regular expressions entered by the user.
-        //  Include the entry if it matches at least one of them.
+        Ok(self.filter_entries(entries))
+    }
+
+    //  Check any regular expressions entered by the user.
+    //  Include the entry if it matches at least one of them.
+    //  With --mine only the user's own commits are included.
+    fn filter_entries(&self, entries: Vec<LogEntry>) -> Vec<LogEntry> {
+        let entries: Vec<LogEntry> = match &self.mine_user {
+            Some(username) => entries.into_iter().filter(|e| &e.author == username).collect(),
+            None           => entries,
+        };
         if self.regexes.is_empty() {
-            Ok(entries)
+            entries
         } else {
             let matching = |entry: &LogEntry| -> bool {
                 let msg = entry.msg.join("\n");
                 self.regexes.iter().any(|r| r.is_match(msg.as_str()))
             };
-            let new_entries = entries.into_iter().filter(matching).collect();
-            Ok(new_entries)
+            entries.into_iter().filter(matching).collect()
         }
     }
 }
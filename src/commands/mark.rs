@@ -0,0 +1,106 @@
+use anyhow::Result;
+use chrono::Local;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::{self, SvError::*};
+
+/// Create or delete a named revision bookmark.
+///
+/// Bookmarks can be used anywhere a revision is expected by prefixing
+/// the name with '@'.  eg. svu log -r @last-known-good:HEAD
+/// Deltas may also be applied to bookmarks.  eg. @release-1
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Bookmarks are saved in the .svu directory of the working copy.\n\
+    Use svu marks to list them."
+)]
+pub struct Mark {
+    /// Name of the bookmark.
+    #[arg(value_name = "NAME", value_parser = parse_mark_name)]
+    name: String,
+
+    /// The revision to bookmark.  Defaults to BASE.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(value_name = "REV", conflicts_with = "delete")]
+    revision: Option<String>,
+
+    /// Replace an existing bookmark.
+    #[arg(short, long)]
+    force: bool,
+
+    /// Delete the bookmark.
+    #[arg(short, long)]
+    delete: bool,
+}
+
+impl Mark {
+    pub fn run(&mut self) -> Result<()> {
+        let mut marks = svn::load_marks()?;
+
+        if self.delete {
+            if marks.remove(&self.name).is_none() {
+                return Err(General(format!("Revision bookmark '{}' does not exist", self.name)).into());
+            }
+            svn::save_marks(&marks)?;
+            println!("Deleted bookmark {}", format!("@{}", self.name).green());
+            return Ok(());
+        }
+
+        if marks.contains_key(&self.name) && !self.force {
+            let msg = format!("Revision bookmark '{}' already exists, use --force to replace it", self.name);
+            return Err(General(msg).into());
+        }
+
+        let creds = crate::auth::get_credentials()?;
+        let rev = self.revision.as_deref().unwrap_or("BASE");
+        let revision = svn::resolve_revision(&creds, rev, ".")?;
+        marks.insert(self.name.clone(), svn::Mark { revision: revision.clone(), date: Local::now() });
+        svn::save_marks(&marks)?;
+        println!("Bookmarked {} as {}", revision.yellow(), format!("@{}", self.name).green());
+        Ok(())
+    }
+}
+
+/// List the named revision bookmarks.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Marks {
+}
+
+impl Marks {
+    pub fn run(&mut self) -> Result<()> {
+        let marks = svn::load_marks()?;
+        if marks.is_empty() {
+            println!("There are no revision bookmarks");
+        }
+        let width = marks.keys().map(|k| k.len() + 1).max().unwrap_or(0);
+        for (name, mark) in &marks {
+            println!(
+                "{} {} {}",
+                format!("{:<width$}", format!("@{}", name), width = width).green(),
+                mark.revision.yellow(),
+                util::display_svn_datetime(&mark.date).magenta()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn parse_mark_name(arg: &str) -> Result<String> {
+    let name = arg.strip_prefix('@').unwrap_or(arg);
+    if svn::is_valid_mark_name(name) {
+        Ok(name.to_string())
+    } else {
+        let msg = "Bookmark names must start with a letter and contain only letters, digits, '_', '.' or '-' not followed by a digit";
+        Err(General(msg.to_string()).into())
+    }
+}
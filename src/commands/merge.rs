@@ -0,0 +1,87 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::{join_paths, SvError::*};
+use std::path::PathBuf;
+
+/// Merge the changes from another branch into the working copy.
+///
+/// The branch is found using the configured trunk, branch and tag
+/// prefixes (see the `prefix` command) so only its short name is needed.
+///
+/// After the merge the revisions that were merged are listed along
+/// with a suggested commit message.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A BRANCH can be the trunk prefix, a full location such as branches/8.1\n\
+    or just the name of a branch.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes.\n\n\
+    To merge a feature branch back to the trunk, check out the trunk and\n\
+    run 'svu merge BRANCH'.  Subversion detects this automatically; use\n\
+    --reintegrate only with servers older than 1.8."
+)]
+pub struct Merge {
+    /// The branch to merge from.
+    #[arg(value_name = "BRANCH")]
+    branch: String,
+
+    /// Show what would be merged but do not change the working copy.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+
+    /// Pass --reintegrate to svn merge.
+    #[arg(long)]
+    reintegrate: bool,
+}
+
+impl Merge {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.as_ref().unwrap());
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let prefixes = svn::load_prefixes()?;
+        let location = svn::resolve_location(&creds, &wc_info.root_url, &self.branch, &prefixes)?;
+        let source = format!("^/{}", location);
+        let url = join_paths(&wc_info.root_url, &location);
+
+        if source == wc_info.rel_url {
+            return Err(General(format!("Cannot merge {} into itself", source)).into());
+        }
+
+        let revisions = svn::mergeinfo_eligible(&creds, &url, &wc_root.to_string_lossy())?;
+        if revisions.is_empty() {
+            println!("There are no revisions on {} to merge into {}", source.green(), wc_info.rel_url.green());
+            return Ok(());
+        }
+        let entries = svn::log(&creds, &[url.to_string()], &revisions, true, None, false, false)?;
+
+        let output = svn::merge(&creds, &url, self.dry_run, self.reintegrate, Some(&wc_root))?;
+        print!("{}", String::from_utf8_lossy(&output));
+
+        println!();
+        let verb = if self.dry_run { "Would merge" } else { "Merged" };
+        println!("{} {} {} from {}", verb, entries.len(), if entries.len() == 1 { "revision" } else { "revisions" }, source.green());
+        for entry in &entries {
+            println!("{} {} {}", entry.revision.yellow(), entry.author.cyan(), entry.msg_1st());
+        }
+
+        if !self.dry_run {
+            let revs = entries.iter().map(|e| format!("r{}", e.revision)).collect::<Vec<String>>().join(", ");
+            println!();
+            println!("Suggested commit message:");
+            println!("{}", "-".repeat(70));
+            println!("Merge {} into {}: {}", source, wc_info.rel_url, revs);
+            println!();
+            for entry in &entries {
+                println!("r{} {}: {}", entry.revision, entry.author, entry.msg_1st());
+            }
+            println!("{}", "-".repeat(70));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::{self, join_paths, SvError::*};
+
+/// Show the revisions merged and eligible to merge between branches.
+///
+/// The number of revisions on the source branch that have been merged
+/// to the target branch and the number still eligible to be merged are
+/// displayed, followed by a list of the eligible revisions.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A BRANCH can be the trunk prefix, a full location such as branches/8.1\n\
+    or just the name of a branch.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes.\n\
+    If --to is omitted it defaults to the branch checked out to the\n\
+    working copy."
+)]
+pub struct Mergeinfo {
+    /// The branch that changes are merged from.
+    #[arg(value_name = "BRANCH")]
+    source: String,
+
+    /// The branch that changes are merged to.
+    #[arg(short, long, value_name = "BRANCH")]
+    to: Option<String>,
+
+    /// List the merged revisions instead of the eligible revisions.
+    #[arg(short, long)]
+    merged: bool,
+}
+
+impl Mergeinfo {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let prefixes = svn::load_prefixes()?;
+
+        let source = svn::resolve_location(&creds, &wc_info.root_url, &self.source, &prefixes)?;
+        let target = match &self.to {
+            Some(branch) => svn::resolve_location(&creds, &wc_info.root_url, branch, &prefixes)?,
+            None         => svn::containing_location(&wc_info.rel_url, &prefixes)?,
+        };
+        if source == target {
+            return Err(General(format!("The source and target are both ^/{}", source)).into());
+        }
+        let source_url = join_paths(&wc_info.root_url, &source);
+        let target_url = join_paths(&wc_info.root_url, &target);
+
+        let merged = svn::mergeinfo_merged(&creds, &source_url, &target_url)?;
+        let eligible = svn::mergeinfo_eligible(&creds, &source_url, &target_url)?;
+
+        println!("{:<10} {}", "Source:", format!("^/{}", source).green());
+        println!("{:<10} {}", "Target:", format!("^/{}", target).green());
+        println!("{:<10} {}", "Merged:", merged.len().to_string().yellow());
+        println!("{:<10} {}", "Eligible:", eligible.len().to_string().yellow());
+
+        let (heading, revisions) = if self.merged {
+            ("Merged revisions", &merged)
+        } else {
+            ("Eligible revisions", &eligible)
+        };
+        if revisions.is_empty() {
+            return Ok(());
+        }
+
+        println!();
+        println!("{}", heading);
+        println!("{:->70}", "-");
+        let entries = svn::log(&creds, &[source_url.to_string()], revisions, true, None, false, false)?;
+        let max_rev_len = entries.iter().map(|e| e.revision.len()).max().unwrap_or(0);
+        let max_author_len = entries.iter().map(|e| e.author.len()).max().unwrap_or(0);
+        for entry in &entries {
+            println!(
+                "{} {} {} {}",
+                format!("{:>width$}", entry.revision, width = max_rev_len).yellow(),
+                format!("{:<width$}", entry.author, width = max_author_len).cyan(),
+                util::display_svn_date(&entry.date).magenta(),
+                entry.msg_1st()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::snapshot;
+use crate::svn::{self, StatusEntry};
+use crate::util;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Detect a working copy that contains mixed revisions.
+///
+/// Reports the range of revisions present in each subtree of the
+/// working copy.  Mixed revisions can skew the base revision recorded
+/// by stash and the revisions tested by bisect.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Mixed {
+    /// Report subtrees down to this many directory levels.
+    #[arg(short, long, value_name = "DEPTH", default_value_t = 1)]
+    depth: usize,
+
+    /// Offer to update the working copy to a single revision.
+    #[arg(short, long)]
+    update: bool,
+
+    /// The revision to update to.  Defaults to the highest revision
+    /// in the working copy.
+    #[arg(short, long, value_name = "REV", requires = "update")]
+    revision: Option<String>,
+
+    /// Do not ask for confirmation before updating.
+    #[arg(short, long, requires = "update")]
+    yes: bool,
+}
+
+impl Mixed {
+    pub fn run(&mut self) -> Result<()> {
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        let entries = versioned_entries(&wc_root)?;
+
+        let (min_rev, max_rev) = match revision_span(&entries) {
+            Some((min, max)) if min != max => (min, max),
+            Some((rev, _)) => {
+                println!("Working copy is at a single revision: {}", rev.to_string().yellow());
+                return Ok(());
+            }
+            None => {
+                println!("Working copy has no committed items");
+                return Ok(());
+            }
+        };
+
+        println!("Working copy is at mixed revisions: {}", span_display(min_rev, max_rev).yellow());
+        for (subtree, (min, max)) in subtree_spans(&entries, self.depth) {
+            let span = format!("{:<width$}", span_display(min, max), width = max_rev.to_string().len() * 2 + 1);
+            if min == max {
+                println!("{} {}", span, subtree);
+            } else {
+                println!("{} {}", span.yellow(), subtree);
+            }
+        }
+
+        if self.update {
+            let revision = match &self.revision {
+                Some(rev) => svn::resolve_revision(&None, rev, &wc_root.to_string_lossy())?,
+                None      => max_rev.to_string(),
+            };
+            let prompt = format!("Update the working copy to revision {}?", revision);
+            if self.yes || util::confirm(&prompt)? {
+                snapshot::before("mixed --update")?;
+                svn::update_path(".", &revision, true, Some(&wc_root))?;
+                println!("Updated working copy to revision {}", revision.yellow());
+            }
+        } else {
+            println!("Use svu mixed --update to update the working copy to a single revision");
+        }
+        Ok(())
+    }
+}
+
+//  Print a warning if the working copy contains mixed revisions.
+//  Used by commands that record or rely on the working copy revision.
+pub fn warn_if_mixed(wc_root: &Path) -> Result<()> {
+    if let Some((min, max)) = revision_span(&versioned_entries(wc_root)?) {
+        if min != max {
+            eprintln!(
+                "{} working copy is at mixed revisions ({}), see svu mixed",
+                "warning:".yellow(),
+                span_display(min, max)
+            );
+        }
+    }
+    Ok(())
+}
+
+//  Return the status entries of all committed items in the working
+//  copy along with their working revision.  Added, unversioned and
+//  external items do not have a meaningful revision and are omitted.
+fn versioned_entries(wc_root: &Path) -> Result<Vec<(StatusEntry, u64)>> {
+    let status = svn::status_verbose(".", Some(wc_root))?;
+    Ok(status.entries
+        .into_iter()
+        .filter(|e| !matches!(e.item_status.as_str(), "unversioned" | "external" | "added" | "ignored"))
+        .filter_map(|e| {
+            let rev = e.revision.parse::<u64>().ok().filter(|r| *r > 0)?;
+            Some((e, rev))
+        })
+        .collect())
+}
+
+fn revision_span(entries: &[(StatusEntry, u64)]) -> Option<(u64, u64)> {
+    let min = entries.iter().map(|(_, r)| *r).min()?;
+    let max = entries.iter().map(|(_, r)| *r).max()?;
+    Some((min, max))
+}
+
+//  Group the entries by the directory that contains them, truncated
+//  to `depth` levels, and return the revision span of each group.
+fn subtree_spans(entries: &[(StatusEntry, u64)], depth: usize) -> BTreeMap<String, (u64, u64)> {
+    let mut spans = BTreeMap::<String, (u64, u64)>::new();
+    for (entry, rev) in entries {
+        let components: Vec<&str> = entry.path.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+        let parent_len = components.len().saturating_sub(1).min(depth);
+        let subtree = if parent_len == 0 { ".".to_string() } else { components[..parent_len].join("/") };
+        let span = spans.entry(subtree).or_insert((*rev, *rev));
+        span.0 = span.0.min(*rev);
+        span.1 = span.1.max(*rev);
+    }
+    spans
+}
+
+fn span_display(min: u64, max: u64) -> String {
+    if min == max {
+        min.to_string()
+    } else {
+        format!("{}:{}", min, max)
+    }
+}
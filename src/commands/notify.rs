@@ -0,0 +1,304 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn::{self, LogEntry};
+use crate::util::{self, SvError::*, datetime_serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Send notifications for new commits on branches.
+///
+/// Each new commit on the watched branches is sent as a JSON object to
+/// a command on its standard input and/or posted to a webhook URL.
+/// This provides commit notifications without installing a post-commit
+/// hook on the server.
+///
+/// Without --daemon the branches are checked once, which is suitable for
+/// running from cron.  The last revision notified for each branch is
+/// recorded so that no commits are missed or sent twice between runs.
+/// The first time a branch is checked only its current revision is
+/// recorded.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Use --save to store the branches, command, webhook and interval in\n\
+    .svu/notify.json so that later runs need no options.  Options given\n\
+    on the command line override the saved settings.\n\n\
+    The JSON object contains: branch, revision, author, date, message and\n\
+    paths, where each path has an action and a path."
+)]
+pub struct Notify {
+    /// A branch to watch.  May be given multiple times.
+    ///
+    /// Defaults to the branch checked out to the working copy.
+    #[arg(short, long = "branch", value_name = "BRANCH")]
+    branches: Vec<String>,
+
+    /// Command that receives each commit as JSON on its standard input.
+    #[arg(short, long, value_name = "CMD")]
+    command: Option<String>,
+
+    /// URL to which each commit is posted as JSON. (uses curl)
+    #[arg(short, long, value_name = "URL")]
+    webhook: Option<String>,
+
+    /// Keep running and check for new commits every --interval seconds.
+    #[arg(short, long)]
+    daemon: bool,
+
+    /// Number of seconds between checks when running as a daemon.
+    #[arg(short, long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    interval: Option<u64>,
+
+    /// Save the settings for future runs.
+    #[arg(long)]
+    save: bool,
+}
+
+const DEFAULT_INTERVAL: u64 = 60;
+
+//  Settings saved to .svu/notify.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct NotifyConfig {
+    branches: Vec<String>,
+    command: Option<String>,
+    webhook: Option<String>,
+    interval: Option<u64>,
+}
+
+//  The JSON object sent for each commit.
+#[derive(Debug, Serialize)]
+struct CommitPayload<'a> {
+    branch: &'a str,
+    revision: &'a str,
+    author: &'a str,
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    message: String,
+    paths: Vec<PathPayload<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct PathPayload<'a> {
+    action: &'a str,
+    path: &'a str,
+}
+
+fn config_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("notify.json"))
+}
+
+fn load_config() -> Result<NotifyConfig> {
+    let path = config_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(serde_json::from_reader(reader)?)
+    } else {
+        Ok(NotifyConfig::default())
+    }
+}
+
+fn save_config(config: &NotifyConfig) -> Result<()> {
+    util::save_json(&config_file()?, config)
+}
+
+//  The last revision notified for each branch
+//  saved to .svu/notify_state.json
+fn state_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("notify_state.json"))
+}
+
+fn load_state() -> Result<BTreeMap<String, u64>> {
+    let path = state_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(serde_json::from_reader(reader)?)
+    } else {
+        Ok(BTreeMap::new())
+    }
+}
+
+//  Used by `svu doctor` to validate the data files.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![
+        (config_file()?, load_config().map(|_| ())),
+        (state_file()?,  load_state().map(|_| ())),
+    ])
+}
+
+fn save_state(state: &BTreeMap<String, u64>) -> Result<()> {
+    util::save_json(&state_file()?, state)
+}
+
+//  Update the saved settings and state for a branch that has been renamed.
+//  Locations are relative to the repository root. eg. branches/foo
+//  Returns true if anything was updated.
+pub fn rename_branch(old_location: &str, new_location: &str) -> Result<bool> {
+    let mut updated = false;
+    if config_file()?.is_file() {
+        let mut config = load_config()?;
+        let old_name = old_location.rsplit('/').next().unwrap_or(old_location);
+        for branch in config.branches.iter_mut() {
+            let name = branch.trim_start_matches("^/").trim_matches('/');
+            if name == old_location || name == old_name {
+                *branch = new_location.to_string();
+                updated = true;
+            }
+        }
+        if updated {
+            save_config(&config)?;
+        }
+    }
+    let mut state = load_state()?;
+    if let Some(revision) = state.remove(old_location) {
+        state.insert(new_location.to_string(), revision);
+        save_state(&state)?;
+        updated = true;
+    }
+    Ok(updated)
+}
+
+impl Notify {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+
+        let saved = load_config()?;
+        let config = NotifyConfig {
+            branches: if self.branches.is_empty() { saved.branches } else { self.branches.clone() },
+            command:  self.command.clone().or(saved.command),
+            webhook:  self.webhook.clone().or(saved.webhook),
+            interval: self.interval.or(saved.interval),
+        };
+        if self.save {
+            save_config(&config)?;
+            println!("Saved notification settings");
+        }
+        if config.command.is_none() && config.webhook.is_none() {
+            return Err(General("A --command or --webhook is required".to_string()).into());
+        }
+
+        let prefixes = svn::load_prefixes()?;
+        let locations = if config.branches.is_empty() {
+            vec![wc_info.rel_url.trim_start_matches("^/").to_string()]
+        } else {
+            let mut locations = Vec::new();
+            for branch in &config.branches {
+                locations.push(svn::resolve_location(&creds, &wc_info.root_url, branch, &prefixes)?);
+            }
+            locations
+        };
+
+        if self.daemon {
+            let interval = config.interval.unwrap_or(DEFAULT_INTERVAL);
+            let names = locations.iter().map(|l| format!("^/{}", l)).collect::<Vec<String>>().join(", ");
+            println!("Watching {} for new commits every {} seconds", names.blue(), interval);
+            util::poll(interval, || check_branches(&creds, &wc_info.root_url, &locations, &config))
+        } else {
+            check_branches(&creds, &wc_info.root_url, &locations, &config)
+        }
+    }
+}
+
+//  Send notifications for any commits made on the branches
+//  since they were last checked.
+fn check_branches(creds: &Option<Credentials>, root_url: &str, locations: &[String], config: &NotifyConfig) -> Result<()> {
+    for location in locations {
+        let url = util::join_paths(root_url, location);
+        let last_changed = svn::info(creds, &url, Some("HEAD"))?.commit_rev.parse::<u64>()?;
+        let mut state = load_state()?;
+
+        match state.get(location).copied() {
+            None => {
+                state.insert(location.clone(), last_changed);
+                save_state(&state)?;
+            }
+            Some(last) if last_changed > last => {
+                let range = format!("{}:{}", last + 1, last_changed);
+                let entries = svn::log(creds, &[url.as_str()], &[range.as_str()], true, None, false, true)?;
+                for entry in &entries {
+                    dispatch(&format!("^/{}", location), entry, config)?;
+                    //  Record each commit as it is sent so that a failure
+                    //  does not cause earlier commits to be sent again.
+                    state.insert(location.clone(), entry.revision.parse::<u64>()?);
+                    save_state(&state)?;
+                }
+                state.insert(location.clone(), last_changed);
+                save_state(&state)?;
+            }
+            Some(_) => (),
+        }
+    }
+    Ok(())
+}
+
+fn dispatch(branch: &str, entry: &LogEntry, config: &NotifyConfig) -> Result<()> {
+    let payload = CommitPayload {
+        branch,
+        revision: &entry.revision,
+        author: &entry.author,
+        date: entry.date,
+        message: entry.msg.join("\n"),
+        paths: entry.paths.iter().map(|p| PathPayload { action: &p.action, path: &p.path }).collect(),
+    };
+    let json = serde_json::to_string(&payload)?;
+
+    if let Some(cmd) = &config.command {
+        let program = util::command_from_str(cmd)
+            .ok_or_else(|| General("The notify command is empty".to_string()))?;
+        send(program, &json, &format!("Notify command '{}'", cmd))?;
+    }
+    if let Some(url) = &config.webhook {
+        let mut curl = Command::new("curl");
+        curl.args(["--silent", "--show-error", "--fail", "--request", "POST"])
+            .args(["--header", "Content-Type: application/json", "--data-binary", "@-"])
+            .arg(url);
+        send(curl, &json, &format!("Webhook '{}'", url))?;
+    }
+    println!("Notified {} {}", branch, entry.revision.yellow());
+    Ok(())
+}
+
+//  Run a notify command for a commit.  The command is given a summary
+//  with the revision and author and the first line of the message as
+//  arguments, which suits desktop notifiers such as notify-send.
+pub(crate) fn run_command(cmd: &str, entry: &LogEntry) -> Result<()> {
+    let summary = format!("r{} {}", entry.revision, entry.author);
+    let status = util::command_from_str(cmd)
+        .ok_or_else(|| General("The notify command is empty".to_string()))?
+        .arg(summary)
+        .arg(entry.msg_1st())
+        .env("SVU_REVISION", &entry.revision)
+        .env("SVU_AUTHOR", &entry.author)
+        .env("SVU_DATE", util::display_svn_datetime(&entry.date))
+        .env("SVU_MESSAGE", entry.msg.join("\n"))
+        .status()?;
+    if !status.success() {
+        let msg = format!("Notify command '{}' failed for revision {}", cmd, entry.revision);
+        eprintln!("{}", msg.red());
+    }
+    Ok(())
+}
+
+//  Run the command with the JSON on its standard input.
+fn send(mut cmd: Command, json: &str, description: &str) -> Result<()> {
+    let mut child = cmd.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(json.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(General(format!("{} failed", description)).into())
+    }
+}
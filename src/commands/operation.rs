@@ -0,0 +1,257 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util::{self, SvError::*, datetime_serializer};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, remove_file};
+use std::path::{Path, PathBuf};
+
+/// Continue an operation that was interrupted by conflicts.
+///
+/// Some commands, such as `stash pop` and `stash apply`, cannot complete
+/// when conflicts occur.  The remaining steps are saved and, after the
+/// conflicts have been resolved, running `svu continue` completes them.
+/// The changes stashed by `switch --stash` and `bisect start --autostash`
+/// are reapplied the same way when the switch or `bisect reset` stops.
+///
+/// Use --abort to abandon the operation.  The changes made by the operation
+/// are reverted and any stash entry involved remains in the stash.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Continue {
+    /// Abandon the operation and revert its changes.
+    #[arg(long)]
+    abort: bool,
+}
+
+//  The steps that remain to be performed when an operation is continued.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "step")]
+pub enum Step {
+    //  Revert added items that were unversioned when they were stashed.
+    RestoreUnversioned {
+        paths: Vec<String>,
+    },
+    //  Remove an entry from the stash.
+    DropStash {
+        #[serde(rename = "patchName")]
+        patch_name: String,
+    },
+    //  Reapply and remove an entry from the stash.
+    //  This is always the last step.
+    PopStash {
+        #[serde(rename = "patchName")]
+        patch_name: String,
+    },
+}
+
+//  An operation in progress saved to .svu/operation.json
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Operation {
+    name: String,
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    steps: Vec<Step>,
+    //  Paths that are reverted when the operation is aborted.
+    #[serde(rename = "abortPaths")]
+    abort_paths: Vec<String>,
+}
+
+fn operation_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("operation.json"))
+}
+
+fn load_operation() -> Result<Option<Operation>> {
+    let path = operation_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        let operation: Operation = serde_json::from_reader(reader)?;
+        Ok(Some(operation))
+    } else {
+        Ok(None)
+    }
+}
+
+//  Used by `svu doctor` to validate the data file.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(operation_file()?, load_operation().map(|_| ()))])
+}
+
+fn save_operation(operation: &Operation) -> Result<()> {
+    util::save_json(&operation_file()?, operation)
+}
+
+//  A failed stash pop can only be retried while its operation
+//  is in progress so its record is removed with the operation.
+fn remove_operation() -> Result<()> {
+    let path = operation_file()?;
+    if path.is_file() {
+        remove_file(path)?;
+    }
+    crate::commands::stash::remove_pop_failure()
+}
+
+//  Save the remaining steps of an operation that was interrupted by conflicts.
+pub fn suspend(name: &str, steps: Vec<Step>, abort_paths: Vec<String>) -> Result<()> {
+    begin(name, steps, abort_paths)?;
+    show_stopped(name);
+    Ok(())
+}
+
+//  Save the steps of an operation before the work that precedes them is
+//  attempted, so that they can be completed with `svu continue` if that
+//  work fails.  Use complete() once the work has been done.
+pub fn begin(name: &str, steps: Vec<Step>, abort_paths: Vec<String>) -> Result<()> {
+    let operation = Operation {
+        name: name.to_string(),
+        date: Local::now(),
+        steps,
+        abort_paths,
+    };
+    save_operation(&operation)
+}
+
+//  Perform the steps saved by begin().  If there are conflicts the
+//  operation is left to be completed by `svu continue`.
+pub fn complete(wc_root: &Path) -> Result<()> {
+    let operation = load_operation()?.ok_or(General("There is no operation in progress.".to_string()))?;
+    if unresolved_conflicts(wc_root)?.is_empty() {
+        resume(wc_root)
+    } else {
+        show_stopped(&operation.name);
+        Ok(())
+    }
+}
+
+fn show_stopped(name: &str) {
+    let cmd_name: String = std::env::args().take(1).collect();
+    println!();
+    println!("{}", format!("'{}' stopped due to conflicts.", name).red());
+    println!("Resolve the conflicts and run '{} continue' to complete the operation", cmd_name);
+    println!("or run '{} continue --abort' to abandon it.", cmd_name);
+}
+
+//  Called when the work that precedes the steps saved by begin() fails.
+pub fn show_failed(name: &str) {
+    let cmd_name: String = std::env::args().take(1).collect();
+    eprintln!("'{}' did not complete.", name);
+    eprintln!("Correct the problem and run '{} continue' to complete the operation", cmd_name);
+    eprintln!("or run '{} continue --abort' to abandon it.", cmd_name);
+}
+
+//  Commands that could interfere with an operation in progress
+//  call this first.
+pub fn ensure_none_in_progress() -> Result<()> {
+    match load_operation()? {
+        Some(operation) => {
+            let cmd_name: String = std::env::args().take(1).collect();
+            let msg = format!(
+                "'{}' is in progress.\n\
+                 Use '{} continue' to complete it or '{} continue --abort' to abandon it.",
+                 operation.name, cmd_name, cmd_name
+            );
+            Err(General(msg).into())
+        }
+        None => Ok(()),
+    }
+}
+
+//  Return the name of the operation in progress, if any.
+pub fn in_progress() -> Result<Option<String>> {
+    Ok(load_operation()?.map(|operation| operation.name))
+}
+
+//  Perform the remaining steps of the operation in progress.
+//  Fails if there are conflicts that have not been resolved.
+pub fn resume(wc_root: &Path) -> Result<()> {
+    let mut operation = load_operation()?.ok_or(General("There is no operation in progress.".to_string()))?;
+    let unresolved = unresolved_conflicts(wc_root)?;
+    if !unresolved.is_empty() {
+        println!("The following conflicts have not been resolved:");
+        for path in &unresolved {
+            println!("  {}", path.red());
+        }
+        let msg = "Resolve the conflicts (and remove any .svnpatch.rej files) before continuing.";
+        return Err(General(msg.to_string()).into());
+    }
+
+    //  Save the remaining steps after each step so that
+    //  the operation can be resumed if a step fails.
+    while !operation.steps.is_empty() {
+        let step = operation.steps.remove(0);
+        if let Step::PopStash { .. } = step {
+            //  The stash pop saves its own operation if it stops
+            //  due to conflicts so this one must be finished first.
+            remove_operation()?;
+            println!("Completed '{}'", operation.name);
+            return perform_step(&step, wc_root);
+        }
+        perform_step(&step, wc_root)?;
+        save_operation(&operation)?;
+    }
+    remove_operation()?;
+    println!("Completed '{}'", operation.name);
+    Ok(())
+}
+
+impl Continue {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("continue an operation")?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        let operation = load_operation()?.ok_or(General("There is no operation in progress.".to_string()))?;
+
+        if self.abort {
+            if !operation.abort_paths.is_empty() {
+                svn::revert(&operation.abort_paths, "infinity", true, Some(&wc_root))?;
+            }
+            remove_rejects(&wc_root)?;
+            remove_operation()?;
+            println!("Aborted '{}'", operation.name);
+            if operation.steps.iter().any(|s| matches!(s, Step::PopStash { .. })) {
+                println!("The stashed changes remain in the stash");
+            }
+            return Ok(());
+        }
+
+        resume(&wc_root)
+    }
+}
+
+fn perform_step(step: &Step, wc_root: &Path) -> Result<()> {
+    match step {
+        Step::RestoreUnversioned { paths } => {
+            crate::commands::stash::restore_unversioned(paths, wc_root)
+        }
+        Step::DropStash { patch_name } => {
+            crate::commands::stash::drop_stash_entry(patch_name)
+        }
+        Step::PopStash { patch_name } => {
+            crate::commands::stash::pop_working_copy(patch_name)
+        }
+    }
+}
+
+//  Returns the paths of items that are in conflict and
+//  any reject files created by `svn patch`.
+fn unresolved_conflicts(wc_root: &Path) -> Result<Vec<String>> {
+    let status = svn::status(".", Some(wc_root))?;
+    Ok(status.entries
+        .into_iter()
+        .filter(|e| e.item_status == "conflicted" || e.path.ends_with(".svnpatch.rej"))
+        .map(|e| e.path)
+        .collect())
+}
+
+fn remove_rejects(wc_root: &Path) -> Result<()> {
+    let status = svn::status(".", Some(wc_root))?;
+    for entry in status.entries.iter().filter(|e| e.path.ends_with(".svnpatch.rej")) {
+        remove_file(wc_root.join(&entry.path))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn::{self, LogEntry};
+use crate::util::{self, join_paths};
+
+/// Show where a file came from by following its copy history.
+///
+/// The history of the path is followed back through each copy and move,
+/// across branches, until the commit that first added it.  Each location
+/// that the path has had is listed, most recent first, with the commit that
+/// copied or moved it there and the number of commits made at that location.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A copy is shown as a move when the same commit deleted the source path."
+)]
+pub struct Origin {
+    /// Start from the path as it was at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Path to working copy file or repository URL.
+    #[arg(value_name = "PATH")]
+    path: String,
+}
+
+//  One location in the history of the path.
+struct Segment {
+    path:    String,
+    commits: usize,
+    start:   LogEntry,
+    //  The copy source and whether the source was deleted.
+    from:    Option<(String, String, bool)>,
+}
+
+impl Origin {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revision = match &self.revision {
+            Some(rev) => svn::resolve_revision(&creds, rev, &self.path)?,
+            None => svn::info(&creds, &self.path, None)?.commit_rev,
+        };
+        let info = svn::info(&creds, &self.path, Some(&revision))?;
+        let mut path = info.rel_url.trim_start_matches('^').to_string();
+        let mut peg = revision;
+        let mut segments = Vec::new();
+
+        loop {
+            let url = svn::peg_path(&join_paths(&info.root_url, &path), Some(&peg));
+            let range = format!("{}:1", peg);
+            let entries = svn::log(&creds, &[url], &[range], true, None, true, true)?;
+            let Some(start) = entries.last().cloned() else { break };
+
+            //  The path, or a directory that contains it, was copied
+            //  in the first commit of this part of its history.
+            let from = start.paths
+                .iter()
+                .filter(|p| p.action == "A" || p.action == "R")
+                .filter(|p| path == p.path || path.starts_with(&format!("{}/", p.path)))
+                .max_by_key(|p| p.path.len())
+                .and_then(|p| {
+                    p.from_path.as_ref().map(|from| {
+                        let from_path = format!("{}{}", from.path, &path[p.path.len()..]);
+                        let moved = start.paths.iter().any(|d| d.action == "D" && d.path == from.path);
+                        (from_path, from.revision.clone(), moved)
+                    })
+                });
+
+            let next = from.as_ref().map(|(p, r, _)| (p.clone(), r.clone()));
+            segments.push(Segment { path: path.clone(), commits: entries.len(), start, from });
+            match next {
+                Some((from_path, from_rev)) => {
+                    path = from_path;
+                    peg = from_rev;
+                }
+                None => break,
+            }
+        }
+
+        let path_len = segments.iter().map(|s| s.path.len()).max().unwrap_or(0);
+        let rev_len = segments.iter().map(|s| s.start.revision.len()).max().unwrap_or(0);
+        for segment in &segments {
+            let commits = format!("{} {}", segment.commits, if segment.commits == 1 { "commit" } else { "commits" });
+            let event = match &segment.from {
+                Some((from_path, from_rev, moved)) => format!(
+                    "{} from {}",
+                    if *moved { "moved" } else { "copied" },
+                    format!("{}@{}", from_path, from_rev).green()
+                ),
+                None => "created".bold().to_string(),
+            };
+            println!(
+                "{}  {} {} {} {} ({})",
+                format!("{:w$}", segment.path, w = path_len).blue(),
+                format!("{:>w$}", segment.start.revision, w = rev_len).yellow(),
+                segment.start.author.cyan(),
+                util::display_svn_date(&segment.start.date).magenta(),
+                event,
+                commits
+            );
+        }
+        Ok(())
+    }
+}
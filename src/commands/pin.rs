@@ -0,0 +1,247 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::*;
+use crate::config::{self, Value};
+use crate::svn;
+use crate::util::{self, SvError::*, datetime_serializer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Record or restore the revisions of the working copy and its externals.
+///
+/// With no options, the current revision of the working copy root and of
+/// every svn:externals directory within it are recorded in the pins file
+/// (.svu/pins.toml).
+///
+/// With --restore, the working copy root and each of the recorded externals
+/// are updated back to their pinned revisions.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Pin {
+    /// Update the working copy and externals to the pinned revisions.
+    #[arg(short, long, conflicts_with = "show")]
+    restore: bool,
+
+    /// Display the pinned revisions without changing anything.
+    #[arg(short, long)]
+    show: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PinEntry {
+    path: String,
+    url: String,
+    revision: String,
+}
+
+//  Pins saved to .svu/pins.toml
+//  The path of each external is relative to the working copy root.
+//
+//  date = "2024-05-01T14:30:00.000000Z"
+//
+//  [root]
+//  url = "https://svn.example.com/repo/trunk"
+//  revision = "1234"
+//
+//  [externals."lib/common"]
+//  url = "https://svn.example.com/common/trunk"
+//  revision = "1230"
+//
+//  Earlier versions saved the pins to .svu/pins.json which is
+//  still read until the pins are saved again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Pins {
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    root: PinEntry,
+    externals: Vec<PinEntry>,
+}
+
+impl Pin {
+    pub fn run(&mut self) -> Result<()> {
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+
+        if self.restore {
+            let pins = load_pins()?;
+            restore_pins(&pins, &wc_root)
+        } else if self.show {
+            let pins = load_pins()?;
+            println!("Pinned on {}", util::display_svn_datetime(&pins.date).magenta());
+            show_pins(&pins);
+            Ok(())
+        } else {
+            let root_info = svn::info(&None, &wc_root.to_string_lossy(), None)?;
+            let root = PinEntry {
+                path: ".".to_string(),
+                url: root_info.url,
+                revision: root_info.repo_rev,
+            };
+            let pins = Pins {
+                date: Local::now(),
+                root,
+                externals: get_externals(&wc_root, "")?,
+            };
+            save_pins(&pins)?;
+            show_pins(&pins);
+            Ok(())
+        }
+    }
+}
+
+//  Update the pinned URLs after the repository has been relocated.
+//  Returns the number of URLs updated.
+pub fn relocate_urls(old_root: &str, new_root: &str) -> Result<usize> {
+    if !pins_file()?.is_file() && !legacy_pins_file()?.is_file() {
+        return Ok(0);
+    }
+    let mut pins = load_pins()?;
+    let mut count = 0;
+    for entry in std::iter::once(&mut pins.root).chain(pins.externals.iter_mut()) {
+        if entry.url == old_root || entry.url.starts_with(&format!("{}/", old_root)) {
+            entry.url = format!("{}{}", new_root, &entry.url[old_root.len()..]);
+            count += 1;
+        }
+    }
+    if count > 0 {
+        save_pins(&pins)?;
+    }
+    Ok(count)
+}
+
+fn pins_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("pins.toml"))
+}
+
+fn legacy_pins_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("pins.json"))
+}
+
+fn load_pins() -> Result<Pins> {
+    let path = pins_file()?;
+    let legacy_path = legacy_pins_file()?;
+    if path.is_file() {
+        pins_from_toml(&config::load_file(&path)?)
+            .map_err(|e| General(format!("{}: {}", path.display(), e)).into())
+    } else if legacy_path.is_file() {
+        let reader = File::open(legacy_path)?;
+        let pins: Pins = serde_json::from_reader(reader)?;
+        Ok(pins)
+    } else {
+        Err(General("No revisions have been pinned in this working copy.".to_string()).into())
+    }
+}
+
+//  Each external is a table named by its quoted path.
+fn pins_from_toml(entries: &BTreeMap<String, Value>) -> Result<Pins, String> {
+    let string = |key: &str| match entries.get(key) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        _ => Err(format!("Missing string value for '{}'", key)),
+    };
+    let root = PinEntry {
+        path: ".".to_string(),
+        url: string("root.url")?,
+        revision: string("root.revision")?,
+    };
+    let mut externals = Vec::new();
+    for key in entries.keys() {
+        if let Some(table) = key.strip_prefix("externals.").and_then(|k| k.strip_suffix(".url")) {
+            let (path, _) = config::parse_string(table)?;
+            externals.push(PinEntry {
+                path,
+                url: string(key)?,
+                revision: string(&format!("externals.{}.revision", table))?,
+            });
+        }
+    }
+    //  The tables are sorted by path so each external
+    //  comes before the externals nested within it.
+    Ok(Pins {
+        date: util::parse_svn_date(&string("date")?),
+        root,
+        externals,
+    })
+}
+
+//  Used by `svu doctor` to validate the data file.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(pins_file()?, load_pins().map(|_| ()))])
+}
+
+fn save_pins(pins: &Pins) -> Result<()> {
+    let mut entries = BTreeMap::new();
+    entries.insert("date".to_string(), Value::String(util::svn_date_to_rfc3339_string(&pins.date)));
+    entries.insert("root.url".to_string(), Value::String(pins.root.url.clone()));
+    entries.insert("root.revision".to_string(), Value::String(pins.root.revision.clone()));
+    for pin in &pins.externals {
+        let table = format!("externals.{}", config::quote(&pin.path));
+        entries.insert(format!("{}.url", table), Value::String(pin.url.clone()));
+        entries.insert(format!("{}.revision", table), Value::String(pin.revision.clone()));
+    }
+    config::save_file(&pins_file()?, &entries)?;
+    let legacy_path = legacy_pins_file()?;
+    if legacy_path.is_file() {
+        std::fs::remove_file(legacy_path)?;
+    }
+    Ok(())
+}
+
+//  Recursively collect all of the externals within the given directory.
+//  Nested externals are not reported by `svn status` on the outer working copy
+//  so we must run it again in each external directory.
+fn get_externals(wc_root: &Path, rel_dir: &str) -> Result<Vec<PinEntry>> {
+    let mut externals = Vec::<PinEntry>::new();
+    let dir = wc_root.join(rel_dir);
+    let status = svn::status(".", Some(&dir))?;
+
+    for entry in status.entries.iter().filter(|e| e.item_status == "external") {
+        let path = if rel_dir.is_empty() {
+            entry.path.clone()
+        } else {
+            util::join_paths(rel_dir, &entry.path)
+        };
+        let info = svn::info(&None, &wc_root.join(&path).to_string_lossy(), None)?;
+        externals.push(PinEntry {
+            path: path.clone(),
+            url: info.url,
+            revision: info.repo_rev,
+        });
+        externals.extend(get_externals(wc_root, &path)?);
+    }
+    Ok(externals)
+}
+
+fn show_pins(pins: &Pins) {
+    let width = pins.externals
+        .iter()
+        .fold(pins.root.path.len(), |m, p| m.max(p.path.len()));
+
+    for pin in std::iter::once(&pins.root).chain(pins.externals.iter()) {
+        println!("{:width$} [{}] {}", pin.path.blue(), pin.revision.yellow(), pin.url.green());
+    }
+}
+
+//  First update the working copy root without its externals, then update each
+//  of the externals to its pinned revision.  Externals are restored outermost first
+//  so that an external containing nested externals is updated before them.
+fn restore_pins(pins: &Pins, wc_root: &Path) -> Result<()> {
+    println!("Updating {} to [{}]", pins.root.path.blue(), pins.root.revision.yellow());
+    svn::update_path(".", &pins.root.revision, true, Some(wc_root))?;
+
+    for pin in &pins.externals {
+        if wc_root.join(&pin.path).is_dir() {
+            println!("Updating {} to [{}]", pin.path.blue(), pin.revision.yellow());
+            svn::update_path(&pin.path, &pin.revision, true, Some(wc_root))?;
+        } else {
+            let msg = format!("external {} no longer exists, skipping", pin.path);
+            println!("{}", msg.red());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::commands::stash::{apply_patch_file, reverse_patch_file};
+use crate::svn;
+use crate::util::{self, datetime_serializer, SvError::*};
+use serde::{Deserialize, Serialize};
+use std::fs::{create_dir, remove_file, File};
+use std::path::{Path, PathBuf};
+
+mod new;
+mod push;
+mod pop;
+mod refresh;
+mod series;
+
+/// Maintain a stack of patches on top of the working copy.
+///
+/// Each patch in the series has a name and is saved in .svu/patches.
+/// The patches are applied to the working copy in order with `pq push`
+/// and removed in reverse order with `pq pop`.  Changes made to the
+/// working copy are recorded in the topmost applied patch with `pq refresh`.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A typical session creates a patch with `pq new`, edits files and records\n\
+    the changes with `pq refresh`.  Repeat to build a series of patches and\n\
+    use `pq pop` and `pq push` to move up and down the series."
+)]
+#[command(flatten_help = false)]
+pub struct Pq {
+    #[command(subcommand)]
+    command: PqCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum PqCommands {
+    New(new::New),
+    Push(push::Push),
+    Pop(pop::Pop),
+    Refresh(refresh::Refresh),
+    Series(series::Series),
+}
+use PqCommands::*;
+
+impl Pq {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            New(cmd)     => cmd.run(),
+            Push(cmd)    => cmd.run(),
+            Pop(cmd)     => cmd.run(),
+            Refresh(cmd) => cmd.run(),
+            Series(cmd)  => cmd.run(),
+        }
+    }
+}
+
+//  A patch in the series.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PatchEntry {
+    name: String,
+    description: String,
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+}
+
+//  The patch series saved to .svu/patches/series.json
+//  The first `applied` patches are applied to the working copy.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct PatchSeries {
+    applied: usize,
+    patches: Vec<PatchEntry>,
+}
+
+impl PatchSeries {
+    fn top(&self) -> Option<&PatchEntry> {
+        self.applied.checked_sub(1).map(|index| &self.patches[index])
+    }
+
+    fn position(&self, name: &str) -> Result<usize> {
+        self.patches
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| General(format!("Patch {} is not in the series", name)).into())
+    }
+}
+
+fn patches_path() -> Result<PathBuf> {
+    let path = util::data_directory()?.join("patches");
+
+    if !path.is_dir() {
+        create_dir(path.as_path())?
+    }
+    Ok(path)
+}
+
+fn series_file() -> Result<PathBuf> {
+    Ok(patches_path()?.join("series.json"))
+}
+
+fn patch_file(name: &str) -> Result<PathBuf> {
+    Ok(patches_path()?.join(format!("{}.patch", name)))
+}
+
+fn load_series() -> Result<PatchSeries> {
+    let path = series_file()?;
+    if path.is_file() {
+        let reader = File::open(&path)?;
+        let series: PatchSeries = serde_json::from_reader(reader)?;
+        if series.applied > series.patches.len() {
+            let msg = format!("{} has more applied patches than patches", path.display());
+            return Err(General(msg).into());
+        }
+        Ok(series)
+    } else {
+        Ok(PatchSeries::default())
+    }
+}
+
+fn save_series(series: &PatchSeries) -> Result<()> {
+    util::save_json(&series_file()?, series)
+}
+
+//  Used by `svu doctor` to validate the data files.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(series_file()?, load_series().map(|_| ()))])
+}
+
+//  The root directory of the working copy.
+fn wc_root() -> Result<PathBuf> {
+    let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+    Ok(PathBuf::from(wc_info.wc_path.unwrap()))
+}
+
+//  An empty patch has nothing to apply and svn patch would ignore it.
+fn is_empty_patch(path: &Path) -> bool {
+    path.metadata().map(|m| m.len() == 0).unwrap_or(true)
+}
+
+//  Write the changes in the working copy to a patch file.
+fn write_patch(path: &Path, wc_root: &Path) -> Result<()> {
+    svn::create_patch(path, &["."], "infinity", &[] as &[&str], wc_root)
+}
+
+//  Apply the next unapplied patch in the series.
+//  The patch is counted as applied even when some of its changes are
+//  rejected so that they can be fixed and recorded with `pq refresh`.
+fn push_next(series: &mut PatchSeries, wc_root: &Path) -> Result<()> {
+    let patch = series.patches[series.applied].clone();
+    let path = patch_file(&patch.name)?;
+    let outcome = if is_empty_patch(&path) {
+        Default::default()
+    } else {
+        apply_patch_file(&path, wc_root, false)?
+    };
+    series.applied += 1;
+    save_series(series)?;
+    if outcome.is_clean() {
+        println!("Applied patch {}", patch.name.green());
+        Ok(())
+    } else {
+        outcome.show_summary(wc_root);
+        let msg = format!(
+            "Patch {} did not apply cleanly.  Fix the rejected changes and run `svu pq refresh`",
+            patch.name
+        );
+        Err(General(msg).into())
+    }
+}
+
+//  Remove the topmost applied patch from the working copy.
+fn pop_top(series: &mut PatchSeries, wc_root: &Path) -> Result<()> {
+    let patch = series.top().cloned().ok_or_else(|| General("No patches are applied".to_string()))?;
+    let path = patch_file(&patch.name)?;
+    if !is_empty_patch(&path) {
+        let outcome = reverse_patch_file(&path, wc_root)?;
+        if !outcome.is_clean() {
+            outcome.show_summary(wc_root);
+            let msg = format!("Patch {} could not be removed cleanly", patch.name);
+            return Err(General(msg).into());
+        }
+    }
+    series.applied -= 1;
+    save_series(series)?;
+    println!("Removed patch {}", patch.name.green());
+    Ok(())
+}
+
+fn show_top(series: &PatchSeries) {
+    match series.top() {
+        Some(patch) => println!("Now at patch {}", patch.name.green()),
+        None        => println!("No patches applied"),
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    if path.is_file() {
+        remove_file(path)?;
+    }
+    Ok(())
+}
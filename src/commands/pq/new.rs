@@ -0,0 +1,50 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use regex::Regex;
+
+/// Create a new patch above the topmost applied patch.
+///
+/// The new patch becomes the topmost applied patch.  It is empty
+/// until the changes in the working copy are recorded with `pq refresh`.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct New {
+    /// A short description of the patch.
+    #[arg(short, long)]
+    message: Option<String>,
+
+    /// Name of the new patch.
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+impl New {
+    pub fn run(&mut self) -> Result<()> {
+        wc_root()?;
+        let name_re = Regex::new(r"^[A-Za-z0-9][A-Za-z0-9._-]*$")?;
+        if !name_re.is_match(&self.name) {
+            let msg = "A patch name may only contain letters, digits, '.', '_' and '-'".to_string();
+            return Err(General(msg).into());
+        }
+        let mut series = load_series()?;
+        if series.patches.iter().any(|p| p.name == self.name) {
+            return Err(General(format!("Patch {} already exists", self.name)).into());
+        }
+
+        File::create(patch_file(&self.name)?)?;
+        let patch = PatchEntry {
+            name: self.name.clone(),
+            description: self.message.clone().unwrap_or_default(),
+            date: Local::now(),
+        };
+        series.patches.insert(series.applied, patch);
+        series.applied += 1;
+        save_series(&series)?;
+        show_top(&series);
+        Ok(())
+    }
+}
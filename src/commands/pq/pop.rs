@@ -0,0 +1,51 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Remove the topmost applied patch from the working copy.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Changes made since the last `pq refresh` are not saved in the patch.\n\
+    Run `pq refresh` first to keep them."
+)]
+pub struct Pop {
+    /// Remove all of the applied patches.
+    #[arg(short, long, conflicts_with = "name")]
+    all: bool,
+
+    /// Remove patches until NAME is the topmost applied patch.
+    #[arg(value_name = "NAME")]
+    name: Option<String>,
+}
+
+impl Pop {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("remove patches")?;
+        let wc_root = wc_root()?;
+        let mut series = load_series()?;
+        if series.applied == 0 {
+            println!("No patches applied");
+            return Ok(());
+        }
+
+        let target = match &self.name {
+            Some(name) => {
+                let index = series.position(name)?;
+                if index >= series.applied {
+                    return Err(General(format!("Patch {} is not applied", name)).into());
+                }
+                index + 1
+            }
+            None if self.all => 0,
+            None             => series.applied - 1,
+        };
+        while series.applied > target {
+            pop_top(&mut series, &wc_root)?;
+        }
+        show_top(&series);
+        Ok(())
+    }
+}
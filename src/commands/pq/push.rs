@@ -0,0 +1,52 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Apply the next patch in the series to the working copy.
+///
+/// If a patch does not apply cleanly the rejected files are listed and
+/// no further patches are applied.  The patch is still counted as
+/// applied so that the fixed changes can be recorded with `pq refresh`.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Push {
+    /// Apply all of the remaining patches.
+    #[arg(short, long, conflicts_with = "name")]
+    all: bool,
+
+    /// Apply patches until NAME is the topmost applied patch.
+    #[arg(value_name = "NAME")]
+    name: Option<String>,
+}
+
+impl Push {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("apply patches")?;
+        let wc_root = wc_root()?;
+        let mut series = load_series()?;
+        if series.applied == series.patches.len() {
+            println!("All patches are applied");
+            return Ok(());
+        }
+
+        let target = match &self.name {
+            Some(name) => {
+                let index = series.position(name)?;
+                if index < series.applied {
+                    return Err(General(format!("Patch {} is already applied", name)).into());
+                }
+                index + 1
+            }
+            None if self.all => series.patches.len(),
+            None             => series.applied + 1,
+        };
+        while series.applied < target {
+            push_next(&mut series, &wc_root)?;
+        }
+        show_top(&series);
+        Ok(())
+    }
+}
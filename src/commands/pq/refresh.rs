@@ -0,0 +1,84 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Record the changes in the working copy in the topmost applied patch.
+///
+/// The changes of the patches beneath the topmost patch are removed from
+/// the working copy while the patch is written and are then reapplied.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A patch that changes the same lines as a patch beneath it cannot be\n\
+    refreshed.  The working copy is restored and the patch is left as it was."
+)]
+pub struct Refresh;
+
+impl Refresh {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("refresh patches")?;
+        let wc_root = wc_root()?;
+        let series = load_series()?;
+        let top = series.top().cloned().ok_or_else(|| General("No patches are applied".to_string()))?;
+        let top_file = patch_file(&top.name)?;
+        let lower: Vec<PathBuf> = series.patches[..series.applied - 1]
+            .iter()
+            .map(|p| patch_file(&p.name))
+            .collect::<Result<Vec<PathBuf>>>()?
+            .into_iter()
+            .filter(|p| !is_empty_patch(p))
+            .collect();
+
+        if lower.is_empty() {
+            write_patch(&top_file, &wc_root)?;
+        } else {
+            refresh_above(&top_file, &lower, &wc_root)?;
+        }
+        println!("Refreshed patch {}", top.name.green());
+        Ok(())
+    }
+}
+
+//  Remove the lower patches, write the patch and put the lower patches back.
+//  Each lower patch is checked with a dry run before it is removed so that
+//  a failure only requires the patches already removed to be reapplied.
+fn refresh_above(top_file: &Path, lower: &[PathBuf], wc_root: &Path) -> Result<()> {
+    let mut removed: Vec<&PathBuf> = Vec::new();
+    for path in lower.iter().rev() {
+        let stdout = svn::reverse_patch(path, true, Some(wc_root))?;
+        if !is_clean_output(&stdout) {
+            reapply(&removed, wc_root)?;
+            return Err(General("The patch overlaps a patch beneath it and cannot be refreshed".to_string()).into());
+        }
+        reverse_patch_file(path, wc_root)?;
+        removed.push(path);
+    }
+    let new_file = top_file.with_extension("patch.new");
+    let written = write_patch(&new_file, wc_root);
+    if let Err(err) = reapply(&removed, wc_root) {
+        remove_if_exists(&new_file)?;
+        return Err(err);
+    }
+    written?;
+    std::fs::rename(&new_file, top_file)?;
+    Ok(())
+}
+
+//  Put back the lower patches that were removed, most recently removed first.
+fn reapply(removed: &[&PathBuf], wc_root: &Path) -> Result<()> {
+    for path in removed.iter().rev() {
+        if !apply_patch_file(path, wc_root, false)?.is_clean() {
+            return Err(General("The patches beneath the topmost patch could not be reapplied".to_string()).into());
+        }
+    }
+    Ok(())
+}
+
+//  True if the output of svn patch shows no rejected or skipped changes.
+fn is_clean_output(stdout: &[u8]) -> bool {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .all(|line| !line.starts_with('C') && !line.starts_with("Skipped"))
+}
@@ -0,0 +1,44 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// List the patches in the series.
+///
+/// Applied patches are marked with '+' and the topmost
+/// applied patch is marked with '='.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Series;
+
+impl Series {
+    pub fn run(&mut self) -> Result<()> {
+        wc_root()?;
+        let series = load_series()?;
+        if series.patches.is_empty() {
+            println!("The patch series is empty");
+            return Ok(());
+        }
+        let width = series.patches.iter().map(|p| p.name.len()).max().unwrap_or(0);
+        for (index, patch) in series.patches.iter().enumerate() {
+            let name = format!("{:<width$}", patch.name, width = width);
+            let (mark, name) = if index + 1 == series.applied {
+                ("=", name.green().bold())
+            } else if index < series.applied {
+                ("+", name.green())
+            } else {
+                (" ", name.normal())
+            };
+            println!(
+                "{} {} {} {}",
+                mark,
+                name,
+                util::display_svn_date(&patch.date).magenta(),
+                patch.description
+            );
+        }
+        Ok(())
+    }
+}
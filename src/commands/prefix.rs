@@ -46,6 +46,12 @@ pub struct Prefix {
     /// Set the trunk prefix.
     #[arg(long, value_name = "PREFIX", value_parser = parse_prefix)]
     set_trunk: Option<String>,
+
+    /// Detect the prefixes from the top level directories of the repository.
+    ///
+    /// This replaces all of the configured prefixes.
+    #[arg(long, conflicts_with_all = ["add_branch", "rem_branch", "add_tag", "rem_tag", "set_trunk"])]
+    detect: bool,
 }
 
 impl Prefix {
@@ -53,6 +59,13 @@ impl Prefix {
         let mut prefixes = svn::load_prefixes()?;
         let mut modified = false;
 
+        if self.detect {
+            let creds = crate::auth::get_credentials()?;
+            let root_url = svn::workingcopy_info()?.root_url;
+            prefixes = svn::detect_prefixes(&creds, &root_url)?;
+            modified = true;
+        }
+
         if let Some(trunk_prefix) = &self.set_trunk {
             prefixes.trunk_prefix = trunk_prefix.clone();
             modified = true;
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::{bisect, stash};
+use crate::svn;
+use crate::util;
+use serde::{Deserialize, Serialize};
+use std::env::current_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Print a short summary of the working copy for a shell prompt.
+///
+/// The summary contains the current branch, the working copy revision, a `*`
+/// if there are local modifications, and the number of stash entries and
+/// whether a bisect session is active.  Nothing is printed outside of a
+/// working copy.
+///
+/// The branch and revision are cached in .svu/prompt_cache.json until the
+/// working copy is updated.  The check for local modifications is cached for
+/// the number of seconds given by --cache.  Nothing is cached until another
+/// command has created the .svu directory.  The repository is never contacted.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    eg. in bash:  PS1='\\w $(svu prompt) \\$ '\n\
+    Use --color with shells that allow escape sequences in command output\n\
+    in the prompt, such as zsh or starship."
+)]
+pub struct Prompt {
+    /// Seconds to reuse the check for local modifications.
+    #[arg(short, long, value_name = "SECS", default_value_t = 15)]
+    cache: u64,
+
+    /// Do not check for local modifications.
+    #[arg(long)]
+    no_dirty: bool,
+
+    /// Color the output.
+    #[arg(long)]
+    color: bool,
+}
+
+//  Saved in .svu/prompt_cache.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptCache {
+    //  Modification time of .svn/wc.db when the branch was read.
+    #[serde(rename = "wcDbModified")]
+    wc_db_modified: u128,
+    branch: String,
+    revision: String,
+    //  Seconds since the epoch of the last check for local modifications.
+    #[serde(rename = "dirtyChecked")]
+    dirty_checked: u64,
+    dirty: bool,
+}
+
+impl Prompt {
+    pub fn run(&mut self) -> Result<()> {
+        //  A prompt must never show an error, so any failure
+        //  simply results in no output.
+        if let Ok(Some(summary)) = self.summary() {
+            println!("{}", summary);
+        }
+        Ok(())
+    }
+
+    fn summary(&self) -> Result<Option<String>> {
+        let wc_root = match workingcopy_root()? {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+        //  The data directory is never created here, so that a legacy .sv
+        //  directory is left to be renamed by the next svu command.
+        let data_dir = util::existing_data_directory(&wc_root);
+        let cache_file = data_dir.as_ref().map(|dir| dir.join("prompt_cache.json"));
+        let wc_db_modified = fs::metadata(wc_root.join(".svn").join("wc.db"))?
+            .modified()?
+            .duration_since(UNIX_EPOCH)?
+            .as_nanos();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        let mut cache: PromptCache = cache_file
+            .as_ref()
+            .and_then(|file| fs::read(file).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let mut changed = false;
+
+        if cache.wc_db_modified != wc_db_modified {
+            let (branch, revision) = svn::current_branch(&wc_root)?;
+            cache = PromptCache {
+                wc_db_modified,
+                branch: branch.trim_start_matches("^/").to_string(),
+                revision,
+                ..Default::default()
+            };
+            changed = true;
+        }
+
+        if !self.no_dirty && now.saturating_sub(cache.dirty_checked) >= self.cache {
+            cache.dirty = !svn::local_changes(&wc_root)?.is_empty();
+            cache.dirty_checked = now;
+            changed = true;
+        }
+
+        if let Some(cache_file) = cache_file.as_ref().filter(|_| changed) {
+            let _ = util::save_json(cache_file, &cache);
+        }
+
+        //  The output of a prompt command is never a terminal.
+        if self.color {
+            colored::control::set_override(true);
+        }
+        let dirty = if !self.no_dirty && cache.dirty { "*" } else { "" };
+        let stashes = data_dir.as_deref().map(stash::entry_count).unwrap_or(0);
+        let mut summary = if self.color {
+            format!("{}@{}{}", cache.branch.green(), cache.revision.yellow(), dirty.red())
+        } else {
+            format!("{}@{}{}", cache.branch, cache.revision, dirty)
+        };
+        if data_dir.as_deref().is_some_and(bisect::in_progress) {
+            let text = if self.color { "bisect".magenta().to_string() } else { "bisect".to_string() };
+            summary.push_str(&format!(" {}", text));
+        }
+        if stashes > 0 {
+            let text = format!("stash:{}", stashes);
+            let text = if self.color { text.cyan().to_string() } else { text };
+            summary.push_str(&format!(" {}", text));
+        }
+        Ok(Some(summary))
+    }
+}
+
+//  Find the working copy root by looking for its .svn directory
+//  so that no svn command is needed when the cache is current.
+fn workingcopy_root() -> Result<Option<PathBuf>> {
+    let cwd = current_dir()?;
+    Ok(cwd.ancestors()
+        .find(|dir| dir.join(".svn").is_dir())
+        .map(Path::to_path_buf))
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::svn;
+use crate::util::{self, SvError::*};
+
+mod get;
+mod set;
+mod del;
+mod list;
+
+/// View and edit svn properties.
+///
+/// Property values that span multiple lines can be edited
+/// in your editor with `props set`.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+#[command(flatten_help = false)]
+pub struct Props {
+    #[command(subcommand)]
+    command: PropsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum PropsCommands {
+    Get(get::Get),
+    Set(set::Set),
+    Del(del::Del),
+    List(list::List),
+}
+use PropsCommands::*;
+
+impl Props {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Get(cmd)  => cmd.run(),
+            Set(cmd)  => cmd.run(),
+            Del(cmd)  => cmd.run(),
+            List(cmd) => cmd.run(),
+        }
+    }
+}
+
+//  Print a property value.  Each line of a multi-line value is indented.
+fn show_value(value: &str, indent: &str) {
+    for line in value.lines() {
+        println!("{}{}", indent, line);
+    }
+}
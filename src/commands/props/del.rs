@@ -0,0 +1,32 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Remove a property from a working copy path.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Del {
+    /// Name of the property.
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// Working copy path.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+impl Del {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        if svn::propget(&creds, &self.name, &self.path)?.is_none() {
+            let msg = format!("Property {} is not set on {}", self.name, self.path);
+            return Err(General(msg).into());
+        }
+        svn::propdel(&self.name, &[self.path.as_str()])?;
+        println!("Deleted property {} from {}", self.name.cyan(), self.path.green());
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Display the value of a property.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Get {
+    /// Name of the property.
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// Working copy path or URL.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+impl Get {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        match svn::propget(&creds, &self.name, &self.path)? {
+            Some(value) => {
+                show_value(&value, "");
+                Ok(())
+            }
+            None => {
+                let msg = format!("Property {} is not set on {}", self.name, self.path);
+                Err(General(msg).into())
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// List the properties of a path and their values.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct List {
+    /// Also list the properties of every path beneath PATH.
+    #[arg(short = 'R', long)]
+    recursive: bool,
+
+    /// Only list the property names without their values.
+    #[arg(short, long)]
+    names: bool,
+
+    /// Working copy path or URL.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let mut targets = svn::proplist(&creds, &self.path, self.recursive)?;
+        targets.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for target in targets.iter().filter(|t| !t.props.is_empty()) {
+            println!("{}", target.path.green());
+            for (name, value) in &target.props {
+                if self.names || value.contains('\n') {
+                    println!("  {}", name.cyan());
+                    if !self.names {
+                        show_value(value, "    ");
+                    }
+                } else {
+                    println!("  {} = {}", name.cyan(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::Write;
+
+/// Set the value of a property.
+///
+/// If neither --value nor --file is given the current value is
+/// opened in your editor.  This is the easiest way to edit properties
+/// that span multiple lines such as svn:ignore and svn:externals.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The editor is taken from $VISUAL or $EDITOR (default vi).\n\
+    Saving an empty value does not delete the property, use `props del`."
+)]
+pub struct Set {
+    /// Name of the property.
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// Working copy path.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+
+    /// The new value of the property.
+    #[arg(short, long, value_name = "VALUE", conflicts_with = "file")]
+    value: Option<String>,
+
+    /// Read the new value of the property from FILE.
+    #[arg(short = 'F', long, value_name = "FILE")]
+    file: Option<String>,
+}
+
+impl Set {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let current = svn::propget(&creds, &self.name, &self.path)?;
+        let value = match (&self.value, &self.file) {
+            (Some(value), _) => value.clone(),
+            (_, Some(file))  => fs::read_to_string(file)?,
+            _                => edit_value(current.as_deref().unwrap_or(""))?,
+        };
+
+        if current.as_deref() == Some(value.as_str()) {
+            println!("Property {} was not changed", self.name.cyan());
+            return Ok(());
+        }
+        svn::propset(&self.name, &value, &[self.path.as_str()])?;
+        println!("Set property {} on {}", self.name.cyan(), self.path.green());
+        Ok(())
+    }
+}
+
+//  Open the editor on the current value and return the edited value.
+fn edit_value(current: &str) -> Result<String> {
+    let path = util::data_directory()?.join("property_value.txt");
+    let mut text = current.to_string();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    File::create(&path)?.write_all(text.as_bytes())?;
+    util::edit_file(&path)?;
+    let edited = fs::read_to_string(&path)?;
+    fs::remove_file(&path)?;
+
+    //  Single line values are stored without a trailing newline.
+    if edited.trim_end_matches('\n').contains('\n') {
+        Ok(edited)
+    } else {
+        Ok(edited.trim_end_matches('\n').to_string())
+    }
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use clap::Parser;
+use regex::Regex;
+use serde::Deserialize;
+use crate::auth::Credentials;
+use crate::svn::{self, LogEntry};
+use crate::util::{join_paths, SvError::*};
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+/// Generate Markdown release notes from the commits between two points.
+///
+/// The commits on the branch containing PATH that were made after --from up
+/// to and including --to are grouped into sections by matching their messages
+/// against a list of rules.  Each commit goes in the section of the first rule
+/// that matches, or under "Other changes" if none match.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A tag is replaced by the revision that it was copied from.\n\
+    \n\
+    The rules file is a JSON list of sections and regular expressions:\n\
+    \n    [\n      \
+    { \"section\": \"Features\", \"pattern\": \"(?i)^(feat|add)\" },\n      \
+    { \"section\": \"Fixes\",    \"pattern\": \"(?i)\\\\bfix\" },\n      \
+    { \"section\": \"\",         \"pattern\": \"^Merged \" }\n    \
+    ]\n\
+    \n\
+    Commits that match a rule with an empty section are left out.\n\
+    Without a rules file commits are sorted into Features and Fixes."
+)]
+pub struct ReleaseNotes {
+    /// The tag or revision of the previous release.
+    #[arg(short, long, value_name = "TAG|REV")]
+    from: String,
+
+    /// The tag or revision of this release.
+    #[arg(short, long, value_name = "TAG|REV", default_value = "HEAD")]
+    to: String,
+
+    /// Read the rules that assign commits to sections from FILE.
+    #[arg(long, value_name = "FILE", env = "SVU_RELEASE_NOTES_RULES")]
+    rules: Option<PathBuf>,
+
+    /// Title of the release notes.
+    #[arg(long, value_name = "TEXT")]
+    title: Option<String>,
+
+    /// Leave out the revision and author of each commit.
+    #[arg(long)]
+    no_details: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleSpec {
+    section: String,
+    pattern: String,
+}
+
+#[derive(Debug)]
+struct Rule {
+    section: String,
+    regex: Regex,
+}
+
+const OTHER_SECTION: &str = "Other changes";
+
+impl ReleaseNotes {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let rules = self.load_rules()?;
+        let from_rev = self.resolve_point(&creds, &self.from)?;
+        let to_rev = self.resolve_point(&creds, &self.to)?;
+        if to_rev.parse::<u64>()? <= from_rev.parse::<u64>()? {
+            let msg = format!("--to ({}) must be more recent than --from ({})", to_rev, from_rev);
+            return Err(General(msg).into());
+        }
+
+        let range = format!("{}:{}", to_rev, from_rev.parse::<u64>()? + 1);
+        let entries = svn::log(&creds, &[self.path.as_str()], &[range.as_str()], true, None, false, false)?;
+
+        //  Sections appear in the order of the rules.
+        let mut sections: Vec<(String, Vec<&LogEntry>)> = Vec::new();
+        for entry in &entries {
+            let message = entry.msg.join("\n");
+            let section = rules
+                .iter()
+                .find(|rule| rule.regex.is_match(&message))
+                .map(|rule| rule.section.as_str())
+                .unwrap_or(OTHER_SECTION);
+            if section.is_empty() {
+                continue;
+            }
+            match sections.iter_mut().find(|(name, _)| name == section) {
+                Some((_, commits)) => commits.push(entry),
+                None => sections.push((section.to_string(), vec![entry])),
+            }
+        }
+        let order = |name: &str| rules.iter().position(|r| r.section == name).unwrap_or(rules.len());
+        sections.sort_by_key(|(name, _)| order(name));
+
+        let title = self.title.clone().unwrap_or(format!("Changes from {} to {}", self.from, self.to));
+        println!("## {}", title);
+        if sections.is_empty() {
+            println!();
+            println!("No changes.");
+        }
+        for (name, commits) in &sections {
+            println!();
+            println!("### {}", name);
+            println!();
+            for entry in commits {
+                if self.no_details {
+                    println!("- {}", entry.msg_1st().trim());
+                } else {
+                    println!("- {} (r{}, {})", entry.msg_1st().trim(), entry.revision, entry.author);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn load_rules(&self) -> Result<Vec<Rule>> {
+        let specs = match &self.rules {
+            Some(path) => {
+                let text = read_to_string(path)?;
+                serde_json::from_str::<Vec<RuleSpec>>(&text).map_err(|e| {
+                    General(format!("Cannot read the rules file {}: {}", path.to_string_lossy(), e))
+                })?
+            }
+            None => vec![
+                RuleSpec { section: "Features".to_string(), pattern: r"(?i)^\s*(feat(ure)?|add(ed|s)?|new)\b".to_string() },
+                RuleSpec { section: "Fixes".to_string(), pattern: r"(?i)\b(fix(es|ed)?|bug)\b".to_string() },
+            ],
+        };
+        specs
+            .into_iter()
+            .map(|spec| match Regex::new(&spec.pattern) {
+                Ok(regex) => Ok(Rule { section: spec.section, regex }),
+                Err(e) => Err(General(format!("Invalid pattern for section '{}': {}", spec.section, e)).into()),
+            })
+            .collect()
+    }
+
+    //  Return the revision for a tag or revision.
+    fn resolve_point(&self, creds: &Option<Credentials>, point: &str) -> Result<String> {
+        if svn::looks_like_revision(point) {
+            return svn::resolve_revision(creds, point, &self.path);
+        }
+        let root_url = svn::info(creds, &self.path, None)?.root_url;
+        let prefixes = svn::load_prefixes()?;
+        let location = svn::resolve_location(creds, &root_url, point, &prefixes)?;
+        copied_from_revision(creds, &root_url, &location)
+    }
+}
+
+//  The revision that a tag was copied from, or the revision
+//  that created it if it was not created by a copy.
+fn copied_from_revision(creds: &Option<Credentials>, root_url: &str, location: &str) -> Result<String> {
+    let url = join_paths(root_url, location);
+    let abs_path = format!("/{}", location);
+    let entries = svn::log(creds, &[url.as_str()], &["1:HEAD"], false, Some(1), true, true)?;
+    match entries.first() {
+        Some(entry) => Ok(entry.paths
+            .iter()
+            .find(|p| p.path == abs_path)
+            .and_then(|p| p.from_path.as_ref())
+            .map(|from| from.revision.clone())
+            .unwrap_or(entry.revision.clone())),
+        None => Err(General(format!("Cannot find the revision of {}", location)).into()),
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::pin;
+use crate::svn;
+use crate::util::SvError::*;
+use std::path::Path;
+
+/// Point the working copy at a new location of its repository.
+///
+/// Use this when the repository has moved to a new server or URL.  The
+/// repository at the new URL must have the same UUID as the one the working
+/// copy was checked out from.  The URLs recorded by `svu pin` are updated.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    eg. svu relocate https://svn.example.com/repos/project"
+)]
+pub struct Relocate {
+    /// Check the new URL without relocating the working copy.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+
+    /// The URL of the root of the repository at its new location.
+    #[arg(value_name = "URL")]
+    new_root: String,
+}
+
+impl Relocate {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?;  // Make sure we are in a working copy.
+        let wc_root = wc_info.wc_path.as_deref().map(Path::new).unwrap();
+        let old_root = wc_info.root_url.trim_end_matches('/').to_string();
+        let new_root = self.new_root.trim_end_matches('/').to_string();
+
+        if new_root == old_root {
+            println!("The working copy already refers to {}", old_root.blue());
+            return Ok(());
+        }
+
+        let new_info = svn::info(&creds, &new_root, None)
+            .map_err(|_| General(format!("Cannot access the repository at {}", new_root)))?;
+        if new_info.repo_uuid != wc_info.repo_uuid {
+            let msg = format!(
+                "The repository at {} is not the same repository.\n\
+                 Its UUID is {} but the working copy has {}",
+                new_root, new_info.repo_uuid, wc_info.repo_uuid
+            );
+            return Err(General(msg).into());
+        }
+        if new_info.root_url.trim_end_matches('/') != new_root {
+            let msg = format!(
+                "{} is not the root of the repository.\nThe root is {}",
+                new_root, new_info.root_url
+            );
+            return Err(General(msg).into());
+        }
+
+        println!("Repository UUID {} matches", wc_info.repo_uuid.yellow());
+        if self.dry_run {
+            println!("Would relocate {} to {}", old_root.blue(), new_root.blue());
+            return Ok(());
+        }
+
+        svn::ensure_writable("relocate the working copy")?;
+        svn::relocate(&creds, &old_root, &new_root, Some(wc_root))?;
+        println!("Relocated {} to {}", old_root.blue(), new_root.blue());
+
+        let pins = pin::relocate_urls(&old_root, &new_root)?;
+        if pins > 0 {
+            println!("Updated {} pinned {}", pins, if pins == 1 { "URL" } else { "URLs" });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use crate::auth::Credentials;
+use crate::svn;
+use crate::util::SvError::*;
+
+/// Perform calculations on revisions.
+///
+/// Each revision is resolved relative to PATH in the same way as the
+/// revisions given to other svu commands, so HEAD-4 refers to the fifth
+/// most recent commit that affected PATH.  Distances and midpoints are
+/// measured in commits that affected PATH, not in revision numbers.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The result is written to stdout as a single line so that it can be\n\
+    captured by shell scripts.  eg.\n\
+    mid=$(svu rev-math midpoint 1200 HEAD)"
+)]
+pub struct RevMath {
+    /// The calculation to perform.
+    #[arg(value_enum)]
+    operation: Operation,
+
+    /// Revisions to operate on.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(value_name = "REV", num_args = 1.., required = true)]
+    revisions: Vec<String>,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(short, long, default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Operation {
+    /// The highest of the revisions
+    Max,
+    /// The lowest of the revisions
+    Min,
+    /// The number of commits between two revisions
+    Distance,
+    /// The commit halfway between two revisions
+    Midpoint,
+}
+
+impl RevMath {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let mut revs = Vec::new();
+        for rev in &self.revisions {
+            let resolved = svn::resolve_revision(&creds, rev, &self.path)?;
+            revs.push(resolved.parse::<u64>()?);
+        }
+
+        let result = match self.operation {
+            Operation::Max => *revs.iter().max().unwrap(),
+            Operation::Min => *revs.iter().min().unwrap(),
+            Operation::Distance | Operation::Midpoint => {
+                if revs.len() != 2 {
+                    return Err(General("This operation requires exactly two revisions".to_string()).into());
+                }
+                let (low, high) = (revs[0].min(revs[1]), revs[0].max(revs[1]));
+                let commits = self.commits_between(&creds, low, high)?;
+                if self.operation == Operation::Distance {
+                    commits.len().saturating_sub(1) as u64
+                } else {
+                    commits[commits.len() / 2]
+                }
+            }
+        };
+        println!("{}", result);
+        Ok(())
+    }
+
+    //  Return the revisions of all commits that affected the path
+    //  between low and high inclusive, in descending order.
+    fn commits_between(&self, creds: &Option<Credentials>, low: u64, high: u64) -> Result<Vec<u64>> {
+        let range = format!("{}:{}", high, low);
+        let entries = svn::log(creds, &[self.path.as_str()], &[range.as_str()], false, None, false, false)?;
+        let mut commits = Vec::new();
+        for entry in &entries {
+            commits.push(entry.revision.parse::<u64>()?);
+        }
+        if commits.is_empty() {
+            commits.push(high);
+        }
+        Ok(commits)
+    }
+}
@@ -0,0 +1,232 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::commit::{select_entries, status_color, status_letter};
+use crate::svn::{self, StatusEntry};
+use crate::util::{self, SvError::*};
+use std::env::current_dir;
+use std::fs::{remove_file, File};
+use std::io::Write;
+
+/// Revert local changes in the working copy.
+///
+/// With --interactive the local changes beneath the current directory (or
+/// beneath each PATH) are listed and only the files you select are reverted.
+/// With --patch each change to the contents of a modified file is shown and
+/// you choose the changes to revert, keeping the others.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Without --interactive or --patch each PATH is reverted along with\n\
+    everything beneath it.\n\
+    With --patch answer y to revert a change, n to keep it, a to revert it and\n\
+    the rest of the changes in the file, d to keep the rest of the file or q\n\
+    to stop.  The changes chosen before stopping are reverted.\n\
+    Added files are removed from version control but are left on disk."
+)]
+pub struct Revert {
+    /// Choose the files to revert.
+    #[arg(short, long)]
+    interactive: bool,
+
+    /// Choose the changes within each file to revert.
+    #[arg(short, long, conflicts_with = "interactive")]
+    patch: bool,
+
+    /// Paths to revert.
+    #[arg(value_name = "PATH", num_args = 0..)]
+    paths: Vec<String>,
+}
+
+//  A file's diff split into its header and hunks.
+struct FileDiff {
+    header: Vec<String>,
+    hunks: Vec<Vec<String>>,
+}
+
+impl Revert {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("revert changes")?;
+        if !self.interactive && !self.patch {
+            if self.paths.is_empty() {
+                return Err(General("Specify the paths to revert or use --interactive".to_string()).into());
+            }
+            svn::revert(&self.paths, "infinity", false, None)?;
+            for path in &self.paths {
+                println!("Reverted {}", path.blue());
+            }
+            return Ok(());
+        }
+
+        let cwd = current_dir()?;
+        let mut entries: Vec<StatusEntry> = svn::local_changes(&cwd)?
+            .into_iter()
+            .filter(|e| self.is_included(&e.path))
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        if entries.is_empty() {
+            println!("No local changes to revert");
+            return Ok(());
+        }
+
+        if self.interactive {
+            revert_files(&entries)
+        } else {
+            revert_hunks(&entries)
+        }
+    }
+
+    //  Returns true if the path is beneath one of the paths given.
+    fn is_included(&self, path: &str) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|p| {
+            let p = p.trim_start_matches("./").trim_end_matches('/');
+            p == "." || p.is_empty() || path == p || path.starts_with(&format!("{}/", p))
+        })
+    }
+}
+
+fn revert_files(entries: &[StatusEntry]) -> Result<()> {
+    let selected = match select_entries(entries, vec![false; entries.len()])? {
+        Some(selected) => selected,
+        None => return Ok(()),
+    };
+    let paths: Vec<&str> = entries.iter()
+        .zip(selected.iter())
+        .filter(|(_, s)| **s)
+        .map(|(e, _)| e.path.as_str())
+        .collect();
+    if paths.is_empty() {
+        println!("Nothing selected, nothing reverted");
+        return Ok(());
+    }
+    svn::revert(&paths, "empty", false, None)?;
+    for path in paths {
+        println!("Reverted {}", path.blue());
+    }
+    Ok(())
+}
+
+//  Ask about each hunk of the modified files and each of the other changes.
+fn revert_hunks(entries: &[StatusEntry]) -> Result<()> {
+    let mut whole_files = Vec::<&str>::new();
+    let mut patch = Vec::<String>::new();
+
+    'files: for entry in entries {
+        let diff = match entry.item_status.as_str() {
+            "modified" => split_diff(&svn::diff(&None, &[entry.path.as_str()], None, &[] as &[&str])?),
+            _ => None,
+        };
+        let diff = match diff {
+            Some(diff) => diff,
+            None => {
+                let letter = status_letter(entry).color(status_color(entry));
+                println!();
+                match ask(&format!("Revert {} {}", letter, entry.path.color(status_color(entry))), "ynq")? {
+                    'y' => whole_files.push(&entry.path),
+                    'q' => break 'files,
+                    _   => (),
+                }
+                continue;
+            }
+        };
+
+        let mut chosen = Vec::<&Vec<String>>::new();
+        let mut remaining = None;
+        for (index, hunk) in diff.hunks.iter().enumerate() {
+            let answer = match remaining {
+                Some(answer) => answer,
+                None => {
+                    println!();
+                    for line in diff.header.iter().filter(|l| l.starts_with("---") || l.starts_with("+++")) {
+                        util::print_diff_line(line);
+                    }
+                    for line in hunk {
+                        util::print_diff_line(line);
+                    }
+                    let prompt = format!("Revert this change ({}/{})", index + 1, diff.hunks.len());
+                    ask(&prompt, "ynadq")?
+                }
+            };
+            match answer {
+                'y' => chosen.push(hunk),
+                'a' => { chosen.push(hunk); remaining = Some('y'); }
+                'd' => remaining = Some('n'),
+                'q' => {
+                    add_to_patch(&mut patch, &diff, &chosen);
+                    break 'files;
+                }
+                _   => (),
+            }
+        }
+        add_to_patch(&mut patch, &diff, &chosen);
+    }
+
+    if !whole_files.is_empty() {
+        svn::revert(&whole_files, "empty", false, None)?;
+        for path in &whole_files {
+            println!("Reverted {}", path.blue());
+        }
+    }
+    if !patch.is_empty() {
+        let patch_file = util::data_directory()?.join("revert.patch");
+        File::create(&patch_file)?.write_all((patch.join("\n") + "\n").as_bytes())?;
+        let result = svn::reverse_patch(&patch_file, false, None);
+        remove_file(&patch_file)?;
+        print!("{}", String::from_utf8_lossy(&result?));
+    }
+    if whole_files.is_empty() && patch.is_empty() {
+        println!("Nothing selected, nothing reverted");
+    }
+    Ok(())
+}
+
+fn add_to_patch(patch: &mut Vec<String>, diff: &FileDiff, chosen: &[&Vec<String>]) {
+    if !chosen.is_empty() {
+        patch.extend(diff.header.iter().cloned());
+        for hunk in chosen {
+            patch.extend(hunk.iter().cloned());
+        }
+    }
+}
+
+//  Split the diff of a single file into its header and hunks.
+//  Property changes are not included.  Returns None if the
+//  diff has no hunks, such as for a binary file.
+fn split_diff(lines: &[String]) -> Option<FileDiff> {
+    let mut header = Vec::new();
+    let mut hunks: Vec<Vec<String>> = Vec::new();
+    for line in lines {
+        if line.starts_with("Property changes on:") {
+            break;
+        } else if line.starts_with("@@") {
+            hunks.push(vec![line.clone()]);
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.push(line.clone());
+        } else {
+            header.push(line.clone());
+        }
+    }
+    //  Remove the blank line that separates the hunks from the property changes.
+    if let Some(hunk) = hunks.last_mut() {
+        while hunk.last().is_some_and(|l| l.is_empty()) {
+            hunk.pop();
+        }
+    }
+    if hunks.is_empty() { None } else { Some(FileDiff { header, hunks }) }
+}
+
+//  Ask a question that is answered with one of the given letters.
+//  Anything else is taken as 'n'.
+fn ask(prompt: &str, choices: &str) -> Result<char> {
+    let help = choices.chars().map(|c| c.to_string()).collect::<Vec<String>>().join(",");
+    print!("{} [{}]? ", prompt, help);
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line)? == 0 {
+        return Ok('q');
+    }
+    let answer = line.trim().to_lowercase().chars().next().unwrap_or('n');
+    Ok(if choices.contains(answer) { answer } else { 'n' })
+}
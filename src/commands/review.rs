@@ -0,0 +1,174 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::util::{self, SvError::*};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod create;
+
+/// Create review bundles of commits and local changes.
+///
+/// A review bundle is a directory containing the unified diff of the
+/// changes, a colorized HTML page of the diff and a summary with the
+/// commit details and the list of affected files.  It can be attached
+/// to a review tool or sent by email.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+#[command(flatten_help = false)]
+pub struct Review {
+    #[command(subcommand)]
+    command: ReviewCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReviewCommands {
+    Create(create::Create),
+}
+use ReviewCommands::*;
+
+impl Review {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Create(cmd) => cmd.run(),
+        }
+    }
+}
+
+//  The contents of a review bundle.
+struct Bundle {
+    name:    String,
+    title:   String,
+    //  Label and value of each detail such as the author.
+    details: Vec<(String, String)>,
+    message: Vec<String>,
+    //  Action and path of each affected file.
+    files:   Vec<(String, String)>,
+    diff:    Vec<String>,
+}
+
+impl Bundle {
+    //  Write the bundle to a directory in output_dir and return its path.
+    fn write(&self, output_dir: &Path) -> Result<PathBuf> {
+        let dir = output_dir.join(&self.name);
+        if dir.exists() {
+            return Err(General(format!("{} already exists", dir.display())).into());
+        }
+        fs::create_dir_all(&dir)?;
+
+        let mut writer = File::create(dir.join("changes.diff"))?;
+        for line in &self.diff {
+            writeln!(writer, "{}", line)?;
+        }
+        fs::write(dir.join("summary.txt"), self.summary())?;
+        fs::write(dir.join("review.html"), self.html())?;
+        Ok(dir)
+    }
+
+    fn summary(&self) -> String {
+        let mut text = format!("{}\n{}\n", self.title, util::divider(70));
+        for (label, value) in &self.details {
+            text += &format!("{:8}: {}\n", label, value);
+        }
+        if !self.message.is_empty() {
+            text += "\n";
+            for line in &self.message {
+                text += &format!("    {}\n", line);
+            }
+        }
+        text += &format!("\n{} affected {}\n", self.files.len(), if self.files.len() == 1 { "file" } else { "files" });
+        for (action, path) in &self.files {
+            text += &format!("  {} {}\n", action, path);
+        }
+        text
+    }
+
+    fn html(&self) -> String {
+        let mut html = String::from(HTML_HEAD);
+        html += &format!("<title>{}</title>\n</head>\n<body>\n", escape(&self.title));
+        html += &format!("<h1>{}</h1>\n<table class=\"details\">\n", escape(&self.title));
+        for (label, value) in &self.details {
+            html += &format!("<tr><th>{}</th><td>{}</td></tr>\n", escape(label), escape(value));
+        }
+        html += "</table>\n";
+        if !self.message.is_empty() {
+            html += &format!("<pre class=\"message\">{}</pre>\n", escape(&self.message.join("\n")));
+        }
+        html += &format!("<h2>{} affected {}</h2>\n<ul class=\"files\">\n", self.files.len(), if self.files.len() == 1 { "file" } else { "files" });
+        for (action, path) in &self.files {
+            html += &format!("<li><span class=\"action-{}\">{}</span> {}</li>\n", escape(action), escape(action), escape(path));
+        }
+        html += "</ul>\n<h2>Diff</h2>\n<pre class=\"diff\">";
+        for line in &self.diff {
+            let class = if line.starts_with("---") || line.starts_with("+++") { "file" }
+                   else if line.starts_with("Index:") || line.starts_with("==========") { "index" }
+                   else if line.starts_with("Property changes on:") { "props" }
+                   else if line.starts_with('+') { "add" }
+                   else if line.starts_with("@@") { "hunk" }
+                   else if line.starts_with('-') { "del" }
+                   else { "" };
+            html += &format!("<span class=\"{}\">{}</span>\n", class, escape(line));
+        }
+        html += "</pre>\n</body>\n</html>\n";
+        html
+    }
+}
+
+const HTML_HEAD: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<style>
+body { font-family: sans-serif; margin: 2em; }
+table.details th { text-align: left; padding-right: 1em; }
+pre { background: #f6f8fa; padding: 1em; overflow-x: auto; }
+pre.diff span { display: block; }
+.add { background: #e6ffed; color: #22863a; }
+.del { background: #ffeef0; color: #b31d28; }
+.hunk { color: #6f42c1; }
+.file { color: #005cc5; font-weight: bold; }
+.index { color: #735c0f; font-weight: bold; }
+.props { color: #735c0f; }
+.action-A { color: #22863a; }
+.action-D { color: #b31d28; }
+.action-M { color: #005cc5; }
+ul.files { font-family: monospace; list-style: none; padding-left: 1em; }
+</style>
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+//  Replace the bundle directory with a compressed tarball.
+//  The directory is kept if the tarball cannot be created.
+fn make_tarball(dir: &Path) -> Result<PathBuf> {
+    let parent = dir.parent().unwrap_or(Path::new("."));
+    let name = dir.file_name().unwrap_or_default();
+    let tarball = parent.join(format!("{}.tar.gz", name.to_string_lossy()));
+    let output = Command::new("tar")
+        .arg("-czf")
+        .arg(&tarball)
+        .arg("-C")
+        .arg(parent)
+        .arg(name)
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            fs::remove_dir_all(dir)?;
+            Ok(tarball)
+        }
+        _ => {
+            println!("{}", "Could not create the tarball, the bundle was left as a directory".yellow());
+            Ok(dir.to_path_buf())
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use chrono::Local;
+use crate::svn;
+
+/// Create a review bundle for a commit or for local changes.
+///
+/// The bundle is written to a directory named review-rREV or review-wc
+/// followed by the date and time.  It contains changes.diff, review.html
+/// and summary.txt.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+#[command(group(clap::ArgGroup::new("source").required(true).args(["revision", "wc"])))]
+pub struct Create {
+    /// Review the changes made by this commit.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Review the local changes in the working copy.
+    #[arg(short, long)]
+    wc: bool,
+
+    /// Directory where the bundle is written.
+    #[arg(short, long, value_name = "DIR", default_value = ".")]
+    output: PathBuf,
+
+    /// Write the bundle as a compressed tarball.
+    #[arg(short, long)]
+    tar: bool,
+
+    /// Path to working copy directory or repository URL.
+    ///
+    /// With --wc only the changes below PATH are included.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+impl Create {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let bundle = match &self.revision {
+            Some(rev) => {
+                let revision = svn::resolve_revision(&creds, rev, &self.path)?;
+                self.commit_bundle(&creds, &revision)?
+            }
+            None => self.wc_bundle(&creds)?,
+        };
+
+        let mut path = bundle.write(&self.output)?;
+        if self.tar {
+            path = make_tarball(&path)?;
+        }
+        println!(
+            "Review of {} {} written to {}",
+            bundle.files.len(),
+            if bundle.files.len() == 1 { "file" } else { "files" },
+            path.display().to_string().blue()
+        );
+        Ok(())
+    }
+
+    fn commit_bundle(&self, creds: &Option<crate::auth::Credentials>, revision: &str) -> Result<Bundle> {
+        let info = svn::info(creds, &self.path, None)?;
+        let entry = svn::log(creds, &[info.root_url.as_str()], &[revision], true, Some(1), false, true)?
+            .pop()
+            .ok_or_else(|| General(format!("Revision {} was not found", revision)))?;
+        let diff = svn::change_diff(creds, &[info.root_url.as_str()], revision)?;
+        Ok(Bundle {
+            name:    format!("review-r{}", revision),
+            title:   format!("Revision {}", revision),
+            details: vec![
+                ("Revision".to_string(), revision.to_string()),
+                ("Author".to_string(), entry.author.clone()),
+                ("Date".to_string(), util::display_svn_datetime(&entry.date)),
+                ("Root".to_string(), info.root_url.clone()),
+            ],
+            message: entry.msg.clone(),
+            files:   entry.paths.iter().map(|p| (p.action.clone(), p.path.clone())).collect(),
+            diff,
+        })
+    }
+
+    fn wc_bundle(&self, creds: &Option<crate::auth::Credentials>) -> Result<Bundle> {
+        let path = Path::new(&self.path);
+        let (branch, revision) = svn::current_branch(&path.canonicalize()?)?;
+        let changes = svn::local_changes(path)?;
+        if changes.is_empty() {
+            return Err(General("There are no local changes to review".to_string()).into());
+        }
+        let diff = svn::diff(creds, &[self.path.as_str()], None, &[] as &[&str])?;
+        let action = |status: &str| match status {
+            "added"    => "A",
+            "deleted"  => "D",
+            "replaced" => "R",
+            "conflicted" => "C",
+            _          => "M",
+        };
+        let now = Local::now();
+        Ok(Bundle {
+            name:    format!("review-wc-{}", now.format("%Y%m%d-%H%M%S")),
+            title:   format!("Local changes to {}", branch),
+            details: vec![
+                ("Branch".to_string(), branch.clone()),
+                ("Base".to_string(), revision),
+                ("Date".to_string(), util::display_svn_datetime(&now)),
+            ],
+            message: vec![],
+            files:   changes.iter().map(|c| (action(&c.item_status).to_string(), c.path.clone())).collect(),
+            diff,
+        })
+    }
+}
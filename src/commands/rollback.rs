@@ -0,0 +1,85 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::impact::{self, Impact};
+use crate::svn::{self, LogEntry};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Revert the changes made by commits in the working copy.
+///
+/// The changes of each revision are reverse merged into the working
+/// copy, most recent first.  Nothing is committed, so you can review
+/// the result and commit it when you are satisfied.
+///
+/// Before any changes are made a summary of the commits being reverted
+/// is displayed.  If the number of commits or affected paths exceeds the
+/// impact thresholds you must confirm the rollback or use --yes.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The impact thresholds are set with the impact.revisions (default 5)\n\
+    and impact.paths (default 50) settings.  See `svu config`."
+)]
+pub struct Rollback {
+    /// The revisions to revert.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    /// A range such as 1200:1210 reverts every commit in the range
+    /// that affected PATH.
+    #[arg(value_name = "REV", num_args = 1.., required = true)]
+    revisions: Vec<String>,
+
+    /// Working copy directory to revert the changes in.
+    #[arg(short, long, default_value = ".")]
+    path: String,
+
+    /// Do not ask for confirmation.
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Show the impact of the rollback but do not change the working copy.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Rollback {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+
+        //  Collect the commits to revert keyed by revision number
+        //  so that duplicates are removed.
+        let mut commits = BTreeMap::<u64, LogEntry>::new();
+        for rev in &self.revisions {
+            let range = svn::resolve_revision_range(&creds, rev, &self.path)?;
+            let range = if range.contains(':') { range } else { format!("{}:{}", range, range) };
+            for entry in svn::log(&creds, &[self.path.as_str()], &[range.as_str()], true, None, false, true)? {
+                commits.insert(entry.revision.parse::<u64>()?, entry);
+            }
+        }
+
+        let entries: Vec<LogEntry> = commits.into_values().rev().collect();
+        let impact = Impact::from_log(&entries);
+        for entry in &entries {
+            println!("{} {} {}", entry.revision.yellow(), entry.author.cyan(), entry.msg_1st());
+        }
+        impact.show("reverted");
+
+        if self.dry_run || entries.is_empty() {
+            return Ok(());
+        }
+
+        let prompt = format!("Revert {} {}?", entries.len(), if entries.len() == 1 { "commit" } else { "commits" });
+        if impact::confirm(&impact, self.yes, &prompt)? {
+            for entry in &entries {
+                println!("Reverting {}", entry.revision.yellow());
+                let output = svn::reverse_merge(&creds, &entry.revision, false, Some(Path::new(&self.path)))?;
+                print!("{}", String::from_utf8_lossy(&output));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use colored::*;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use crate::svn;
+use crate::util::{self, datetime_serializer, display_svn_date};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Search the commit messages of the whole repository.
+///
+/// The messages are kept in an index in the .svu directory of the working
+/// copy.  The first search fetches the entire history of the repository,
+/// after that only the commits made since the previous search are fetched.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Matching commits are listed newest first with the first line of their\n\
+    message.  If the match is on another line that line is also shown."
+)]
+pub struct Search {
+    /// Regular expression to search for.
+    #[arg(value_name = "PATTERN")]
+    pattern: String,
+
+    /// Ignore case when matching.
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Include only commits by this author.
+    #[arg(short, long, value_name = "USER")]
+    author: Option<String>,
+
+    /// Limit the number of commits displayed.
+    #[arg(short, long, value_name = "NUM")]
+    limit: Option<usize>,
+
+    /// Search the index without fetching new commits.
+    #[arg(short = 'n', long, conflicts_with = "rebuild")]
+    no_update: bool,
+
+    /// Discard the index and fetch the entire history again.
+    #[arg(long)]
+    rebuild: bool,
+}
+
+//  The commit messages of the repository saved in .svu/search_index.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SearchIndex {
+    repo_uuid: String,
+    last_revision: u64,
+    commits: Vec<IndexedCommit>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexedCommit {
+    revision: u64,
+    author: String,
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    msg: String,
+}
+
+fn search_index_file() -> Result<PathBuf> {
+    util::data_directory().map(|dir| dir.join("search_index.json"))
+}
+
+fn load_search_index() -> Result<Option<SearchIndex>> {
+    let path = search_index_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(Some(serde_json::from_reader(reader)?))
+    } else {
+        Ok(None)
+    }
+}
+
+//  Load the data file used by this module so that
+//  `svu doctor` can report it if it is damaged.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(search_index_file()?, load_search_index().map(|_| ()))])
+}
+
+impl Search {
+    pub fn run(&mut self) -> Result<()> {
+        let regex = RegexBuilder::new(&self.pattern)
+            .case_insensitive(self.ignore_case)
+            .build()?;
+        let index = if self.no_update {
+            load_search_index()?.unwrap_or_default()
+        } else {
+            self.update_index()?
+        };
+
+        let matches = index.commits
+            .iter()
+            .rev()
+            .filter(|c| self.author.as_ref().is_none_or(|a| *a == c.author))
+            .filter(|c| regex.is_match(&c.msg))
+            .take(self.limit.unwrap_or(usize::MAX));
+        let mut count = 0;
+        for commit in matches {
+            show_commit(commit, &regex);
+            count += 1;
+        }
+        if count == 0 {
+            println!("No commits match {}", self.pattern);
+        }
+        Ok(())
+    }
+
+    //  Fetch the commits made since the index was last updated.
+    fn update_index(&self) -> Result<SearchIndex> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?;
+        let (repo_uuid, head) = svn::head_revision(&creds, &wc_info.root_url)?;
+
+        let mut index = match load_search_index()? {
+            Some(index) if !self.rebuild && index.repo_uuid == repo_uuid => index,
+            _ => SearchIndex { repo_uuid, ..Default::default() },
+        };
+        if index.last_revision < head {
+            if index.commits.is_empty() {
+                println!("Fetching the history of {}", wc_info.root_url.green());
+            }
+            let range = format!("{}:{}", index.last_revision + 1, head);
+            let entries = svn::log(&creds, &[wc_info.root_url.as_str()], &[range.as_str()], true, None, false, false)?;
+            for entry in entries {
+                index.commits.push(IndexedCommit {
+                    revision: entry.revision.parse()?,
+                    author: entry.author,
+                    date: entry.date,
+                    msg: entry.msg.join("\n"),
+                });
+            }
+            index.last_revision = head;
+            util::save_json(&search_index_file()?, &index)?;
+        }
+        Ok(index)
+    }
+}
+
+fn highlight(line: &str, regex: &Regex) -> String {
+    regex.replace_all(line, |caps: &regex::Captures| caps[0].red().bold().to_string()).to_string()
+}
+
+fn show_commit(commit: &IndexedCommit, regex: &Regex) {
+    let mut lines = commit.msg.lines();
+    let first = lines.next().unwrap_or("");
+    println!(
+        "{} {} {} {}",
+        commit.revision.to_string().yellow(),
+        commit.author.cyan(),
+        display_svn_date(&commit.date).magenta(),
+        highlight(first, regex)
+    );
+    if !regex.is_match(first) {
+        if let Some(line) = lines.find(|line| regex.is_match(line)) {
+            println!("    {}", highlight(line.trim(), regex));
+        }
+    }
+}
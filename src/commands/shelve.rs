@@ -0,0 +1,82 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use crate::svn::{self, SvnCmd};
+use crate::util::SvError::*;
+use std::path::Path;
+
+mod save;
+mod restore;
+mod list;
+
+/// Shelve local changes using svn shelving or the svu stash.
+///
+/// Subversion 1.10 and later can shelve the changes in a working copy.
+/// With those clients these commands run the svn shelving commands.
+/// With older clients the svu stash is used instead, where the shelf
+/// name is the description of the stash entry, so that the same
+/// commands work with any client.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Shelving is an experimental feature of svn.  Subversion 1.10 provides\n\
+    the shelve, unshelve and shelves commands and later versions provide\n\
+    x-shelve, x-unshelve and x-shelves."
+)]
+#[command(flatten_help = false)]
+pub struct Shelve {
+    #[command(subcommand)]
+    command: ShelveCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum ShelveCommands {
+    Save(save::Save),
+    Restore(restore::Restore),
+    List(list::List),
+}
+use ShelveCommands::*;
+
+impl Shelve {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Save(cmd)    => cmd.run(),
+            Restore(cmd) => cmd.run(),
+            List(cmd)    => cmd.run(),
+        }
+    }
+}
+
+//  Where the shelved changes are kept.
+enum Backend {
+    //  The names of the svn shelving commands.  The shelf is
+    //  removed when it is restored by giving the `drop` option.
+    Svn {
+        shelve:   &'static str,
+        unshelve: &'static str,
+        shelves:  &'static str,
+        drop:     Option<&'static str>,
+    },
+    Stash,
+}
+
+fn backend() -> Result<Backend> {
+    let backend = match svn::client_version()? {
+        (1, 10) => Backend::Svn { shelve: "shelve", unshelve: "unshelve", shelves: "shelves", drop: None },
+        (1, minor) if minor < 10 => Backend::Stash,
+        _ => Backend::Svn { shelve: "x-shelve", unshelve: "x-unshelve", shelves: "x-shelves", drop: Some("--drop") },
+    };
+    Ok(backend)
+}
+
+//  Run an svn shelving command in the working copy root.
+fn run_svn(cmd: &mut SvnCmd, wc_root: &Path) -> Result<()> {
+    let output = cmd.with_cwd(Some(wc_root)).run()?;
+    if output.status.success() {
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
@@ -0,0 +1,24 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::commands::stash;
+use std::path::PathBuf;
+
+/// Display the shelves.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct List;
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        match backend()? {
+            Backend::Svn { shelves, .. } => run_svn(&mut SvnCmd::new(shelves), &wc_root),
+            Backend::Stash => stash::list_entries(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::commands::stash;
+use std::path::PathBuf;
+
+/// Apply a shelf to the working copy and remove the shelf.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Restore {
+    /// Name of the shelf.  Defaults to the most recent shelf.
+    name: Option<String>,
+}
+
+impl Restore {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("restore shelved changes")?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        match backend()? {
+            Backend::Svn { unshelve, drop, .. } => {
+                run_svn(SvnCmd::new(unshelve).opt_arg(&drop).opt_arg(&self.name), &wc_root)
+            }
+            Backend::Stash => match stash::find_entry(self.name.as_deref())? {
+                Some(patch_name) => stash::pop_working_copy(&patch_name),
+                None => {
+                    let msg = match &self.name {
+                        Some(name) => format!("There is no shelf named '{}'", name),
+                        None => "There are no shelves".to_string(),
+                    };
+                    Err(General(msg).into())
+                }
+            },
+        }
+    }
+}
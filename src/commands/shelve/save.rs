@@ -0,0 +1,28 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::commands::stash;
+use std::path::PathBuf;
+
+/// Shelve the local changes and revert the working copy.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Save {
+    /// Name of the shelf.
+    name: String,
+}
+
+impl Save {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("shelve changes")?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        match backend()? {
+            Backend::Svn { shelve, .. } => run_svn(SvnCmd::new(shelve).arg(&self.name).arg("."), &wc_root),
+            Backend::Stash => stash::push_working_copy(Some(self.name.clone())).map(|_| ()),
+        }
+    }
+}
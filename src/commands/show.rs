@@ -2,17 +2,21 @@
 use anyhow::Result;
 use clap::Parser;
 use crate::svn;
-use crate::util;
+use crate::util::{self, MessageStyle, SvError::*};
 
 /// Show the details of a commit
 #[derive(Debug, Parser)]
 #[command(
+    visible_alias = "sh",
     author,
     help_template = crate::app::HELP_TEMPLATE,
     after_help = "\
     The revision defaults to the current working copy revision.\n\
     If no revision is given and the first path looks like a revision it will be treated as one.\n\
-    If no path is given it defaults to the current working copy directory."
+    If no path is given it defaults to the current working copy directory.\n\n\
+    When paths are given the changed paths and the diff are limited to\n\
+    the items within those paths.  Use -- to separate the paths from the\n\
+    other arguments: svu show 1234 -- src/ui docs"
 )]
 pub struct Show {
     /// The commit revision.
@@ -35,7 +39,25 @@ pub struct Show {
     #[arg(short, long)]
     no_message: bool,
 
+    /// Reflow long message lines to fit within COLS columns.
+    ///
+    /// If COLS is omitted the width of the terminal is used.
+    /// The indentation of list items is preserved.
+    #[arg(long, value_name = "COLS", num_args = 0..=1, default_missing_value = "0")]
+    wrap: Option<usize>,
+
+    /// Display only the first line of the commit message.
+    ///
+    /// An ellipsis is added if the message has been truncated.
+    #[arg(short, long, conflicts_with = "no_message")]
+    subject_only: bool,
+
     /// Limit commits to specific paths [default: .]
+    ///
+    /// The changed paths and diff are limited to these paths.
+    ///
+    /// A path may include a peg revision such as path@1200 to refer to
+    /// an item that has since been renamed or replaced.
     #[arg(value_name = "PATH", num_args = 0..)]
     paths: Vec<String>,
 }
@@ -52,12 +74,21 @@ impl Show {
             self.revision = Some(paths.remove(0).to_string());
         }
 
+        //  The changed paths are only filtered when paths are given.
+        let filter_paths = !paths.is_empty();
         if paths.is_empty() {
             paths.push(".");
         }
 
         let creds = crate::auth::get_credentials()?;
 
+        //  Validate any peg revisions and resolve those such as path@HEAD-2
+        let resolved_paths = paths
+            .iter()
+            .map(|p| svn::resolve_peg_path(&creds, p))
+            .collect::<Result<Vec<String>>>()?;
+        let paths: Vec<&str> = resolved_paths.iter().map(|p| p.as_str()).collect();
+
         //  Resolve the revision if necessary and coerce it into
         //  a vector
         //  In some cases when the revision is PREV, it may not produce a log entry
@@ -71,11 +102,28 @@ impl Show {
             rev_vector.push(resolved_rev.as_str());
         }
 
-        let log_entry = &svn::log(&creds, &paths, &rev_vector, true, Some(1), false, true)?[0];
-        util::show_commit(log_entry, !self.no_message, self.show_paths);
+        let mut log_entry = svn::log(&creds, &paths, &rev_vector, true, Some(1), false, true)?
+            .pop()
+            .ok_or_else(|| General(format!("No commit found for {}", paths.join(" "))))?;
+        if filter_paths {
+            //  The repository paths of the items, eg. /trunk/src
+            let prefixes = paths
+                .iter()
+                .map(|p| Ok(svn::info(&creds, p, None)?.rel_url.trim_start_matches('^').trim_end_matches('/').to_string()))
+                .collect::<Result<Vec<String>>>()?;
+            log_entry.paths.retain(|lp| {
+                prefixes.iter().any(|prefix| lp.path == *prefix || lp.path.starts_with(&format!("{}/", prefix)))
+            });
+        }
+        let msg_style = if self.no_message {
+            MessageStyle::Hidden
+        } else {
+            MessageStyle::from_options(self.wrap, self.subject_only)
+        };
+        util::show_commit(&log_entry, msg_style, self.show_paths);
         if self.show_diff {
             println!();
-            let lines = svn::change_diff(&creds, paths[0], &log_entry.revision)?;
+            let lines = svn::change_diff(&creds, &paths, &log_entry.revision)?;
             for line in &lines {
                 util::print_diff_line(line);
             }
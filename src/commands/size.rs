@@ -0,0 +1,102 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+use crate::util;
+use std::collections::HashMap;
+
+/// Report the size of the files in a branch or directory.
+///
+/// The files are listed with `svn list --recursive` and their sizes are
+/// totaled for each directory.  The largest files and the total size of
+/// all of the files are also reported.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The size of a directory includes all of the files beneath it.\n\
+    Sizes are those of the files in the repository, which does not\n\
+    reflect how the repository stores them."
+)]
+pub struct Size {
+    /// Report the sizes at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Report directories down to this many levels.
+    #[arg(short, long, value_name = "DEPTH", default_value_t = 1)]
+    depth: usize,
+
+    /// Number of directories and files to report.
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+    top: usize,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+impl Size {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revision = self.revision
+            .as_ref()
+            .map(|r| svn::resolve_revision(&creds, r, &self.path))
+            .transpose()?;
+        let list = svn::path_list_recursive(&creds, &self.path, revision.as_deref())?;
+
+        let files: Vec<(String, u64)> = list.entries
+            .into_iter()
+            .filter(|e| e.kind == "file")
+            .map(|e| (e.name, e.size.unwrap_or(0)))
+            .collect();
+        let total: u64 = files.iter().map(|(_, size)| size).sum();
+
+        let mut directories = HashMap::<String, u64>::new();
+        for (name, size) in &files {
+            let components: Vec<&str> = name.split('/').collect();
+            let dir_levels = components.len() - 1;
+            if dir_levels == 0 {
+                *directories.entry("(top level files)".to_string()).or_default() += size;
+            }
+            for level in 1..=dir_levels.min(self.depth) {
+                *directories.entry(components[..level].join("/")).or_default() += size;
+            }
+        }
+        let mut directories: Vec<(String, u64)> = directories.into_iter().collect();
+        directories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        directories.truncate(self.top);
+
+        let mut largest = files.clone();
+        largest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        largest.truncate(self.top);
+
+        if !directories.is_empty() {
+            println!("{}", "Largest directories".bold());
+            show_sizes(&directories);
+            println!();
+        }
+        if !largest.is_empty() {
+            println!("{}", "Largest files".bold());
+            show_sizes(&largest);
+            println!();
+        }
+        println!(
+            "Total: {} in {} {}",
+            util::display_size(total).yellow(),
+            files.len(),
+            if files.len() == 1 { "file" } else { "files" }
+        );
+        Ok(())
+    }
+}
+
+fn show_sizes(sizes: &[(String, u64)]) {
+    for (name, size) in sizes {
+        println!("  {:>9}  {}", util::display_size(*size).yellow(), name);
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use clap::{Args, Parser, Subcommand};
+use colored::*;
+use crate::svn;
+use crate::util::{self, datetime_serializer, SvError::*};
+use serde::{Deserialize, Serialize};
+use std::fs::{create_dir, remove_file, File};
+use std::path::{Path, PathBuf};
+
+mod enable;
+mod disable;
+mod list;
+mod restore;
+
+/// Save snapshots of the local modifications in the working copy.
+///
+/// A snapshot is a patch of the changes to versioned files.  Once snapshots
+/// are enabled one is saved automatically before commands that update the
+/// working copy such as switch, up, mixed --update and bisect.  Only the
+/// most recent snapshots are kept.  Snapshots are independent of the stash
+/// and never change the working copy when they are saved.
+///
+/// Run svu snapshot without a command to save a snapshot now.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    To save snapshots periodically run `svu snapshot --auto` from cron or\n\
+    your shell prompt.  It saves a snapshot only when snapshots are enabled\n\
+    and the most recent one is older than the interval given to `snapshot enable`.\n\n\
+    Unversioned files, binary files and property changes are not saved."
+)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(flatten_help = false)]
+pub struct Snapshot {
+    #[command(subcommand)]
+    command: Option<SnapshotCommands>,
+
+    #[command(flatten)]
+    save_args: SaveArgs,
+}
+
+#[derive(Debug, Args, Clone)]
+struct SaveArgs {
+    /// A short description of the snapshot.
+    #[arg(short, long)]
+    message: Option<String>,
+
+    /// Only save a snapshot if snapshots are enabled and the most
+    /// recent snapshot is older than the snapshot interval.
+    #[arg(long)]
+    auto: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum SnapshotCommands {
+    Enable(enable::Enable),
+    Disable(disable::Disable),
+    List(list::List),
+    Restore(restore::Restore),
+}
+use SnapshotCommands::*;
+
+impl Snapshot {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Some(Enable(cmd))  => cmd.run(),
+            Some(Disable(cmd)) => cmd.run(),
+            Some(List(cmd))    => cmd.run(),
+            Some(Restore(cmd)) => cmd.run(),
+            None               => self.save(),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let wc_root = wc_root()?;
+        let index = load_snapshot_index()?;
+        if self.save_args.auto {
+            let interval = Duration::minutes(index.interval as i64);
+            let recent = index.snapshots.first().is_some_and(|s| Local::now() - s.date < interval);
+            if !index.enabled || recent {
+                return Ok(());
+            }
+        }
+        let description = self.save_args.message.clone().unwrap_or("snapshot".to_string());
+        match save_snapshot(&wc_root, &description)? {
+            Some(snapshot) => println!("Saved {}", snapshot.summary_display()),
+            None if !self.save_args.auto => println!("No local changes to save"),
+            None => (),
+        }
+        Ok(())
+    }
+}
+
+//  Save a snapshot before an operation that changes the working copy
+//  if snapshots have been enabled.
+pub fn before(operation: &str) -> Result<()> {
+    let wc_root = wc_root()?;
+    if load_snapshot_index()?.enabled {
+        if let Some(snapshot) = save_snapshot(&wc_root, &format!("before {}", operation))? {
+            println!("Saved {}", snapshot.summary_display());
+        }
+    }
+    Ok(())
+}
+
+//  Snapshots are saved every 60 minutes by `snapshot --auto`
+//  and the 20 most recent snapshots are kept by default.
+const DEFAULT_INTERVAL: u32 = 60;
+const DEFAULT_KEEP: usize = 20;
+
+fn default_interval() -> u32 { DEFAULT_INTERVAL }
+fn default_keep() -> usize { DEFAULT_KEEP }
+
+//  A snapshot entry.  The newest snapshot is first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotEntry {
+    id: u64,
+    branch: String,
+    revision: String,
+    description: String,
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    #[serde(rename = "patchName")]
+    patch_name: String,
+}
+
+impl SnapshotEntry {
+    fn summary_display(&self) -> String {
+        format!(
+            "{} {} [{}]: {}",
+            snapshot_id_display(self.id).yellow(),
+            self.branch.green(),
+            self.revision.yellow(),
+            self.description
+        )
+    }
+}
+
+//  The snapshot settings and entries saved to .svu/snapshots/snapshots.json
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SnapshotIndex {
+    enabled: bool,
+    //  Minutes between snapshots saved by `snapshot --auto`
+    #[serde(default = "default_interval")]
+    interval: u32,
+    #[serde(default = "default_keep")]
+    keep: usize,
+    #[serde(rename = "nextId", default)]
+    next_id: u64,
+    snapshots: Vec<SnapshotEntry>,
+}
+
+impl Default for SnapshotIndex {
+    fn default() -> Self {
+        SnapshotIndex {
+            enabled: false,
+            interval: DEFAULT_INTERVAL,
+            keep: DEFAULT_KEEP,
+            next_id: 0,
+            snapshots: Vec::new(),
+        }
+    }
+}
+
+fn parse_snapshot_id(arg: &str) -> Result<u64> {
+    arg.trim_start_matches("snapshot-")
+        .parse::<u64>()
+        .map_err(|_| General("Snapshot id must be 'snapshot-<n>' or '<n>'".to_string()).into())
+}
+
+fn snapshot_id_display(id: u64) -> String {
+    format!("snapshot-{}", id)
+}
+
+fn wc_root() -> Result<PathBuf> {
+    let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+    Ok(PathBuf::from(wc_info.wc_path.unwrap()))
+}
+
+fn snapshots_path() -> Result<PathBuf> {
+    let path = util::data_directory()?.join("snapshots");
+
+    if !path.is_dir() {
+        create_dir(path.as_path())?
+    }
+    Ok(path)
+}
+
+fn snapshot_index_file() -> Result<PathBuf> {
+    Ok(snapshots_path()?.join("snapshots.json"))
+}
+
+fn load_snapshot_index() -> Result<SnapshotIndex> {
+    let path = snapshot_index_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(serde_json::from_reader(reader)?)
+    } else {
+        Ok(SnapshotIndex::default())
+    }
+}
+
+fn save_snapshot_index(index: &SnapshotIndex) -> Result<()> {
+    util::save_json(&snapshot_index_file()?, index)
+}
+
+//  Used by `svu doctor` to validate the data files.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![(snapshot_index_file()?, load_snapshot_index().map(|_| ()))])
+}
+
+//  Write a patch of the local modifications and add it to the snapshots,
+//  pruning the oldest snapshots.  Returns None if there are no modifications.
+fn save_snapshot(wc_root: &Path, description: &str) -> Result<Option<SnapshotEntry>> {
+    let mut index = load_snapshot_index()?;
+    let patch_name = format!("snapshot-{}.patch", index.next_id);
+    let patch_file = snapshots_path()?.join(&patch_name);
+    svn::create_patch(&patch_file, &["."], "infinity", &[] as &[&str], wc_root)?;
+    if patch_file.metadata()?.len() == 0 {
+        remove_file(&patch_file)?;
+        return Ok(None);
+    }
+
+    let (branch, revision) = svn::current_branch(wc_root)?;
+    let snapshot = SnapshotEntry {
+        id: index.next_id,
+        branch,
+        revision,
+        description: description.to_string(),
+        date: Local::now(),
+        patch_name,
+    };
+    index.next_id += 1;
+    index.snapshots.insert(0, snapshot.clone());
+    let keep = index.keep.max(1);
+    if index.snapshots.len() > keep {
+        for old in index.snapshots.split_off(keep) {
+            let old_file = snapshots_path()?.join(&old.patch_name);
+            if old_file.is_file() {
+                remove_file(old_file)?;
+            }
+        }
+    }
+    save_snapshot_index(&index)?;
+    Ok(Some(snapshot))
+}
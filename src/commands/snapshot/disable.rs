@@ -0,0 +1,24 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Stop saving snapshots automatically.
+///
+/// The existing snapshots are kept.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Disable;
+
+impl Disable {
+    pub fn run(&mut self) -> Result<()> {
+        wc_root()?;
+        let mut index = load_snapshot_index()?;
+        index.enabled = false;
+        save_snapshot_index(&index)?;
+        println!("Snapshots disabled");
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Save snapshots automatically.
+///
+/// A snapshot is saved before each command that updates the working copy
+/// and by `snapshot --auto` when the most recent one is older than the interval.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Enable {
+    /// Minutes between the snapshots saved by `snapshot --auto`.
+    #[arg(short, long, value_name = "MINUTES", default_value_t = DEFAULT_INTERVAL)]
+    interval: u32,
+
+    /// Number of snapshots to keep.  Older snapshots are removed.
+    #[arg(short, long, value_name = "N", default_value_t = DEFAULT_KEEP as u64,
+          value_parser = clap::value_parser!(u64).range(1..))]
+    keep: u64,
+}
+
+impl Enable {
+    pub fn run(&mut self) -> Result<()> {
+        wc_root()?;
+        let mut index = load_snapshot_index()?;
+        index.enabled = true;
+        index.interval = self.interval;
+        index.keep = self.keep as usize;
+        save_snapshot_index(&index)?;
+        println!(
+            "Snapshots enabled with an interval of {} minutes, keeping the {} most recent",
+            self.interval.to_string().yellow(),
+            self.keep.to_string().yellow()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+
+/// Display the saved snapshots, newest first.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct List;
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        wc_root()?;
+        let index = load_snapshot_index()?;
+        if index.enabled {
+            println!("Snapshots are enabled");
+        } else {
+            println!("Snapshots are disabled, use 'snapshot enable' to enable them");
+        }
+        for snapshot in &index.snapshots {
+            println!(
+                "{} {}",
+                util::display_svn_date(&snapshot.date).magenta(),
+                snapshot.summary_display()
+            );
+        }
+        Ok(())
+    }
+}
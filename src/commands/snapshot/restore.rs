@@ -0,0 +1,63 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::commands::stash::apply_patch_file;
+
+/// Apply a snapshot to the working copy.
+///
+/// The snapshot is kept so that it can be restored again.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Restore {
+    /// Show the patch output but do not update the working copy.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+
+    /// Restore even if the working copy has local modifications.
+    #[arg(short, long)]
+    force: bool,
+
+    /// Id of the snapshot to restore.  Defaults to the most recent snapshot.
+    #[arg(value_name = "SNAPSHOT", value_parser = parse_snapshot_id)]
+    snapshot_id: Option<u64>,
+}
+
+impl Restore {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("restore a snapshot")?;
+        let wc_root = wc_root()?;
+        let index = load_snapshot_index()?;
+        let snapshot = match self.snapshot_id {
+            Some(id) => index.snapshots.iter().find(|s| s.id == id),
+            None     => index.snapshots.first(),
+        };
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => {
+                let msg = match self.snapshot_id {
+                    Some(id) => format!("{} does not exist", snapshot_id_display(id)),
+                    None     => "There are no snapshots".to_string(),
+                };
+                return Err(General(msg).into());
+            }
+        };
+
+        if !self.dry_run && !self.force && !svn::local_changes(&wc_root)?.is_empty() {
+            let msg = "The working copy has local modifications.\n\
+                       Use --force to restore the snapshot anyway";
+            return Err(General(msg.to_string()).into());
+        }
+
+        let patch_file = snapshots_path()?.join(&snapshot.patch_name);
+        let outcome = apply_patch_file(&patch_file, &wc_root, self.dry_run)?;
+        if !outcome.is_clean() {
+            outcome.show_summary(&wc_root);
+        } else if !self.dry_run {
+            println!("Restored {}", snapshot.summary_display());
+        }
+        Ok(())
+    }
+}
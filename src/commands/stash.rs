@@ -1,14 +1,17 @@
 use anyhow::Result;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local};
 use clap::{Parser, Subcommand};
 use colored::Colorize;
 use crate::util::SvError::*;
 use std::borrow::Cow;
 use std::io::BufRead;
 use std::path::{Path, PathBuf};
-use std::fs::File;
+use std::fs::{File, read_dir, remove_file};
 use crate::svn;
 use crate::util;
+use crate::config;
+use crate::migrate;
+use crate::commands::operation::{self, Step};
 use std::fs::create_dir;
 use serde::{Deserialize, Serialize};
 use regex::Regex;
@@ -22,6 +25,9 @@ mod drop;
 mod list;
 mod show;
 mod clear;
+mod gc;
+mod diff;
+mod format;
 
 use push::PushArgs;
 
@@ -39,6 +45,7 @@ use push::PushArgs;
 /// (eg. the integer n is equivalent to stash-n)
 #[derive(Debug, Parser)]
 #[command(
+    visible_alias = "st",
     author,
     help_template = crate::app::HELP_TEMPLATE,
     after_help = "\
@@ -66,6 +73,9 @@ enum StashCommands {
     List(list::List),
     Show(show::Show),
     Clear(clear::Clear),
+    Gc(gc::Gc),
+    Diff(diff::Diff),
+    Format(format::Format),
 }
 use StashCommands::*;
 
@@ -81,6 +91,9 @@ impl Stash {
             Some(List(cmd))  => cmd.run(),
             Some(Show(cmd))  => cmd.run(),
             Some(Clear(cmd)) => cmd.run(),
+            Some(Gc(cmd))    => cmd.run(),
+            Some(Diff(cmd))  => cmd.run(),
+            Some(Format(cmd)) => cmd.run(),
         }
     }
 }
@@ -101,10 +114,83 @@ fn stash_id_display(id: usize) -> String {
     format!("stash-{}", id)
 }
 
+//  Stash entries older than this age are highlighted by `stash list`
+//  and are removed by `stash gc`.
+//  The age is set with the stash.expire setting and can be overridden
+//  with the SVU_STASH_EXPIRE environment variable.
+const DEFAULT_STASH_EXPIRE: &str = "90d";
+
+fn stash_expire_age() -> Result<Duration> {
+    let age = std::env::var("SVU_STASH_EXPIRE").ok()
+        .or(config::get_str("stash.expire").map(|a| a.to_string()))
+        .unwrap_or(DEFAULT_STASH_EXPIRE.to_string());
+    util::parse_age(&age)
+}
+
+//  Stash the changes in the working copy and revert it.
+//  Used by other commands that need a clean working copy.
+//  Returns the patch name that identifies the new stash entry
+//  or None if there were no changes to stash.
+pub fn push_working_copy(message: Option<String>) -> Result<Option<String>> {
+    let count = load_stash_entries()?.len();
+    push::Push::new(PushArgs::with_message(message)).run()?;
+    let entries = load_stash_entries()?;
+    if entries.len() > count {
+        Ok(entries.first().map(|s| s.patch_name.clone()))
+    } else {
+        Ok(None)
+    }
+}
+
+//  Pop the stash entry that was created by push_working_copy().
+pub fn pop_working_copy(patch_name: &str) -> Result<()> {
+    match load_stash_entries()?.iter().position(|s| s.patch_name == patch_name) {
+        Some(index) => pop::Pop::new(index).run(),
+        None => {
+            let msg = format!("The stash entry {} no longer exists", patch_name);
+            Err(General(msg).into())
+        }
+    }
+}
+
+//  Return the patch name of the most recent stash entry with the
+//  given description, or of the most recent entry if none is given.
+pub fn find_entry(description: Option<&str>) -> Result<Option<String>> {
+    Ok(load_stash_entries()?
+        .into_iter()
+        .find(|s| description.is_none_or(|d| s.description == d))
+        .map(|s| s.patch_name))
+}
+
+//  Display the stash entries as `stash list` does.
+pub fn list_entries() -> Result<()> {
+    list::List.run()
+}
+
+//  Update the stash entries created on a branch that has been renamed.
+//  Returns the number of entries updated.
+pub fn rename_branch(old_branch: &str, new_branch: &str) -> Result<usize> {
+    let mut entries = load_stash_entries()?;
+    let mut count = 0;
+    for entry in entries.iter_mut() {
+        if entry.branch == old_branch || entry.branch.starts_with(&format!("{}/", old_branch)) {
+            entry.branch = format!("{}{}", new_branch, &entry.branch[old_branch.len()..]);
+            count += 1;
+        }
+    }
+    if count > 0 {
+        save_stash_entries(&entries)?;
+    }
+    Ok(count)
+}
+
 // Common structures and functions used by all of the stash commands.
 
+const STASH_DIR: &str = "stash";
+const STASH_ENTRIES_FILE: &str = "stash_entries.json";
+
 fn stash_path() -> Result<PathBuf> {
-    let path = util::data_directory()?.join("stash");
+    let path = util::data_directory()?.join(STASH_DIR);
 
     if !path.is_dir() {
         create_dir(path.as_path())?
@@ -114,7 +200,7 @@ fn stash_path() -> Result<PathBuf> {
 
 
 fn stash_entries_file() -> Result<PathBuf> {
-    Ok(stash_path()?.join("stash_entries.json"))
+    Ok(stash_path()?.join(STASH_ENTRIES_FILE))
 }
 
 const UNVERSIONED: &str = "unversioned";
@@ -133,8 +219,10 @@ const MODIFIED:    &str = "modified";
     path: String,
     revision: String,
     status: String,
-    #[serde(rename(serialize = "isDir", deserialize = "isDir"))]
+    #[serde(rename(serialize = "isDir", deserialize = "isDir"), alias = "is_dir")]
     is_dir: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    changelist: Option<String>,
 }
 
 impl StashItem {
@@ -166,14 +254,20 @@ struct StashFileEntry {
     branch: String,
     revision: String,
     description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
     #[serde(with = "datetime_serializer")]
     date: DateTime<Local>,
-    #[serde(rename(serialize = "patchName", deserialize = "patchName"))]
+    #[serde(rename(serialize = "patchName", deserialize = "patchName"), alias = "patch_name")]
     patch_name: String,
     items: Vec<StashItem>,
 }
 
 impl StashFileEntry {
+    fn is_older_than(&self, age: &Duration) -> bool {
+        Local::now() - self.date > *age
+    }
+
     fn summary_display(&self) -> String {
         format!(
             "{} [{}]: {}",
@@ -188,25 +282,32 @@ impl StashFileEntry {
 fn load_stash_entries() -> Result<Vec<StashFileEntry>> {
     let path = stash_entries_file()?;
     if path.is_file() {
-        let reader = File::open(path)?;
-        let entries: Vec<StashFileEntry> = serde_json::from_reader(reader)?;
-        Ok(entries)
+        migrate::load_json(&path)
     } else {
         Ok(vec![])
     }
 }
 
+//  The number of stash entries in a data directory.
+//  Used by `svu prompt` which must not run svn.
+pub(crate) fn entry_count(data_dir: &Path) -> usize {
+    let path = data_dir.join(STASH_DIR).join(STASH_ENTRIES_FILE);
+    if path.is_file() {
+        migrate::load_json::<Vec<StashFileEntry>>(&path).map(|e| e.len()).unwrap_or(0)
+    } else {
+        0
+    }
+}
+
 fn add_stash_entry(stash: &StashFileEntry) -> Result<()> {
     let mut entries = load_stash_entries()?;
 
     entries.insert(0, stash.clone());
-    let writer = File::create(stash_entries_file()?)?;
-    Ok(serde_json::to_writer_pretty(writer, &entries)?)
+    save_stash_entries(&entries)
 }
 
 fn save_stash_entries(entries: &[StashFileEntry]) -> Result<()> {
-    let writer = File::create(stash_entries_file()?)?;
-    Ok(serde_json::to_writer_pretty(writer, entries)?)
+    migrate::save_json(&stash_entries_file()?, entries)
 }
 
 //  Runs `svn status` on the working copy root directory
@@ -220,12 +321,15 @@ fn save_stash_entries(entries: &[StashFileEntry]) -> Result<()> {
 //  At this point `svn status` will return all of the previously unversioned items as
 //  "added" so we must mark them as unversioned in our own item list.
 //  So this function will alter the working copy when unversioned items are being stashed.
-fn get_stash_items(wc_root: &Path, unversioned: bool) -> Result<Vec<StashItem>> {
-    fn get_wc_items(wc_root: &Path, unversioned: bool) -> Result<Vec<StashItem>> {
-        let status = svn::status(".", Some(wc_root))?;
+//
+//  If any changelists are given then only the items in those changelists are included.
+fn get_stash_items(wc_root: &Path, unversioned: bool, changelists: &[String]) -> Result<Vec<StashItem>> {
+    fn get_wc_items(wc_root: &Path, unversioned: bool, changelists: &[String]) -> Result<Vec<StashItem>> {
+        let status = svn::status_changelists(".", changelists, Some(wc_root))?;
         let mut items = Vec::<StashItem>::new();
 
-        for entry in status.entries {
+        //  Our own data directory is never stashed.
+        for entry in status.entries.into_iter().filter(|e| !util::is_data_directory_path(&e.path)) {
             if entry.item_status != NORMAL && (unversioned || entry.item_status != UNVERSIONED) {
                 let is_dir = wc_root.join(&entry.path).is_dir();
                 items.push(StashItem {
@@ -233,6 +337,7 @@ fn get_stash_items(wc_root: &Path, unversioned: bool) -> Result<Vec<StashItem>>
                     revision: entry.revision,
                     status: entry.item_status,
                     is_dir,
+                    changelist: entry.changelist,
                 });
             }
         }
@@ -240,9 +345,9 @@ fn get_stash_items(wc_root: &Path, unversioned: bool) -> Result<Vec<StashItem>>
     }
 
     fn fixup_unversioned_items<'a>(
-        initial_items: &'a Vec<StashItem>,
+        initial_items: &'a [StashItem],
         wc_root: &Path
-    ) -> Result<Cow<'a, Vec<StashItem>>> {
+    ) -> Result<Cow<'a, [StashItem]>> {
         let unversioned_paths: Vec<String> = initial_items
             .iter()
             .filter(|i| i.status == UNVERSIONED)
@@ -261,13 +366,16 @@ fn get_stash_items(wc_root: &Path, unversioned: bool) -> Result<Vec<StashItem>>
             //  status values back to "unversioned" so we can restore the properly when the stash is reapplied.
             //  If there were no unversioned directores in the initial list then this is not necessary.
 
-            svn::add(&unversioned_paths, "infinity", false, Some(wc_root))?;
+            //  Everything beneath an unversioned directory must be added,
+            //  since the directory is removed when the stash is reverted.
+            let auto_props = config::get_bool("stash.auto-props");
+            svn::add(&unversioned_paths, "infinity", auto_props, Some(wc_root))?;
 
             if initial_items
                 .iter()
                 .any(|i| i.is_dir && i.status == UNVERSIONED)
             {
-                let new_items = get_wc_items(wc_root, false)?;
+                let new_items = get_wc_items(wc_root, false, &[])?;
                 let mut fixed_items = Vec::<StashItem>::new();
                 for item in new_items {
                     if item.status == ADDED
@@ -287,29 +395,124 @@ fn get_stash_items(wc_root: &Path, unversioned: bool) -> Result<Vec<StashItem>>
         }
     }
 
-    match get_wc_items(wc_root, unversioned)? {
+    match get_wc_items(wc_root, unversioned, changelists)? {
         items if unversioned => Ok(fixup_unversioned_items(&items, wc_root)?.into_owned()),
         items => Ok(items),
     }
 }
 
-fn apply_stash(stash: &StashFileEntry, wc_root: &Path, dry_run: bool) -> Result<()> {
-    let path_re    = Regex::new(r"^([ADUCG>])(\s+)(.+)$")?;
+//  The result of applying a patch to the working copy.
+//  All paths are relative to the working copy root.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct PatchOutcome {
+    //  Files that were patched without any rejected hunks
+    applied: Vec<String>,
+    //  Files that had one or more rejected hunks
+    rejected: Vec<String>,
+    //  Files that svn patch could not patch at all
+    skipped: Vec<String>,
+}
+
+impl PatchOutcome {
+    pub(crate) fn is_clean(&self) -> bool {
+        self.rejected.is_empty() && self.skipped.is_empty()
+    }
+
+    //  The files that have not yet been patched successfully.
+    fn remaining(&self) -> Vec<String> {
+        self.rejected.iter().chain(self.skipped.iter()).cloned().collect()
+    }
+
+    pub(crate) fn show_summary(&self, wc_root: &Path) {
+        let cwd = current_dir().unwrap_or_default();
+        let show = |heading: &str, paths: &[String], color: &str| {
+            if !paths.is_empty() {
+                println!("{}", heading);
+                for path in paths {
+                    let rel_path = diff_paths(wc_root.join(path), &cwd).unwrap_or(PathBuf::from(path));
+                    println!("  {}", rel_path.to_string_lossy().color(color));
+                }
+            }
+        };
+        println!();
+        show("Applied cleanly:", &self.applied, "green");
+        show("Rejected:", &self.rejected, "red");
+        show("Skipped:", &self.skipped, "red");
+    }
+}
+
+//  When the patch is not applied cleanly the unversioned items are not restored.
+//  That must be done after the conflicts have been resolved. (see: suspend_stash_operation())
+fn apply_stash(stash: &StashFileEntry, wc_root: &Path, dry_run: bool, acquire_locks: bool) -> Result<PatchOutcome> {
     let patch_file = stash_path()?.join(&stash.patch_name);
     let cwd        = current_dir()?;
-    let stdout     = svn::apply_patch(&patch_file, dry_run, Some(wc_root))?;
+
+    let unlocked = unlocked_needs_lock_paths(stash, wc_root)?;
+    if !unlocked.is_empty() {
+        if acquire_locks && !dry_run {
+            svn::lock(&unlocked, None, false, Some(wc_root))?;
+            for path in &unlocked {
+                let rel_path = diff_paths(wc_root.join(path), &cwd).unwrap();
+                println!("Locked: {}", rel_path.to_string_lossy().green());
+            }
+        } else {
+            for path in &unlocked {
+                let rel_path = diff_paths(wc_root.join(path), &cwd).unwrap();
+                eprintln!(
+                    "{} {} has svn:needs-lock and is read-only because it is not locked",
+                    "warning:".yellow(),
+                    rel_path.to_string_lossy()
+                );
+            }
+            if !acquire_locks {
+                eprintln!("Use --acquire-locks to lock these files before applying the stash");
+            }
+        }
+    }
+
+    let outcome = apply_patch_file(&patch_file, wc_root, dry_run)?;
+
+    if !dry_run {
+        restore_changelists(stash, wc_root)?;
+    }
+
+    if !dry_run && outcome.is_clean() {
+        restore_unversioned(&unversioned_revert_paths(stash), wc_root)?;
+        println!("Updated working copy state: {}", stash.summary_display());
+    }
+    Ok(outcome)
+}
+
+//  Apply a patch file to the working copy and display the output
+//  of svn patch with paths relative to the current directory.
+pub(crate) fn apply_patch_file(patch_file: &Path, wc_root: &Path, dry_run: bool) -> Result<PatchOutcome> {
+    let stdout = svn::apply_patch(patch_file, dry_run, Some(wc_root))?;
+    show_patch_output(&stdout, wc_root)
+}
+
+//  Remove the changes of a patch file from the working copy and
+//  display the output of svn patch in the same way.
+pub(crate) fn reverse_patch_file(patch_file: &Path, wc_root: &Path) -> Result<PatchOutcome> {
+    let stdout = svn::reverse_patch(patch_file, false, Some(wc_root))?;
+    show_patch_output(&stdout, wc_root)
+}
+
+fn show_patch_output(stdout: &[u8], wc_root: &Path) -> Result<PatchOutcome> {
+    let path_re    = Regex::new(r"^([ADUCG>])(\s+)(.+)$")?;
+    let skipped_re = Regex::new(r"^Skipped.*'(.+)'")?;
+    let cwd        = current_dir()?;
     let mut last_status = "".to_string();
+    let mut outcome = PatchOutcome::default();
 
     for line in stdout.lines() {
         let line = line?;
         if let Some(captures) = path_re.captures(line.as_str()) {
-            // let x = &captures[1];
             let status = &captures[1];
             let space  = &captures[2];
             let path   = &captures[3];
             let rel_path = match status {
                 ">" => path.to_string(), // Not a path
-                _   => diff_paths(&wc_root.join(path), &cwd)
+                _   => diff_paths(wc_root.join(path), &cwd)
                         .unwrap()
                         .to_string_lossy()
                         .to_string(),
@@ -323,53 +526,196 @@ fn apply_stash(stash: &StashFileEntry, wc_root: &Path, dry_run: bool) -> Result<
             };
             let new_line = format!("{}{}{}", status, space, rel_path);
             println!("{}", new_line.color(color));
+            match status {
+                ">" => (),
+                "C" => outcome.rejected.push(path.to_string()),
+                _   => outcome.applied.push(path.to_string()),
+            }
             last_status = status.to_string();
+        } else if let Some(captures) = skipped_re.captures(line.as_str()) {
+            println!("{}", line.red());
+            outcome.skipped.push(captures[1].to_string());
         } else {
             println!("{}", line);
         }
     }
+    Ok(outcome)
+}
 
-    if !dry_run {
-        // The working copy has been restored via the patch, but and files that were
-        // `unversioned`` when the stash was created will not appear as `added``.
-        // We must run `svn revert` on each unversioned item so that it will
-        // once again become unversioned.
-        let unversioned: Vec<StashItem> = stash
-            .items.
-            iter()
-            .filter_map(|i| {
-                if i.status == UNVERSIONED {
-                    Some(i.clone())
-                } else {
-                    None
-                }
-            })
+//  Return the paths of the stashed files that carry the svn:needs-lock property
+//  but are currently read-only in the working copy because we do not hold a lock.
+//  The paths are relative to the working copy root.
+fn unlocked_needs_lock_paths(stash: &StashFileEntry, wc_root: &Path) -> Result<Vec<String>> {
+    let is_read_only = |path: &str| {
+        wc_root.join(path)
+            .metadata()
+            .map(|m| m.is_file() && m.permissions().readonly())
+            .unwrap_or(false)
+    };
+    let candidates: Vec<&str> = stash.items
+        .iter()
+        .filter(|i| !i.is_dir && is_read_only(&i.path))
+        .map(|i| i.path.as_str())
+        .collect();
+
+    if candidates.is_empty() {
+        Ok(vec![])
+    } else {
+        let needs_lock = svn::needs_lock_paths(wc_root)?;
+        Ok(candidates
+            .into_iter()
+            .filter(|p| needs_lock.iter().any(|n| n == p))
+            .map(|p| p.to_string())
+            .collect())
+    }
+}
+
+//  The working copy has been restored via the patch, but and files that were
+//  `unversioned`` when the stash was created will not appear as `added``.
+//  We must run `svn revert` on each unversioned item so that it will
+//  once again become unversioned.
+fn unversioned_revert_paths(stash: &StashFileEntry) -> Vec<String> {
+    let unversioned: Vec<StashItem> = stash
+        .items.
+        iter()
+        .filter_map(|i| {
+            if i.status == UNVERSIONED {
+                Some(i.clone())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let unversioned_dirs: Vec<String> = unversioned
+        .iter()
+        .filter_map(|i| if i.is_dir { Some(i.path.clone()) } else { None})
+        .collect();
+    let can_skip = |i: &StashItem| -> bool {
+        unversioned_dirs
+            .iter()
+            .any(|d| i.path.starts_with(d)  && i.path != *d)
+    };
+    unversioned
+        .iter()
+        .filter_map(|i| {
+            if can_skip(i) {
+                None
+            } else {
+                Some(i.path.clone())
+            }
+        })
+        .collect()
+}
+
+//  Add the stashed items back to the changelists that they belonged to.
+fn restore_changelists(stash: &StashFileEntry, wc_root: &Path) -> Result<()> {
+    let mut names: Vec<&String> = stash.items.iter().filter_map(|i| i.changelist.as_ref()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let paths: Vec<&str> = stash.items
+            .iter()
+            .filter(|i| i.changelist.as_ref() == Some(name) && wc_root.join(&i.path).exists())
+            .map(|i| i.path.as_str())
             .collect();
+        if !paths.is_empty() {
+            svn::changelist(name, &paths, Some(wc_root))?;
+        }
+    }
+    Ok(())
+}
 
-        if !unversioned.is_empty() {
-            let unversioned_dirs: Vec<String> = unversioned
-                .iter()
-                .filter_map(|i| if i.is_dir { Some(i.path.clone()) } else { None})
-                .collect();
-            let can_skip = |i: &StashItem| -> bool {
-                unversioned_dirs
-                    .iter()
-                    .any(|d| i.path.starts_with(d)  && i.path != *d)
-            };
-            let revert_paths: Vec<String> = unversioned
-                .iter()
-                .filter_map(|i| {
-                    if can_skip(i) {
-                        None
-                    } else {
-                        Some(i.path.clone())
-                    }
-                })
-                .collect();
-            svn::revert(&revert_paths, "infinity", false, Some(wc_root))?;
+pub(crate) fn restore_unversioned(revert_paths: &[String], wc_root: &Path) -> Result<()> {
+    if !revert_paths.is_empty() {
+        svn::revert(revert_paths, "infinity", false, Some(wc_root))?;
+    }
+    Ok(())
+}
+
+//  Returns the paths that must be reverted to remove the stash items
+//  from the working copy.
+//  We will explicitly revert all entries to ensure that the --remove-added flag is honored.
+//  For added/unversioned directories we do not need to revert any entries below them
+//  as these entries will be reverted recursively with their respective  directories.
+fn stash_revert_paths(items: &[StashItem]) -> Vec<String> {
+    let added_unversioned: Vec<&StashItem> = items
+        .iter()
+        .filter(|i| i.is_dir && (i.status == ADDED || i.status == UNVERSIONED))
+        .collect();
+    let can_skip = |i: &StashItem| -> bool {
+        added_unversioned
+            .iter()
+            .any(|p| i.path.starts_with(&p.path) && i.path != p.path)
+    };
+    items
+        .iter()
+        .filter(|i| !can_skip(i))
+        .map(|i| i.path.clone())
+        .collect()
+}
+
+//  Used by `svu doctor` to validate the data files.
+pub(crate) fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![
+        (stash_entries_file()?, load_stash_entries().map(|_| ())),
+        pop::check_data_file()?,
+    ])
+}
+
+//  Return the patch files in the stash directory that do not belong to
+//  any stash entry and the patch names of the stash entries whose patch
+//  file is missing.
+pub(crate) fn stale_patch_files() -> Result<(Vec<PathBuf>, Vec<String>)> {
+    let stash_entries = load_stash_entries()?;
+    let dir = stash_path()?;
+    let mut orphans = Vec::new();
+    for dir_entry in read_dir(&dir)? {
+        let path = dir_entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let is_patch = name.ends_with(".patch") || name.ends_with(".patch.retry");
+        if path.is_file() && is_patch && !stash_entries.iter().any(|s| s.patch_name == name) {
+            orphans.push(path);
         }
+    }
+    orphans.sort();
+    let missing = stash_entries
+        .iter()
+        .filter(|s| !dir.join(&s.patch_name).is_file())
+        .map(|s| s.patch_name.clone())
+        .collect();
+    Ok((orphans, missing))
+}
 
-        println!("Updated working copy state: {}", stash.summary_display());
+//  Remove the record of a failed stash pop.  Called when the
+//  operation in progress is completed or aborted.
+pub(crate) fn remove_pop_failure() -> Result<()> {
+    pop::remove_pop_failure()
+}
+
+//  Drop the stash entry with the given patch name.
+//  We use the patch name because the index of the entry may have changed.
+pub(crate) fn drop_stash_entry(patch_name: &str) -> Result<()> {
+    let mut stash_entries = load_stash_entries()?;
+    if let Some(index) = stash_entries.iter().position(|s| s.patch_name == patch_name) {
+        let stash = stash_entries.remove(index);
+        save_stash_entries(&stash_entries)?;
+        let patch_file = stash_path()?.join(patch_name);
+        if patch_file.is_file() {
+            remove_file(patch_file)?;
+        }
+        println!("Dropped stash: {}", stash.summary_display());
     }
     Ok(())
 }
+
+//  Called when a stash could not be applied cleanly.
+//  The remaining steps are saved so that they can be completed
+//  with `svu continue` once the conflicts have been resolved.
+fn suspend_stash_operation(name: &str, stash: &StashFileEntry, drop: bool) -> Result<()> {
+    let mut steps = vec![Step::RestoreUnversioned { paths: unversioned_revert_paths(stash) }];
+    if drop {
+        steps.push(Step::DropStash { patch_name: stash.patch_name.clone() });
+    }
+    operation::suspend(name, steps, stash_revert_paths(&stash.items))
+}
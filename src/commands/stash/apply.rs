@@ -18,6 +18,11 @@ pub struct Apply {
     #[arg(short = 'n', long)]
     dry_run: bool,
 
+    /// Lock any files that have the svn:needs-lock property
+    /// before applying the stash.
+    #[arg(long)]
+    acquire_locks: bool,
+
     /// Id of the stash you wish to apply
     #[arg(value_name = "STASH", value_parser = parse_stash_id, default_value = "stash-0")]
     stash_id: usize,
@@ -28,10 +33,14 @@ impl Apply {
         let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
         let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
         let stash_entries = load_stash_entries()?;
+        operation::ensure_none_in_progress()?;
 
         if self.stash_id < stash_entries.len() {
             let stash = &stash_entries[self.stash_id];
-            apply_stash(stash, &wc_root, self.dry_run)?;
+            let outcome = apply_stash(stash, &wc_root, self.dry_run, self.acquire_locks)?;
+            if !outcome.is_clean() && !self.dry_run {
+                suspend_stash_operation("stash apply", stash, false)?;
+            }
             Ok(())
         } else {
             let msg = format!(
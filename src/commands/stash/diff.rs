@@ -0,0 +1,170 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::util::print_diff_line;
+use std::collections::BTreeMap;
+use std::io::BufReader;
+
+/// Compare the patches of two stash entries.
+///
+/// Displays the files that appear in only one of the stash entries and,
+/// for files that appear in both, whether the changes are identical or
+/// how many hunks differ.  Hunks are compared by their content so changes
+/// that apply at different line numbers are considered the same.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Diff {
+    /// Show the hunks that differ.
+    #[arg(short = 'p', long)]
+    show_hunks: bool,
+
+    /// Id of the first stash entry.
+    #[arg(value_name = "STASH-A", value_parser = parse_stash_id)]
+    stash_a: usize,
+
+    /// Id of the second stash entry.
+    #[arg(value_name = "STASH-B", value_parser = parse_stash_id)]
+    stash_b: usize,
+}
+
+//  A hunk of a patch.  The header contains the line numbers
+//  which are ignored when comparing hunks.
+#[derive(Debug, Clone)]
+struct Hunk {
+    header: String,
+    lines: Vec<String>,
+}
+
+impl PartialEq for Hunk {
+    fn eq(&self, other: &Self) -> bool {
+        self.lines == other.lines
+    }
+}
+
+impl Diff {
+    pub fn run(&mut self) -> Result<()> {
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        let cwd = current_dir()?;
+        let stash_entries = load_stash_entries()?;
+
+        for id in [self.stash_a, self.stash_b] {
+            if id >= stash_entries.len() {
+                let msg = format!("{} does not exist in the stash", stash_id_display(id));
+                return Err(General(msg).into());
+            }
+        }
+
+        let name_a = stash_id_display(self.stash_a);
+        let name_b = stash_id_display(self.stash_b);
+        let files_a = parse_patch(&stash_path()?.join(&stash_entries[self.stash_a].patch_name))?;
+        let files_b = parse_patch(&stash_path()?.join(&stash_entries[self.stash_b].patch_name))?;
+
+        println!("{:<8} | {}", name_a, stash_entries[self.stash_a].summary_display());
+        println!("{:<8} | {}", name_b, stash_entries[self.stash_b].summary_display());
+        println!("{:->70}", "-");
+
+        let mut paths: Vec<&String> = files_a.keys().chain(files_b.keys()).collect();
+        paths.sort();
+        paths.dedup();
+        let mut identical = true;
+
+        for path in paths {
+            let rel_path = diff_paths(wc_root.join(path), &cwd).unwrap();
+            let rel_path = rel_path.to_string_lossy();
+            match (files_a.get(path), files_b.get(path)) {
+                (Some(_), None) => {
+                    identical = false;
+                    println!("{} {} (only in {})", "<".red(), rel_path.red(), name_a);
+                }
+                (None, Some(_)) => {
+                    identical = false;
+                    println!("{} {} (only in {})", ">".green(), rel_path.green(), name_b);
+                }
+                (Some(hunks_a), Some(hunks_b)) => {
+                    let only_a = hunks_not_in(hunks_a, hunks_b);
+                    let only_b = hunks_not_in(hunks_b, hunks_a);
+                    if only_a.is_empty() && only_b.is_empty() {
+                        println!("{} {}", "=".normal(), rel_path);
+                    } else {
+                        identical = false;
+                        println!(
+                            "{} {} ({} only in {}, {} only in {})",
+                            "~".yellow(),
+                            rel_path.yellow(),
+                            hunk_count(only_a.len()), name_a,
+                            hunk_count(only_b.len()), name_b
+                        );
+                        if self.show_hunks {
+                            show_hunks(&only_a, &name_a);
+                            show_hunks(&only_b, &name_b);
+                        }
+                    }
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+
+        println!();
+        if identical {
+            println!("{} and {} contain the same changes", name_a, name_b);
+        } else {
+            println!("{} and {} differ", name_a, name_b);
+        }
+        Ok(())
+    }
+}
+
+//  Return the hunks in `hunks` that are not matched by a hunk in `others`.
+//  Each hunk in `others` can only be matched once.
+fn hunks_not_in<'a>(hunks: &'a [Hunk], others: &[Hunk]) -> Vec<&'a Hunk> {
+    let mut unmatched: Vec<&Hunk> = others.iter().collect();
+    let mut result = Vec::new();
+    for hunk in hunks {
+        match unmatched.iter().position(|h| *h == hunk) {
+            Some(index) => { unmatched.remove(index); }
+            None        => result.push(hunk),
+        }
+    }
+    result
+}
+
+fn hunk_count(count: usize) -> String {
+    if count == 1 { "1 hunk".to_string() } else { format!("{} hunks", count) }
+}
+
+fn show_hunks(hunks: &[&Hunk], name: &str) {
+    for hunk in hunks {
+        println!("  {}", format!("[{}]", name).cyan());
+        print_diff_line(&hunk.header);
+        for line in &hunk.lines {
+            print_diff_line(line);
+        }
+    }
+}
+
+//  Parse a patch created by svn diff returning the hunks
+//  for each file keyed by the path of the file.
+fn parse_patch(patch_file: &Path) -> Result<BTreeMap<String, Vec<Hunk>>> {
+    let reader = BufReader::new(File::open(patch_file)?);
+    let mut files = BTreeMap::<String, Vec<Hunk>>::new();
+    let mut current: Option<String> = None;
+
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(path) = line.strip_prefix("Index: ") {
+            current = Some(path.to_string());
+            files.entry(path.to_string()).or_default();
+        } else if let Some(hunks) = current.as_ref().and_then(|p| files.get_mut(p)) {
+            if line.starts_with("@@") {
+                hunks.push(Hunk { header: line, lines: vec![] });
+            } else if let Some(hunk) = hunks.last_mut() {
+                hunk.lines.push(line);
+            }
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,76 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use std::io::{self, Read, Write};
+
+/// Export a stash entry as a patch for review.
+///
+/// The patch is preceded by a header containing the branch, base
+/// revision, author, date, description and list of files of the stash
+/// entry so that it can be attached to a review tool with its context.
+///
+/// The header is ignored by `svn patch` so the output can be
+/// applied to any working copy in the usual way.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The paths in the patch are relative to the root of the working copy.\n\
+    To apply it, run 'svn patch FILE' from the root of a working copy."
+)]
+pub struct Format {
+    /// Write the patch to a file instead of standard output.
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Id of the stash entry to export.
+    #[arg(value_name = "STASH", value_parser = parse_stash_id, default_value = "stash-0")]
+    stash_id: usize,
+}
+
+impl Format {
+    pub fn run(&mut self) -> Result<()> {
+        svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let stash_entries = load_stash_entries()?;
+
+        if self.stash_id >= stash_entries.len() {
+            let msg = format!("{} does not exist in the stash", stash_id_display(self.stash_id));
+            return Err(General(msg).into());
+        }
+        let stash = &stash_entries[self.stash_id];
+        let mut patch = String::new();
+        File::open(stash_path()?.join(&stash.patch_name))?.read_to_string(&mut patch)?;
+
+        let formatted = format!("{}\n{}", header(stash), patch);
+        match &self.output {
+            Some(path) => {
+                File::create(path)?.write_all(formatted.as_bytes())?;
+                println!("Wrote {} to {}", stash_id_display(self.stash_id), path.to_string_lossy().blue());
+            }
+            None => io::stdout().write_all(formatted.as_bytes())?,
+        }
+        Ok(())
+    }
+}
+
+//  The header lines must not look like the start of a diff
+//  so that `svn patch` skips them.
+fn header(stash: &StashFileEntry) -> String {
+    let mut lines = vec![
+        format!("Branch:      {}", stash.branch),
+        format!("Revision:    {}", stash.revision),
+        format!("Author:      {}", stash.author.as_deref().unwrap_or("unknown")),
+        format!("Date:        {}", util::svn_date_to_rfc3339_string(&stash.date)),
+        format!("Description: {}", stash.description),
+        "Files:".to_string(),
+    ];
+    for item in &stash.items {
+        //  Unversioned items are added to the patch.
+        let letter = if item.status == UNVERSIONED { "A" } else { item.status_letter() };
+        let suffix = if item.is_dir { "/" } else { "" };
+        lines.push(format!("  {} {}{}", letter, item.path, suffix));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
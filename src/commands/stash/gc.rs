@@ -0,0 +1,76 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use std::fs::remove_file;
+
+/// Remove old stash entries.
+///
+/// Drops all stash entries that are older than the expire age.
+/// The expire age defaults to 90 days and can be set with the
+/// stash.expire setting, the SVU_STASH_EXPIRE environment variable
+/// or the --expire option.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Gc {
+    /// Remove entries older than AGE. (eg. 30d, 8w, 6m, 1y)
+    #[arg(short, long, value_name = "AGE", value_parser = util::parse_age)]
+    expire: Option<Duration>,
+
+    /// Do not ask for confirmation.
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Show the entries that would be removed but do not remove them.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Gc {
+    pub fn run(&mut self) -> Result<()> {
+        svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let expire_age = match self.expire {
+            Some(age) => age,
+            None      => stash_expire_age()?,
+        };
+        let stash_entries = load_stash_entries()?;
+        let (expired, keep): (Vec<_>, Vec<_>) = stash_entries
+            .into_iter()
+            .enumerate()
+            .partition(|(_, stash)| stash.is_older_than(&expire_age));
+
+        if expired.is_empty() {
+            println!("No stash entries are older than {} days", expire_age.num_days());
+            return Ok(());
+        }
+
+        for (index, stash) in &expired {
+            println!(
+                "{:<8} | {} {}",
+                stash_id_display(*index),
+                stash.summary_display(),
+                format!("({})", util::display_age(&stash.date)).red()
+            );
+        }
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let prompt = format!("Drop these {} stash entries?", expired.len());
+        if self.yes || util::confirm(&prompt)? {
+            let keep: Vec<StashFileEntry> = keep.into_iter().map(|(_, stash)| stash).collect();
+            save_stash_entries(&keep)?;
+            for (_, stash) in &expired {
+                let patch_file = stash_path()?.join(stash.patch_name.as_str());
+                if patch_file.is_file() {
+                    remove_file(patch_file)?;
+                }
+            }
+            println!("Dropped {} stash entries", expired.len());
+        }
+        Ok(())
+    }
+}
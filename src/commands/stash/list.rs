@@ -1,10 +1,14 @@
-
 use clap::Parser;
 use super::*;
 use anyhow::Result;
 use crate::svn;
 
 /// Display stash entries.
+///
+/// Entries that are older than the stash expire age are highlighted.
+/// The expire age defaults to 90 days and can be set with the
+/// stash.expire setting or the SVU_STASH_EXPIRE environment variable,
+/// which overrides it. (eg. SVU_STASH_EXPIRE=30d)
 #[derive(Debug, Parser)]
 #[command(
     author,
@@ -15,13 +19,34 @@ pub struct List;
 impl List {
     pub fn run(&mut self) -> Result<()> {
         svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let expire_age = stash_expire_age()?;
+        let mut num_expired = 0;
 
         for (index, stash) in load_stash_entries()?.iter().enumerate() {
-            println!(
-                "{:<8} | {}",
-                stash_id_display(index),
-                stash.summary_display()
+            if stash.is_older_than(&expire_age) {
+                num_expired += 1;
+                println!(
+                    "{:<8} | {} {}",
+                    stash_id_display(index),
+                    stash.summary_display(),
+                    format!("({})", util::display_age(&stash.date)).red()
+                );
+            } else {
+                println!(
+                    "{:<8} | {}",
+                    stash_id_display(index),
+                    stash.summary_display()
+                );
+            }
+        }
+
+        if num_expired > 0 {
+            let noun = if num_expired == 1 { "entry is" } else { "entries are" };
+            let msg = format!(
+                "{} stash {} older than {} days, use 'stash gc' to remove them",
+                num_expired, noun, expire_age.num_days()
             );
+            println!("{}", msg.yellow());
         }
         Ok(())
     }
@@ -1,10 +1,17 @@
-
 use clap::Parser;
 use super::*;
 use anyhow::Result;
 use std::fs::remove_file;
+use std::io::{BufReader, Write};
+use crate::util::datetime_serializer;
+use crate::hooks;
 
 /// Remove a stash entry and apply it to the working copy.
+///
+/// If the stash cannot be applied cleanly the stash entry is kept and
+/// a summary of the files that were applied and rejected is displayed.
+/// After fixing the rejected files use `svu stash pop --retry` to apply
+/// only the files that remain.
 #[derive(Debug, Parser)]
 #[command(
     author,
@@ -16,25 +23,70 @@ pub struct Pop {
     #[arg(short = 'n', long)]
     dry_run: bool,
 
+    /// Lock any files that have the svn:needs-lock property
+    /// before applying the stash.
+    #[arg(long)]
+    acquire_locks: bool,
+
+    /// Retry a failed stash pop, applying only the files that
+    /// were rejected or skipped.
+    #[arg(long, conflicts_with_all = ["stash_id", "acquire_locks"])]
+    retry: bool,
+
     /// Id of the stash you wish to apply and drop.
     #[arg(value_name = "STASH", value_parser = parse_stash_id, default_value = "stash-0")]
     stash_id: usize,
 }
 
+//  Written to .svu/stash/pop_failure.json when a stash pop
+//  cannot be applied cleanly so that it can be retried.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PopFailure {
+    #[serde(with = "datetime_serializer")]
+    date: DateTime<Local>,
+    #[serde(rename = "patchName")]
+    patch_name: String,
+    #[serde(flatten)]
+    outcome: PatchOutcome,
+}
+
 impl Pop {
+    //  Pop the given stash entry with the default options.
+    pub fn new(stash_id: usize) -> Self {
+        Pop { dry_run: false, acquire_locks: false, retry: false, stash_id }
+    }
+
     pub fn run(&mut self) -> Result<()> {
         let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
         let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+
+        if self.retry {
+            return self.retry_pop(&wc_root);
+        }
+
         let mut stash_entries = load_stash_entries()?;
+        operation::ensure_none_in_progress()?;
+
         if self.stash_id < stash_entries.len() {
             let stash = stash_entries.remove(self.stash_id);
-            apply_stash(&stash, &wc_root, self.dry_run)?;
+            let outcome = apply_stash(&stash, &wc_root, self.dry_run, self.acquire_locks)?;
 
-            if !self.dry_run {
+            if self.dry_run {
+                // Nothing to do
+            } else if !outcome.is_clean() {
+                //  Keep the stash entry until the conflicts have been resolved.
+                outcome.show_summary(&wc_root);
+                save_pop_failure(&stash, &outcome)?;
+                suspend_stash_operation("stash pop", &stash, true)?;
+                let cmd_name: String = std::env::args().take(1).collect();
+                println!("After fixing the rejected files you may run '{} stash pop --retry'", cmd_name);
+                println!("to apply the files that remain.");
+            } else {
                 let patch_file = stash_path()?.join(stash.patch_name.as_str());
                 save_stash_entries(&stash_entries)?;
                 remove_file(patch_file)?;
                 println!("Dropped stash: {}", stash.summary_display());
+                hooks::run("post-stash-pop", &[("BRANCH", &stash.branch), ("REVISION", &stash.revision)])?;
             }
             Ok(())
         } else {
@@ -45,4 +97,108 @@ impl Pop {
             Err(General(msg).into())
         }
     }
+
+    //  Apply the portion of the stash patch for the files that were
+    //  rejected or skipped by the failed pop.  When everything has been
+    //  applied the stash pop operation is completed.
+    fn retry_pop(&self, wc_root: &Path) -> Result<()> {
+        let failure = match (operation::in_progress()?, load_pop_failure()?) {
+            (Some(name), Some(failure)) if name == "stash pop" => failure,
+            _ => return Err(General("There is no failed stash pop to retry".to_string()).into()),
+        };
+        let stash = load_stash_entries()?
+            .into_iter()
+            .find(|s| s.patch_name == failure.patch_name)
+            .ok_or(General("The stash entry of the failed stash pop no longer exists".to_string()))?;
+
+        let remaining = failure.outcome.remaining();
+        let patch_file = stash_path()?.join(&stash.patch_name);
+        let retry_file = stash_path()?.join(format!("{}.retry", stash.patch_name));
+        write_partial_patch(&patch_file, &remaining, &retry_file)?;
+
+        if !self.dry_run {
+            //  Any reject files from the failed attempt would otherwise be overwritten.
+            for path in &remaining {
+                let reject = wc_root.join(format!("{}.svnpatch.rej", path));
+                if reject.is_file() {
+                    remove_file(reject)?;
+                }
+            }
+        }
+        let result = apply_patch_file(&retry_file, wc_root, self.dry_run);
+        remove_file(&retry_file)?;
+        let outcome = result?;
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let mut combined = failure.outcome.clone();
+        combined.applied.extend(outcome.applied);
+        combined.rejected = outcome.rejected;
+        combined.skipped = outcome.skipped;
+
+        if combined.is_clean() {
+            operation::resume(wc_root)?;
+        } else {
+            combined.show_summary(wc_root);
+            save_pop_failure(&stash, &combined)?;
+        }
+        Ok(())
+    }
+}
+
+//  Write a patch file containing only the sections of the original
+//  patch that apply to the given paths.
+fn write_partial_patch(patch_file: &Path, paths: &[String], output: &Path) -> Result<()> {
+    let reader = BufReader::new(File::open(patch_file)?);
+    let mut writer = File::create(output)?;
+    let mut include = false;
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(path) = line.strip_prefix("Index: ") {
+            include = paths.iter().any(|p| p == path);
+        }
+        if include {
+            writeln!(writer, "{}", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn pop_failure_file() -> Result<PathBuf> {
+    Ok(stash_path()?.join("pop_failure.json"))
+}
+
+fn load_pop_failure() -> Result<Option<PopFailure>> {
+    let path = pop_failure_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        let failure: PopFailure = serde_json::from_reader(reader)?;
+        Ok(Some(failure))
+    } else {
+        Ok(None)
+    }
+}
+
+//  Used by `svu doctor` to validate the data file.
+pub(super) fn check_data_file() -> Result<(PathBuf, Result<()>)> {
+    Ok((pop_failure_file()?, load_pop_failure().map(|_| ())))
+}
+
+fn save_pop_failure(stash: &StashFileEntry, outcome: &PatchOutcome) -> Result<()> {
+    let failure = PopFailure {
+        date: Local::now(),
+        patch_name: stash.patch_name.clone(),
+        outcome: outcome.clone(),
+    };
+    util::save_json(&pop_failure_file()?, &failure)
+}
+
+pub(super) fn remove_pop_failure() -> Result<()> {
+    let path = pop_failure_file()?;
+    if path.is_file() {
+        remove_file(path)?;
+    }
+    Ok(())
 }
@@ -5,6 +5,9 @@ use std::path::Path;
 use super::*;
 use anyhow::Result;
 use uuid::Uuid;
+use crate::commands::mixed;
+use crate::interrupt;
+use crate::hooks;
 
 /// Push the working copy to the stash and revert the working copy.
 #[derive(Debug, Args, Clone)]
@@ -21,15 +24,47 @@ pub struct PushArgs {
     /// Include unversioned files in the stash.
     ///
     /// Normally, unversioned files are not saved in the stash.
-    #[arg(short, long)]
+    /// The unversioned items and everything beneath them are temporarily added
+    /// to the working copy using the auto-props setting of your svn
+    /// configuration, which the stash.auto-props setting overrides.
+    #[arg(short, long, conflicts_with = "changelists")]
     unversioned: bool,
 
+    /// Only stash items that belong to changelist NAME.
+    ///
+    /// May be given multiple times to include the items from several changelists.
+    /// The changelist membership of each item is restored when the stash is reapplied.
+    #[arg(short, long = "changelist", value_name = "NAME")]
+    changelists: Vec<String>,
+
     /// Do not revert the working copy.
     /// 
     /// Save the working copy changes, but leaave the working copy as is.  This can be
     /// used to take a snapshot of your current changes.
     #[arg(short, long)]
     no_revert: bool,
+
+    /// Create a separate stash entry for each component directory.
+    ///
+    /// Items are grouped by the first DEPTH directories of their paths
+    /// and each group is saved with its own patch file so that the components
+    /// can later be reapplied individually.  Items that live above DEPTH are
+    /// grouped by their parent directory.
+    #[arg(short, long, value_name = "DEPTH", value_parser = clap::value_parser!(u32).range(1..))]
+    split_by_dir: Option<u32>,
+}
+
+impl PushArgs {
+    //  The arguments for a plain push with the given description.
+    pub fn with_message(message: Option<String>) -> Self {
+        PushArgs {
+            message,
+            unversioned: false,
+            changelists: Vec::new(),
+            no_revert: false,
+            split_by_dir: None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -45,14 +80,17 @@ impl Push {
 
     pub fn run(&mut self) -> Result<()> {
 
+        svn::ensure_writable("stash changes")?;
         let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
         let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
-        let items = get_stash_items(&wc_root, self.args.unversioned)?;
+        let items = get_stash_items(&wc_root, self.args.unversioned, &self.args.changelists)?;
 
         if items.is_empty() {
             println!("No local changes to save");
         } else {
+            mixed::warn_if_mixed(&wc_root)?;
             let (branch, revision) = svn::current_branch(&wc_root)?;
+            hooks::run("pre-stash-push", &[("BRANCH", &branch), ("REVISION", &revision)])?;
             let description = self
                 .args
                 .message
@@ -60,44 +98,69 @@ impl Push {
                 .unwrap_or(get_log_message_1st(&wc_root)?);
 
             let stash_path = stash_path()?;
-            let patch_name = create_patch_name();
-
-            svn::create_patch(&stash_path.join(patch_name.as_str()), &wc_root)?;
-
-            let stash = StashFileEntry {
-                branch,
-                revision,
-                description,
-                date: Local::now(),
-                patch_name,
-                items: items.clone(),
+            let groups = match self.args.split_by_dir {
+                Some(depth) => group_items_by_dir(&items, depth as usize),
+                None        => vec![ItemGroup::whole(&items)],
             };
-            add_stash_entry(&stash)?;
+            let mut stashes = Vec::<StashFileEntry>::new();
+            let mut progress = interrupt::Progress::new();
+
+            for group in &groups {
+                let patch_name = create_patch_name();
+                let description = match &group.component {
+                    Some(component) => format!("{} ({})", description, component),
+                    None            => description.clone(),
+                };
+
+                let patch_file = stash_path.join(patch_name.as_str());
+                progress.step(format!("Writing the patch for: {}", description));
+                progress.track_file(&patch_file);
+                svn::create_patch(
+                    &patch_file,
+                    &group.targets,
+                    group.depth,
+                    &self.args.changelists,
+                    &wc_root
+                )?;
+
+                let stash = StashFileEntry {
+                    branch: branch.clone(),
+                    revision: revision.clone(),
+                    description,
+                    author: std::env::var("USER").or(std::env::var("USERNAME")).ok(),
+                    date: Local::now(),
+                    patch_name,
+                    items: group.items.clone(),
+                };
+                add_stash_entry(&stash)?;
+                progress.keep_files();
+                stashes.push(stash);
+            }
 
             if !self.args.no_revert {
                 // Lastly we revert the working copy.
-                // We will explicitly revert all entries to ensure that the --remove-added flag is honored.
-                // For added/unversioned directories we do not need to revert any entries below them
-                // as these entries will be reverted recursively with their respective  directories.
-                let added_unversioned: Vec<StashItem> = items
-                    .iter()
-                    .filter(|i| i.is_dir && (i.status == ADDED || i.status == UNVERSIONED))
-                    .cloned()
-                    .collect();
-                let can_skip = |i: &StashItem| -> bool {
-                    added_unversioned
-                        .iter()
-                        .any(|p| i.path.starts_with(&p.path) && i.path != p.path)
-                };
-                let revert_paths: Vec<String> = items
-                    .iter()
-                    .filter(|i| !can_skip(i))
-                    .map(|i| i.path.clone())
-                    .collect();
+                progress.step_with_hint(
+                    "Reverting the working copy",
+                    "Your changes were saved in the stash.  Run 'svn revert -R .' in the\n\
+                     working copy root to finish reverting them."
+                );
+                let revert_paths = stash_revert_paths(&items);
                 svn::revert(&revert_paths, "infinity", true, Some(&wc_root))?;
             }
+            progress.finish();
 
-            println!("Saved working copy state - {}", stash.summary_display());
+            for stash in &stashes {
+                println!("Saved working copy state - {}", stash.summary_display());
+            }
+            let patch_files: Vec<String> = stashes
+                .iter()
+                .map(|s| stash_path.join(&s.patch_name).to_string_lossy().to_string())
+                .collect();
+            hooks::run("post-stash-push", &[
+                ("BRANCH", &branch),
+                ("REVISION", &revision),
+                ("PATCH_FILES", &patch_files.join("\n")),
+            ])?;
         }
         Ok(())
     }
@@ -120,3 +183,61 @@ fn create_patch_name() -> String {
     format!("{}.patch", Uuid::new_v4())
 }
 
+//  A set of stash items that are saved together in a single patch.
+struct ItemGroup {
+    component: Option<String>,
+    targets: Vec<String>,
+    depth: &'static str,
+    items: Vec<StashItem>,
+}
+
+impl ItemGroup {
+    fn whole(items: &[StashItem]) -> Self {
+        ItemGroup {
+            component: None,
+            targets: vec![".".to_string()],
+            depth: "infinity",
+            items: items.to_vec(),
+        }
+    }
+}
+
+//  Group stash items by the first `depth` directories of their paths.
+//  When a component directory is `depth` levels deep, the patch for that
+//  group is created by diffing the directory recursively.
+//  Items that are located above `depth` are grouped with their parent
+//  directory and are diffed individually with depth=empty so that their
+//  patch does not overlap with the patches of deeper components.
+fn group_items_by_dir(items: &[StashItem], depth: usize) -> Vec<ItemGroup> {
+    let mut groups = Vec::<ItemGroup>::new();
+
+    for item in items {
+        let path = item.path.trim_end_matches('/');
+        let mut dirs: Vec<&str> = path.split('/').collect();
+        if !item.is_dir {
+            dirs.pop();
+        }
+        let complete = dirs.len() >= depth;
+        dirs.truncate(depth);
+        let component = if dirs.is_empty() { ".".to_string() } else { dirs.join("/") };
+
+        let index = match groups.iter().position(|g| g.component.as_ref() == Some(&component)) {
+            Some(index) => index,
+            None => {
+                groups.push(ItemGroup {
+                    component: Some(component.clone()),
+                    targets: if complete { vec![component.clone()] } else { vec![] },
+                    depth: if complete { "infinity" } else { "empty" },
+                    items: vec![],
+                });
+                groups.len() - 1
+            }
+        };
+        let group = &mut groups[index];
+        if group.depth == "empty" {
+            group.targets.push(path.to_string());
+        }
+        group.items.push(item.clone());
+    }
+    groups
+}
@@ -7,6 +7,7 @@ use crate::svn;
 use std::env::current_dir;
 use std::io::{BufReader, BufRead};
 use pathdiff::diff_paths;
+use std::collections::BTreeMap;
 
 /// Display the details of a stash entry.
 #[derive(Debug, Parser)]
@@ -19,6 +20,13 @@ pub struct Show {
     #[arg(short = 'd', long)]
     show_diff: bool,
 
+    /// Display the items as a directory tree.
+    ///
+    /// Each directory shows the number of items beneath it
+    /// along with a count of each status.
+    #[arg(short, long)]
+    tree: bool,
+
     /// Id of the stash entry you wish to display.
     #[arg(value_name = "STASH", value_parser = parse_stash_id, default_value = "stash-0")]
     stash_id: usize,
@@ -53,28 +61,27 @@ impl Show {
                 rel_patch.to_string_lossy().blue()
             );
             println!("{:->70}", "-");
-            for item in &stash.items {
-                let mut pathname = item.path.clone();
-                //  append '/' for directories
-                if item.is_dir {
-                    pathname.push('/');
+            if self.tree {
+                show_tree(&stash.items);
+            } else {
+                for item in &stash.items {
+                    let mut pathname = item.path.clone();
+                    //  append '/' for directories
+                    if item.is_dir {
+                        pathname.push('/');
+                    }
+                    let path = Path::new(pathname.as_str());
+                    // First create the full path to the item relative to the working copy root.
+                    // Then make that relative to the current working directory.
+                    let rel_path = diff_paths(wc_root.join(path), &cwd).unwrap();
+                    let color = item.status_color();
+                    println!(
+                        "{} {}{}",
+                        item.status_letter().color(color),
+                        rel_path.to_string_lossy().color(color),
+                        item_details(item)
+                    );
                 }
-                let path = Path::new(pathname.as_str());
-                // First create the full path to the item relative to the working copy root.
-                // Then make that relative to the current working directory.
-                let rel_path = diff_paths(&wc_root.join(path), &cwd).unwrap();
-                let revision = match item.status.as_str() {
-                    UNVERSIONED => "unversioned",
-                    ADDED => "new",
-                    _ => item.revision.as_str(),
-                };
-                let color = item.status_color();
-                println!(
-                    "{} {} [{}]",
-                    item.status_letter().color(color),
-                    rel_path.to_string_lossy().color(color),
-                    revision.yellow()
-                );
             }
 
             if self.show_diff {
@@ -95,3 +102,90 @@ impl Show {
         }
     }
 }
+
+//  The revision and changelist displayed after an item's path.
+fn item_details(item: &StashItem) -> String {
+    let revision = match item.status.as_str() {
+        UNVERSIONED => "unversioned",
+        ADDED => "new",
+        _ => item.revision.as_str(),
+    };
+    match &item.changelist {
+        Some(name) => format!(" [{}] ({})", revision.yellow(), name.cyan()),
+        None       => format!(" [{}]", revision.yellow()),
+    }
+}
+
+//  A directory in the tree of stash items.
+//  A directory may also be a stash item itself if it was added or deleted.
+#[derive(Default)]
+struct TreeNode<'a> {
+    item: Option<&'a StashItem>,
+    children: BTreeMap<String, TreeNode<'a>>,
+}
+
+impl<'a> TreeNode<'a> {
+    fn insert(&mut self, item: &'a StashItem) {
+        let node = item.path
+            .split('/')
+            .filter(|c| !c.is_empty())
+            .fold(self, |node, name| node.children.entry(name.to_string()).or_default());
+        node.item = Some(item);
+    }
+
+    //  Count the items beneath this node by status letter.
+    fn counts(&self, counts: &mut BTreeMap<&'static str, usize>) {
+        for child in self.children.values() {
+            if let Some(item) = child.item {
+                *counts.entry(item.status_letter()).or_insert(0) += 1;
+            }
+            child.counts(counts);
+        }
+    }
+
+    fn show(&self, name: &str, depth: usize) {
+        let indent = "  ".repeat(depth);
+        if self.children.is_empty() {
+            if let Some(item) = self.item {
+                let color = item.status_color();
+                let name = if item.is_dir { format!("{}/", name) } else { name.to_string() };
+                println!("{}{} {}{}", indent, item.status_letter().color(color), name.color(color), item_details(item));
+            }
+            return;
+        }
+
+        //  Collapse chains of directories that contain only a single directory.
+        let mut name = name.to_string();
+        let mut node = self;
+        while node.item.is_none() && node.children.len() == 1 {
+            let (child_name, child) = node.children.iter().next().unwrap();
+            if child.children.is_empty() {
+                break;
+            }
+            name = if name.is_empty() { child_name.clone() } else { format!("{}/{}", name, child_name) };
+            node = child;
+        }
+
+        let mut counts = BTreeMap::new();
+        node.counts(&mut counts);
+        let total: usize = counts.values().sum();
+        let summary = counts.iter()
+            .map(|(letter, count)| format!("{}{}", count, letter))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let letter = node.item.map(|item| item.status_letter().color(item.status_color())).unwrap_or(" ".normal());
+        let dir_name = if name.is_empty() { ".".to_string() } else { format!("{}/", name) };
+        println!("{}{} {} ({}: {})", indent, letter, dir_name.blue(), total, summary);
+        for (child_name, child) in &node.children {
+            child.show(child_name, depth + 1);
+        }
+    }
+}
+
+fn show_tree(items: &[StashItem]) {
+    let mut root = TreeNode::default();
+    for item in items {
+        root.insert(item);
+    }
+    root.show("", 0);
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn::{self, StatusEntry};
+use crate::util;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Show the status of the working copy grouped by state.
+///
+/// The current branch and revision are displayed followed by the
+/// changed items grouped as conflicted, modified, added, deleted,
+/// replaced, missing and unversioned.  Items that belong to a changelist
+/// are shown with the name of the changelist.
+///
+/// The column after the status letter shows `K` for an item locked by
+/// this working copy and `L` for an item that has the svn:needs-lock
+/// property but is not locked.  Unmodified items that are locked are
+/// listed as locked.
+#[derive(Debug, Parser)]
+#[command(
+    visible_alias = "stat",
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Items that have only property modifications are included with\n\
+    the modified items.  Ignored items are never shown."
+)]
+pub struct Status {
+    /// Only show items that belong to changelist NAME.
+    ///
+    /// May be given multiple times to include the items from several changelists.
+    #[arg(short, long = "changelist", value_name = "NAME")]
+    changelists: Vec<String>,
+
+    /// Also show the status of the items within svn:externals directories.
+    #[arg(short = 'x', long)]
+    externals: bool,
+
+    /// Do not show unversioned items.
+    #[arg(short = 'q', long)]
+    quiet: bool,
+
+    /// Path to a working copy file or directory.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+//  The groups in the order that they are displayed.
+//  (heading, letter, color)
+const GROUPS: &[(&str, &str, &str)] = &[
+    ("Conflicted",   "C", "red"),
+    ("Modified",     "M", "magenta"),
+    ("Added",        "A", "green"),
+    ("Deleted",      "D", "red"),
+    ("Replaced",     "R", "yellow"),
+    ("Missing",      "!", "red"),
+    ("Obstructed",   "~", "red"),
+    ("Incomplete",   "I", "yellow"),
+    ("Locked",       " ", "cyan"),
+    ("Unversioned",  "?", "white"),
+];
+
+fn group_of(entry: &StatusEntry) -> Option<&'static str> {
+    if entry.item_status == "conflicted" || entry.props_status == "conflicted" {
+        return Some("Conflicted");
+    }
+    match entry.item_status.as_str() {
+        "modified"    => Some("Modified"),
+        "added"       => Some("Added"),
+        "deleted"     => Some("Deleted"),
+        "replaced"    => Some("Replaced"),
+        "missing"     => Some("Missing"),
+        "obstructed"  => Some("Obstructed"),
+        "incomplete"  => Some("Incomplete"),
+        "unversioned" => Some("Unversioned"),
+        "normal" if entry.props_status == "modified" => Some("Modified"),
+        "normal" if entry.locked => Some("Locked"),
+        _             => None,
+    }
+}
+
+impl Status {
+    pub fn run(&mut self) -> Result<()> {
+        let (branch, revision) = svn::current_branch(Path::new(&self.path))?;
+        println!("On branch {} at revision {}", branch.green(), revision.yellow());
+
+        let targets = if self.externals {
+            svn::status_with_externals(&self.path, &self.changelists, None)?
+        } else {
+            vec![svn::status_changelists(&self.path, &self.changelists, None)?]
+        };
+
+        let needs_lock = self.needs_lock_paths()?;
+        let mut targets = targets.iter();
+        if let Some(main) = targets.next() {
+            let shown = self.show_entries(&main.entries, &needs_lock);
+            let externals = main.entries.iter().filter(|e| e.item_status == "external").count();
+            if !self.externals && externals > 0 {
+                println!();
+                println!(
+                    "{} {} not shown (use --externals)",
+                    externals,
+                    if externals == 1 { "external" } else { "externals" }
+                );
+            } else if shown == 0 {
+                println!("No local changes");
+            }
+        }
+
+        for external in targets {
+            println!();
+            println!("{} {}", "External".cyan(), external.path.cyan());
+            if self.show_entries(&external.entries, &needs_lock) == 0 {
+                println!("No local changes");
+            }
+        }
+        Ok(())
+    }
+
+    //  Return the paths that have the svn:needs-lock property in the
+    //  same form as the paths reported by svn status.
+    fn needs_lock_paths(&self) -> Result<HashSet<String>> {
+        let path = Path::new(&self.path);
+        if !path.is_dir() {
+            let needs_lock = svn::propget(&None, "svn:needs-lock", &self.path)?.is_some();
+            return Ok(needs_lock.then(|| self.path.clone()).into_iter().collect());
+        }
+        Ok(svn::needs_lock_paths(path)?
+            .into_iter()
+            .map(|p| if self.path == "." { p } else { util::join_paths(&self.path, p) })
+            .collect())
+    }
+
+    //  Display the entries by group and return the number displayed.
+    fn show_entries(&self, entries: &[StatusEntry], needs_lock: &HashSet<String>) -> usize {
+        let mut shown = 0;
+        for (heading, letter, color) in GROUPS {
+            if self.quiet && *heading == "Unversioned" {
+                continue;
+            }
+            //  svu's own data directory is not one of the user's changes.
+            let mut group: Vec<&StatusEntry> = entries
+                .iter()
+                .filter(|e| group_of(e) == Some(heading) && !util::is_data_directory(Path::new(&e.path)))
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+            group.sort_by(|a, b| a.path.cmp(&b.path));
+            println!();
+            println!("{} ({}):", heading, group.len());
+            for entry in group {
+                let changelist = match &entry.changelist {
+                    Some(name) => format!(" ({})", name.cyan()),
+                    None       => String::new(),
+                };
+                let lock = if entry.locked {
+                    "K".cyan()
+                } else if needs_lock.contains(&entry.path) {
+                    "L".yellow()
+                } else {
+                    " ".normal()
+                };
+                println!("  {}{} {}{}", letter.color(*color), lock, entry.path.color(*color), changelist);
+                shown += 1;
+            }
+        }
+        shown
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::operation::{self, Step};
+use crate::commands::{snapshot, stash};
+use crate::hooks;
+use crate::svn;
+use crate::util::{join_paths, SvError::*};
+use std::path::PathBuf;
+
+/// Switch the working copy to another branch or tag.
+///
+/// The branch is found using the configured trunk, branch and tag
+/// prefixes (see the `prefix` command) so only its short name is needed.
+///
+/// The working copy must not contain local modifications.  Use --stash
+/// to stash them first and reapply them after switching, or --force to
+/// carry them over to the new branch.  If the stash cannot be reapplied
+/// use `svu continue` once the conflicts have been resolved.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A BRANCH can be the trunk prefix, a full location such as branches/8.1\n\
+    or just the name of a branch or tag.  A bare name is looked up under each\n\
+    of the branch prefixes and then each of the tag prefixes."
+)]
+pub struct Switch {
+    /// The branch or tag to switch to.
+    #[arg(value_name = "BRANCH")]
+    branch: String,
+
+    /// The revision to switch to.  Defaults to HEAD.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Switch even if the working copy has local modifications.
+    #[arg(short, long, conflicts_with = "stash")]
+    force: bool,
+
+    /// Stash local modifications before switching and reapply them after.
+    #[arg(short, long)]
+    stash: bool,
+}
+
+impl Switch {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.as_ref().unwrap());
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+        let prefixes = svn::load_prefixes()?;
+        let location = svn::resolve_location(&creds, &wc_info.root_url, &self.branch, &prefixes)?;
+        let target = format!("^/{}", location);
+
+        if wc_info.rel_url == target {
+            println!("Already on {}", target.green());
+            return Ok(());
+        }
+
+        let url = join_paths(&wc_info.root_url, &location);
+        if !svn::path_exists(&creds, &url)? {
+            return Err(General(format!("{} does not exist", target)).into());
+        }
+
+        let revision = match &self.revision {
+            Some(rev) => Some(svn::resolve_revision(&creds, rev, &url)?),
+            None      => None,
+        };
+
+        hooks::run("pre-switch", &[("BRANCH", &target), ("OLD_BRANCH", &wc_info.rel_url)])?;
+        operation::ensure_none_in_progress()?;
+        snapshot::before("switch")?;
+        let mut stashed = None;
+        if !svn::local_changes(&wc_root)?.is_empty() {
+            if self.stash {
+                let message = format!("switch from {} to {}", wc_info.rel_url, target);
+                stashed = stash::push_working_copy(Some(message))?;
+            } else if !self.force {
+                let msg = "The working copy has local modifications.\n\
+                           Use --stash to stash them or --force to switch anyway";
+                return Err(General(msg.to_string()).into());
+            }
+        }
+
+        //  The stash is popped by `svu continue` if the switch fails.
+        if let Some(patch_name) = &stashed {
+            operation::begin("switch", vec![Step::PopStash { patch_name: patch_name.clone() }], vec![])?;
+        }
+        if let Err(e) = svn::switch(&creds, &url, revision.as_deref(), Some(&wc_root)) {
+            if stashed.is_some() {
+                operation::show_failed("switch");
+            }
+            return Err(e);
+        }
+        let (_, revision) = svn::current_branch(&wc_root)?;
+        println!("Switched to {} [{}]", target.green(), revision.yellow());
+        hooks::run("post-switch", &[
+            ("BRANCH", &target),
+            ("OLD_BRANCH", &wc_info.rel_url),
+            ("REVISION", &revision),
+        ])?;
+        if stashed.is_some() {
+            operation::complete(&wc_root)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use colored::*;
+use crate::svn;
+use crate::util::{self, SvError::*};
+
+mod create;
+mod list;
+
+/// Create and list tags.
+///
+/// Tags are placed under the first tag prefix and are listed from
+/// all of the tag prefixes (see the `prefix` command).
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+#[command(flatten_help = false)]
+pub struct Tag {
+    #[command(subcommand)]
+    command: TagCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum TagCommands {
+    Create(create::Create),
+    List(list::List),
+}
+use TagCommands::*;
+
+impl Tag {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Create(cmd) => cmd.run(),
+            List(cmd)   => cmd.run(),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::commands::branch::copy_source;
+
+/// Create a new tag from the current branch.
+///
+/// The tag is created in the repository by copying the branch that
+/// is checked out to your working copy, or the branch or revision given
+/// with --from.  It is placed under the first tag prefix.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Create {
+    /// Name of the new tag relative to the tag prefix.
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// The branch, tag or revision to create the tag from.
+    ///
+    /// This may be the name of a branch, a revision of the current
+    /// branch such as HEAD-3, or a branch at a specific revision such as
+    /// trunk@1200.  Defaults to the current branch at HEAD.
+    #[arg(long, value_name = "BRANCH|REV")]
+    from: Option<String>,
+
+    /// Commit message used when creating the tag.
+    #[arg(short, long, value_name = "MSG")]
+    message: Option<String>,
+
+    /// Show the tag that would be created but do not create it.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Create {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let wc_info = svn::info(&creds, wc_info.wc_path.as_ref().unwrap(), None)?; // The branch root
+
+        let prefixes = svn::load_prefixes()?;
+        let prefix = prefixes.tag_prefixes.first().map(|p| p.as_str()).unwrap_or("tags");
+        let name = self.name.trim_matches('/');
+        let (source, source_url) = copy_source(&creds, &wc_info, self.from.as_deref())?;
+        let target_url = util::join_paths(util::join_paths(&wc_info.root_url, prefix), name);
+        let target = format!("^/{}/{}", prefix, name);
+
+        if svn::path_exists(&creds, &target_url)? {
+            return Err(General(format!("{} already exists", target)).into());
+        }
+
+        let message = self.message.clone().unwrap_or(format!("Create tag {} from {}", target, source));
+        if self.dry_run {
+            println!("Would create tag {} from {}", target.green(), source.green());
+        } else {
+            svn::copy(&creds, &source_url, &target_url, &message)?;
+            println!("Created tag {} from {}", target.green(), source.green());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use regex::Regex;
+
+/// List the tags in the repository.
+///
+/// Each tag is shown with the revision and date that it was created.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct List {
+    /// Only list tags that match <REGEX>.
+    #[arg(value_name = "REGEX")]
+    regex: Option<Regex>,
+}
+
+impl List {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let prefixes = svn::load_prefixes()?;
+
+        //  A tag is never modified once it has been created so the
+        //  last commit of its directory is the commit that created it.
+        let mut tags = Vec::new();
+        let mut tag_prefixes = prefixes.tag_prefixes.clone();
+        tag_prefixes.sort();
+        for prefix in &tag_prefixes {
+            let path_list = svn::path_list(&creds, &util::join_paths(&wc_info.root_url, prefix))?;
+            for entry in path_list.entries {
+                let tag = format!("^/{}", util::join_paths(prefix, &entry.name));
+                if !prefixes.tag_prefixes.iter().any(|p| p == &util::join_paths(prefix, &entry.name))
+                    && self.regex.as_ref().map(|r| r.is_match(&tag)).unwrap_or(true) {
+                    tags.push((tag, entry.commit_rev, entry.commit_date));
+                }
+            }
+        }
+        tags.sort_by_key(|(_, rev, _)| rev.parse::<u64>().unwrap_or(0));
+
+        let max_rev_len = tags.iter().map(|(_, rev, _)| rev.len()).max().unwrap_or(0);
+        for (tag, rev, date) in &tags {
+            println!(
+                "{} {} {}",
+                format!("{:>width$}", rev, width = max_rev_len).yellow(),
+                util::display_svn_date(date).magenta(),
+                tag.green()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use crate::commands::commit::status_letter;
+use crate::svn::StatusEntry;
+use crate::issues;
+use crate::util;
+use regex::Regex;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+mod render;
+
+/// Work with the commit message template.
+///
+/// The message template is read from .svu/commit-template.txt and is
+/// used by `svu commit` as the starting point of each commit message,
+/// so that a team can standardize its messages.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The template may contain these placeholders:\n\
+      {branch}  the branch checked out to the working copy\n\
+      {ticket}  the issue ID, also written {issue}\n\
+      {files}   the changed files, one per line\n\n\
+    The ticket is taken from --ticket or found in the branch name using\n\
+    the issues.pattern setting (default [A-Z][A-Z0-9]+-[0-9]+).\n\
+    Without a template file the message starts with the ticket."
+)]
+#[command(flatten_help = false)]
+pub struct Template {
+    #[command(subcommand)]
+    command: TemplateCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum TemplateCommands {
+    Render(render::Render),
+}
+use TemplateCommands::*;
+
+impl Template {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            Render(cmd) => cmd.run(),
+        }
+    }
+}
+
+const DEFAULT_TICKET_PATTERN: &str = "[A-Z][A-Z0-9]+-[0-9]+";
+
+const DEFAULT_TEMPLATE: &str = "{ticket} \n";
+
+fn template_file() -> Result<PathBuf> {
+    Ok(util::data_directory()?.join("commit-template.txt"))
+}
+
+pub(crate) fn load_template() -> Result<String> {
+    let path = template_file()?;
+    if path.is_file() {
+        Ok(fs::read_to_string(path)?)
+    } else {
+        Ok(DEFAULT_TEMPLATE.to_string())
+    }
+}
+
+//  Replace the {branch}, {ticket}, {issue} and {files} placeholders.
+//  If there is no ticket the placeholder is removed.
+pub(crate) fn expand_template(template: &str, branch: &str, ticket: Option<&str>, files: &[&StatusEntry]) -> String {
+    let files = files
+        .iter()
+        .map(|e| format!("{} {}", status_letter(e), e.path))
+        .collect::<Vec<String>>()
+        .join("\n");
+    template
+        .replace("{branch}", branch)
+        .replace("{ticket}", ticket.unwrap_or(""))
+        .replace("{issue}", ticket.unwrap_or(""))
+        .replace("{files}", &files)
+}
+
+//  The ticket is found with the same pattern as the issue IDs in
+//  commit messages so a capture group selects the ID.
+pub(crate) fn ticket_from_branch(branch: &str) -> Option<String> {
+    static DEFAULT_PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = issues::configured_pattern()
+        .unwrap_or_else(|| DEFAULT_PATTERN.get_or_init(|| Regex::new(DEFAULT_TICKET_PATTERN).unwrap()));
+    issues::find(pattern, branch).into_iter().next().map(|(_, id)| id)
+}
@@ -0,0 +1,37 @@
+use clap::Parser;
+use super::*;
+use anyhow::Result;
+use crate::svn;
+use std::env::current_dir;
+
+/// Display the commit message built from the template.
+///
+/// The placeholders are filled in using the branch checked out to the
+/// working copy and the changes beneath the current directory, as
+/// `svu commit` would do when committing all of them.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Render {
+    /// Value of the {ticket} placeholder.
+    #[arg(short, long, value_name = "ID")]
+    ticket: Option<String>,
+}
+
+impl Render {
+    pub fn run(&mut self) -> Result<()> {
+        let cwd = current_dir()?;
+        let (branch, _) = svn::current_branch(&cwd)?;
+        let mut entries = svn::local_changes(&cwd)?;
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let ticket = match &self.ticket {
+            Some(ticket) => Some(ticket.clone()),
+            None => ticket_from_branch(&branch),
+        };
+        let files: Vec<&StatusEntry> = entries.iter().collect();
+        println!("{}", expand_template(&load_template()?, &branch, ticket.as_deref(), &files).trim_end());
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::svn::{self, ListEntry};
+use crate::util;
+use std::collections::BTreeMap;
+
+/// Display the layout of the repository as a tree.
+///
+/// The entries below a directory are listed with `svn list` and drawn as
+/// an indented tree with directories shown in blue.  The size of each file
+/// and the last revision that changed each entry can be shown as well.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Listing a large tree can take a while, use --depth to limit it.\n\
+    With --depth the tree is listed one level at a time so nothing below\n\
+    that depth is fetched from the repository."
+)]
+pub struct Tree {
+    /// Show the tree as it was at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Descend at most N directories below the top of the tree.
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    depth: Option<u32>,
+
+    /// Show the size of each file.
+    #[arg(short, long)]
+    size: bool,
+
+    /// Show the last revision that changed each entry.
+    #[arg(short, long)]
+    changed: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+//  The number of directories listed by each svn list command.
+const BATCH_SIZE: usize = 100;
+
+//  The lines drawn in front of each entry.
+struct Glyphs {
+    branch: &'static str,
+    last:   &'static str,
+    pipe:   &'static str,
+    space:  &'static str,
+}
+
+impl Glyphs {
+    fn new() -> Self {
+        if util::is_ascii() {
+            Glyphs { branch: "|-- ", last: "`-- ", pipe: "|   ", space: "    " }
+        } else {
+            Glyphs { branch: "\u{251c}\u{2500}\u{2500} ", last: "\u{2514}\u{2500}\u{2500} ", pipe: "\u{2502}   ", space: "    " }
+        }
+    }
+}
+
+impl Tree {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revision = match &self.revision {
+            Some(rev) => Some(svn::resolve_revision(&creds, rev, &self.path)?),
+            None => None,
+        };
+        let info = svn::info(&creds, &self.path, revision.as_deref())?;
+        let entries: Vec<ListEntry> = if info.kind != "dir" {
+            Vec::new()
+        } else if let Some(depth) = self.depth {
+            list_to_depth(&creds, &info.url, revision.as_deref(), depth)?
+        } else {
+            svn::path_list_recursive(&creds, &info.url, revision.as_deref())?
+                .entries
+                .into_iter()
+                .filter(|e| !e.name.is_empty())
+                .collect()
+        };
+
+        //  The entries of each directory keyed by the directory path.
+        let mut children = BTreeMap::<&str, Vec<&ListEntry>>::new();
+        for entry in &entries {
+            let parent = entry.name.rsplit_once('/').map(|(p, _)| p).unwrap_or("");
+            children.entry(parent).or_default().push(entry);
+        }
+        for list in children.values_mut() {
+            list.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        println!("{}", svn::peg_path(&info.rel_url, revision.as_deref()).blue().bold());
+        self.show_children(&children, "", "", &Glyphs::new());
+
+        let dirs = entries.iter().filter(|e| e.kind == "dir").count();
+        let files = entries.len() - dirs;
+        println!();
+        println!(
+            "{} {}, {} {}",
+            dirs,
+            if dirs == 1 { "directory" } else { "directories" },
+            files,
+            if files == 1 { "file" } else { "files" }
+        );
+        Ok(())
+    }
+
+    fn show_children(&self, children: &BTreeMap<&str, Vec<&ListEntry>>, dir: &str, indent: &str, glyphs: &Glyphs) {
+        let Some(entries) = children.get(dir) else { return };
+        for (index, entry) in entries.iter().enumerate() {
+            let is_last = index + 1 == entries.len();
+            let base_name = entry.name.rsplit('/').next().unwrap_or(&entry.name);
+            let name = if entry.kind == "dir" {
+                format!("{}/", base_name).blue().to_string()
+            } else {
+                base_name.to_string()
+            };
+            println!(
+                "{}{}{}{}",
+                indent,
+                if is_last { glyphs.last } else { glyphs.branch },
+                name,
+                self.details(entry)
+            );
+            if entry.kind == "dir" {
+                let indent = format!("{}{}", indent, if is_last { glyphs.space } else { glyphs.pipe });
+                self.show_children(children, &entry.name, &indent, glyphs);
+            }
+        }
+    }
+
+    fn details(&self, entry: &ListEntry) -> String {
+        let mut details = Vec::new();
+        if self.changed {
+            details.push(format!("r{}", entry.commit_rev).yellow().to_string());
+        }
+        if self.size {
+            if let Some(size) = entry.size {
+                details.push(util::display_size(size).green().to_string());
+            }
+        }
+        if details.is_empty() {
+            String::new()
+        } else {
+            format!("  {}", details.join(" "))
+        }
+    }
+}
+
+//  List the tree one level at a time, listing the directories of each
+//  level together, so that nothing below the given depth is fetched.
+//  The names of the entries are relative to the url.
+fn list_to_depth(creds: &Option<Credentials>, url: &str, revision: Option<&str>, depth: u32) -> Result<Vec<ListEntry>> {
+    let mut entries = Vec::new();
+    let mut dirs = vec![String::new()];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for batch in dirs.chunks(BATCH_SIZE) {
+            let targets: Vec<String> = batch
+                .iter()
+                .map(|dir| {
+                    let dir_url = if dir.is_empty() { url.to_string() } else { util::join_paths(url, dir) };
+                    svn::peg_path(&dir_url, revision)
+                })
+                .collect();
+            //  svn lists the targets in the order they are given.
+            for (dir, list) in batch.iter().zip(svn::path_lists(creds, &targets)?) {
+                for mut entry in list.entries.into_iter().filter(|e| !e.name.is_empty()) {
+                    if !dir.is_empty() {
+                        entry.name = format!("{}/{}", dir, entry.name);
+                    }
+                    if entry.kind == "dir" {
+                        next.push(entry.name.clone());
+                    }
+                    entries.push(entry);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        dirs = next;
+    }
+    Ok(entries)
+}
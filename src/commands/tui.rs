@@ -0,0 +1,436 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::commands::bisect;
+use crate::svn::{self, LogEntry};
+use crate::tui::{self, Key, Terminal};
+use crate::util;
+use std::collections::HashMap;
+
+/// Browse the commit history in a terminal interface.
+///
+/// The commits are listed most recent first.  Type `/` to search the list
+/// as you type, and press enter to expand a commit to see its message,
+/// changed paths and diff.  Revisions may be marked good or bad and are
+/// recorded in the bisect session when you quit.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Keys:\n  \
+    up/down k/j       Move the cursor\n  \
+    pgup/pgdn         Move a page at a time\n  \
+    home/end          Move to the first or last commit\n  \
+    enter             Show the selected commit\n  \
+    /                 Search by revision, author or message\n  \
+    g b               Mark the revision good or bad for bisect\n  \
+    u                 Remove the mark from the revision\n  \
+    y                 Copy the revision number to the clipboard\n  \
+    q esc             Go back or quit\n\n\
+    Copying uses the OSC 52 escape sequence which must be supported by your terminal."
+)]
+pub struct Tui {
+    /// Limit the number of commits loaded.
+    #[arg(short, long, value_name = "NUM", default_value_t = 500)]
+    limit: u32,
+
+    /// Do not cross copies while traversing history.
+    #[arg(long)]
+    stop_on_copy: bool,
+
+    /// Path or URL of the history to browse.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+impl Tui {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let entries = svn::log(
+            &creds,
+            std::slice::from_ref(&self.path),
+            &["HEAD:0".to_string()],
+            true,
+            Some(self.limit),
+            self.stop_on_copy,
+            true
+        )?;
+        if entries.is_empty() {
+            println!("No commits found for {}", self.path.blue());
+            return Ok(());
+        }
+
+        let mut browser = Browser::new(&creds, &self.path, entries);
+        {
+            let mut terminal = Terminal::open()?;
+            browser.browse(&mut terminal)?;
+        }
+
+        if browser.good.is_some() || browser.bad.is_some() {
+            for (rev, term) in [(&browser.good, "good"), (&browser.bad, "bad")] {
+                if let Some(rev) = rev {
+                    println!("Marked {} as {}", rev.yellow(), term);
+                }
+            }
+            if util::confirm("Record these revisions in the bisect session?")? {
+                bisect::mark_revisions(browser.good.as_deref(), browser.bad.as_deref())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+//  The commit shown in the detail view.
+struct Detail {
+    index: usize,
+    lines: Vec<String>,
+    top:   usize,
+}
+
+struct Browser<'a> {
+    creds:     &'a Option<Credentials>,
+    path:      &'a str,
+    entries:   Vec<LogEntry>,
+    visible:   Vec<usize>,
+    cursor:    usize,
+    top:       usize,
+    search:    String,
+    searching: bool,
+    status:    String,
+    detail:    Option<Detail>,
+    diffs:     HashMap<String, Vec<String>>,
+    good:      Option<String>,
+    bad:       Option<String>,
+}
+
+impl<'a> Browser<'a> {
+    fn new(creds: &'a Option<Credentials>, path: &'a str, entries: Vec<LogEntry>) -> Self {
+        let visible = (0..entries.len()).collect();
+        Browser {
+            creds,
+            path,
+            entries,
+            visible,
+            cursor:    0,
+            top:       0,
+            search:    String::new(),
+            searching: false,
+            status:    String::new(),
+            detail:    None,
+            diffs:     HashMap::new(),
+            good:      None,
+            bad:       None,
+        }
+    }
+
+    fn browse(&mut self, terminal: &mut Terminal) -> Result<()> {
+        loop {
+            let (width, height) = terminal.size();
+            let page = height.saturating_sub(2).max(1);
+            let screen = match &self.detail {
+                Some(_) => self.detail_screen(width, page),
+                None    => self.list_screen(width, page),
+            };
+            terminal.draw(&screen)?;
+
+            let key = terminal.read_key()?;
+            self.status.clear();
+            let done = if self.detail.is_some() {
+                self.detail_key(terminal, key, page)?
+            } else if self.searching {
+                self.search_key(key);
+                false
+            } else {
+                self.list_key(terminal, key, page)?
+            };
+            if done {
+                return Ok(());
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&LogEntry> {
+        self.visible.get(self.cursor).map(|&index| &self.entries[index])
+    }
+
+    //  Returns true when the user quits.
+    fn list_key(&mut self, terminal: &mut Terminal, key: Key, page: usize) -> Result<bool> {
+        let last = self.visible.len().saturating_sub(1);
+        match key {
+            Key::Up | Key::Char('k')     => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down | Key::Char('j')   => self.cursor = (self.cursor + 1).min(last),
+            Key::PageUp                  => self.cursor = self.cursor.saturating_sub(page),
+            Key::PageDown | Key::Char(' ') => self.cursor = (self.cursor + page).min(last),
+            Key::Home                    => self.cursor = 0,
+            Key::End                     => self.cursor = last,
+            Key::Char('/') => {
+                self.searching = true;
+            }
+            Key::Enter | Key::Right | Key::Char('l') => {
+                if let Some(&index) = self.visible.get(self.cursor) {
+                    self.open_detail(terminal, index)?;
+                }
+            }
+            Key::Escape if !self.search.is_empty() => {
+                self.search.clear();
+                self.filter();
+            }
+            Key::Escape | Key::Char('q') => return Ok(true),
+            Key::Char(c) => {
+                if let Some(revision) = self.selected().map(|e| e.revision.clone()) {
+                    self.command_key(c, &revision)?;
+                }
+            }
+            _ => (),
+        }
+        self.top = scroll_to(self.cursor, self.top, page);
+        Ok(false)
+    }
+
+    fn search_key(&mut self, key: Key) {
+        match key {
+            Key::Char(c) => self.search.push(c),
+            Key::Backspace => {
+                self.search.pop();
+            }
+            Key::Enter => self.searching = false,
+            Key::Escape => {
+                self.searching = false;
+                self.search.clear();
+            }
+            _ => return,
+        }
+        self.filter();
+    }
+
+    //  Returns true when the user quits.
+    fn detail_key(&mut self, terminal: &mut Terminal, key: Key, page: usize) -> Result<bool> {
+        let detail = self.detail.as_mut().unwrap();
+        let last = detail.lines.len().saturating_sub(page);
+        match key {
+            Key::Up | Key::Char('k')       => detail.top = detail.top.saturating_sub(1),
+            Key::Down | Key::Char('j')     => detail.top = (detail.top + 1).min(last),
+            Key::PageUp                    => detail.top = detail.top.saturating_sub(page),
+            Key::PageDown | Key::Char(' ') => detail.top = (detail.top + page).min(last),
+            Key::Home                      => detail.top = 0,
+            Key::End                       => detail.top = last,
+            Key::Escape | Key::Left | Key::Char('h') | Key::Char('q') => self.detail = None,
+            Key::Char('n') | Key::Char('p') => {
+                //  Move to the next or previous commit in the list.
+                let cursor = if key == Key::Char('n') {
+                    (self.cursor + 1).min(self.visible.len().saturating_sub(1))
+                } else {
+                    self.cursor.saturating_sub(1)
+                };
+                if cursor != self.cursor {
+                    self.cursor = cursor;
+                    self.top = scroll_to(self.cursor, self.top, page);
+                    self.open_detail(terminal, self.visible[cursor])?;
+                }
+            }
+            Key::Char(c) => {
+                let revision = self.entries[detail.index].revision.clone();
+                self.command_key(c, &revision)?;
+            }
+            _ => (),
+        }
+        Ok(false)
+    }
+
+    //  Keys that act on a revision in both the list and detail views.
+    fn command_key(&mut self, key: char, revision: &str) -> Result<()> {
+        match key {
+            'g' => {
+                if self.bad.as_deref() == Some(revision) {
+                    self.bad = None;
+                }
+                self.good = Some(revision.to_string());
+                self.status = format!("Marked {} as good", revision);
+            }
+            'b' => {
+                if self.good.as_deref() == Some(revision) {
+                    self.good = None;
+                }
+                self.bad = Some(revision.to_string());
+                self.status = format!("Marked {} as bad", revision);
+            }
+            'u' => {
+                if self.good.as_deref() == Some(revision) {
+                    self.good = None;
+                }
+                if self.bad.as_deref() == Some(revision) {
+                    self.bad = None;
+                }
+                self.status = format!("Removed the mark from {}", revision);
+            }
+            'y' => {
+                tui::copy_to_clipboard(revision)?;
+                self.status = format!("Copied {} to the clipboard", revision);
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    //  Show only the commits whose revision, author or message
+    //  contains the search text.
+    fn filter(&mut self) {
+        let search = self.search.to_lowercase();
+        let selected = self.visible.get(self.cursor).copied();
+        self.visible = self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                search.is_empty()
+                    || entry.revision.contains(&search)
+                    || entry.author.to_lowercase().contains(&search)
+                    || entry.msg.iter().any(|line| line.to_lowercase().contains(&search))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        //  Keep the cursor on the same commit if it is still visible.
+        self.cursor = selected
+            .and_then(|index| self.visible.iter().position(|&i| i == index))
+            .unwrap_or(0);
+        self.top = 0;
+    }
+
+    fn open_detail(&mut self, terminal: &mut Terminal, index: usize) -> Result<()> {
+        let entry = &self.entries[index];
+        let mut lines = vec![
+            format!("Commit: {}", entry.revision.yellow()),
+            format!("Author: {}", entry.author.cyan()),
+            format!("Date  : {}", util::display_svn_datetime(&entry.date).magenta()),
+            String::new(),
+        ];
+        lines.extend(entry.msg.iter().map(|line| format!("    {}", line)));
+        lines.push(String::new());
+        lines.extend(entry.paths.iter().map(util::formatted_log_path));
+        lines.push(String::new());
+
+        if !self.diffs.contains_key(&entry.revision) {
+            let (width, _) = terminal.size();
+            let mut screen = lines.clone();
+            screen.push(status_line(&format!("Loading the diff for {}...", entry.revision), width));
+            terminal.draw(&screen)?;
+
+            let diff = match svn::change_diff(self.creds, &[self.path], &entry.revision) {
+                Ok(diff) => diff.iter().map(|line| util::colored_diff_line(line).to_string()).collect(),
+                Err(e) => vec![format!("Cannot load the diff: {}", e).red().to_string()],
+            };
+            self.diffs.insert(entry.revision.clone(), diff);
+        }
+        lines.extend(self.diffs[&entry.revision].iter().cloned());
+        self.detail = Some(Detail { index, lines, top: 0 });
+        Ok(())
+    }
+
+    fn mark(&self, revision: &str) -> ColoredString {
+        if self.good.as_deref() == Some(revision) {
+            "G".green()
+        } else if self.bad.as_deref() == Some(revision) {
+            "B".red()
+        } else {
+            " ".normal()
+        }
+    }
+
+    fn list_screen(&self, width: usize, page: usize) -> Vec<String> {
+        let header = format!(
+            "{} -- {} of {} commits",
+            self.path,
+            self.visible.len(),
+            self.entries.len()
+        );
+        let mut screen = vec![status_line(&header, width)];
+
+        let rev_len = self.entries.iter().map(|e| e.revision.len()).max().unwrap_or(0);
+        let author_len = self.entries.iter().map(|e| e.author.len()).max().unwrap_or(0).min(16);
+        for (row, &index) in self.visible.iter().enumerate().skip(self.top).take(page) {
+            let entry = &self.entries[index];
+            let author: String = entry.author.chars().take(author_len).collect();
+            let date = util::display_svn_date(&entry.date);
+            let line = if row == self.cursor {
+                let plain = format!(
+                    "{} {:>rw$} {:aw$} {} {}",
+                    self.mark(&entry.revision).clear(),
+                    entry.revision,
+                    author,
+                    date,
+                    entry.msg_1st(),
+                    rw = rev_len,
+                    aw = author_len
+                );
+                format!("\x1b[7m{}", tui::pad(&tui::truncate(&plain, width), width))
+            } else {
+                format!(
+                    "{} {} {} {} {}",
+                    self.mark(&entry.revision),
+                    format!("{:>rw$}", entry.revision, rw = rev_len).yellow(),
+                    format!("{:aw$}", author, aw = author_len).cyan(),
+                    date.magenta(),
+                    entry.msg_1st()
+                )
+            };
+            screen.push(line);
+        }
+        while screen.len() <= page {
+            screen.push(String::new());
+        }
+
+        let status = if self.searching {
+            format!("/{}", self.search)
+        } else if !self.status.is_empty() {
+            self.status.clone()
+        } else if !self.search.is_empty() {
+            format!("Showing commits matching '{}'  (esc to show all)", self.search)
+        } else {
+            "enter:show  /:search  g:good  b:bad  u:unmark  y:copy  q:quit".to_string()
+        };
+        screen.push(status_line(&status, width));
+        screen
+    }
+
+    fn detail_screen(&self, width: usize, page: usize) -> Vec<String> {
+        let detail = self.detail.as_ref().unwrap();
+        let entry = &self.entries[detail.index];
+        let header = format!("{} -- {}", entry.revision, entry.msg_1st());
+        let mut screen = vec![status_line(&header, width)];
+        screen.extend(detail.lines.iter().skip(detail.top).take(page).cloned());
+        while screen.len() <= page {
+            screen.push(String::new());
+        }
+
+        let status = if !self.status.is_empty() {
+            self.status.clone()
+        } else {
+            let shown = (detail.top + page).min(detail.lines.len());
+            format!(
+                "lines {}-{} of {}  n:next  p:previous  g:good  b:bad  u:unmark  y:copy  q:back",
+                detail.top + 1,
+                shown,
+                detail.lines.len()
+            )
+        };
+        screen.push(status_line(&status, width));
+        screen
+    }
+}
+
+//  Adjust the first row shown so that the cursor is on the page.
+fn scroll_to(cursor: usize, top: usize, page: usize) -> usize {
+    if cursor < top {
+        cursor
+    } else if cursor >= top + page {
+        cursor + 1 - page
+    } else {
+        top
+    }
+}
+
+//  A line shown in reverse video across the width of the screen.
+fn status_line(text: &str, width: usize) -> String {
+    format!("\x1b[7m{}", tui::pad(&tui::truncate(text, width), width))
+}
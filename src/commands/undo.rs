@@ -0,0 +1,103 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::commit;
+use crate::svn::{self, LogEntry};
+use crate::util::SvError::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Undo a commit by reverse merging it into the working copy.
+///
+/// The changes of the revision are reverse merged into the working copy
+/// and the resulting changes are shown.  A commit message of the form
+/// "Revert rNNN: original subject" is prepared and is used by the next
+/// `svu commit` in place of the message template.
+///
+/// A range such as 1200:1210 undoes every commit in the range that
+/// affected PATH, most recent first.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The prepared message is written to .svu/commit_message.txt unless a\n\
+    message saved by a failed commit is already there."
+)]
+pub struct Undo {
+    /// The revision or range of revisions to undo.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(value_name = "REV")]
+    revision: String,
+
+    /// Working copy directory to undo the changes in.
+    #[arg(short, long, default_value = ".")]
+    path: String,
+
+    /// Show the changes that would be made but do not change the working copy.
+    #[arg(short = 'n', long)]
+    dry_run: bool,
+}
+
+impl Undo {
+    pub fn run(&mut self) -> Result<()> {
+        if !self.dry_run {
+            svn::ensure_writable("undo a commit")?;
+        }
+        let creds = crate::auth::get_credentials()?;
+        let range = svn::resolve_revision_range(&creds, &self.revision, &self.path)?;
+        let range = if range.contains(':') { range } else { format!("{}:{}", range, range) };
+
+        //  Most recent first so that later changes are removed before
+        //  the changes that they depend on.
+        let mut commits = BTreeMap::<u64, LogEntry>::new();
+        for entry in svn::log(&creds, &[self.path.as_str()], &[range.as_str()], true, None, false, false)? {
+            commits.insert(entry.revision.parse::<u64>()?, entry);
+        }
+        let entries: Vec<LogEntry> = commits.into_values().rev().collect();
+        if entries.is_empty() {
+            let msg = format!("No commits in {} affected {}", self.revision, self.path);
+            return Err(General(msg).into());
+        }
+
+        for entry in &entries {
+            println!("{} r{} {} {}", "Undoing".bold(), entry.revision.yellow(), entry.author.cyan(), entry.msg_1st());
+            let output = svn::reverse_merge(&creds, &entry.revision, self.dry_run, Some(Path::new(&self.path)))?;
+            print!("{}", String::from_utf8_lossy(&output));
+        }
+
+        let message = revert_message(&entries);
+        if self.dry_run {
+            println!("\nThe commit message would be:\n{}", message);
+            return Ok(());
+        }
+
+        let message_file = commit::message_file()?;
+        if message_file.is_file() {
+            println!("\n{} was not replaced, the suggested commit message is:\n{}", message_file.to_string_lossy().blue(), message);
+        } else {
+            fs::write(&message_file, &message)?;
+            println!("\nThe commit message has been prepared, use `svu commit` to commit the changes:\n{}", message);
+        }
+        Ok(())
+    }
+}
+
+//  A single commit is reverted with its subject in the first line.
+//  Several commits are listed in the body.
+fn revert_message(entries: &[LogEntry]) -> String {
+    match entries {
+        [entry] => format!("Revert r{}: {}\n", entry.revision, entry.msg_1st()),
+        _ => {
+            let revisions: Vec<String> = entries.iter().map(|e| format!("r{}", e.revision)).collect();
+            let mut message = format!("Revert {}\n\n", revisions.join(", "));
+            for entry in entries {
+                message.push_str(&format!("r{}: {}\n", entry.revision, entry.msg_1st()));
+            }
+            message
+        }
+    }
+}
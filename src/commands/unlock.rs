@@ -0,0 +1,36 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::svn;
+
+/// Unlock files in the repository.
+///
+/// Releases the locks that you hold on the given files.
+/// Use --break to release locks held by other users.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    A URL can be given to break a lock without a working copy of the file,\n\
+    such as one reported by `svu lock list`."
+)]
+pub struct Unlock {
+    /// Break locks held by other users.
+    #[arg(long = "break")]
+    break_locks: bool,
+
+    /// Working copy paths or URLs of the files to unlock.
+    #[arg(value_name = "PATH|URL", required = true)]
+    paths: Vec<String>,
+}
+
+impl Unlock {
+    pub fn run(&mut self) -> Result<()> {
+        svn::unlock(&self.paths, self.break_locks, None)?;
+        for path in &self.paths {
+            println!("Unlocked: {}", path.green());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::commands::snapshot;
+use crate::hooks;
+use crate::svn;
+use crate::util::{self, display_svn_date};
+use std::collections::BTreeMap;
+
+/// Update the working copy after previewing the incoming commits.
+///
+/// The commits that the update would bring in are listed and you are
+/// asked to confirm before the update is made.  Afterwards a summary of
+/// the files that were updated, merged and conflicted is shown along with
+/// the new revision of the working copy.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Up {
+    /// Update without asking for confirmation.
+    #[arg(short, long)]
+    yes: bool,
+
+    /// List each file that was changed by the update.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Path to update.
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: String,
+}
+
+//  The actions reported by svn update in the first column of its output.
+const ACTIONS: [(char, &str); 6] = [
+    ('U', "updated"),
+    ('G', "merged"),
+    ('C', "conflicted"),
+    ('A', "added"),
+    ('D', "deleted"),
+    ('E', "existed"),
+];
+
+impl Up {
+    pub fn run(&mut self) -> Result<()> {
+        svn::ensure_writable("update the working copy")?;
+        let creds = crate::auth::get_credentials()?;
+        let path_info = svn::info(&creds, &self.path, None)?;
+        let base = path_info.repo_rev.parse::<u64>()?;
+        let (_, head) = svn::head_revision(&creds, &self.path)?;
+
+        if head <= base {
+            println!("Already up to date at revision {}", base.to_string().yellow());
+            return Ok(());
+        }
+
+        let range = format!("HEAD:{}", base + 1);
+        let entries = svn::log(&creds, &[self.path.as_str()], &[range.as_str()], true, None, false, false)?;
+        if entries.is_empty() {
+            println!("No incoming commits affect {}", self.path.blue());
+        } else {
+            println!("Incoming commits:");
+            let author_width = entries.iter().map(|e| e.author.len()).max().unwrap_or(0);
+            for entry in &entries {
+                println!(
+                    "{} {:author_width$} {} {}",
+                    entry.revision.yellow(),
+                    entry.author.cyan(),
+                    display_svn_date(&entry.date).magenta(),
+                    entry.msg_1st(),
+                    author_width = author_width
+                );
+            }
+        }
+
+        let prompt = format!("Update {} to revision {}?", self.path, head);
+        if !self.yes && !util::confirm(&prompt)? {
+            return Ok(());
+        }
+
+        let revision = head.to_string();
+        hooks::run("pre-update", &[("PATH", &self.path), ("REVISION", &revision)])?;
+        snapshot::before("up")?;
+        let output = svn::update_path(&self.path, &revision, false, None)?;
+        self.show_summary(&String::from_utf8_lossy(&output));
+        hooks::run("post-update", &[("PATH", &self.path), ("REVISION", &revision)])
+    }
+
+    fn show_summary(&self, output: &str) {
+        let mut changes = BTreeMap::<char, Vec<&str>>::new();
+        let mut new_revision = None;
+        for line in output.lines() {
+            if let Some(rev) = line.strip_prefix("Updated to revision ").or(line.strip_prefix("At revision ")) {
+                new_revision = Some(rev.trim_end_matches('.').to_string());
+                continue;
+            }
+            //  The first four columns hold the text, property, lock and tree
+            //  conflict status and the path starts in the sixth column.
+            let columns: Vec<char> = line.chars().take(5).collect();
+            if columns.len() < 5 || columns[4] != ' ' || !columns[..4].iter().all(|c| " UGCADEB".contains(*c)) {
+                continue;
+            }
+            let path = match line.get(5..) {
+                Some(path) if !path.is_empty() => path,
+                _ => continue,
+            };
+            let (text, props, tree) = (columns[0], columns[1], columns[3]);
+            let action = if [text, props, tree].contains(&'C') { 'C' } else if text != ' ' { text } else { props };
+            if ACTIONS.iter().any(|(a, _)| *a == action) {
+                changes.entry(action).or_default().push(path);
+            }
+        }
+
+        let counts: Vec<String> = ACTIONS
+            .iter()
+            .filter_map(|(action, name)| changes.get(action).map(|paths| format!("{} {}", paths.len(), name)))
+            .collect();
+        if counts.is_empty() {
+            println!("No files changed");
+        } else {
+            println!("{}", counts.join(", "));
+        }
+        if self.verbose {
+            for (action, paths) in &changes {
+                for path in paths {
+                    println!("  {} {}", action_letter(*action), path);
+                }
+            }
+        } else if let Some(conflicts) = changes.get(&'C') {
+            for path in conflicts {
+                println!("  {} {}", action_letter('C'), path);
+            }
+        }
+        if changes.contains_key(&'C') {
+            println!("Use {} to resolve the conflicts", "svu conflicts".blue());
+        }
+        if let Some(rev) = new_revision {
+            println!("The working copy is at revision {}", rev.yellow());
+        }
+    }
+}
+
+fn action_letter(action: char) -> ColoredString {
+    match action {
+        'C'       => action.to_string().red(),
+        'A'       => action.to_string().green(),
+        'D'       => action.to_string().red(),
+        _         => action.to_string().magenta(),
+    }
+}
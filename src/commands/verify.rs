@@ -0,0 +1,178 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::sha1;
+use crate::svn::{self, SvnInfo};
+use crate::util::SvError::*;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Verify the working copy files against their pristine copies.
+///
+/// Subversion keeps a pristine copy of each file, named by the SHA-1 checksum
+/// of its contents.  This command checks that the pristine copy of each file
+/// is present and has the expected checksum.  It also compares the files that
+/// `svn status` reports as unmodified with their pristine copies to find
+/// changes that svn has not noticed, such as a file changed by a tool that
+/// restored its timestamp.
+///
+/// Files with the svn:keywords, svn:eol-style or svn:special properties are
+/// not compared since svn translates their contents.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    With --repair:\n  \
+    Unnoticed changes are made visible to svn by updating the file's timestamp.\n  \
+    Missing or corrupt pristine copies are fetched again from the repository.\n  \
+    `svn cleanup` is then run to correct the timestamps recorded by svn."
+)]
+pub struct Verify {
+    /// Repair the problems that are found.
+    #[arg(short, long)]
+    repair: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Problem {
+    Unnoticed,
+    MissingPristine,
+    CorruptPristine,
+}
+
+impl Problem {
+    fn label(&self) -> ColoredString {
+        match self {
+            Problem::Unnoticed       => "modified, not noticed by svn".yellow(),
+            Problem::MissingPristine => "pristine copy missing".red(),
+            Problem::CorruptPristine => "pristine copy corrupt".red(),
+        }
+    }
+}
+
+//  Properties that cause svn to translate the contents of a file.
+const TRANSLATED: [&str; 3] = ["svn:keywords", "svn:eol-style", "svn:special"];
+
+impl Verify {
+    pub fn run(&mut self) -> Result<()> {
+        use rayon::prelude::*;
+
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?;  // Make sure we are in a working copy.
+        let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+        let root = wc_root.to_string_lossy().to_string();
+        if self.repair {
+            svn::ensure_writable("repair the working copy")?;
+        }
+
+        let files: Vec<SvnInfo> = svn::info_recursive(&root)?
+            .into_iter()
+            .filter(|info| info.kind == "file" && info.checksum.is_some())
+            .collect();
+        let changed: HashSet<String> = svn::status(root.as_str(), None)?
+            .entries
+            .into_iter()
+            .filter(|e| e.item_status != "normal")
+            .map(|e| e.path)
+            .collect();
+        let translated: HashSet<String> = svn::proplist(&None, &root, true)?
+            .into_iter()
+            .filter(|t| t.props.iter().any(|(name, _)| TRANSLATED.contains(&name.as_str())))
+            .map(|t| t.path)
+            .collect();
+
+        let mut problems: Vec<(&SvnInfo, Problem)> = files
+            .par_iter()
+            .filter_map(|info| {
+                let checksum = info.checksum.as_deref().unwrap();
+                let pristine = pristine_path(&wc_root, checksum);
+                if !pristine.is_file() {
+                    return Some((info, Problem::MissingPristine));
+                }
+                if sha1::file_digest(&pristine).ok().as_deref() != Some(checksum) {
+                    return Some((info, Problem::CorruptPristine));
+                }
+                if !changed.contains(&info.path) && !translated.contains(&info.path) {
+                    let path = Path::new(&info.path);
+                    if path.is_file() && sha1::file_digest(path).ok().as_deref() != Some(checksum) {
+                        return Some((info, Problem::Unnoticed));
+                    }
+                }
+                None
+            })
+            .collect();
+        problems.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+
+        //  Recent versions of svn can be configured not to store pristine copies.
+        if !problems.is_empty() && problems.iter().all(|(_, p)| *p == Problem::MissingPristine) && problems.len() == files.len() {
+            println!("The working copy does not store pristine copies, only unnoticed changes were not checked");
+            return Ok(());
+        }
+
+        for (info, problem) in &problems {
+            let rel_path = Path::new(&info.path).strip_prefix(&wc_root).unwrap_or(Path::new(&info.path));
+            println!("{}: {}", rel_path.display(), problem.label());
+        }
+        println!("Verified {} files, found {} {}", files.len(), problems.len(), if problems.len() == 1 { "problem" } else { "problems" });
+
+        if problems.is_empty() {
+            Ok(())
+        } else if self.repair {
+            for (info, problem) in &problems {
+                match problem {
+                    Problem::Unnoticed => touch(Path::new(&info.path))?,
+                    _ => restore_pristine(&creds, &wc_root, info)?,
+                }
+            }
+            svn::cleanup(&wc_root)?;
+            println!("Repaired {} {}", problems.len(), if problems.len() == 1 { "problem" } else { "problems" });
+            Ok(())
+        } else {
+            Err(General("Use --repair to fix the problems".to_string()).into())
+        }
+    }
+}
+
+fn pristine_path(wc_root: &Path, checksum: &str) -> PathBuf {
+    wc_root
+        .join(".svn")
+        .join("pristine")
+        .join(&checksum[..2.min(checksum.len())])
+        .join(format!("{}.svn-base", checksum))
+}
+
+//  Give a file a new timestamp so that svn compares its contents.
+fn touch(path: &Path) -> Result<()> {
+    File::options().write(true).open(path)?.set_modified(SystemTime::now())?;
+    Ok(())
+}
+
+//  Fetch the base revision of a file from the repository and
+//  write it as the pristine copy.  svn cat returns the contents
+//  without translation which is the form of the pristine copy.
+fn restore_pristine(creds: &Option<crate::auth::Credentials>, wc_root: &Path, info: &SvnInfo) -> Result<()> {
+    let checksum = info.checksum.as_deref().unwrap();
+    let content = svn::cat(creds, &info.url, Some(&info.repo_rev))?;
+    if sha1::digest(&content) != checksum {
+        let msg = format!("The contents of {}@{} do not match the recorded checksum", info.url, info.repo_rev);
+        return Err(General(msg).into());
+    }
+    let path = pristine_path(wc_root, checksum);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        let mut perms = fs::metadata(&path)?.permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(&path, perms)?;
+    }
+    fs::write(&path, &content)?;
+    let mut perms = fs::metadata(&path)?.permissions();
+    perms.set_readonly(true);
+    fs::set_permissions(&path, perms)?;
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::*;
+use crate::auth::Credentials;
+use crate::commands::notify;
+use crate::hooks;
+use crate::svn::{self, LogEntry};
+use crate::util;
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Watch paths for new commits.
+///
+/// The repository is checked every --interval seconds and each commit
+/// that touches one of the paths is displayed.  The HEAD revision of each
+/// repository is recorded, as it is by `svu log --new`, so that commits
+/// made while svu was not running are shown the next time it is started.
+/// If no HEAD revision has been recorded for a repository the commits made
+/// since the BASE revision of a working copy path are shown.
+///
+/// For each new commit a desktop notification can be shown with --notify
+/// and the post-watch hook is run.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    The post-watch hook receives the commit in the environment variables\n\
+    SVU_REVISION, SVU_AUTHOR, SVU_DATE and SVU_MESSAGE.\n\
+    Desktop notifications use notify-send, or osascript on macOS.\n\
+    See also `svu notify` for sending commits to a command or webhook."
+)]
+pub struct Watch {
+    /// Number of seconds between checks.
+    #[arg(
+        short,
+        long,
+        value_name = "SECS",
+        default_value_t = 60,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    interval: u64,
+
+    /// Check once and exit.  Suitable for running from cron.
+    #[arg(long)]
+    once: bool,
+
+    /// Show a desktop notification for each new commit.
+    #[arg(short, long)]
+    notify: bool,
+
+    /// Display the paths changed by each commit.
+    #[arg(short = 'v', long = "verbose")]
+    show_paths: bool,
+
+    /// Paths to working copy files or directories or repository URLs.
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+}
+
+//  A watched path, its URL, the UUID of its repository and the revision
+//  to start from when no HEAD revision has been recorded.
+struct Target {
+    path:  String,
+    url:   String,
+    uuid:  String,
+    start: u64,
+}
+
+impl Watch {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let mut targets = Vec::new();
+        for path in &self.paths {
+            let info = svn::info(&creds, path, None)?;
+            //  Start from the BASE revision of a working copy path,
+            //  or from HEAD for a URL.
+            let start = info.repo_rev.parse::<u64>()?;
+            targets.push(Target { path: path.clone(), url: info.url, uuid: info.repo_uuid, start });
+        }
+
+        if self.once {
+            return self.check_targets(&creds, &targets);
+        }
+
+        println!("Watching {} for new commits every {} seconds", self.paths.join(", ").blue(), self.interval);
+        util::poll(self.interval, || self.check_targets(&creds, &targets))
+    }
+
+    //  Display the commits made on any of the targets since they were last
+    //  checked.  A commit that touches several targets is shown once.
+    //  The targets may be in different repositories.
+    fn check_targets(&self, creds: &Option<Credentials>, targets: &[Target]) -> Result<()> {
+        //  The HEAD revision of each repository keyed by its UUID.
+        let mut heads = BTreeMap::<&str, u64>::new();
+        for target in targets {
+            if !heads.contains_key(target.uuid.as_str()) {
+                let (_, head) = svn::head_revision(creds, &target.path)?;
+                heads.insert(&target.uuid, head);
+            }
+        }
+        //  The commits keyed by repository and revision.
+        let mut commits = BTreeMap::<(&str, u64), LogEntry>::new();
+        for target in targets {
+            let head = heads[target.uuid.as_str()];
+            let last = svn::last_seen_head(&target.uuid)?.unwrap_or(target.start);
+            if last < head {
+                let range = format!("{}:{}", last + 1, head);
+                for entry in svn::log(creds, &[target.url.as_str()], &[range.as_str()], true, None, false, self.show_paths)? {
+                    commits.insert((&target.uuid, entry.revision.parse::<u64>()?), entry);
+                }
+            }
+        }
+
+        for entry in commits.values() {
+            self.show_commit(entry);
+            if self.notify {
+                desktop_notification(entry);
+            }
+            hooks::run("post-watch", &[
+                ("REVISION", &entry.revision),
+                ("AUTHOR", &entry.author),
+                ("DATE", &util::display_svn_datetime(&entry.date)),
+                ("MESSAGE", &entry.msg.join("\n")),
+            ])?;
+        }
+
+        for (uuid, head) in heads {
+            svn::record_head(uuid, head)?;
+        }
+        Ok(())
+    }
+
+    fn show_commit(&self, entry: &LogEntry) {
+        println!(
+            "{} {} {} {}",
+            entry.revision.yellow(),
+            entry.author.cyan(),
+            util::display_svn_datetime(&entry.date).magenta(),
+            entry.msg_1st()
+        );
+        if self.show_paths {
+            for path in &entry.paths {
+                println!("{}", util::formatted_log_path(path))
+            }
+        }
+    }
+}
+
+//  A notification that cannot be shown is reported but does not
+//  stop watching.
+fn desktop_notification(entry: &LogEntry) {
+    let shown = if cfg!(target_os = "macos") {
+        let title = format!("r{} {}", entry.revision, entry.author);
+        let body = entry.msg_1st();
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        Command::new("osascript")
+            .arg("-e")
+            .arg(format!("display notification {} with title {}", quote(&body), quote(&title)))
+            .status()
+            .is_ok_and(|status| status.success())
+    } else {
+        notify::run_command("notify-send", entry).is_ok()
+    };
+    if !shown {
+        eprintln!("{} cannot show a desktop notification for revision {}", "warning:".yellow(), entry.revision);
+    }
+}
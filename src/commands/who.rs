@@ -0,0 +1,127 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::*;
+use crate::svn::{self, ListEntry};
+use crate::util;
+use std::collections::BTreeMap;
+
+/// Show who last changed each file beneath a directory.
+///
+/// Every file beneath PATH is listed with the author, revision and date
+/// of the last commit that changed it, using a single recursive
+/// `svn list`.  With --summary the number of files last changed by each
+/// author is shown instead, to give a quick picture of who owns the
+/// code in a directory.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+)]
+pub struct Who {
+    /// Show the files as they were at this revision.
+    ///
+    /// Revisions can be a numeric value or one of: HEAD, BASE, PREV, COMMITTED.
+    /// Additionally you can specify offsets such as HEAD-4.
+    #[arg(short, long, value_name = "REV")]
+    revision: Option<String>,
+
+    /// Order of the files.
+    #[arg(short, long, value_enum, default_value_t = Sort::Path)]
+    sort: Sort,
+
+    /// Show the age of each change rather than its date.
+    #[arg(short, long)]
+    age: bool,
+
+    /// Show the number of files last changed by each author.
+    #[arg(long)]
+    summary: bool,
+
+    /// Path to working copy directory or repository URL.
+    #[arg(default_value = ".")]
+    path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Sort {
+    /// By path
+    Path,
+    /// By author, then by path
+    Author,
+    /// Most recently changed first
+    Age,
+}
+
+impl Who {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let revision = self.revision
+            .as_ref()
+            .map(|r| svn::resolve_revision(&creds, r, &self.path))
+            .transpose()?;
+        let mut files: Vec<ListEntry> = svn::path_list_recursive(&creds, &self.path, revision.as_deref())?
+            .entries
+            .into_iter()
+            .filter(|e| e.kind == "file")
+            .collect();
+
+        match self.sort {
+            Sort::Path   => files.sort_by(|a, b| a.name.cmp(&b.name)),
+            Sort::Author => files.sort_by(|a, b| (&a.commit_author, &a.name).cmp(&(&b.commit_author, &b.name))),
+            Sort::Age    => files.sort_by(|a, b| b.commit_date.cmp(&a.commit_date).then_with(|| a.name.cmp(&b.name))),
+        }
+
+        if self.summary {
+            self.show_summary(&files);
+            return Ok(());
+        }
+
+        let date = |e: &ListEntry| if self.age { util::display_age(&e.commit_date) } else { util::display_svn_date(&e.commit_date) };
+        let author_len = files.iter().map(|e| e.commit_author.len()).max().unwrap_or(0);
+        let rev_len = files.iter().map(|e| e.commit_rev.len()).max().unwrap_or(0);
+        let date_len = files.iter().map(|e| date(e).len()).max().unwrap_or(0);
+        for entry in &files {
+            println!(
+                "{} {} {} {}",
+                format!("{:w$}", entry.commit_author, w = author_len).cyan(),
+                format!("{:>w$}", entry.commit_rev, w = rev_len).yellow(),
+                format!("{:w$}", date(entry), w = date_len).magenta(),
+                entry.name
+            );
+        }
+        Ok(())
+    }
+
+    //  The number of files and the most recent change of each author,
+    //  with the author of the most files first.
+    fn show_summary(&self, files: &[ListEntry]) {
+        let mut authors = BTreeMap::<&str, (usize, &ListEntry)>::new();
+        for entry in files {
+            let (count, latest) = authors.entry(&entry.commit_author).or_insert((0, entry));
+            *count += 1;
+            if entry.commit_date > latest.commit_date {
+                *latest = entry;
+            }
+        }
+        let mut authors: Vec<(&str, (usize, &ListEntry))> = authors.into_iter().collect();
+        match self.sort {
+            Sort::Age => authors.sort_by_key(|(_, (_, latest))| std::cmp::Reverse(latest.commit_date)),
+            Sort::Author => (),
+            Sort::Path => authors.sort_by(|a, b| b.1.0.cmp(&a.1.0).then_with(|| a.0.cmp(b.0))),
+        }
+
+        let author_len = authors.iter().map(|(a, _)| a.len()).max().unwrap_or(0);
+        let count_len = authors.iter().map(|(_, (n, _))| n.to_string().len()).max().unwrap_or(0);
+        for (author, (count, latest)) in &authors {
+            let date = if self.age { util::display_age(&latest.commit_date) } else { util::display_svn_date(&latest.commit_date) };
+            println!(
+                "{} {:>w$} {} (latest {})",
+                format!("{:a$}", author, a = author_len).cyan(),
+                count,
+                if *count == 1 { "file " } else { "files" },
+                date.magenta(),
+                w = count_len
+            );
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use chrono::{Duration, Local, NaiveDate};
+use clap::Parser;
+use colored::*;
+use crate::commands::authors::parse_date;
+use crate::config;
+use crate::svn::{self, LogEntry};
+use crate::util::{self, join_paths, SvError::*};
+use std::collections::BTreeMap;
+
+/// Summarize your commits by day.
+///
+/// Your commits on the trunk and on every branch are collected and
+/// listed by day with the branch and the first line of the message of
+/// each, which is handy for standups and timesheets.  By default the
+/// commits of the last 7 days are shown.
+///
+/// The author is taken from --author, the worklog.author setting or your
+/// username for the repository.  The branches are taken from --branch,
+/// the worklog.branches setting or the trunk and branch prefixes.
+#[derive(Debug, Parser)]
+#[command(
+    author,
+    help_template = crate::app::HELP_TEMPLATE,
+    after_help = "\
+    Dates are given in a form accepted by svn such as 2024-03-01 or\n\
+    \"2024-03-01 13:00\" and refer to the start of that day or time.\n\
+    A prefix given as a branch includes every branch beneath it."
+)]
+pub struct Worklog {
+    /// Include only commits made on or after this date.
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+
+    /// Include only commits made before this date.
+    #[arg(long, value_name = "DATE")]
+    until: Option<String>,
+
+    /// Show the commits of this author.
+    #[arg(short, long, value_name = "NAME")]
+    author: Option<String>,
+
+    /// A branch or prefix to search.  May be given multiple times.
+    #[arg(short, long = "branch", value_name = "BRANCH")]
+    branches: Vec<String>,
+}
+
+//  Number of days shown when --since is not given.
+const DEFAULT_DAYS: i64 = 7;
+
+impl Worklog {
+    pub fn run(&mut self) -> Result<()> {
+        let creds = crate::auth::get_credentials()?;
+        let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+        let root_url = wc_info.root_url;
+
+        let author = match self.author.clone().or(config::get_str("worklog.author").map(|s| s.to_string())) {
+            Some(author) => author,
+            None => crate::auth::current_username(&creds, &root_url)
+                .ok_or_else(|| General("Cannot determine your username, use --author".to_string()))?,
+        };
+
+        let since = match &self.since {
+            Some(date) => date.clone(),
+            None => (Local::now().date_naive() - Duration::days(DEFAULT_DAYS - 1)).format("%Y-%m-%d").to_string(),
+        };
+        let since_date = parse_date(&since)
+            .ok_or_else(|| General(format!("Invalid date: {}", since)))?;
+        let until = self.until.as_ref().map(|d| format!("{{{}}}", d)).unwrap_or("HEAD".to_string());
+        let range = format!("{{{}}}:{}", since, until);
+
+        let prefixes = svn::load_prefixes()?;
+        let branches = if !self.branches.is_empty() {
+            self.branches.clone()
+        } else {
+            match config::get_list("worklog.branches") {
+                Some(branches) if !branches.is_empty() => branches.to_vec(),
+                _ => std::iter::once(&prefixes.trunk_prefix).chain(&prefixes.branch_prefixes).cloned().collect(),
+            }
+        };
+
+        //  The commits keyed by revision with the branch of each.
+        //  A commit found beneath more than one branch is listed once.
+        let mut commits = BTreeMap::<u64, (String, LogEntry)>::new();
+        for branch in &branches {
+            let branch = branch.trim_start_matches("^/").trim_matches('/');
+            let is_prefix = prefixes.branch_prefixes.iter().chain(&prefixes.tag_prefixes).any(|p| p == branch);
+            let location = if branch == prefixes.trunk_prefix || is_prefix {
+                branch.to_string()
+            } else {
+                svn::resolve_location(&creds, &root_url, branch, &prefixes)?
+            };
+            let url = join_paths(&root_url, &location);
+            if !svn::path_exists(&creds, &url)? {
+                continue;
+            }
+            for entry in svn::log(&creds, &[url.as_str()], &[range.as_str()], true, None, false, is_prefix)? {
+                //  svn includes the revision that was current at the start
+                //  date, which was committed before it.
+                if entry.author != author || entry.date < since_date {
+                    continue;
+                }
+                let name = if is_prefix { branch_of(&location, &entry) } else { location.clone() };
+                commits.insert(entry.revision.parse::<u64>()?, (name, entry));
+            }
+        }
+
+        if commits.is_empty() {
+            println!("No commits by {} since {}", author.cyan(), util::display_svn_date(&since_date));
+            return Ok(());
+        }
+
+        let mut days = BTreeMap::<NaiveDate, Vec<&(String, LogEntry)>>::new();
+        for commit in commits.values() {
+            days.entry(commit.1.date.date_naive()).or_default().push(commit);
+        }
+        let rev_len = commits.values().map(|(_, e)| e.revision.len()).max().unwrap_or(0);
+        let branch_len = commits.values().map(|(b, _)| b.len()).max().unwrap_or(0);
+        for (index, (_, day)) in days.iter().enumerate() {
+            let date = &day[0].1.date;
+            if index > 0 {
+                println!();
+            }
+            println!("{} {}", util::display_svn_date(date).magenta(), date.format("%A").to_string().magenta());
+            for (branch, entry) in day {
+                println!(
+                    "  {} {} {} {}",
+                    format!("{:>w$}", entry.revision, w = rev_len).yellow(),
+                    util::display_svn_time(&entry.date),
+                    format!("{:w$}", branch, w = branch_len).green(),
+                    entry.msg_1st()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+//  The branch beneath a prefix that a commit changed, taken from the
+//  first of its paths.  eg. /branches/foo/src/main.rs is branches/foo
+fn branch_of(prefix: &str, entry: &LogEntry) -> String {
+    let start = format!("/{}/", prefix);
+    entry.paths
+        .iter()
+        .find_map(|p| p.path.strip_prefix(&start))
+        .and_then(|rest| rest.split('/').next())
+        .map(|name| join_paths(prefix, name))
+        .unwrap_or(prefix.to_string())
+}
@@ -0,0 +1,522 @@
+use anyhow::Result;
+use chrono::format::{Item, StrftimeItems};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use crate::profile;
+use crate::util::SvError::*;
+
+//  Settings are read from two files, svu/config.toml in the user's
+//  configuration directory and .svu/config.toml in the working copy.
+//  Settings in the working copy take precedence over the user's settings,
+//  and options given on the command line take precedence over both.
+//
+//  [core]
+//  jobs = 8
+//  ascii = false
+//  pager = "less -R"
+//
+//  [color]
+//  ui = "auto"
+//
+//  [date]
+//  format = "%Y-%m-%d"
+//
+//  [log]
+//  limit = 20
+//  author = true
+//
+//  [prefixes]
+//  trunk = "trunk"
+//  branches = ["branches", "releases"]
+//  tags = ["tags"]
+//
+//  [lint]
+//  subject-length = 72
+//  subject-forbid = ["^(Added|Fixed|Changed) "]
+//
+//  Only the part of TOML needed for these settings is supported:
+//  tables, strings, integers, booleans and single line arrays of strings.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<String>),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(s)  => write!(f, "{}", quote(s)),
+            Value::Integer(n) => write!(f, "{}", n),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Array(a)   => write!(f, "[{}]", a.iter().map(|s| quote(s)).collect::<Vec<String>>().join(", ")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    String,
+    Integer,
+    Boolean,
+    Array,
+}
+
+pub struct Setting {
+    pub key: &'static str,
+    pub kind: Kind,
+    pub description: &'static str,
+}
+
+pub const SETTINGS: [Setting; 28] = [
+    Setting { key: "core.jobs",        kind: Kind::Integer, description: "Maximum number of svn commands to run concurrently" },
+    Setting { key: "core.ascii",       kind: Kind::Boolean, description: "Use only ASCII characters in output" },
+    Setting { key: "core.pager",       kind: Kind::String,  description: "Page the output of log, show, blame, cat, diff-across, compare and tree" },
+    Setting { key: "color.ui",         kind: Kind::String,  description: "When to use colors: auto, always or never" },
+    Setting { key: "date.format",      kind: Kind::String,  description: "The strftime format used to display dates" },
+    Setting { key: "log.limit",        kind: Kind::Integer, description: "Default number of log entries to show" },
+    Setting { key: "log.author",       kind: Kind::Boolean, description: "Show the author of each log entry" },
+    Setting { key: "log.date",         kind: Kind::Boolean, description: "Show the date of each log entry" },
+    Setting { key: "log.time",         kind: Kind::Boolean, description: "Show the date and time of each log entry" },
+    Setting { key: "log.paths",        kind: Kind::Boolean, description: "Show the paths affected by each log entry" },
+    Setting { key: "log.stop-on-copy", kind: Kind::Boolean, description: "Stop the log at the commit that created the branch" },
+    Setting { key: "prefixes.trunk",   kind: Kind::String,  description: "Trunk prefix when none has been set with `svu prefix`" },
+    Setting { key: "prefixes.branches", kind: Kind::Array,  description: "Branch prefixes when none have been set with `svu prefix`" },
+    Setting { key: "prefixes.tags",    kind: Kind::Array,   description: "Tag prefixes when none have been set with `svu prefix`" },
+    Setting { key: "issues.pattern",   kind: Kind::String,  description: "Regular expression matching issue IDs in commit messages" },
+    Setting { key: "issues.url",       kind: Kind::String,  description: "URL of an issue with %BUGID% in place of its ID" },
+    Setting { key: "lint.subject-length", kind: Kind::Integer, description: "Maximum length of the first line of a commit message" },
+    Setting { key: "lint.blank-line",  kind: Kind::Boolean, description: "Require a blank line between the subject and body of a commit message" },
+    Setting { key: "lint.require-issue", kind: Kind::Boolean, description: "Require an issue ID matching issues.pattern in commit messages" },
+    Setting { key: "lint.subject-forbid", kind: Kind::Array, description: "Regular expressions that the subject of a commit message must not match" },
+    Setting { key: "archive.name",     kind: Kind::String,  description: "File name of archives with {name}, {location} and {rev} placeholders" },
+    Setting { key: "stash.auto-props", kind: Kind::Boolean, description: "Apply auto-props to the files added by stash --unversioned" },
+    Setting { key: "stash.expire",     kind: Kind::String,  description: "Age after which stash entries expire, eg. 90d" },
+    Setting { key: "worklog.author",   kind: Kind::String,  description: "Author whose commits are shown by `svu worklog`" },
+    Setting { key: "worklog.branches", kind: Kind::Array,   description: "Branches and prefixes searched by `svu worklog`" },
+    Setting { key: "impact.revisions", kind: Kind::Integer, description: "Number of revisions discarded before confirmation is required" },
+    Setting { key: "impact.paths",     kind: Kind::Integer, description: "Number of paths affected before confirmation is required" },
+    Setting { key: "hints.slow-seconds", kind: Kind::Integer, description: "Seconds a command runs before hints are shown, 0 to disable them" },
+];
+
+pub fn find_setting(key: &str) -> Option<&'static Setting> {
+    SETTINGS.iter().find(|s| s.key == key)
+}
+
+//  The merged settings and the file that each one came from.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    pub entries: BTreeMap<String, (Value, PathBuf)>,
+}
+
+fn loaded_config() -> &'static OnceLock<Config> {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    &CONFIG
+}
+
+//  Load the settings once.  Called before any command runs.
+pub fn load() -> Result<&'static Config> {
+    if let Some(config) = loaded_config().get() {
+        return Ok(config);
+    }
+    let mut config = Config::default();
+    for path in [user_config_file(), working_copy_config_file()].into_iter().flatten() {
+        if path.is_file() {
+            for (key, value) in load_file(&path)? {
+                config.entries.insert(key, (value, path.clone()));
+            }
+        }
+    }
+    Ok(loaded_config().get_or_init(|| config))
+}
+
+fn get(key: &str) -> Option<&'static Value> {
+    loaded_config().get().and_then(|c| c.entries.get(key)).map(|(value, _)| value)
+}
+
+pub fn get_str(key: &str) -> Option<&'static str> {
+    match get(key) {
+        Some(Value::String(s)) => Some(s.as_str()),
+        _ => None,
+    }
+}
+
+pub fn get_int(key: &str) -> Option<i64> {
+    match get(key) {
+        Some(Value::Integer(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+pub fn get_bool(key: &str) -> Option<bool> {
+    match get(key) {
+        Some(Value::Boolean(b)) => Some(*b),
+        _ => None,
+    }
+}
+
+pub fn get_list(key: &str) -> Option<&'static [String]> {
+    match get(key) {
+        Some(Value::Array(a)) => Some(a.as_slice()),
+        _ => None,
+    }
+}
+
+//  SVU_CONFIG may give the location of the user's config file.
+pub fn user_config_file() -> Option<PathBuf> {
+    if let Ok(path) = env::var("SVU_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    profile::user_config_dir().map(|dir| dir.join("config.toml"))
+}
+
+//  The config file in the data directory of the working copy that
+//  contains the current directory.  The working copy root is found by
+//  looking for its .svn directory so that no svn command is needed.
+pub fn working_copy_config_file() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    cwd.ancestors()
+        .find(|dir| dir.join(".svn").is_dir())
+        .map(|root| root.join(".svu").join("config.toml"))
+}
+
+pub fn load_file(path: &Path) -> Result<BTreeMap<String, Value>> {
+    let text = fs::read_to_string(path)?;
+    let location = |line: usize| format!("{}:{}", path.display(), line + 1);
+    let mut entries = BTreeMap::new();
+    let mut section = String::new();
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            if section.is_empty() {
+                return Err(General(format!("{}: Empty table name", location(index))).into());
+            }
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => (key.trim(), value.trim()),
+            _ => return Err(General(format!("{}: Expected key = value", location(index))).into()),
+        };
+        let key = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+        let value = parse_value(value).map_err(|e| General(format!("{}: {}", location(index), e)))?;
+        check_value(&key, &value).map_err(|e| General(format!("{}: {}", location(index), e)))?;
+        entries.insert(key, value);
+    }
+    Ok(entries)
+}
+
+//  Rewrite a config file with the given settings grouped by table.
+//  Comments in the file are not preserved.
+pub fn save_file(path: &Path, entries: &BTreeMap<String, Value>) -> Result<()> {
+    let mut tables = BTreeMap::<&str, Vec<(&str, &Value)>>::new();
+    for (key, value) in entries {
+        let (table, name) = key.rsplit_once('.').unwrap_or(("", key));
+        tables.entry(table).or_default().push((name, value));
+    }
+    let mut text = String::new();
+    for (table, settings) in &tables {
+        if !table.is_empty() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("[{}]\n", table));
+        }
+        for (name, value) in settings {
+            text.push_str(&format!("{} = {}\n", name, value));
+        }
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, text)?;
+    Ok(())
+}
+
+//  Make sure a value has the type of its setting and is valid.
+//  Settings that svu does not know about are accepted as is.
+pub fn check_value(key: &str, value: &Value) -> Result<(), String> {
+    let setting = match find_setting(key) {
+        Some(setting) => setting,
+        None => return Ok(()),
+    };
+    let kind = match value {
+        Value::String(_)  => Kind::String,
+        Value::Integer(_) => Kind::Integer,
+        Value::Boolean(_) => Kind::Boolean,
+        Value::Array(_)   => Kind::Array,
+    };
+    if kind != setting.kind {
+        return Err(format!("{} must be {}", key, kind_name(setting.kind)));
+    }
+    match (key, value) {
+        ("color.ui", Value::String(s)) if !["auto", "always", "never"].contains(&s.as_str()) => {
+            Err("color.ui must be one of: auto, always, never".to_string())
+        }
+        ("date.format", Value::String(s)) if StrftimeItems::new(s).any(|i| matches!(i, Item::Error)) => {
+            Err(format!("Invalid date format '{}'", s))
+        }
+        ("core.jobs", Value::Integer(n)) if *n < 1 || *n > u16::MAX as i64 => {
+            Err("core.jobs must be a positive number".to_string())
+        }
+        ("log.limit", Value::Integer(n)) if *n < 1 || *n > u32::MAX as i64 => {
+            Err("log.limit must be a positive number".to_string())
+        }
+        ("stash.expire", Value::String(s)) if crate::util::parse_age(s).is_err() => {
+            Err(format!("Invalid age '{}', expected a number followed by d, w, m or y (eg. 90d)", s))
+        }
+        ("issues.pattern", Value::String(s)) if regex::Regex::new(s).is_err() => {
+            Err(format!("Invalid regular expression '{}'", s))
+        }
+        ("impact.revisions" | "impact.paths" | "hints.slow-seconds", Value::Integer(n)) if *n < 0 => {
+            Err(format!("{} must not be negative", key))
+        }
+        ("lint.subject-length", Value::Integer(n)) if *n < 1 => {
+            Err("lint.subject-length must be a positive number".to_string())
+        }
+        ("lint.subject-forbid", Value::Array(a)) if a.iter().any(|s| regex::Regex::new(s).is_err()) => {
+            let s = a.iter().find(|s| regex::Regex::new(s).is_err()).unwrap();
+            Err(format!("Invalid regular expression '{}'", s))
+        }
+        ("issues.url", Value::String(s)) if !s.contains(crate::issues::URL_PLACEHOLDER) => {
+            Err(format!("issues.url must contain {}", crate::issues::URL_PLACEHOLDER))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::String  => "a string",
+        Kind::Integer => "an integer",
+        Kind::Boolean => "true or false",
+        Kind::Array   => "an array of strings",
+    }
+}
+
+//  Parse a value given on the command line using the type of its setting.
+//  The items of an array are given as separate arguments, or as a single
+//  argument of comma separated values.
+pub fn parse_arg<S: AsRef<str>>(key: &str, args: &[S]) -> Result<Value> {
+    let is_array = find_setting(key).is_some_and(|s| s.kind == Kind::Array);
+    let arg = match args {
+        [arg] => arg.as_ref(),
+        _ if is_array => return Ok(Value::Array(args.iter().map(|s| s.as_ref().to_string()).collect())),
+        _ => return Err(General(format!("{} takes a single value", key)).into()),
+    };
+    let value = match find_setting(key).map(|s| s.kind) {
+        Some(Kind::String)  => Value::String(arg.to_string()),
+        Some(Kind::Array)   => Value::Array(arg.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()),
+        Some(Kind::Integer) => Value::Integer(arg.parse().map_err(|_| General(format!("{} must be an integer", key)))?),
+        Some(Kind::Boolean) => Value::Boolean(parse_bool(arg).ok_or(General(format!("{} must be true or false", key)))?),
+        None => parse_value(arg).unwrap_or(Value::String(arg.to_string())),
+    };
+    check_value(key, &value).map_err(General)?;
+    Ok(value)
+}
+
+fn parse_bool(arg: &str) -> Option<bool> {
+    match arg.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1"  => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+//  Remove a comment that is not within a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..index],
+            None => (),
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    match text {
+        "true"  => return Ok(Value::Boolean(true)),
+        "false" => return Ok(Value::Boolean(false)),
+        _ => (),
+    }
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner.strip_suffix(']').ok_or("Arrays must be on a single line")?;
+        let mut items = Vec::new();
+        let mut rest = inner.trim();
+        while !rest.is_empty() {
+            let (item, remaining) = parse_string(rest)?;
+            items.push(item);
+            rest = remaining.trim_start();
+            rest = match rest.strip_prefix(',') {
+                Some(r) => r.trim_start(),
+                None if rest.is_empty() => rest,
+                None => return Err("Expected ',' between array items".to_string()),
+            };
+        }
+        return Ok(Value::Array(items));
+    }
+    if text.starts_with('"') || text.starts_with('\'') {
+        return match parse_string(text)? {
+            (s, rest) if rest.trim().is_empty() => Ok(Value::String(s)),
+            _ => Err("Unexpected text after string".to_string()),
+        };
+    }
+    text.replace('_', "")
+        .parse::<i64>()
+        .map(Value::Integer)
+        .map_err(|_| format!("Invalid value '{}'", text))
+}
+
+//  Parse a basic "string" or a literal 'string' and return it with the text that follows.
+pub(crate) fn parse_string(text: &str) -> Result<(String, &str), String> {
+    if let Some(rest) = text.strip_prefix('\'') {
+        return match rest.find('\'') {
+            Some(end) => Ok((rest[..end].to_string(), &rest[end + 1..])),
+            None => Err("Unterminated string".to_string()),
+        };
+    }
+    let rest = text.strip_prefix('"').ok_or("Expected a string")?;
+    let mut value = String::new();
+    let mut chars = rest.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &rest[index + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n'))  => value.push('\n'),
+                Some((_, 't'))  => value.push('\t'),
+                Some((_, '"'))  => value.push('"'),
+                Some((_, '\\')) => value.push('\\'),
+                _ => return Err("Invalid escape in string".to_string()),
+            },
+            c => value.push(c),
+        }
+    }
+    Err("Unterminated string".to_string())
+}
+
+pub(crate) fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //  Load the text as a config file.
+    fn load_text(name: &str, text: &str) -> Result<BTreeMap<String, Value>> {
+        let path = env::temp_dir().join(format!("svu_config_test_{}_{}.toml", std::process::id(), name));
+        fs::write(&path, text)?;
+        let result = load_file(&path);
+        fs::remove_file(&path)?;
+        result
+    }
+
+    #[test]
+    fn basic_and_literal_strings() {
+        assert_eq!(parse_value(r#""less -R""#), Ok(Value::String("less -R".to_string())));
+        assert_eq!(parse_value(r#""a\"b\\c\nd\te""#), Ok(Value::String("a\"b\\c\nd\te".to_string())));
+        assert_eq!(parse_value(r"'C:\temp\'"), Ok(Value::String(r"C:\temp\".to_string())));
+        assert_eq!(parse_string(r#""one" rest"#), Ok(("one".to_string(), " rest")));
+    }
+
+    #[test]
+    fn quoted_strings_round_trip() {
+        for s in ["plain", "with \"quotes\"", r"back\slash", "new\nline\tand tab"] {
+            assert_eq!(parse_value(&quote(s)), Ok(Value::String(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn other_values() {
+        assert_eq!(parse_value("true"), Ok(Value::Boolean(true)));
+        assert_eq!(parse_value("false"), Ok(Value::Boolean(false)));
+        assert_eq!(parse_value("-12"), Ok(Value::Integer(-12)));
+        assert_eq!(parse_value("1_000"), Ok(Value::Integer(1000)));
+        assert_eq!(parse_value("[]"), Ok(Value::Array(vec![])));
+        assert_eq!(
+            parse_value(r#"["branches", 'releases' ,"x,y"]"#),
+            Ok(Value::Array(vec!["branches".to_string(), "releases".to_string(), "x,y".to_string()]))
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(parse_value(r#""unterminated"#).is_err());
+        assert!(parse_value("'unterminated").is_err());
+        assert!(parse_value(r#""bad \q escape""#).is_err());
+        assert!(parse_value(r#""text" after"#).is_err());
+        assert!(parse_value("bare").is_err());
+        assert!(parse_value(r#"["a" "b"]"#).is_err());
+        assert!(parse_value(r#"["a","#).is_err());
+        assert!(parse_value("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(strip_comment("jobs = 8 # eight"), "jobs = 8 ");
+        assert_eq!(strip_comment("# whole line"), "");
+        assert_eq!(strip_comment(r#"pager = "less # not a comment""#), r#"pager = "less # not a comment""#);
+        assert_eq!(strip_comment(r##"x = "a\"#b" # c"##), r##"x = "a\"#b" "##);
+        assert_eq!(strip_comment("x = 'a#b' # c"), "x = 'a#b' ");
+    }
+
+    #[test]
+    fn sections() {
+        let text = "\
+            # A config file\n\
+            top = 1\n\
+            \n\
+            [core]\n\
+            jobs = 8   # comment\n\
+            pager = \"less -R\"\n\
+            [ log ]\n\
+            author = true\n";
+        let entries = load_text("sections", text).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries["top"], Value::Integer(1));
+        assert_eq!(entries["core.jobs"], Value::Integer(8));
+        assert_eq!(entries["core.pager"], Value::String("less -R".to_string()));
+        assert_eq!(entries["log.author"], Value::Boolean(true));
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let error = |name, text| load_text(name, text).unwrap_err().to_string();
+        assert!(error("no_equals", "[core]\njobs 8\n").ends_with(":2: Expected key = value"));
+        assert!(error("no_key", "= 8\n").ends_with(":1: Expected key = value"));
+        assert!(error("empty_table", "[ ]\n").ends_with(":1: Empty table name"));
+        assert!(error("bad_value", "[core]\npager = less\n").ends_with(":2: Invalid value 'less'"));
+        assert!(error("wrong_kind", "[core]\njobs = \"8\"\n").ends_with(":2: core.jobs must be an integer"));
+        assert!(error("multi_line_array", "[prefixes]\ntags = [\n").ends_with(":2: Arrays must be on a single line"));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = env::temp_dir().join(format!("svu_config_test_{}_save.toml", std::process::id()));
+        let mut entries = BTreeMap::new();
+        entries.insert("core.pager".to_string(), Value::String("less \"-R\"".to_string()));
+        entries.insert("log.limit".to_string(), Value::Integer(20));
+        entries.insert("prefixes.branches".to_string(), Value::Array(vec!["branches".to_string(), "a#b".to_string()]));
+        save_file(&path, &entries).unwrap();
+        let loaded = load_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), entries);
+    }
+}
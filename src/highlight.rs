@@ -0,0 +1,199 @@
+use colored::*;
+use std::path::Path;
+
+//  A simple syntax highlighter for displaying file contents.
+//  It colors comments, strings, numbers and keywords based on the
+//  extension of the file.  It does not attempt to parse the language,
+//  so it is fooled by unusual constructs, but it is good enough for
+//  reading code in a terminal.
+
+struct Syntax {
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    keywords: &'static [&'static str],
+    ignore_case: bool,
+}
+
+const SYNTAXES: &[Syntax] = &[
+    Syntax {
+        extensions: &["rs"],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+            "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+            "type", "unsafe", "use", "where", "while",
+        ],
+        ignore_case: false,
+    },
+    Syntax {
+        extensions: &[
+            "c", "h", "cc", "cpp", "cxx", "hpp", "hh", "java", "js", "jsx", "ts", "tsx", "go", "cs",
+            "kt", "scala", "swift", "groovy", "php",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\'', '`'],
+        keywords: &[
+            "abstract", "async", "await", "break", "case", "catch", "class", "const", "continue",
+            "default", "defer", "delete", "do", "else", "enum", "export", "extends", "false", "final",
+            "finally", "for", "func", "function", "go", "if", "implements", "import", "interface",
+            "let", "namespace", "new", "null", "package", "private", "protected", "public", "return",
+            "static", "struct", "super", "switch", "this", "throw", "throws", "true", "try", "typedef",
+            "var", "void", "while",
+        ],
+        ignore_case: false,
+    },
+    Syntax {
+        extensions: &["py", "rb", "pl", "pm", "sh", "bash", "zsh", "yaml", "yml", "toml", "conf", "cfg", "properties"],
+        line_comments: &["#"],
+        block_comment: None,
+        quotes: &['"', '\''],
+        keywords: &[
+            "and", "as", "begin", "break", "case", "class", "def", "do", "done", "elif", "else", "elsif",
+            "end", "esac", "except", "export", "false", "fi", "finally", "for", "from", "function", "if",
+            "import", "in", "is", "lambda", "local", "module", "my", "None", "nil", "not", "or", "pass",
+            "raise", "require", "return", "self", "sub", "then", "True", "False", "true", "try", "unless",
+            "until", "use", "while", "with", "yield",
+        ],
+        ignore_case: false,
+    },
+    Syntax {
+        extensions: &["sql"],
+        line_comments: &["--"],
+        block_comment: Some(("/*", "*/")),
+        quotes: &['\''],
+        keywords: &[
+            "alter", "and", "as", "by", "create", "delete", "drop", "from", "group", "having", "index",
+            "insert", "into", "join", "key", "left", "not", "null", "on", "or", "order", "primary",
+            "select", "set", "table", "union", "update", "values", "view", "where",
+        ],
+        ignore_case: true,
+    },
+    Syntax {
+        extensions: &["xml", "html", "htm", "xsd", "xsl", "svg", "pom"],
+        line_comments: &[],
+        block_comment: Some(("<!--", "-->")),
+        quotes: &['"', '\''],
+        keywords: &[],
+        ignore_case: false,
+    },
+];
+
+pub struct Highlighter {
+    syntax: &'static Syntax,
+    in_block_comment: bool,
+}
+
+impl Highlighter {
+    //  Returns None if the type of file is not recognized.
+    pub fn for_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_string_lossy().to_lowercase();
+        SYNTAXES
+            .iter()
+            .find(|s| s.extensions.contains(&ext.as_str()))
+            .map(|syntax| Highlighter { syntax, in_block_comment: false })
+    }
+
+    //  Highlight the next line of the file.
+    pub fn line(&mut self, line: &str) -> String {
+        let mut result = String::new();
+        let mut rest = line;
+
+        while !rest.is_empty() {
+            if self.in_block_comment {
+                let (_, end) = self.syntax.block_comment.unwrap();
+                let len = match rest.find(end) {
+                    Some(pos) => {
+                        self.in_block_comment = false;
+                        pos + end.len()
+                    }
+                    None => rest.len(),
+                };
+                result += &rest[..len].bright_black().to_string();
+                rest = &rest[len..];
+                continue;
+            }
+            if self.syntax.line_comments.iter().any(|c| rest.starts_with(c)) {
+                result += &rest.bright_black().to_string();
+                break;
+            }
+            if let Some((start, _)) = self.syntax.block_comment.filter(|(start, _)| rest.starts_with(start)) {
+                self.in_block_comment = true;
+                result += &start.bright_black().to_string();
+                rest = &rest[start.len()..];
+                continue;
+            }
+
+            let ch = rest.chars().next().unwrap();
+            let len = if self.syntax.quotes.contains(&ch) {
+                let len = string_len(rest, ch);
+                result += &rest[..len].green().to_string();
+                len
+            } else if ch.is_ascii_digit() {
+                let len = number_len(rest);
+                result += &rest[..len].cyan().to_string();
+                len
+            } else if ch.is_alphabetic() || ch == '_' {
+                let len = word_len(rest);
+                let word = &rest[..len];
+                if self.is_keyword(word) {
+                    result += &word.blue().bold().to_string();
+                } else {
+                    result += word;
+                }
+                len
+            } else {
+                result.push(ch);
+                ch.len_utf8()
+            };
+            rest = &rest[len..];
+        }
+        result
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        if self.syntax.ignore_case {
+            self.syntax.keywords.iter().any(|k| k.eq_ignore_ascii_case(word))
+        } else {
+            self.syntax.keywords.contains(&word)
+        }
+    }
+}
+
+//  The length of the identifier at the start of text.
+fn word_len(text: &str) -> usize {
+    text.char_indices()
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '_'))
+        .map(|(i, _)| i)
+        .unwrap_or(text.len())
+}
+
+//  The length of the number at the start of text, including any
+//  decimal point, exponent or type suffix.
+fn number_len(text: &str) -> usize {
+    text.char_indices()
+        .find(|(_, c)| !(c.is_alphanumeric() || *c == '_' || *c == '.'))
+        .map(|(i, _)| i)
+        .unwrap_or(text.len())
+}
+
+//  The length of the quoted string at the start of text, including the quotes.
+//  A string that is not closed extends to the end of the line.
+fn string_len(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return i + c.len_utf8();
+        }
+    }
+    text.len()
+}
@@ -0,0 +1,144 @@
+use colored::*;
+use std::time::Duration;
+use crate::config;
+use crate::svn;
+
+//  When a command takes longer than the hints.slow-seconds setting (default 10)
+//  we look at where the time was spent running svn and, if we know of a
+//  faster way to run the command, print a hint to stderr.
+//  Setting hints.slow-seconds to 0 disables the hints.
+
+const DEFAULT_SLOW_SECONDS: u64 = 10;
+
+struct Hint {
+    //  The svu command
+    command: &'static str,
+    //  The svn subcommand that took most of the time
+    svn: &'static str,
+    //  The hint is only given if one of these arguments is present.
+    when: &'static [&'static str],
+    //  The hint is not given if one of these arguments is present
+    //  because the user is already following it.
+    unless: &'static [&'static str],
+    text: &'static str,
+}
+
+const HINTS: &[Hint] = &[
+    Hint {
+        command: "log",
+        svn: "log",
+        when: &[],
+        unless: &["-l", "--limit"],
+        text: "Use --limit NUM to fetch only the most recent commits.",
+    },
+    Hint {
+        command: "log",
+        svn: "log",
+        when: &["-p", "--show-paths"],
+        unless: &[],
+        text: "Listing the paths of each commit is slow on large repositories, omit --show-paths if you do not need them.",
+    },
+    Hint {
+        command: "filerevs",
+        svn: "list",
+        when: &["-B", "--all-branches", "-T", "--all-tags"],
+        unless: &[],
+        text: "Use --branch REGEX or --tag REGEX to check only the branches and tags of interest.",
+    },
+    Hint {
+        command: "filerevs",
+        svn: "info",
+        when: &[],
+        unless: &["-j", "--jobs"],
+        text: "Use --jobs NUM to run more svn commands in parallel.",
+    },
+    Hint {
+        command: "grep",
+        svn: "cat",
+        when: &[],
+        unless: &[],
+        text: "Give a PATH to search only the directories that you need.",
+    },
+    Hint {
+        command: "grep",
+        svn: "list",
+        when: &["-b", "--branches", "-t", "--tags"],
+        unless: &[],
+        text: "Use more specific --branches and --tags regular expressions to search fewer locations.",
+    },
+    Hint {
+        command: "graph",
+        svn: "log",
+        when: &["-T", "--tags"],
+        unless: &[],
+        text: "Omit --tags to graph only the branches.",
+    },
+    Hint {
+        command: "stash",
+        svn: "status",
+        when: &[],
+        unless: &["-c", "--changelist"],
+        text: "Use --changelist NAME to stash only the files in a changelist.",
+    },
+    Hint {
+        command: "mixed",
+        svn: "status",
+        when: &[],
+        unless: &[],
+        text: "Run mixed from a subdirectory to check only that part of the working copy.",
+    },
+];
+
+fn slow_threshold() -> Option<Duration> {
+    let seconds = config::get_int("hints.slow-seconds")
+        .and_then(|n| u64::try_from(n).ok())
+        .unwrap_or(DEFAULT_SLOW_SECONDS);
+    if seconds == 0 { None } else { Some(Duration::from_secs(seconds)) }
+}
+
+//  Called when a command completes successfully.
+pub fn show_slow_hint(args: &[String], elapsed: Duration) {
+    match slow_threshold() {
+        Some(threshold) if elapsed >= threshold => (),
+        _ => return,
+    }
+
+    //  The svn subcommand where most of the time was spent
+    let timings = svn::svn_timings();
+    let (svn_name, timing) = match timings.iter().max_by_key(|(_, t)| t.elapsed) {
+        Some(slowest) => slowest,
+        None => return,
+    };
+
+    let has_arg = |names: &[&str]| {
+        args.iter().skip(1).any(|a| {
+            names.iter().any(|n| a == n || a.starts_with(&format!("{}=", n)) || (n.len() == 2 && !n.starts_with("--") && a.starts_with(n)))
+        })
+    };
+    let command = match args.iter().skip(1).find(|a| HINTS.iter().any(|h| h.command == a.as_str())) {
+        Some(command) => command.as_str(),
+        None => return,
+    };
+
+    let hint = HINTS.iter().find(|h| {
+        h.command == command
+            && h.svn == svn_name
+            && (h.when.is_empty() || has_arg(h.when))
+            && !has_arg(h.unless)
+    });
+
+    if let Some(hint) = hint {
+        eprintln!(
+            "{} {} took {:.1}s, {:.1}s of it running 'svn {}' {} {}",
+            "hint:".cyan(),
+            command,
+            elapsed.as_secs_f64(),
+            timing.elapsed.as_secs_f64(),
+            svn_name,
+            timing.count,
+            if timing.count == 1 { "time" } else { "times" }
+        );
+        eprintln!("      {}", hint.text);
+        eprintln!("      (set hints.slow-seconds with `svu config` to change when hints are shown or 0 to disable them)");
+    }
+}
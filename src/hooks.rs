@@ -0,0 +1,105 @@
+use anyhow::Result;
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::svn;
+use crate::util::{self, SvError::*};
+
+//  Client side hooks are executable files in .svu/hooks that are
+//  named after the event that runs them.  The events are:
+//
+//  pre-commit, post-commit
+//  pre-stash-push, post-stash-push, post-stash-pop
+//  pre-switch, post-switch
+//  pre-update, post-update
+//  post-bisect-complete
+//  post-watch
+//
+//  A hook runs in the working copy root directory.  The SVU_HOOK
+//  environment variable holds the event name and SVU_WC_ROOT the
+//  working copy root.  Each event adds its own SVU_ variables such
+//  as SVU_BRANCH or SVU_REVISION.
+//  A pre- hook that exits with a non-zero status stops the command.
+//  The exit status of a post- hook is only reported.
+//  Set SVU_NO_HOOKS to run commands without their hooks.
+
+//  Run the hook for an event if one exists.
+//  Each (name, value) pair of the context is passed to the hook
+//  in the SVU_<name> environment variable.
+pub fn run(event: &str, ctx: &[(&str, &str)]) -> Result<()> {
+    if std::env::var("SVU_NO_HOOKS").is_ok_and(|v| !v.is_empty()) {
+        return Ok(());
+    }
+    let wc_info = svn::workingcopy_info()?; // Make sure we are in a working copy.
+    let wc_root = PathBuf::from(wc_info.wc_path.unwrap());
+    let hook = match find_hook(&util::data_directory()?.join("hooks"), event) {
+        Some(hook) => hook,
+        None => return Ok(()),
+    };
+
+    let mut cmd = hook_command(&hook);
+    cmd.current_dir(&wc_root)
+        .env("SVU_HOOK", event)
+        .env("SVU_WC_ROOT", &wc_root);
+    for (name, value) in ctx {
+        cmd.env(format!("SVU_{}", name), value);
+    }
+    let status = cmd.status()
+        .map_err(|e| General(format!("Cannot run the {} hook {}: {}", event, hook.display(), e)))?;
+
+    match status.code() {
+        Some(0) => Ok(()),
+        code => {
+            let code = code.map(|c| c.to_string()).unwrap_or("a signal".to_string());
+            if event.starts_with("pre-") {
+                Err(General(format!("The {} hook failed with exit status {}", event, code)).into())
+            } else {
+                eprintln!("{} the {} hook failed with exit status {}", "warning:".yellow(), event, code);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn find_hook(dir: &Path, event: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join(event);
+    let metadata = path.metadata().ok().filter(|m| m.is_file())?;
+    if metadata.permissions().mode() & 0o111 == 0 {
+        eprintln!(
+            "{} the {} hook was ignored because it is not executable",
+            "warning:".yellow(),
+            event
+        );
+        None
+    } else {
+        Some(path)
+    }
+}
+
+#[cfg(not(unix))]
+fn find_hook(dir: &Path, event: &str) -> Option<PathBuf> {
+    ["exe", "bat", "cmd"]
+        .iter()
+        .map(|ext| dir.join(event).with_extension(ext))
+        .find(|path| path.is_file())
+}
+
+#[cfg(unix)]
+fn hook_command(hook: &Path) -> Command {
+    Command::new(hook)
+}
+
+//  Batch files must be run by the command interpreter.
+#[cfg(not(unix))]
+fn hook_command(hook: &Path) -> Command {
+    match hook.extension().and_then(|e| e.to_str()) {
+        Some("bat") | Some("cmd") => {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(hook);
+            cmd
+        }
+        _ => Command::new(hook),
+    }
+}
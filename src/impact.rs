@@ -0,0 +1,88 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use colored::*;
+use std::collections::BTreeSet;
+use crate::config;
+use crate::svn::LogEntry;
+use crate::util::{self, SvError::*};
+
+//  Commands that discard or revert commits, such as `branch delete`
+//  and `rollback`, summarize the commits involved before they run.
+//  When the impact exceeds a threshold the user must confirm the
+//  command interactively or pass --yes.
+//
+//  The thresholds are set with the impact.revisions and
+//  impact.paths settings.
+
+const DEFAULT_MAX_REVISIONS: usize = 5;
+const DEFAULT_MAX_PATHS: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct Impact {
+    pub revisions: usize,
+    pub paths: usize,
+    pub authors: BTreeSet<String>,
+    pub last_activity: Option<DateTime<Local>>,
+}
+
+impl Impact {
+    //  The log entries must include their changed paths.
+    pub fn from_log(entries: &[LogEntry]) -> Impact {
+        let paths: BTreeSet<&str> = entries.iter()
+            .flat_map(|e| e.paths.iter().map(|p| p.path.as_str()))
+            .collect();
+        Impact {
+            revisions: entries.len(),
+            paths: paths.len(),
+            authors: entries.iter().map(|e| e.author.clone()).collect(),
+            last_activity: entries.iter().map(|e| e.date).max(),
+        }
+    }
+
+    //  `verb` describes what happens to the revisions. eg. discarded
+    pub fn show(&self, verb: &str) {
+        let authors = self.authors.iter().cloned().collect::<Vec<String>>().join(", ");
+        let last_activity = self.last_activity
+            .map(|d| util::display_svn_datetime(&d))
+            .unwrap_or("n/a".to_string());
+        println!("{:<20} {}", format!("Revisions {}:", verb), self.revisions.to_string().yellow());
+        println!("{:<20} {}", "Paths affected:", self.paths.to_string().yellow());
+        println!("{:<20} {}", "Authors:", authors.cyan());
+        println!("{:<20} {}", "Last activity:", last_activity.magenta());
+    }
+
+    pub fn exceeds_thresholds(&self) -> Result<bool> {
+        let max_revisions = threshold("impact.revisions", DEFAULT_MAX_REVISIONS)?;
+        let max_paths = threshold("impact.paths", DEFAULT_MAX_PATHS)?;
+        Ok(self.revisions > max_revisions || self.paths > max_paths)
+    }
+}
+
+fn threshold(key: &str, default: usize) -> Result<usize> {
+    match config::get_int(key) {
+        Some(value) => usize::try_from(value)
+            .map_err(|_| General(format!("{} must not be negative", key)).into()),
+        None => Ok(default),
+    }
+}
+
+//  Returns true if the command should proceed.
+//  Confirmation is only requested when the impact exceeds a threshold.
+pub fn confirm(impact: &Impact, yes: bool, prompt: &str) -> Result<bool> {
+    if needs_confirmation(impact, yes)? {
+        util::confirm(prompt)
+    } else {
+        Ok(true)
+    }
+}
+
+//  Returns true, after telling the user why, when the impact
+//  exceeds a threshold and confirmation was not given with `yes`.
+pub fn needs_confirmation(impact: &Impact, yes: bool) -> Result<bool> {
+    if yes || !impact.exceeds_thresholds()? {
+        Ok(false)
+    } else {
+        println!("{}", "This exceeds the impact thresholds. (see impact.revisions, impact.paths in `svu config`)".yellow());
+        Ok(true)
+    }
+}
@@ -0,0 +1,161 @@
+use colored::*;
+use std::fs::remove_file;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+//  When the user presses Ctrl-C we do not want to leave the working copy
+//  or our data files in an unknown state.
+//
+//  While an svn command is running the interrupt terminates it and
+//  SvnCmd::run() returns an Interrupted error so the svu command stops
+//  at that point.  Commands that make several changes record their
+//  progress in a `Progress` so that when they are interrupted any partially
+//  written files are removed and the user is told which steps were
+//  completed and which were not.
+//
+//  At any other time the interrupt terminates svu immediately.
+//  A second interrupt always terminates svu immediately.
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+static REPORTED: AtomicBool = AtomicBool::new(false);
+//  The child processes that are terminated if we are interrupted.
+//  svn commands may run in parallel so each one has a slot.  A slot holds
+//  zero when it is free.  Atomics are used because the signal handler
+//  cannot take a lock.
+const MAX_CHILDREN: usize = 64;
+static CHILD_PIDS: [AtomicI32; MAX_CHILDREN] = [const { AtomicI32::new(0) }; MAX_CHILDREN];
+
+//  The exit status used when svu is terminated by an interrupt.
+pub const EXIT_STATUS: i32 = 130;
+
+#[cfg(unix)]
+extern "C" fn handle_signal(_signal: libc::c_int) {
+    let running = CHILD_PIDS.iter().any(|slot| slot.load(Ordering::SeqCst) > 0);
+    if INTERRUPTED.swap(true, Ordering::SeqCst) || (!running && !IN_PROGRESS.load(Ordering::SeqCst)) {
+        unsafe { libc::_exit(EXIT_STATUS) };
+    }
+    for slot in &CHILD_PIDS {
+        let pid = slot.load(Ordering::SeqCst);
+        if pid > 0 {
+            unsafe { libc::kill(pid, libc::SIGTERM) };
+        }
+    }
+}
+
+//  Install the handler for SIGINT and SIGTERM.
+//  On Windows the default handling is used.
+pub fn install() {
+    #[cfg(unix)]
+    unsafe {
+        let handler = handle_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+//  Returns true if a Progress has already told the user about the interrupt.
+pub fn reported() -> bool {
+    REPORTED.load(Ordering::SeqCst)
+}
+
+//  Record a child process that is terminated if we are interrupted.
+//  If every slot is taken the child is not recorded and is left to
+//  receive the interrupt from the terminal.
+pub fn child_started(pid: u32) {
+    let pid = pid as i32;
+    let _ = CHILD_PIDS.iter().find(|slot| slot.compare_exchange(0, pid, Ordering::SeqCst, Ordering::SeqCst).is_ok());
+}
+
+pub fn child_finished(pid: u32) {
+    let pid = pid as i32;
+    let _ = CHILD_PIDS.iter().find(|slot| slot.compare_exchange(pid, 0, Ordering::SeqCst, Ordering::SeqCst).is_ok());
+}
+
+//  The steps of a command that makes several changes.
+//  When it is dropped after an interrupt, the files that are still
+//  being tracked are removed and the steps are reported.
+#[derive(Debug, Default)]
+pub struct Progress {
+    completed: Vec<String>,
+    current: Option<(String, Option<String>)>,
+    files: Vec<PathBuf>,
+    finished: bool,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        IN_PROGRESS.store(true, Ordering::SeqCst);
+        Progress::default()
+    }
+
+    //  Start the next step.  The previous step is complete.
+    pub fn step<S: Into<String>>(&mut self, description: S) {
+        self.start(description.into(), None);
+    }
+
+    //  Start the next step giving instructions for finishing
+    //  it by hand if it is interrupted.
+    pub fn step_with_hint<S: Into<String>, T: Into<String>>(&mut self, description: S, hint: T) {
+        self.start(description.into(), Some(hint.into()));
+    }
+
+    fn start(&mut self, description: String, hint: Option<String>) {
+        if let Some((previous, _)) = self.current.take() {
+            self.completed.push(previous);
+        }
+        self.current = Some((description, hint));
+    }
+
+    //  Remove this file if we are interrupted before `keep_files()` is called.
+    pub fn track_file(&mut self, path: &Path) {
+        self.files.push(path.to_path_buf());
+    }
+
+    //  The tracked files are complete and must not be removed.
+    pub fn keep_files(&mut self) {
+        self.files.clear();
+    }
+
+    //  All of the steps were completed.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    fn report(&self) {
+        eprintln!();
+        eprintln!("{}", "Interrupted".red());
+        for path in self.files.iter().filter(|p| p.is_file()) {
+            if remove_file(path).is_ok() {
+                eprintln!("Removed the partially written file {}", path.to_string_lossy());
+            }
+        }
+        if !self.completed.is_empty() {
+            eprintln!("Completed:");
+            for step in &self.completed {
+                eprintln!("  {}", step.green());
+            }
+        }
+        if let Some((step, hint)) = &self.current {
+            eprintln!("Not completed:");
+            eprintln!("  {}", step.red());
+            if let Some(hint) = hint {
+                eprintln!("{}", hint);
+            }
+        }
+        REPORTED.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if interrupted() && !self.finished {
+            self.report();
+        }
+        IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,62 @@
+use colored::*;
+use regex::Regex;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+use crate::config;
+use crate::pager;
+
+//  Issue tracker IDs found in commit messages.
+//
+//  issues.pattern is a regular expression that matches an issue ID such as
+//  PROJ-123.  If the pattern has a capture group then the group is the ID,
+//  otherwise the whole match is.  issues.url is the URL of an issue with
+//  %BUGID% in place of its ID, following the bugtraq:url convention.
+
+pub const URL_PLACEHOLDER: &str = "%BUGID%";
+
+//  The configured pattern.  An invalid pattern is rejected
+//  by `svu config set`, and ignored here.
+pub fn configured_pattern() -> Option<&'static Regex> {
+    static PATTERN: OnceLock<Option<Regex>> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| config::get_str("issues.pattern").and_then(|p| Regex::new(p).ok()))
+        .as_ref()
+}
+
+//  The IDs referenced in some text with the range of each one.
+pub fn find(pattern: &Regex, text: &str) -> Vec<(std::ops::Range<usize>, String)> {
+    pattern
+        .captures_iter(text)
+        .filter_map(|caps| caps.get(1).or(caps.get(0)))
+        .filter(|m| !m.is_empty())
+        .map(|m| (m.range(), m.as_str().to_string()))
+        .collect()
+}
+
+pub fn url(id: &str) -> Option<String> {
+    config::get_str("issues.url").map(|template| template.replace(URL_PLACEHOLDER, id))
+}
+
+//  Highlight the issue IDs in a line of a commit message.  When an issue
+//  URL is configured and the output is a terminal, each ID is also made
+//  into a hyperlink using the OSC 8 escape sequence.
+pub fn decorate(line: &str) -> String {
+    let pattern = match configured_pattern() {
+        Some(pattern) if colored::control::SHOULD_COLORIZE.should_colorize() => pattern,
+        _ => return line.to_string(),
+    };
+    let hyperlinks = std::io::stdout().is_terminal() || pager::is_paging();
+    let mut out = String::new();
+    let mut last = 0;
+    for (range, id) in find(pattern, line) {
+        out.push_str(&line[last..range.start]);
+        let text = id.bright_blue().underline().to_string();
+        match url(&id) {
+            Some(url) if hyperlinks => out.push_str(&format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)),
+            _ => out.push_str(&text),
+        }
+        last = range.end;
+    }
+    out.push_str(&line[last..]);
+    out
+}
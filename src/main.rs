@@ -1,22 +1,70 @@
 
 use clap::Parser;
 use std::process;
-use crate::app::{Commands, Run};
+use std::time::Instant;
+use crate::app::{App, Run};
+use crate::util::SvError;
 
 mod app;
 mod util;
 mod commands;
 mod svn;
 mod auth;
+mod audit;
+mod impact;
+mod hints;
+mod migrate;
+mod transcript;
+mod interrupt;
+mod profile;
+mod highlight;
+mod hooks;
+mod config;
+mod pager;
+mod tui;
+mod issues;
+mod sha1;
 
 
 fn main() {
-    match Commands::parse().run() {
+    let args: Vec<String> = std::env::args().collect();
+    let start = Instant::now();
+    interrupt::install();
+    let mut app = App::parse();
+    if let Some(path) = app.transcript() {
+        process::exit(transcript::record(path, &args));
+    }
+    if app.is_pageable() {
+        if let Some(status) = pager::page(&args) {
+            process::exit(status);
+        }
+    }
+    match app.run() {
         Ok(_) => {
+            hints::show_slow_hint(&args, start.elapsed());
+            audit::record(&args, 0);
             process::exit(0);
         }
         Err(e) => {
+            if let Some(SvError::Exit(code)) = e.downcast_ref::<SvError>() {
+                audit::record(&args, *code);
+                process::exit(*code);
+            }
+            if let Some(SvError::Interrupted) = e.downcast_ref::<SvError>() {
+                if !interrupt::reported() {
+                    eprintln!("\nInterrupted");
+                }
+                audit::record(&args, interrupt::EXIT_STATUS);
+                process::exit(interrupt::EXIT_STATUS);
+            }
+            if svn::is_format_too_old(&e) {
+                commands::doctor::offer_upgrade();
+                audit::record(&args, 1);
+                process::exit(1);
+            }
             eprintln!("{:?}", e);
+            let _ = svn::save_last_failure();
+            audit::record(&args, 1);
             process::exit(1);
         }
     }
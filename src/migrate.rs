@@ -0,0 +1,95 @@
+use anyhow::Result;
+use colored::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs::{copy, File};
+use std::path::{Path, PathBuf};
+use crate::svn;
+use crate::util;
+
+//  Data files written by earlier versions (including the .sv files of
+//  the original sv program) may use different field names or contain
+//  fields that are no longer used, such as `localPath` in the bisect data.
+//  The serde aliases on our structures allow these files to be read.
+//
+//  Files are upgraded in memory when they are loaded, so that reading a
+//  file never changes it.  The file is upgraded on disk the next time it
+//  is saved with save_json(): a backup copy of the original is saved with
+//  a .bak extension and the file is written in the current schema.
+
+//  The field names used by earlier versions.  Only these are reported,
+//  since optional fields are left out of the current files when unset.
+const LEGACY_FIELDS: [&str; 13] = [
+    //  Bisect data
+    "localPath", "original_rev", "head_rev", "first_rev", "max_rev", "min_rev",
+    "term_good", "term_bad",
+    //  Stash entries
+    "is_dir", "patch_name",
+    //  Prefixes
+    "trunk_prefix", "branch_prefixes", "tag_prefixes",
+];
+
+pub fn load_json<T>(path: &Path) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    Ok(serde_json::from_reader(File::open(path)?)?)
+}
+
+//  Save a data file that is read with load_json(), first saving a backup
+//  of the existing file if it was written in an earlier format.
+pub fn save_json<T>(path: &Path, data: &T) -> Result<()>
+where
+    T: Serialize + ?Sized,
+{
+    svn::ensure_writable(&format!("write {}", path.to_string_lossy()))?;
+    let legacy = match File::open(path) {
+        Ok(file) => {
+            let original: Value = serde_json::from_reader(file)?;
+            let mut legacy = BTreeSet::new();
+            legacy_fields(&original, &mut legacy);
+            legacy.into_iter().map(|f| f.to_string()).collect::<Vec<String>>()
+        }
+        Err(_) => Vec::new(),
+    };
+    if !legacy.is_empty() {
+        let backup = backup_path(path);
+        copy(path, &backup)?;
+        eprintln!(
+            "{} Upgraded {} to the current format (obsolete fields: {})",
+            "warning:".yellow(),
+            path.to_string_lossy(),
+            legacy.join(", ")
+        );
+        eprintln!("         The original file was saved to {}", backup.to_string_lossy());
+    }
+    util::save_json(path, data)
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+//  Collect the names of the legacy object fields in the original json.
+fn legacy_fields<'a>(original: &'a Value, legacy: &mut BTreeSet<&'a str>) {
+    match original {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                if LEGACY_FIELDS.contains(&key.as_str()) {
+                    legacy.insert(key.as_str());
+                }
+                legacy_fields(value, legacy);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                legacy_fields(value, legacy);
+            }
+        }
+        _ => (),
+    }
+}
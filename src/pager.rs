@@ -0,0 +1,90 @@
+use anyhow::Result;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process::{Command, Stdio};
+use crate::config;
+
+//  When a pager is configured with core.pager (or SVU_PAGER) and the
+//  output is a terminal, commands that produce long output run svu as
+//  a child process whose output is piped to the pager.
+//
+//  SVU_PAGER_CHILD is set in the child so that it runs normally.
+
+const CHILD_VAR: &str = "SVU_PAGER_CHILD";
+
+//  Run the command with its output sent to the pager.
+//  Returns the exit status of the command or None if the
+//  command should be run without a pager.
+pub fn page(args: &[String]) -> Option<i32> {
+    if env::var(CHILD_VAR).is_ok() {
+        reset_sigpipe();
+        return None;
+    }
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    //  Errors in the config files are reported when the command runs.
+    let _ = config::load();
+    let pager = env::var("SVU_PAGER").ok()
+        .or(config::get_str("core.pager").map(|p| p.to_string()))
+        .filter(|p| !p.trim().is_empty())?;
+
+    match run(&pager, args) {
+        Ok(status) => Some(status),
+        Err(e) => {
+            eprintln!("Cannot run the pager '{}': {}", pager, e);
+            None
+        }
+    }
+}
+
+//  True in the child whose output is sent to the pager.
+pub fn is_paging() -> bool {
+    env::var(CHILD_VAR).is_ok()
+}
+
+fn run(pager: &str, args: &[String]) -> Result<i32> {
+    let mut words = pager.split_whitespace();
+    let mut pager_cmd = Command::new(words.next().unwrap_or_default());
+    pager_cmd.args(words).stdin(Stdio::piped());
+    //  Let less pass the colors through and quit if the output fits on the screen.
+    if env::var("LESS").is_err() {
+        pager_cmd.env("LESS", "FRX");
+    }
+    let mut pager = pager_cmd.spawn()?;
+    let pager_input = pager.stdin.take().unwrap();
+
+    //  The command is dropped as soon as the child starts so that the
+    //  pager sees the end of its input when the child finishes.
+    let child = Command::new(env::current_exe()?)
+        .args(&args[1..])
+        .env(CHILD_VAR, "1")
+        .env("CLICOLOR_FORCE", "1")
+        .stdout(Stdio::from(pager_input))
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = pager.wait();
+            return Err(e.into());
+        }
+    };
+    crate::interrupt::child_started(child.id());
+    let status = child.wait();
+    crate::interrupt::child_finished(child.id());
+    let status = status?.code().unwrap_or(1);
+
+    //  Wait for the user to quit the pager.
+    pager.wait()?;
+    Ok(status)
+}
+
+//  The child exits quietly when the user quits the pager
+//  before reading all of the output.
+#[cfg(unix)]
+fn reset_sigpipe() {
+    unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) };
+}
+
+#[cfg(not(unix))]
+fn reset_sigpipe() {}
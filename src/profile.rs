@@ -0,0 +1,174 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use crate::migrate;
+use crate::svn;
+use crate::util::SvError::*;
+
+//  Profiles let one user work with several svn servers without their
+//  credentials and settings leaking from one to another.  They are kept
+//  in the user's profiles file (see profiles_file()):
+//
+//  {
+//    "profiles": {
+//      "acme": {
+//        "username": "jdoe",
+//        "passwordEnv": "ACME_SVN_PASSWORD",
+//        "configDir": "~/.subversion-acme",
+//        "httpProxy": "proxy.acme.com:3128",
+//        "repoUrl": "https://svn.acme.com/repos"
+//      }
+//    },
+//    "directories": {
+//      "~/work/acme": "acme"
+//    }
+//  }
+//
+//  The profile is chosen by --profile (or SVU_PROFILE), otherwise by the
+//  longest entry in "directories" that contains the current directory,
+//  otherwise by the repoUrl that contains the repository of the working copy.
+//  Options given on the command line or in the environment take precedence
+//  over the settings of the profile.
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    //  The username used to access the repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    //  The environment variable that holds the password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<String>,
+    //  The svn configuration directory, which includes its credentials cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_proxy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_proxy_exceptions: Option<String>,
+    //  Working copies of repositories beneath this URL use the profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+}
+
+impl Profile {
+    //  The password taken from the profile's environment variable.
+    pub fn password(&self) -> Option<String> {
+        self.password_env.as_ref().and_then(|var| env::var(var).ok())
+    }
+
+    pub fn config_dir_path(&self) -> Option<PathBuf> {
+        self.config_dir.as_deref().map(expand_home)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    #[serde(default)]
+    pub directories: BTreeMap<String, String>,
+}
+
+//  The svu directory in the user's configuration directory.
+pub fn user_config_dir() -> Option<PathBuf> {
+    let config_home = if cfg!(windows) {
+        env::var("APPDATA").ok().map(PathBuf::from)
+    } else {
+        env::var("XDG_CONFIG_HOME").ok().map(PathBuf::from)
+            .or_else(|| env::var("HOME").ok().map(|home| PathBuf::from(home).join(".config")))
+    };
+    config_home.map(|dir| dir.join("svu"))
+}
+
+//  SVU_PROFILES may give the location of the profiles file.
+//  Otherwise it is svu/profiles.json in the user's configuration directory.
+pub fn profiles_file() -> Option<PathBuf> {
+    if let Ok(path) = env::var("SVU_PROFILES") {
+        return Some(PathBuf::from(path));
+    }
+    user_config_dir().map(|dir| dir.join("profiles.json"))
+}
+
+pub fn load_profiles() -> Result<Profiles> {
+    match profiles_file() {
+        Some(path) if path.is_file() => migrate::load_json(&path),
+        _ => Ok(Profiles::default()),
+    }
+}
+
+//  Replace a leading ~ with the user's home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), env::var("HOME").or(env::var("USERPROFILE"))) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(path),
+    }
+}
+
+//  Return the name and settings of the profile to use, if any.
+pub fn select(name: Option<&str>) -> Result<Option<(String, Profile)>> {
+    let mut profiles = load_profiles()?;
+    if let Some(name) = name {
+        return match profiles.profiles.remove(name) {
+            Some(profile) => Ok(Some((name.to_string(), profile))),
+            None => {
+                let location = profiles_file().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+                Err(General(format!("Profile '{}' is not defined in {}", name, location)).into())
+            }
+        };
+    }
+    if profiles.profiles.is_empty() {
+        return Ok(None);
+    }
+
+    let by_directory = env::current_dir().ok().and_then(|cwd| directory_profile(&profiles, &cwd));
+    let name = match by_directory {
+        Some(name) => Some(name),
+        None => repository_profile(&profiles),
+    };
+    match name {
+        Some(name) => match profiles.profiles.remove(&name) {
+            Some(profile) => Ok(Some((name, profile))),
+            None => Err(General(format!("The directory mapping refers to an undefined profile '{}'", name)).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+//  The profile mapped to the longest directory containing `cwd`.
+fn directory_profile(profiles: &Profiles, cwd: &Path) -> Option<String> {
+    profiles.directories
+        .iter()
+        .map(|(dir, name)| (expand_home(dir), name))
+        .filter(|(dir, _)| cwd.starts_with(dir))
+        .max_by_key(|(dir, _)| dir.components().count())
+        .map(|(_, name)| name.clone())
+}
+
+//  The profile whose repoUrl contains the repository of the working copy.
+fn repository_profile(profiles: &Profiles) -> Option<String> {
+    if profiles.profiles.values().all(|p| p.repo_url.is_none()) {
+        return None;
+    }
+    let output = svn::SvnCmd::new("info")
+        .arg("--show-item=repos-root-url")
+        .arg(".")
+        .run()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root_url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    profiles.profiles
+        .iter()
+        .filter_map(|(name, p)| p.repo_url.as_ref().map(|url| (name, url.trim_end_matches('/'))))
+        .filter(|(_, url)| {
+            //  The repoUrl may be the repository root, a parent of it
+            //  or a location within it.
+            root_url == *url || root_url.starts_with(&format!("{}/", url)) || url.starts_with(&format!("{}/", root_url))
+        })
+        .max_by_key(|(_, url)| url.len())
+        .map(|(name, _)| name.clone())
+}
@@ -1,16 +1,19 @@
 
 use std::env;
 use std::io::Write;
-use std::sync::OnceLock;
-use std::process::{Command, Output};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use std::process::{Command, Output, Stdio};
 use std::path::{Path, PathBuf};
 use std::fs::File;
+use std::collections::BTreeMap;
 use chrono::{DateTime, Local};
 use roxmltree::{Document, Node};
 use anyhow::Result;
 use crate::auth::Credentials;
+use crate::config;
 use crate::util::SvError::*;
-use crate::util::{parse_svn_date_opt, null_date, data_directory};
+use crate::util::{parse_svn_date, parse_svn_date_opt, null_date, data_directory, scrub_secrets, datetime_serializer, join_paths, save_json};
 use regex::Regex;
 use std::fmt::Display;
 use serde::{Deserialize, Serialize};
@@ -20,11 +23,156 @@ fn svn_cmd() -> &'static String {
     static SVN_CMD: OnceLock<String> = OnceLock::new();
     SVN_CMD.get_or_init(|| {
         env::var("SVU_SVN")
-            .map(|s| s.clone())
             .unwrap_or("svn".to_string())
     })
 }
 
+//  Options that are passed to every svn command that we run.
+//  These are set once from the global command line options.
+#[derive(Debug, Clone, Default)]
+pub struct SvnOptions {
+    pub trust_server_cert_failures: Option<String>,
+    pub config_dir: Option<PathBuf>,
+    pub config_options: Vec<String>,
+    pub read_only: bool,
+}
+
+//  The svn subcommands that modify the working copy or the repository.
+//  These are refused when svu is running in read-only mode,
+//  except for a merge or patch that is only a dry run.
+const MODIFYING_COMMANDS: [&str; 23] = [
+    "add", "changelist", "cleanup", "commit", "copy", "delete", "import",
+    "lock", "merge", "mkdir", "move", "patch", "propdel", "propedit", "propset",
+    "relocate", "resolve", "resolved", "revert", "switch", "unlock", "update",
+    "upgrade",
+];
+
+impl SvnOptions {
+    fn args(&self) -> Vec<String> {
+        let mut args = Vec::<String>::new();
+        if let Some(failures) = &self.trust_server_cert_failures {
+            //  svn only honors this option in non-interactive mode
+            args.push("--non-interactive".to_string());
+            args.push(format!("--trust-server-cert-failures={}", failures));
+        }
+        if let Some(dir) = &self.config_dir {
+            args.push(format!("--config-dir={}", dir.to_string_lossy()));
+        }
+        for option in &self.config_options {
+            args.push(format!("--config-option={}", option));
+        }
+        args
+    }
+}
+
+fn svn_options() -> &'static OnceLock<SvnOptions> {
+    static SVN_OPTIONS: OnceLock<SvnOptions> = OnceLock::new();
+    &SVN_OPTIONS
+}
+
+pub fn set_options(options: SvnOptions) {
+    let _ = svn_options().set(options);
+}
+
+//  The svn configuration directory if one was given.
+pub fn config_dir() -> Option<PathBuf> {
+    svn_options().get().and_then(|o| o.config_dir.clone())
+}
+
+pub fn is_read_only() -> bool {
+    svn_options().get().map(|o| o.read_only).unwrap_or(false)
+}
+
+//  Return an error if svu is running in read-only mode.
+//  Used by commands that modify the working copy directly
+//  before they run any svn commands.
+pub fn ensure_writable(action: &str) -> Result<()> {
+    if is_read_only() {
+        let msg = format!("Cannot {} while running in read-only mode", action);
+        Err(General(msg).into())
+    } else {
+        Ok(())
+    }
+}
+
+//  Details of the most recent svn command that failed.
+//  This is saved when svu exits with an error so that it
+//  can be included in a bug report.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FailedCommand {
+    #[serde(with = "datetime_serializer")]
+    pub date: DateTime<Local>,
+    pub command: Vec<String>,
+    pub cwd: String,
+    #[serde(rename(serialize = "exitStatus", deserialize = "exitStatus"))]
+    pub exit_status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+fn last_failure() -> &'static Mutex<Option<FailedCommand>> {
+    static LAST_FAILURE: Mutex<Option<FailedCommand>> = Mutex::new(None);
+    &LAST_FAILURE
+}
+
+fn last_failure_file() -> Result<PathBuf> {
+    data_directory().map(|dir| dir.join("last_failure.json"))
+}
+
+//  Called when svu exits with an error.
+//  The last failed svn command (if any) is saved in the .svu directory
+//  or in the temp directory if we are not in a working copy.
+pub fn save_last_failure() -> Result<()> {
+    let failure = last_failure().lock().ok().and_then(|mut f| f.take());
+    if let Some(failure) = failure {
+        let path = last_failure_file()
+            .unwrap_or(env::temp_dir().join("svu_last_failure.json"));
+        let writer = File::create(path)?;
+        serde_json::to_writer_pretty(writer, &failure)?;
+    }
+    Ok(())
+}
+
+//  Load the last failed svn command that was saved in the working copy
+//  or the temp directory.
+pub fn load_last_failure() -> Result<Option<FailedCommand>> {
+    let paths = [last_failure_file().ok(), Some(env::temp_dir().join("svu_last_failure.json"))];
+    let mut failures = Vec::<FailedCommand>::new();
+    for path in paths.into_iter().flatten() {
+        if path.is_file() {
+            let reader = File::open(path)?;
+            failures.push(serde_json::from_reader(reader)?);
+        }
+    }
+    Ok(failures.into_iter().max_by_key(|f| f.date))
+}
+
+//  The number of times each svn subcommand was run
+//  and the total time spent running it.
+#[derive(Debug, Clone, Default)]
+pub struct SvnTiming {
+    pub count: u32,
+    pub elapsed: Duration,
+}
+
+fn timings() -> &'static Mutex<BTreeMap<String, SvnTiming>> {
+    static TIMINGS: Mutex<BTreeMap<String, SvnTiming>> = Mutex::new(BTreeMap::new());
+    &TIMINGS
+}
+
+fn record_timing(name: &str, elapsed: Duration) {
+    if let Ok(mut timings) = timings().lock() {
+        let timing = timings.entry(name.to_string()).or_default();
+        timing.count += 1;
+        timing.elapsed += elapsed;
+    }
+}
+
+//  Return the timing of each svn subcommand run so far.
+pub fn svn_timings() -> BTreeMap<String, SvnTiming> {
+    timings().lock().map(|t| t.clone()).unwrap_or_default()
+}
+
 #[derive(Debug, Clone)]
 pub struct FromPath {
     pub path: String,
@@ -36,7 +184,9 @@ pub struct LogPath {
     pub path: String,
     pub kind: String,
     pub action: String,
+    #[allow(dead_code)]
     pub text_mods: bool,
+    #[allow(dead_code)]
     pub prop_mods: bool,
     pub from_path: Option<FromPath>,
 }
@@ -74,6 +224,8 @@ pub struct SvnInfo {
     pub commit_author: String,
     pub commit_date: DateTime<Local>,
     pub wc_path: Option<String>,
+    //  SHA-1 of the pristine copy of a working copy file.
+    pub checksum: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +240,7 @@ pub struct ListEntry {
 
 #[derive(Debug, Clone)]
 pub struct SvnList {
+    #[allow(dead_code)]
     pub path: String,
     pub entries: Vec<ListEntry>
 }
@@ -98,6 +251,9 @@ pub struct StatusEntry {
     pub item_status: String,
     pub props_status: String,
     pub revision: String,
+    pub changelist: Option<String>,
+    //  True when this working copy holds a lock on the item.
+    pub locked: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -134,6 +290,16 @@ impl SvnCmd {
     }
 
 
+    pub fn with_changelists<S>(&mut self, changelists: &[S]) -> &mut Self
+    where
+        S: AsRef<str> + Display,
+    {
+        for changelist in changelists {
+            self.arg(format!("--changelist={}", changelist));
+        }
+        self
+    }
+
     pub fn with_creds(&mut self, creds: &Option<Credentials>) -> &mut Self {
         if let Some(Credentials(username, password)) = creds {
             self.arg(format!("--username={}", username));
@@ -181,14 +347,62 @@ impl SvnCmd {
     }
 
     pub fn run(&mut self) -> Result<Output> {
+        if crate::interrupt::interrupted() {
+            return Err(Interrupted.into());
+        }
+        let dry_run = matches!(self.name.as_str(), "merge" | "patch") && self.args.iter().any(|a| a == "--dry-run");
+        if MODIFYING_COMMANDS.contains(&self.name.as_str()) && !dry_run {
+            ensure_writable(&format!("run 'svn {}'", self.name))?;
+        }
         let mut cmd = Command::new(svn_cmd());
         if let Some(dir) = &self.cwd {
             cmd.current_dir(dir);
         }
         cmd.arg(&self.name);
+        if let Some(options) = svn_options().get() {
+            cmd.args(options.args());
+        }
         cmd.args(&self.args);
 
-        Ok(cmd.output()?)
+        cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let start = Instant::now();
+        let child = cmd.spawn()?;
+        let pid = child.id();
+        crate::interrupt::child_started(pid);
+        let output = child.wait_with_output();
+        crate::interrupt::child_finished(pid);
+        let output = output?;
+        record_timing(&self.name, start.elapsed());
+        if crate::interrupt::interrupted() {
+            return Err(Interrupted.into());
+        }
+        if !output.status.success() {
+            self.record_failure(&cmd, &output);
+        }
+        Ok(output)
+    }
+
+    fn record_failure(&self, cmd: &Command, output: &Output) {
+        let command = std::iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|a| scrub_secrets(&a.to_string_lossy()))
+            .collect();
+        let cwd = match &self.cwd {
+            Some(dir) => dir.to_string_lossy().to_string(),
+            None => env::current_dir().map(|d| d.to_string_lossy().to_string()).unwrap_or_default(),
+        };
+        let failure = FailedCommand {
+            date: Local::now(),
+            command,
+            cwd,
+            exit_status: output.status.code(),
+            stdout: scrub_secrets(&String::from_utf8_lossy(&output.stdout)),
+            stderr: scrub_secrets(&String::from_utf8_lossy(&output.stderr)),
+        };
+        if let Ok(mut last) = last_failure().lock() {
+            *last = Some(failure);
+        }
     }
 }
 
@@ -230,26 +444,94 @@ fn get_child_text_or(parent: &Node, name: &str, default: &str) -> String {
 }
 
 
+//  A revision bookmark name.  Hyphens are allowed within the name
+//  as long as they are not followed by a digit so that @name-2 is
+//  treated as a delta from the bookmark.
+const MARK_NAME: &str = r"[A-Za-z][_.A-Za-z0-9]*(?:-[A-Za-z][_.A-Za-z0-9]*)*";
+
 //  Regular expression for validating svn REVISION arguments
-//  We allow revisions such as HEAD-1
+//  We allow revisions such as HEAD-1 and bookmarks such as @name
 fn rev_re() -> &'static Regex {
     static REV: OnceLock<Regex> = OnceLock::new();
     REV.get_or_init(|| {
-        Regex::new(r"^(\d+|HEAD|BASE|PREV|COMMITTED)([+-]\d+)?$")
+        Regex::new(&format!(r"^(\d+|HEAD|BASE|PREV|COMMITTED|@{})([+-]\d+)?$", MARK_NAME))
             .expect("Error parsing REV regular expression")
     })
 }
 
+pub fn is_valid_mark_name(name: &str) -> bool {
+    static MARK: OnceLock<Regex> = OnceLock::new();
+    MARK.get_or_init(|| {
+        Regex::new(&format!("^{}$", MARK_NAME)).expect("Error parsing MARK regular expression")
+    })
+    .is_match(name)
+}
+
 pub fn looks_like_revision(text: &str) -> bool {
     rev_re().is_match(text)
 }
 
+//  Regular expression for the peg revisions understood by svn itself.
+fn svn_peg_re() -> &'static Regex {
+    static PEG: OnceLock<Regex> = OnceLock::new();
+    PEG.get_or_init(|| {
+        Regex::new(r"^(?:\d+|HEAD|BASE|PREV|COMMITTED|\{[^}]+\})$")
+            .expect("Error parsing PEG regular expression")
+    })
+}
+
+//  Split a path into the path and its peg revision.
+//  As with svn, only the last '@' introduces a peg revision and it must
+//  follow the last '/' so that the user name in a URL such as
+//  svn+ssh://user@host/repo is not mistaken for one.
+//  A bookmark may be used as a peg by doubling the '@'. eg. path@@name
+//  An empty peg, path@ or path@@, means there is no peg revision.
+pub fn split_peg(path: &str) -> (&str, Option<&str>) {
+    //  The start of the last path component.  The host of a URL
+    //  with no path is not a component.
+    let start = match path.find("://") {
+        Some(scheme_end) if !path[scheme_end + 3..].contains('/') => path.len(),
+        _ => path.rfind('/').map(|i| i + 1).unwrap_or(0),
+    };
+    match path[start..].rfind('@').map(|i| i + start) {
+        Some(i) if i == path.len() - 1 => {
+            let base = &path[..i];
+            (base.strip_suffix('@').filter(|b| b.len() > start).unwrap_or(base), None)
+        }
+        Some(i) if i > start + 1 && path[..i].ends_with('@') && is_valid_mark_name(&path[i + 1..]) => {
+            (&path[..i - 1], Some(&path[i..]))
+        }
+        Some(i) if i > start => (&path[..i], Some(&path[i + 1..])),
+        _ => (path, None),
+    }
+}
+
+//  Validate the peg revision of a path given on the command line.
+//  Peg revisions may use the same expressions as other revisions,
+//  such as HEAD-3, and these are resolved to a revision number
+//  so that the path can be passed through to svn.
+pub fn resolve_peg_path(creds: &Option<Credentials>, path: &str) -> Result<String> {
+    match split_peg(path) {
+        (_, None) | (_, Some("")) => Ok(path.to_string()),
+        (_, Some(peg)) if svn_peg_re().is_match(peg) => Ok(path.to_string()),
+        (base, Some(peg)) if rev_re().is_match(peg) => {
+            let rev = resolve_revision(creds, peg, &peg_path(base, None))?;
+            Ok(peg_path(base, Some(&rev)))
+        }
+        (_, Some(peg)) => {
+            let msg = format!("Invalid peg revision '{}' in path '{}'", peg, path);
+            Err(General(msg).into())
+        }
+    }
+}
+
 //  Regular expression for validating REVISION ranges
 //  such as HEAD:HEAD-5
 fn rev_range_re() -> &'static Regex {
     static REV: OnceLock<Regex> = OnceLock::new();
     REV.get_or_init(|| {
-        Regex::new(r"^(?:(?:\d+|HEAD|BASE|PREV|COMMITTED)(?:[+-]\d+)?)(?::(?:\d+|HEAD|BASE|PREV|COMMITTED)(?:[+-]\d+)?)?$")
+        let rev = format!(r"(?:\d+|HEAD|BASE|PREV|COMMITTED|@{})(?:[+-]\d+)?", MARK_NAME);
+        Regex::new(&format!("^{}(?::{})?$", rev, rev))
                 .expect("Error parsing REV regular expression")
     })
 }
@@ -269,9 +551,16 @@ fn get_revision_number(
      delta: i32,
      path: &str,
 ) -> Result<String> {
+    //  The history of a path with a peg revision ends at the peg
+    //  so HEAD refers to the peg revision.
+    let head = match split_peg(path) {
+        (_, Some(peg)) if !peg.is_empty() => peg,
+        _ => "HEAD",
+    };
+    let rev = if rev == "HEAD" { head } else { rev };
     let rev_str = match delta {
         d if d <= 0 => format!("{}:0", rev),
-        _ => format!("{}:HEAD", rev),
+        _ => format!("{}:{}", rev, head),
     };
     let limit = Some(delta.unsigned_abs() + 1);
     let entries = log(creds, &[path], &[&rev_str], false, limit, false, false)?;
@@ -285,12 +574,30 @@ fn get_revision_number(
 }
 
 pub fn resolve_revision(creds: &Option<Credentials>, rev_string: &str, path: &str) -> Result<String> {
+    let resolved = resolve_revision_string(creds, rev_string, path)?;
+    crate::audit::note_revision(rev_string, &resolved);
+    Ok(resolved)
+}
+
+fn resolve_revision_string(creds: &Option<Credentials>, rev_string: &str, path: &str) -> Result<String> {
     fn err(r: &str, d: &str, p: &str) -> Result<String> {
         let msg = format!("Cannot resolve revision '{}{}' for path '{}'", r, d, p);
         Err(General(msg).into())
     }
     match rev_re().captures(rev_string) {
         None => err(rev_string, "", path),
+        Some(caps) if caps[1].starts_with('@') => {
+            let name = &caps[1][1..];
+            let mark = load_marks()?
+                .remove(name)
+                .ok_or(General(format!("Revision bookmark '{}' does not exist", name)))?;
+            let delta = match caps.get(2) {
+                Some(delta) => delta.as_str().parse::<i32>()?,
+                None        => 0,
+            };
+            get_revision_number(creds, &mark.revision, delta, path)
+                .or(err(rev_string, "", path))
+        }
         Some(caps) => match (caps.get(1), caps.get(2)) {
             (Some(rev), None) => {
                 get_revision_number(creds, rev.as_str(), 0, path).or(err(rev.as_str(), "", path))
@@ -315,10 +622,10 @@ pub fn resolve_revision_range(
     path: &str,
 ) -> Result<String> {
     let parts: Vec<&str> = rev_string.split(':').collect();
-    let re = Regex::new(r"[-+]")?;
     match parts.len() {
         1 => resolve_revision(creds, parts[0], path),
         2 => {
+            let re = Regex::new(r"[-+@]")?;
             let a = if re.is_match(parts[0]) {
                 resolve_revision(creds, parts[0], path)?
             } else {
@@ -385,6 +692,7 @@ fn parse_svn_info(text: &str) -> Result<Vec<SvnInfo>> {
             commit_author,
             commit_date,
             wc_path: wc_info.map(|x| get_child_text_or(&x, "wcroot-abspath", "n/a")),
+            checksum: wc_info.and_then(|x| get_child_text(&x, "checksum")),
         };
         entries.push(entry);
     }
@@ -435,6 +743,28 @@ where
     }
 }
 
+//  Return the info of a working copy path and everything beneath it.
+pub fn info_recursive(path: &str) -> Result<Vec<SvnInfo>> {
+    info_depth(path, "infinity")
+}
+
+//  Return the info of a working copy path and the items
+//  beneath it down to the given depth.
+pub fn info_depth(path: &str, depth: &str) -> Result<Vec<SvnInfo>> {
+    let output = SvnCmd::new("info")
+        .arg("--xml")
+        .arg(format!("--depth={}", depth))
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        parse_svn_info(&text)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
 fn get_log_entry_paths(log_entry: &Node) -> Vec<LogPath> {
     let mut paths: Vec<LogPath> = vec![];
     for path_node in log_entry.descendants().filter(|n| n.has_tag_name("path")) {
@@ -512,6 +842,53 @@ where
     }
 }
 
+//  A commit that merged revisions from elsewhere in the repository.
+#[derive(Debug, Clone)]
+pub struct MergeCommit {
+    pub revision: String,
+    //  The paths changed by the revisions that were merged.
+    pub merged_paths: Vec<String>,
+}
+
+//  Return the commits on the path (stopping on copy) that merged other revisions.
+//  With --use-merge-history the merged revisions are nested within
+//  the log entry of the commit that merged them.
+pub fn merge_commits(creds: &Option<Credentials>, path: &str) -> Result<Vec<MergeCommit>> {
+    let output = SvnCmd::new("log")
+        .with_creds(creds)
+        .arg("--xml")
+        .arg("--quiet")
+        .arg("--verbose")
+        .arg("--stop-on-copy")
+        .arg("--use-merge-history")
+        .arg(path)
+        .run()?;
+
+    if !output.status.success() {
+        return Err(SvnError(output).into());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let doc = Document::parse(&text)?;
+    let mut commits = vec![];
+    for log_entry in doc.root_element().children().filter(|n| n.has_tag_name("logentry")) {
+        let mut merged_paths: Vec<String> = vec![];
+        for merged in log_entry.descendants().filter(|n| n.has_tag_name("logentry") && *n != log_entry) {
+            for path_node in merged.children().filter(|n| n.has_tag_name("paths")).flat_map(|n| n.children()) {
+                if path_node.has_tag_name("path") {
+                    let path = get_text(&path_node);
+                    if !merged_paths.contains(&path) {
+                        merged_paths.push(path);
+                    }
+                }
+            }
+        }
+        if !merged_paths.is_empty() {
+            commits.push(MergeCommit { revision: get_attr(&log_entry, "revision"), merged_paths });
+        }
+    }
+    Ok(commits)
+}
+
 fn parse_svn_list(text: &str) -> Result<Vec<SvnList>> {
     let mut path_lists = vec![];
     let doc = Document::parse(text)?;
@@ -578,184 +955,1287 @@ pub fn path_list(creds: &Option<Credentials>, path: &str) -> Result<SvnList> {
     Ok(list.remove(0))
 }
 
-pub fn change_diff(creds: &Option<Credentials>, path: &str, commit_rev: &str) -> Result<Vec<String>> {
-
-    let output = SvnCmd::new("diff")
+//  Get a recursive svn list for a single path.
+//  The names of the entries are relative to the path.
+pub fn path_list_recursive(creds: &Option<Credentials>, path: &str, revision: Option<&str>) -> Result<SvnList> {
+    let output = SvnCmd::new("list")
         .with_creds(creds)
-        .arg("--change")
-        .arg(commit_rev)
-        .arg(path)
+        .arg("--xml")
+        .arg("--recursive")
+        .arg(peg_path(path, revision))
         .run()?;
 
     if output.status.success() {
         let text = String::from_utf8_lossy(&output.stdout);
-        Ok(text.split('\n').map(|l| l.to_string()).collect())
-    }
-    else {
+        let mut list = parse_svn_list(&text)?;
+        Ok(list.remove(0))
+    } else {
         Err(SvnError(output).into())
     }
 }
 
-fn prefixes_file() -> Result<PathBuf> {
-    data_directory().map(|dir| dir.join("prefixes.json"))
-}
-#[derive(Serialize, Deserialize)]
-pub struct Prefixes {
-    #[serde(rename(serialize = "trunkPrefix", deserialize = "trunkPrefix"))]
-    pub trunk_prefix: String,
-    #[serde(rename(serialize = "branchPrefixes", deserialize = "branchPrefixes"))]
-    pub branch_prefixes: Vec<String>,
-    #[serde(rename(serialize = "tagPrefixes", deserialize = "tagPrefixes"))]
-    pub tag_prefixes: Vec<String>
-}
+//  Get the contents of a file.
+pub fn cat(creds: &Option<Credentials>, path: &str, revision: Option<&str>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("cat")
+        .with_creds(creds)
+        .arg(peg_path(path, revision))
+        .run()?;
 
-pub fn load_prefixes() -> Result<Prefixes> {
-    let path = prefixes_file()?;
-    if path.is_file() {
-        let reader = File::open(path)?;
-        let prefixes: Prefixes = serde_json::from_reader(reader)?;
-        Ok(prefixes)
+    if output.status.success() {
+        Ok(output.stdout)
     } else {
-        //  Return the defaults
-        Ok(Prefixes {
-            trunk_prefix: "trunk".to_string(),
-            branch_prefixes: vec!["branches".to_string()],
-            tag_prefixes: vec!["tags".to_string()],
-        })
+        Err(SvnError(output).into())
     }
 }
 
-pub fn save_prefixes(prefixes: &Prefixes) -> Result<()> {
-    let writer = File::create(prefixes_file()?)?;
-    Ok(serde_json::to_writer_pretty(writer, prefixes)?)
+//  Export a directory from the repository to a new local directory.
+pub fn export(creds: &Option<Credentials>, url: &str, revision: Option<&str>, dest: &Path) -> Result<()> {
+    let output = SvnCmd::new("export")
+        .with_creds(creds)
+        .arg("--quiet")
+        .arg(peg_path(url, revision))
+        .arg(dest.to_string_lossy())
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
 }
 
-//  Verify that the current working directory is within
-//  a subversion working copy.
-//  Returns the info for the current directory or
-//  and Error if not withing a working copy.
-pub fn workingcopy_info() -> Result<SvnInfo> {
-    info(&None, ".", None).map_err(|_| {
-        General("This command must be run in a serversion working copy directory.".to_string())
-        .into()
-    })
+//  A single line of svn blame output.
+//  Lines that have been modified locally have no commit.
+#[derive(Debug, Clone)]
+pub struct BlameLine {
+    pub line_number: usize,
+    pub revision: Option<String>,
+    pub author: Option<String>,
+    pub date: Option<DateTime<Local>>,
 }
 
-fn parse_svn_status(text: &str) -> Result<SvnStatus> {
-    let mut entries: Vec<StatusEntry> = vec![];
+fn parse_svn_blame(text: &str) -> Result<Vec<BlameLine>> {
+    let mut lines = vec![];
     let doc = Document::parse(text)?;
-    if let Some(target) = doc.descendants().find(|n| n.has_tag_name("target")) {
-        for entry_node in target.children() {
-            if let Some(wc_node) = get_child(&entry_node, "wc-status") {
-                let revision = get_attr(&wc_node, "revision");
-                entries.push(StatusEntry {
-                    path: get_attr(&entry_node, "path"),
-                    item_status: get_attr(&wc_node,    "item"),
-                    props_status: get_attr(&wc_node,    "props"),
-                    revision,
-                });
-            }
-        }
-        let path = get_attr(&target, "path");
-        Ok(SvnStatus{ path, entries })
-    } else {
-        Err(General("Malformed svn status".to_string()).into())
+    for entry in doc.descendants().filter(|n| n.has_tag_name("entry")) {
+        let commit = entry.children().find(|n| n.has_tag_name("commit"));
+        lines.push(BlameLine {
+            line_number: get_attr(&entry, "line-number").parse()?,
+            revision: commit.map(|c| get_attr(&c, "revision")),
+            author: commit.and_then(|c| get_child_text(&c, "author")),
+            date: commit.and_then(|c| get_child_text(&c, "date")).map(|d| parse_svn_date(&d)),
+        });
     }
+    Ok(lines)
 }
 
-pub fn status<S>(path: S, cwd: Option<&Path>) -> Result<SvnStatus>
-where
-    S: AsRef<str> + Display,
-{
-    let output = SvnCmd::new("status")
-        .with_cwd(cwd)
+//  Run svn blame for a single file.
+//  The contents of the lines are not included in the
+//  xml output so they must be fetched separately using cat.
+pub fn blame(creds: &Option<Credentials>, path: &str, revision: Option<&str>) -> Result<Vec<BlameLine>> {
+    let output = SvnCmd::new("blame")
+        .with_creds(creds)
         .arg("--xml")
-        .arg(path)
+        .arg(peg_path(path, revision))
         .run()?;
 
     if output.status.success() {
         let text = String::from_utf8_lossy(&output.stdout);
-        Ok(parse_svn_status(&text)?) }
-    else {
+        parse_svn_blame(&text)
+    } else {
         Err(SvnError(output).into())
     }
 }
 
-pub fn add<S, T>(paths: &[S], depth: T, auto_props: bool, cwd: Option<&Path>) -> Result<()>
-where
-    S: AsRef<str> + Display,
-    T: AsRef<str> + Display,
-{
-    let output = SvnCmd::new("add")
-        .with_cwd(cwd)
-        .arg(format!("--depth={}", depth))
-        .arg(if auto_props { "--auto-props" } else {"--no-auto-props"})
+//  Append a peg revision to a path.
+//  When there is no revision we append a bare '@' so that
+//  paths containing an '@' are not misinterpreted.
+pub fn peg_path(path: &str, revision: Option<&str>) -> String {
+    format!("{}@{}", path, revision.unwrap_or(""))
+}
+
+//  Return the diff of a commit limited to the given paths.
+pub fn change_diff(creds: &Option<Credentials>, paths: &[&str], commit_rev: &str) -> Result<Vec<String>> {
+
+    let output = SvnCmd::new("diff")
+        .with_creds(creds)
+        .arg("--change")
+        .arg(commit_rev)
         .args(paths)
         .run()?;
 
     if output.status.success() {
-        Ok(())
-    } else {
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(text.split('\n').map(|l| l.to_string()).collect())
+    }
+    else {
         Err(SvnError(output).into())
     }
 }
 
-pub fn revert<S, T>(paths: &[S], depth: T, remove_added: bool, cwd: Option<&Path>) -> Result<()>
+//  Return the diff of the given paths.  Without a revision the
+//  local changes are returned.
+pub fn diff<S, T>(creds: &Option<Credentials>, paths: &[S], revision: Option<&str>, changelists: &[T]) -> Result<Vec<String>>
 where
     S: AsRef<str> + Display,
     T: AsRef<str> + Display,
 {
-    let output = SvnCmd::new("revert")
-        .with_cwd(cwd)
-        .arg(format!("--depth={}", depth))
-        .arg_if(remove_added, "--remove-added")
+    let output = SvnCmd::new("diff")
+        .with_creds(creds)
+        .with_changelists(changelists)
+        .opt_arg(&revision.map(|r| format!("--revision={}", r)))
         .args(paths)
         .run()?;
 
     if output.status.success() {
-        Ok(())
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(text.lines().map(|l| l.to_string()).collect())
     } else {
         Err(SvnError(output).into())
     }
 }
 
-pub fn create_patch(patch_file: &Path, cwd: &Path) -> Result<()> {
+//  Return the diff between two repository URLs.
+pub fn url_diff(creds: &Option<Credentials>, from_url: &str, to_url: &str, summarize: bool) -> Result<Vec<String>> {
+
     let output = SvnCmd::new("diff")
-        .with_cwd(Some(cwd))
-        .arg("--depth=infinity")
-        .arg("--ignore-properties")
-        .arg(".")
+        .with_creds(creds)
+        .arg_if(summarize, "--summarize")
+        .arg(format!("--old={}", peg_path(from_url, None)))
+        .arg(format!("--new={}", peg_path(to_url, None)))
         .run()?;
 
     if output.status.success() {
-        let mut writer = File::create(patch_file)?;
-        writer.write_all(&output.stdout)?;
-        Ok(())
-    } else {
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(text.split('\n').map(|l| l.to_string()).collect())
+    }
+    else {
         Err(SvnError(output).into())
     }
 }
 
+fn prefixes_file() -> Result<PathBuf> {
+    data_directory().map(|dir| dir.join("prefixes.json"))
+}
 
-pub fn apply_patch(patch_file: &Path, dry_run: bool, cwd: Option<&Path>) -> Result<Vec<u8>> {
-    let output = SvnCmd::new("patch")
-        .with_cwd(cwd)
-        .arg_if(dry_run, "--dry-run")
-        .arg(patch_file.to_string_lossy())
-        .run()?;
+//  A named revision bookmark saved in .svu/marks.json
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Mark {
+    pub revision: String,
+    #[serde(with = "datetime_serializer")]
+    pub date: DateTime<Local>,
+}
 
-    if output.status.success() {
-        Ok(output.stdout)
+fn marks_file() -> Result<PathBuf> {
+    data_directory().map(|dir| dir.join("marks.json"))
+}
+
+pub fn load_marks() -> Result<BTreeMap<String, Mark>> {
+    let path = marks_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(serde_json::from_reader(reader)?)
     } else {
-        Err(SvnError(output).into())
+        Ok(BTreeMap::new())
     }
 }
 
-pub fn update(revision: &str, depth: &str, cwd: Option<&Path>) -> Result<Vec<u8>> {
-    let output = SvnCmd::new("update")
-        .with_cwd(cwd)
-        .arg(format!("--depth={}", depth))
-        .arg(format!("--revision={}", revision))
+pub fn save_marks(marks: &BTreeMap<String, Mark>) -> Result<()> {
+    let writer = File::create(marks_file()?)?;
+    Ok(serde_json::to_writer_pretty(writer, marks)?)
+}
+
+//  The most recent HEAD revision seen in a repository.
+//  Saved in .svu/head.json keyed by the repository UUID so that
+//  commands can ask only for the revisions committed since then.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HeadWatermark {
+    pub revision: u64,
+    #[serde(with = "datetime_serializer")]
+    pub date: DateTime<Local>,
+}
+
+fn head_watermarks_file() -> Result<PathBuf> {
+    data_directory().map(|dir| dir.join("head.json"))
+}
+
+fn load_head_watermarks() -> Result<BTreeMap<String, HeadWatermark>> {
+    let path = head_watermarks_file()?;
+    if path.is_file() {
+        let reader = File::open(path)?;
+        Ok(serde_json::from_reader(reader)?)
+    } else {
+        Ok(BTreeMap::new())
+    }
+}
+
+//  Load each of the data files used by this module so that
+//  `svu doctor` can report any that are damaged.
+pub fn check_data_files() -> Result<Vec<(PathBuf, Result<()>)>> {
+    Ok(vec![
+        (prefixes_file()?,         load_prefixes().map(|_| ())),
+        (marks_file()?,            load_marks().map(|_| ())),
+        (head_watermarks_file()?,  load_head_watermarks().map(|_| ())),
+        (last_failure_file()?,     load_last_failure().map(|_| ())),
+    ])
+}
+
+//  Return the last HEAD revision recorded for the repository.
+pub fn last_seen_head(repo_uuid: &str) -> Result<Option<u64>> {
+    Ok(load_head_watermarks()?.get(repo_uuid).map(|w| w.revision))
+}
+
+pub fn record_head(repo_uuid: &str, revision: u64) -> Result<()> {
+    let mut watermarks = load_head_watermarks()?;
+    watermarks.insert(repo_uuid.to_string(), HeadWatermark { revision, date: Local::now() });
+    save_json(&head_watermarks_file()?, &watermarks)
+}
+
+//  Fetch the HEAD revision of the repository containing path.
+//  Returns the repository UUID and the HEAD revision.
+//  Only `log --new`, `log --watch` and `watch` record the watermark, with record_head().
+pub fn head_revision(creds: &Option<Credentials>, path: &str) -> Result<(String, u64)> {
+    let head_info = info(creds, path, Some("HEAD"))?;
+    let revision = head_info.repo_rev.parse::<u64>()?;
+    Ok((head_info.repo_uuid, revision))
+}
+
+//  Returns true if the prefixes have been saved for this working copy.
+pub fn prefixes_configured() -> Result<bool> {
+    Ok(prefixes_file()?.is_file())
+}
+#[derive(Serialize, Deserialize)]
+pub struct Prefixes {
+    #[serde(rename(serialize = "trunkPrefix", deserialize = "trunkPrefix"), alias = "trunk_prefix")]
+    pub trunk_prefix: String,
+    #[serde(rename(serialize = "branchPrefixes", deserialize = "branchPrefixes"), alias = "branch_prefixes")]
+    pub branch_prefixes: Vec<String>,
+    #[serde(rename(serialize = "tagPrefixes", deserialize = "tagPrefixes"), alias = "tag_prefixes")]
+    pub tag_prefixes: Vec<String>
+}
+
+pub fn load_prefixes() -> Result<Prefixes> {
+    let path = prefixes_file()?;
+    if path.is_file() {
+        crate::migrate::load_json(&path)
+    } else {
+        //  Return the defaults, which may be set in the config files.
+        let list = |key: &str, default: &str| match config::get_list(key) {
+            Some(prefixes) if !prefixes.is_empty() => prefixes.to_vec(),
+            _ => vec![default.to_string()],
+        };
+        Ok(Prefixes {
+            trunk_prefix: config::get_str("prefixes.trunk").unwrap_or("trunk").to_string(),
+            branch_prefixes: list("prefixes.branches", "branches"),
+            tag_prefixes: list("prefixes.tags", "tags"),
+        })
+    }
+}
+
+//  Examine the top level directories of the repository to determine
+//  where trunk, branches and tags are located.  Any location that
+//  cannot be found falls back to the standard default.
+pub fn detect_prefixes(creds: &Option<Credentials>, root_url: &str) -> Result<Prefixes> {
+    let branch_re = Regex::new(r"(?i)branch")?;
+    let tag_re    = Regex::new(r"(?i)(^tags?$|tag|release)")?;
+    let dirs: Vec<String> = path_list(creds, root_url)?
+        .entries
+        .into_iter()
+        .filter(|e| e.kind == "dir")
+        .map(|e| e.name)
+        .collect();
+
+    let trunk_prefix = dirs
+        .iter()
+        .find(|d| d.eq_ignore_ascii_case("trunk"))
+        .cloned()
+        .unwrap_or("trunk".to_string());
+    let mut branch_prefixes: Vec<String> = dirs.iter().filter(|d| branch_re.is_match(d)).cloned().collect();
+    let mut tag_prefixes: Vec<String> = dirs
+        .iter()
+        .filter(|d| !branch_re.is_match(d) && tag_re.is_match(d))
+        .cloned()
+        .collect();
+
+    if branch_prefixes.is_empty() {
+        branch_prefixes.push("branches".to_string());
+    }
+    if tag_prefixes.is_empty() {
+        tag_prefixes.push("tags".to_string());
+    }
+    Ok(Prefixes { trunk_prefix, branch_prefixes, tag_prefixes })
+}
+
+pub fn save_prefixes(prefixes: &Prefixes) -> Result<()> {
+    crate::migrate::save_json(&prefixes_file()?, prefixes)
+}
+
+//  Return the branches or tags found in each of the given prefixes.
+//  The paths returned are relative to the repository root.
+//  Entries that are themselves prefixes are not included.
+pub fn list_locations(
+    creds: &Option<Credentials>,
+    root_url: &str,
+    prefixes: &[String],
+    all_prefixes: &[String],
+) -> Result<Vec<String>> {
+    let mut paths = Vec::<String>::new();
+    let mut sorted = prefixes.to_vec();
+    sorted.sort();
+    for prefix in &sorted {
+        let path_list = path_list(creds, &join_paths(root_url, prefix))?;
+        for entry in &path_list.entries {
+            let path = join_paths(prefix, &entry.name);
+            if entry.kind == "dir" && !all_prefixes.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+//  We must determine the path to the file relative
+//  to its subversion prefix.
+//  Find find the url entry for one of our prefixes
+//  so we can determine where the relative path starts.
+pub fn prefix_relative_path<S>(rel_url: &str, sorted_prefixes: &[S]) -> Result<String>
+where
+    S: AsRef<str> + Display,
+{
+    // Skip the leading ^/
+    sorted_prefixes
+        .iter()
+        .find(|p| rel_url[2..].starts_with(p.as_ref()))
+        .map(|p| rel_url.get(p.as_ref().len() + 3..).unwrap_or("").to_string())
+        .ok_or(General(format!("Cannot determine relative path for {}", rel_url)).into())
+}
+
+
+//  Resolve a branch or tag name to a location relative to the repository root.
+//  The name may be the trunk prefix, a full location such as branches/8.1
+//  or a bare name that is looked up under each branch prefix and then
+//  each tag prefix.
+pub fn resolve_location(creds: &Option<Credentials>, root_url: &str, branch: &str, prefixes: &Prefixes) -> Result<String> {
+    let branch = branch.trim_start_matches("^/").trim_matches('/');
+    let all_prefixes = || prefixes.branch_prefixes.iter().chain(prefixes.tag_prefixes.iter());
+
+    if branch == prefixes.trunk_prefix || all_prefixes().any(|p| branch.starts_with(&format!("{}/", p))) {
+        return Ok(branch.to_string());
+    }
+
+    for prefix in all_prefixes() {
+        let location = join_paths(prefix, branch);
+        if path_exists(creds, &join_paths(root_url, &location))? {
+            return Ok(location);
+        }
+    }
+    Err(General(format!("Cannot find a branch or tag named {}", branch)).into())
+}
+
+
+//  Return the trunk, branch or tag location that contains the
+//  given relative url. eg. ^/branches/8.1/src/main.c -> branches/8.1
+pub fn containing_location(rel_url: &str, prefixes: &Prefixes) -> Result<String> {
+    let path = rel_url.trim_start_matches("^/");
+    let is_under = |prefix: &str| path == prefix || path.starts_with(&format!("{}/", prefix));
+
+    if is_under(&prefixes.trunk_prefix) {
+        return Ok(prefixes.trunk_prefix.clone());
+    }
+
+    let mut sorted_prefixes: Vec<&String> = prefixes.branch_prefixes.iter()
+        .chain(prefixes.tag_prefixes.iter())
+        .collect();
+    sorted_prefixes.sort_by(|a, b| a.len().cmp(&b.len()).reverse()); // Longest first.
+    for prefix in sorted_prefixes {
+        if path.starts_with(&format!("{}/", prefix)) {
+            if let Some(name) = path[prefix.len() + 1..].split('/').next().filter(|n| !n.is_empty()) {
+                return Ok(join_paths(prefix, name));
+            }
+        }
+    }
+    Err(General(format!("{} is not within a known trunk, branch or tag prefix", rel_url)).into())
+}
+
+//  Return the major and minor version numbers of the svn client.
+pub fn client_version() -> Result<(u32, u32)> {
+    let output = SvnCmd::new("--version").arg("--quiet").run()?;
+    if !output.status.success() {
+        return Err(SvnError(output).into());
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut parts = text.trim().split('.').map(|p| p.parse::<u32>().ok());
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(major), Some(minor)) => Ok((major, minor)),
+        _ => Err(General(format!("Cannot parse the svn version '{}'", text.trim())).into()),
+    }
+}
+
+//  Verify that the current working directory is within
+//  a subversion working copy.
+//  Returns the info for the current directory or
+//  and Error if not withing a working copy.
+pub fn workingcopy_info() -> Result<SvnInfo> {
+    info(&None, ".", None).map_err(|e| {
+        if is_format_too_old(&e) || matches!(e.downcast_ref::<crate::util::SvError>(), Some(Interrupted)) {
+            e
+        } else {
+            General("This command must be run in a serversion working copy directory.".to_string())
+            .into()
+        }
+    })
+}
+
+//  Returns true if svn refused to use the working copy because it was
+//  created by an older svn client and must be upgraded.  (E155036)
+pub fn is_format_too_old(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<crate::util::SvError>() {
+        Some(SvnError(output)) => String::from_utf8_lossy(&output.stderr).contains("E155036"),
+        _ => false,
+    }
+}
+
+//  Items that belong to a changelist are not included in the target element
+//  but are listed in a separate changelist element.
+fn parse_status_entries(parent: &Node, changelist: Option<String>, entries: &mut Vec<StatusEntry>) {
+    for entry_node in parent.children() {
+        if let Some(wc_node) = get_child(&entry_node, "wc-status") {
+            let revision = get_attr(&wc_node, "revision");
+            entries.push(StatusEntry {
+                path: get_attr(&entry_node, "path"),
+                item_status: get_attr(&wc_node,    "item"),
+                props_status: get_attr(&wc_node,    "props"),
+                revision,
+                changelist: changelist.clone(),
+                locked: get_child(&wc_node, "lock").is_some(),
+            });
+        }
+    }
+}
+
+fn parse_svn_status(text: &str) -> Result<SvnStatus> {
+    let mut entries: Vec<StatusEntry> = vec![];
+    let doc = Document::parse(text)?;
+    if let Some(target) = doc.descendants().find(|n| n.has_tag_name("target")) {
+        parse_status_entries(&target, None, &mut entries);
+        for changelist in doc.descendants().filter(|n| n.has_tag_name("changelist")) {
+            parse_status_entries(&changelist, Some(get_attr(&changelist, "name")), &mut entries);
+        }
+        let path = get_attr(&target, "path");
+        Ok(SvnStatus{ path, entries })
+    } else {
+        Err(General("Malformed svn status".to_string()).into())
+    }
+}
+
+//  Parse the status of each target.  When externals are included
+//  each external directory is reported as a separate target.
+//  Changelists are reported after the entries of their target.
+fn parse_svn_status_targets(text: &str) -> Result<Vec<SvnStatus>> {
+    let mut targets: Vec<SvnStatus> = vec![];
+    let doc = Document::parse(text)?;
+    for node in doc.root_element().children() {
+        if node.has_tag_name("target") {
+            let mut entries: Vec<StatusEntry> = vec![];
+            parse_status_entries(&node, None, &mut entries);
+            for changelist in node.children().filter(|n| n.has_tag_name("changelist")) {
+                parse_status_entries(&changelist, Some(get_attr(&changelist, "name")), &mut entries);
+            }
+            targets.push(SvnStatus { path: get_attr(&node, "path"), entries });
+        } else if node.has_tag_name("changelist") {
+            if let Some(target) = targets.last_mut() {
+                parse_status_entries(&node, Some(get_attr(&node, "name")), &mut target.entries);
+            }
+        }
+    }
+    if targets.is_empty() {
+        Err(General("Malformed svn status".to_string()).into())
+    } else {
+        Ok(targets)
+    }
+}
+
+pub fn status<S>(path: S, cwd: Option<&Path>) -> Result<SvnStatus>
+where
+    S: AsRef<str> + Display,
+{
+    status_changelists(path, &[] as &[&str], cwd)
+}
+
+//  Run svn status limited to items that belong to the given changelists.
+//  If no changelists are given then all items are included.
+pub fn status_changelists<S, T>(path: S, changelists: &[T], cwd: Option<&Path>) -> Result<SvnStatus>
+where
+    S: AsRef<str> + Display,
+    T: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("status")
+        .with_cwd(cwd)
+        .with_changelists(changelists)
+        .arg("--xml")
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_svn_status(&text)?) }
+    else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Run svn status returning the status of the target followed by
+//  the status of each external directory within it.
+pub fn status_with_externals<S, T>(path: S, changelists: &[T], cwd: Option<&Path>) -> Result<Vec<SvnStatus>>
+where
+    S: AsRef<str> + Display,
+    T: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("status")
+        .with_cwd(cwd)
+        .with_changelists(changelists)
+        .arg("--xml")
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        parse_svn_status_targets(&text)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Return the status entries of versioned items that have local
+//  modifications.  Unversioned, ignored and external items are omitted.
+pub fn local_changes(cwd: &Path) -> Result<Vec<StatusEntry>> {
+    local_changes_changelists(cwd, &[] as &[&str])
+}
+
+//  Return the local changes limited to items that belong to the given
+//  changelists.  If no changelists are given then all items are included.
+pub fn local_changes_changelists<S>(cwd: &Path, changelists: &[S]) -> Result<Vec<StatusEntry>>
+where
+    S: AsRef<str> + Display,
+{
+    let status = status_changelists(".", changelists, Some(cwd))?;
+    Ok(status.entries
+        .into_iter()
+        .filter(|e| {
+            !matches!(e.item_status.as_str(), "unversioned" | "normal" | "external" | "ignored" | "none")
+                || matches!(e.props_status.as_str(), "modified" | "conflicted")
+        })
+        .collect())
+}
+
+//  Run svn status --verbose so that every versioned item
+//  is included along with its working revision.
+pub fn status_verbose<S>(path: S, cwd: Option<&Path>) -> Result<SvnStatus>
+where
+    S: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("status")
+        .with_cwd(cwd)
+        .arg("--xml")
+        .arg("--verbose")
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_svn_status(&text)?)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Add paths to a changelist.
+pub fn changelist<S>(name: &str, paths: &[S], cwd: Option<&Path>) -> Result<()>
+where
+    S: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("changelist")
+        .with_cwd(cwd)
+        .arg(name)
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  The svn error codes that report a path that does not exist, was
+//  not found at a revision, or is not a file.
+const NOT_FOUND_CODES: [&str; 8] = [
+    "E160013", "W160013", "E200009", "W170000", "W155010", "E195012", "E160017", "E195007",
+];
+
+fn is_not_found_output(output: &Output) -> bool {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    NOT_FOUND_CODES.iter().any(|code| stderr.contains(code))
+}
+
+//  Returns true if the error is an svn failure because a path
+//  does not exist or is not a file.  Errors such as authentication
+//  or network failures return false.
+pub fn is_not_found_error(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<crate::util::SvError>(), Some(SvnError(output)) if is_not_found_output(output))
+}
+
+//  Return true if the path or URL exists in the repository.
+//  Only the errors reporting that the path was not found mean that
+//  it does not exist, any other failure is returned as an error.
+pub fn path_exists(creds: &Option<Credentials>, path: &str) -> Result<bool> {
+    let output = SvnCmd::new("info")
+        .with_creds(creds)
+        .arg("--depth=empty")
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        Ok(true)
+    } else if is_not_found_output(&output) {
+        Ok(false)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Copy a repository location.  When the target is a URL this
+//  commits the copy immediately using the given message.
+pub fn copy(creds: &Option<Credentials>, from: &str, to: &str, message: &str) -> Result<()> {
+    let output = SvnCmd::new("copy")
+        .with_creds(creds)
+        .arg("--parents")
+        .arg(format!("--message={}", message))
+        .arg(from)
+        .arg(to)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Commit the given paths using the message in a file.
+//  With a depth of "empty" only the paths themselves are committed, not
+//  the items beneath them, so each item to be committed must be listed.
+//  A depth of "infinity" is needed to commit a directory that was added,
+//  deleted or replaced along with its children.
+pub fn commit<S, T>(creds: &Option<Credentials>, paths: &[S], depth: &str, changelists: &[T], message_file: &Path, cwd: Option<&Path>) -> Result<Vec<u8>>
+where
+    S: AsRef<str> + Display,
+    T: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("commit")
+        .with_creds(creds)
+        .with_cwd(cwd)
+        .with_changelists(changelists)
+        .arg(format!("--depth={}", depth))
+        .arg(format!("--file={}", message_file.to_string_lossy()))
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Move a repository URL, committing immediately with the given message.
+pub fn move_url(creds: &Option<Credentials>, from: &str, to: &str, message: &str) -> Result<()> {
+    let output = SvnCmd::new("move")
+        .with_creds(creds)
+        .arg(format!("--message={}", message))
+        .arg(from)
+        .arg(to)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Delete a repository URL, committing immediately with the given message.
+pub fn delete_url(creds: &Option<Credentials>, url: &str, message: &str) -> Result<()> {
+    let output = SvnCmd::new("delete")
+        .with_creds(creds)
+        .arg(format!("--message={}", message))
+        .arg(url)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Return the revisions on the source that have not been merged to the target.
+pub fn mergeinfo_eligible(creds: &Option<Credentials>, source: &str, target: &str) -> Result<Vec<String>> {
+    mergeinfo_revisions(creds, source, target, "eligible")
+}
+
+//  Return the revisions on the source that have been merged to the target.
+pub fn mergeinfo_merged(creds: &Option<Credentials>, source: &str, target: &str) -> Result<Vec<String>> {
+    mergeinfo_revisions(creds, source, target, "merged")
+}
+
+//  Non-inheritable revisions are marked with an asterisk which is removed.
+fn mergeinfo_revisions(creds: &Option<Credentials>, source: &str, target: &str, show_revs: &str) -> Result<Vec<String>> {
+    let output = SvnCmd::new("mergeinfo")
+        .with_creds(creds)
+        .arg(format!("--show-revs={}", show_revs))
+        .arg(source)
+        .arg(target)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(text.lines()
+            .map(|l| l.trim().trim_start_matches('r').trim_end_matches('*').to_string())
+            .filter(|l| !l.is_empty())
+            .collect())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Merge the changes from a branch into the working copy.
+pub fn merge(creds: &Option<Credentials>, url: &str, dry_run: bool, reintegrate: bool, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("merge")
+        .with_creds(creds)
+        .with_cwd(cwd)
+        .arg_if(dry_run, "--dry-run")
+        .arg_if(reintegrate, "--reintegrate")
+        .arg(url)
+        .arg(".")
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Merge the changes of a single revision from a branch into the working copy.
+pub fn cherry_pick(creds: &Option<Credentials>, url: &str, revision: &str, dry_run: bool, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("merge")
+        .with_creds(creds)
+        .with_cwd(cwd)
+        .arg_if(dry_run, "--dry-run")
+        .arg(format!("--change={}", revision))
+        .arg(url)
+        .arg(".")
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Reverse merge the changes of a single revision into the working copy.
+pub fn reverse_merge(creds: &Option<Credentials>, revision: &str, dry_run: bool, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("merge")
+        .with_creds(creds)
+        .with_cwd(cwd)
+        .arg_if(dry_run, "--dry-run")
+        .arg(format!("--change=-{}", revision))
+        .arg(".")
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Return the paths of all files within `cwd` that have the
+//  svn:needs-lock property.  The paths are relative to `cwd`.
+pub fn needs_lock_paths(cwd: &Path) -> Result<Vec<String>> {
+    let output = SvnCmd::new("propget")
+        .with_cwd(Some(cwd))
+        .arg("svn:needs-lock")
+        .arg("--xml")
+        .arg("--depth=infinity")
+        .arg(".")
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        let doc = roxmltree::Document::parse(&text)?;
+        let paths = doc
+            .descendants()
+            .filter(|n| n.tag_name().name() == "target")
+            .map(|n| {
+                let path = get_attr(&n, "path");
+                path.strip_prefix("./").unwrap_or(&path).to_string()
+            })
+            .collect();
+        Ok(paths)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Return the value of a property or None if it is not set.
+pub fn propget(creds: &Option<Credentials>, name: &str, path: &str) -> Result<Option<String>> {
+    let output = SvnCmd::new("propget")
+        .with_creds(creds)
+        .arg(name)
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        //  svn adds a newline after the value.
+        let value = String::from_utf8_lossy(&output.stdout);
+        Ok(Some(value.strip_suffix('\n').unwrap_or(&value).to_string()))
+    } else if String::from_utf8_lossy(&output.stderr).contains("W200017") {
+        Ok(None)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Set a property on working copy paths.
+pub fn propset<S>(name: &str, value: &str, paths: &[S]) -> Result<()>
+where
+    S: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("propset")
+        .arg("--")
+        .arg(name)
+        .arg(value)
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Remove a property from working copy paths.
+pub fn propdel<S>(name: &str, paths: &[S]) -> Result<()>
+where
+    S: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("propdel")
+        .arg(name)
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  The properties of a single path.
+//  (name, value)
+#[derive(Debug, Clone)]
+pub struct PropTarget {
+    pub path: String,
+    pub props: Vec<(String, String)>,
+}
+
+//  Return the properties and their values of a path and,
+//  when recursive, of every path beneath it that has properties.
+pub fn proplist(creds: &Option<Credentials>, path: &str, recursive: bool) -> Result<Vec<PropTarget>> {
+    proplist_depth(creds, path, if recursive { "infinity" } else { "empty" })
+}
+
+//  Return the properties of a path and the items beneath
+//  it down to the given depth.
+pub fn proplist_depth(creds: &Option<Credentials>, path: &str, depth: &str) -> Result<Vec<PropTarget>> {
+    let output = SvnCmd::new("proplist")
+        .with_creds(creds)
+        .arg("--xml")
+        .arg("--verbose")
+        .arg(format!("--depth={}", depth))
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        let doc = Document::parse(&text)?;
+        let targets = doc
+            .descendants()
+            .filter(|n| n.tag_name().name() == "target")
+            .map(|target| PropTarget {
+                path: get_attr(&target, "path"),
+                props: target.children()
+                    .filter(|n| n.tag_name().name() == "property")
+                    .map(|n| (get_attr(&n, "name"), get_text(&n)))
+                    .collect(),
+            })
+            .collect();
+        Ok(targets)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Lock the given working copy paths in the repository.
+//  With `steal` any lock held by another user is taken over.
+pub fn lock<S>(paths: &[S], message: Option<&str>, steal: bool, cwd: Option<&Path>) -> Result<()>
+where
+    S: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("lock")
+        .with_cwd(cwd)
+        .opt_arg(&message.map(|m| format!("--message={}", m)))
+        .arg_if(steal, "--force")
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Unlock the given working copy paths or URLs.
+//  With `force` locks held by other users are broken.
+pub fn unlock<S>(paths: &[S], force: bool, cwd: Option<&Path>) -> Result<()>
+where
+    S: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("unlock")
+        .with_cwd(cwd)
+        .arg_if(force, "--force")
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  A lock held on a file in the repository.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct RepoLock {
+    pub url: String,
+    pub rel_url: String,
+    pub owner: String,
+    pub comment: String,
+    pub created: DateTime<Local>,
+}
+
+//  Return the locks held on the files at or beneath a URL.
+pub fn repo_locks(creds: &Option<Credentials>, url: &str) -> Result<Vec<RepoLock>> {
+    let output = SvnCmd::new("info")
+        .with_creds(creds)
+        .arg("--xml")
+        .arg("--depth=infinity")
+        .arg(url)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        let doc = Document::parse(&text)?;
+        let mut locks = Vec::new();
+        for entry in doc.descendants().filter(|n| n.has_tag_name("entry")) {
+            if let Some(lock) = get_child(&entry, "lock") {
+                locks.push(RepoLock {
+                    url: get_child_text_or(&entry, "url", "n/a"),
+                    rel_url: get_child_text_or(&entry, "relative-url", "n/a"),
+                    owner: get_child_text_or(&lock, "owner", "n/a"),
+                    comment: get_child_text_or(&lock, "comment", ""),
+                    created: parse_svn_date_opt(get_child_text(&lock, "created")),
+                });
+            }
+        }
+        Ok(locks)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  When auto_props is None the svn configuration decides whether auto-props are applied.
+pub fn add<S, T>(paths: &[S], depth: T, auto_props: Option<bool>, cwd: Option<&Path>) -> Result<()>
+where
+    S: AsRef<str> + Display,
+    T: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("add")
+        .with_cwd(cwd)
+        .arg(format!("--depth={}", depth))
+        .arg_if(auto_props == Some(true), "--auto-props")
+        .arg_if(auto_props == Some(false), "--no-auto-props")
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+pub fn revert<S, T>(paths: &[S], depth: T, remove_added: bool, cwd: Option<&Path>) -> Result<()>
+where
+    S: AsRef<str> + Display,
+    T: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("revert")
+        .with_cwd(cwd)
+        .arg(format!("--depth={}", depth))
+        .arg_if(remove_added, "--remove-added")
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Mark a conflicted path as resolved.
+//  `accept` is one of the svn --accept values such as working, base,
+//  mine-full or theirs-full.
+pub fn resolve(path: &str, accept: &str, cwd: Option<&Path>) -> Result<()> {
+    let output = SvnCmd::new("resolve")
+        .with_cwd(cwd)
+        .arg(format!("--accept={}", accept))
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  The files that svn creates alongside a file with a text conflict.
+#[derive(Debug, Clone)]
+pub struct ConflictFiles {
+    pub base: PathBuf,
+    pub theirs: PathBuf,
+    pub mine: PathBuf,
+}
+
+//  Return the base, theirs and mine files for a path with a text conflict.
+//  The file names reported by svn are relative to the directory
+//  containing the conflicted path.
+pub fn conflict_files(path: &str, cwd: Option<&Path>) -> Result<Option<ConflictFiles>> {
+    let output = SvnCmd::new("info")
+        .with_cwd(cwd)
+        .arg("--xml")
+        .arg(path)
+        .run()?;
+
+    if output.status.success() {
+        let text = String::from_utf8_lossy(&output.stdout);
+        let doc = Document::parse(&text)?;
+        let find = |name: &str| {
+            doc.descendants()
+                .find(|n| n.has_tag_name(name))
+                .and_then(|n| n.text())
+                .map(|t| t.to_owned())
+        };
+        let dir = cwd.map(PathBuf::from).unwrap_or_default()
+            .join(Path::new(path).parent().unwrap_or(Path::new("")));
+        let locate = |name: String| {
+            let file = PathBuf::from(&name);
+            if file.is_absolute() { file } else { dir.join(file) }
+        };
+        match (find("prev-base-file"), find("cur-base-file"), find("prev-wc-file")) {
+            (Some(base), Some(theirs), Some(mine)) => Ok(Some(ConflictFiles {
+                base:   locate(base),
+                theirs: locate(theirs),
+                mine:   locate(mine),
+            })),
+            _ => Ok(None),
+        }
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Create a patch file for the given paths.
+//  The paths must be relative to the `cwd` directory.
+pub fn create_patch<S, T, U>(
+    patch_file: &Path,
+    paths: &[S],
+    depth: T,
+    changelists: &[U],
+    cwd: &Path
+) -> Result<()>
+where
+    S: AsRef<str> + Display,
+    T: AsRef<str> + Display,
+    U: AsRef<str> + Display,
+{
+    let output = SvnCmd::new("diff")
+        .with_cwd(Some(cwd))
+        .with_changelists(changelists)
+        .arg(format!("--depth={}", depth))
+        .arg("--ignore-properties")
+        .args(paths)
+        .run()?;
+
+    if output.status.success() {
+        let mut writer = File::create(patch_file)?;
+        writer.write_all(&output.stdout)?;
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+
+pub fn apply_patch(patch_file: &Path, dry_run: bool, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("patch")
+        .with_cwd(cwd)
+        .arg_if(dry_run, "--dry-run")
+        .arg(patch_file.to_string_lossy())
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Apply a patch in reverse, removing its changes from the working copy.
+pub fn reverse_patch(patch_file: &Path, dry_run: bool, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("patch")
+        .with_cwd(cwd)
+        .arg_if(dry_run, "--dry-run")
+        .arg("--reverse-diff")
+        .arg(patch_file.to_string_lossy())
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+pub fn update(revision: &str, depth: &str, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("update")
+        .with_cwd(cwd)
+        .arg(format!("--depth={}", depth))
+        .arg(format!("--revision={}", revision))
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Release the working copy locks and finish any interrupted operations.
+pub fn cleanup(path: &Path) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("cleanup")
+        .arg(path.to_string_lossy())
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Upgrade a working copy created by an older svn client to the current format.
+pub fn upgrade(path: &Path) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("upgrade")
+        .arg(path.to_string_lossy())
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Check out a branch to a new working copy directory.
+pub fn checkout(creds: &Option<Credentials>, url: &str, path: &Path) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("checkout")
+        .with_creds(creds)
+        .arg(url)
+        .arg(path.to_string_lossy())
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Switch the working copy to a different branch.
+pub fn switch(creds: &Option<Credentials>, url: &str, revision: Option<&str>, cwd: Option<&Path>) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("switch")
+        .with_creds(creds)
+        .with_cwd(cwd)
+        .opt_arg(&revision.map(|r| format!("--revision={}", r)))
+        .arg(url)
+        .arg(".")
+        .run()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Point the working copy at a new location of its repository.
+pub fn relocate(creds: &Option<Credentials>, from_url: &str, to_url: &str, cwd: Option<&Path>) -> Result<()> {
+    let output = SvnCmd::new("relocate")
+        .with_creds(creds)
+        .with_cwd(cwd)
+        .arg(from_url)
+        .arg(to_url)
+        .arg(".")
+        .run()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(SvnError(output).into())
+    }
+}
+
+//  Update a single path within the working copy.
+pub fn update_path(
+    path: &str,
+    revision: &str,
+    ignore_externals: bool,
+    cwd: Option<&Path>
+) -> Result<Vec<u8>> {
+    let output = SvnCmd::new("update")
+        .with_cwd(cwd)
+        .arg(format!("--revision={}", revision))
+        .arg_if(ignore_externals, "--ignore-externals")
+        .arg(path)
         .run()?;
 
     if output.status.success() {
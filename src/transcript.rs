@@ -0,0 +1,195 @@
+use anyhow::Result;
+use chrono::Local;
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use crate::util;
+
+//  When a transcript file is given, svu runs itself as a child process
+//  and copies the child's output to the terminal as it arrives.  When the
+//  child finishes, the command line, timing and output (without color
+//  escape sequences) are appended to the transcript in Markdown.
+//
+//  On unix each output stream that is a terminal is given to the child
+//  as a pseudo terminal so that colors, hyperlinks, the pager and the
+//  tui work as they do without a transcript.
+//
+//  SVU_TRANSCRIPT_CHILD is set in the child so that it runs normally.
+
+const CHILD_VAR: &str = "SVU_TRANSCRIPT_CHILD";
+
+pub fn is_child() -> bool {
+    env::var(CHILD_VAR).is_ok()
+}
+
+//  Run the command recording it in the transcript.
+//  Returns the exit status of the command.
+pub fn record(transcript: &Path, args: &[String]) -> i32 {
+    match run_child(args) {
+        Ok((status, output, elapsed)) => {
+            if let Err(e) = append(transcript, args, status, &output, elapsed) {
+                eprintln!("Cannot write the transcript {}: {}", transcript.to_string_lossy(), e);
+            }
+            status
+        }
+        Err(e) => {
+            eprintln!("{:?}", e);
+            1
+        }
+    }
+}
+
+fn run_child(args: &[String]) -> Result<(i32, Vec<u8>, f64)> {
+    let start = Instant::now();
+    let (stdout_pty, stdout_slave) = open_pty(io::stdout().is_terminal())?.unzip();
+    let (stderr_pty, stderr_slave) = open_pty(io::stderr().is_terminal())?.unzip();
+    let mut cmd = Command::new(env::current_exe()?);
+    cmd.args(&args[1..])
+        .env(CHILD_VAR, "1")
+        .stdin(Stdio::inherit())
+        .stdout(stdout_slave.unwrap_or(Stdio::piped()))
+        .stderr(stderr_slave.unwrap_or(Stdio::piped()));
+    //  Keep the colors on the terminal when the output must be piped.
+    if io::stdout().is_terminal() && stdout_pty.is_none() {
+        cmd.env("CLICOLOR_FORCE", "1");
+    }
+    let mut child = cmd.spawn()?;
+    crate::interrupt::child_started(child.id());
+    //  Close our copies of the slave ends so that reading
+    //  from a pseudo terminal ends when the child exits.
+    drop(cmd);
+
+    //  The output of both streams is collected in the order it arrives.
+    let output = Arc::new(Mutex::new(Vec::<u8>::new()));
+    let mut stderr: Box<dyn Read + Send> = match stderr_pty {
+        Some(pty) => Box::new(pty),
+        None => Box::new(child.stderr.take().unwrap()),
+    };
+    let stderr_output = Arc::clone(&output);
+    let stderr_thread = thread::spawn(move || copy_stream(&mut stderr, &mut io::stderr(), &stderr_output));
+    let mut stdout: Box<dyn Read> = match stdout_pty {
+        Some(pty) => Box::new(pty),
+        None => Box::new(child.stdout.take().unwrap()),
+    };
+    copy_stream(&mut stdout, &mut io::stdout(), &output);
+    let _ = stderr_thread.join();
+
+    let status = child.wait();
+    crate::interrupt::child_finished(child.id());
+    let status = status?.code().unwrap_or(1);
+    let output = output.lock().map(|o| o.clone()).unwrap_or_default();
+    Ok((status, output, start.elapsed().as_secs_f64()))
+}
+
+//  Open a pseudo terminal, with the size of our terminal, for an output
+//  stream of the child that would otherwise go to a terminal.
+//  Returns the master end that we read and the slave end for the child.
+#[cfg(unix)]
+fn open_pty(is_terminal: bool) -> Result<Option<(File, Stdio)>> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    if !is_terminal {
+        return Ok(None);
+    }
+    let mut master: libc::c_int = -1;
+    let mut slave: libc::c_int = -1;
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let size_ptr = if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0 {
+        &mut size as *mut libc::winsize
+    } else {
+        std::ptr::null_mut()
+    };
+    if unsafe { libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null_mut(), size_ptr) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    //  Neither end is inherited by the svn commands that the child runs.
+    for fd in [master, slave] {
+        unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+    }
+    let master = unsafe { File::from_raw_fd(master) };
+    let slave = unsafe { OwnedFd::from_raw_fd(slave) };
+    Ok(Some((master, Stdio::from(slave))))
+}
+
+#[cfg(not(unix))]
+fn open_pty(_is_terminal: bool) -> Result<Option<(File, Stdio)>> {
+    Ok(None)
+}
+
+//  Output is copied in chunks rather than lines so that prompts are shown.
+//  Reading a pseudo terminal fails rather than reaching the end of the
+//  file once the child has exited, which also ends the copy.
+fn copy_stream(from: &mut impl Read, to: &mut impl Write, output: &Mutex<Vec<u8>>) {
+    let mut buffer = [0u8; 4096];
+    while let Ok(count) = from.read(&mut buffer) {
+        if count == 0 {
+            break;
+        }
+        let _ = to.write_all(&buffer[..count]);
+        let _ = to.flush();
+        if let Ok(mut output) = output.lock() {
+            output.extend_from_slice(&buffer[..count]);
+        }
+    }
+}
+
+fn append(transcript: &Path, args: &[String], status: i32, output: &[u8], elapsed: f64) -> Result<()> {
+    let is_new = !transcript.is_file() || transcript.metadata()?.len() == 0;
+    let mut file = OpenOptions::new().create(true).append(true).open(transcript)?;
+    if is_new {
+        writeln!(file, "# svu transcript")?;
+        writeln!(file)?;
+    }
+
+    let command_line = command_line(args);
+    let text = util::strip_ansi(&String::from_utf8_lossy(output)).replace('\r', "");
+    let fence = if text.contains("```") { "~~~~" } else { "```" };
+    let cwd = env::current_dir().map(|d| d.to_string_lossy().to_string()).unwrap_or_default();
+
+    writeln!(file, "## `{}`", command_line)?;
+    writeln!(file)?;
+    writeln!(
+        file,
+        "_Run {} in {} ({:.1}s, exit status {})_",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        cwd,
+        elapsed,
+        status
+    )?;
+    writeln!(file)?;
+    writeln!(file, "{}text", fence)?;
+    write!(file, "{}", text)?;
+    if !text.is_empty() && !text.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", fence)?;
+    writeln!(file)?;
+    Ok(())
+}
+
+//  The --transcript option itself is left out of the recorded command line.
+fn command_line(args: &[String]) -> String {
+    let mut words = vec!["svu".to_string()];
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--transcript" {
+            iter.next();
+        } else if !arg.starts_with("--transcript=") {
+            words.push(quote(arg));
+        }
+    }
+    words.join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.chars().any(|c| c.is_whitespace() || "'\"$`\\|&;<>()*?".contains(c)) {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    } else {
+        arg.to_string()
+    }
+}
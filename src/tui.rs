@@ -0,0 +1,240 @@
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+use crate::util::{self, SvError::*};
+
+//  A minimal full screen terminal interface.  The terminal is put in raw
+//  mode on the alternate screen while a Terminal exists and is restored
+//  when it is dropped.  Screens are drawn with ANSI escape sequences.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Escape,
+    Backspace,
+    Char(char),
+}
+
+pub struct Terminal {
+    #[cfg(unix)]
+    saved: libc::termios,
+}
+
+impl Terminal {
+    #[cfg(unix)]
+    pub fn open() -> Result<Terminal> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err(General("The terminal interface requires a terminal".to_string()).into());
+        }
+        let saved = unsafe {
+            let mut saved: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut saved) != 0 {
+                return Err(io::Error::last_os_error().into());
+            }
+            let mut raw = saved;
+            libc::cfmakeraw(&mut raw);
+            //  Keep output processing so that \n starts a new line.
+            raw.c_oflag |= libc::OPOST;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(io::Error::last_os_error().into());
+            }
+            saved
+        };
+        //  Use the alternate screen and hide the cursor.
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(Terminal { saved })
+    }
+
+    #[cfg(not(unix))]
+    pub fn open() -> Result<Terminal> {
+        Err(General("The terminal interface is not supported on this platform".to_string()).into())
+    }
+
+    //  The number of columns and rows of the terminal.
+    pub fn size(&self) -> (usize, usize) {
+        match terminal_size::terminal_size() {
+            Some((terminal_size::Width(w), terminal_size::Height(h))) => (w as usize, h as usize),
+            None => (80, 24),
+        }
+    }
+
+    //  Replace the screen with the given lines.  Each line is cut
+    //  to the width of the terminal.
+    pub fn draw(&mut self, lines: &[String]) -> Result<()> {
+        let (width, height) = self.size();
+        let mut out = String::from("\x1b[H\x1b[2J");
+        for (index, line) in lines.iter().take(height).enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+            out.push_str(&truncate(line, width));
+            out.push_str("\x1b[0m");
+        }
+        let mut stdout = io::stdout();
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    pub fn read_key(&mut self) -> Result<Key> {
+        loop {
+            let byte = match read_byte(-1)? {
+                Some(byte) => byte,
+                None => continue,
+            };
+            let key = match byte {
+                b'\r' | b'\n' => Key::Enter,
+                0x7f | 0x08   => Key::Backspace,
+                0x1b          => read_escape()?,
+                0x03          => return Err(Interrupted.into()),
+                byte if byte < 0x80 => Key::Char(byte as char),
+                byte          => Key::Char(read_utf8(byte)?),
+            };
+            return Ok(key);
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn read_key(&mut self) -> Result<Key> {
+        Ok(Key::Escape)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        #[cfg(unix)]
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.saved);
+        }
+    }
+}
+
+//  Read a byte from the terminal waiting at most timeout milliseconds.
+//  A negative timeout waits until a byte is available.
+#[cfg(unix)]
+fn read_byte(timeout: i32) -> Result<Option<u8>> {
+    let mut poll = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+    let ready = unsafe { libc::poll(&mut poll, 1, timeout) };
+    if ready <= 0 {
+        return Ok(None);
+    }
+    let mut byte = 0u8;
+    let count = unsafe { libc::read(libc::STDIN_FILENO, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+    if count == 1 { Ok(Some(byte)) } else { Ok(None) }
+}
+
+//  An escape on its own is the Escape key, otherwise it
+//  starts the sequence sent by a cursor or paging key.
+#[cfg(unix)]
+fn read_escape() -> Result<Key> {
+    match read_byte(50)? {
+        Some(b'[') | Some(b'O') => (),
+        _ => return Ok(Key::Escape),
+    }
+    let mut sequence = Vec::new();
+    while let Some(byte) = read_byte(50)? {
+        sequence.push(byte);
+        if byte.is_ascii_alphabetic() || byte == b'~' {
+            break;
+        }
+    }
+    let key = match sequence.as_slice() {
+        b"A" => Key::Up,
+        b"B" => Key::Down,
+        b"C" => Key::Right,
+        b"D" => Key::Left,
+        b"H" | b"1~" | b"7~" => Key::Home,
+        b"F" | b"4~" | b"8~" => Key::End,
+        b"5~" => Key::PageUp,
+        b"6~" => Key::PageDown,
+        _ => Key::Escape,
+    };
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn read_utf8(first: u8) -> Result<char> {
+    let len = match first {
+        b if b >= 0xf0 => 4,
+        b if b >= 0xe0 => 3,
+        _ => 2,
+    };
+    let mut bytes = vec![first];
+    for _ in 1..len {
+        if let Some(byte) = read_byte(50)? {
+            bytes.push(byte);
+        }
+    }
+    Ok(String::from_utf8_lossy(&bytes).chars().next().unwrap_or('?'))
+}
+
+//  Cut a line that may contain color escape sequences to the given
+//  number of columns.  Tabs are expanded.
+pub fn truncate(line: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut columns = 0;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            for c in chars.by_ref() {
+                out.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else if columns < width {
+            if c == '\t' {
+                let spaces = (4 - columns % 4).min(width - columns);
+                out.push_str(&" ".repeat(spaces));
+                columns += spaces;
+            } else {
+                out.push(c);
+                columns += 1;
+            }
+        }
+    }
+    out
+}
+
+//  Pad a line with spaces to the given number of columns.
+pub fn pad(line: &str, width: usize) -> String {
+    let len = util::display_width(line);
+    format!("{}{}", line, " ".repeat(width.saturating_sub(len)))
+}
+
+//  Ask the terminal to copy text to the clipboard using the OSC 52
+//  escape sequence.  Terminals that do not support it ignore it.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    print!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    io::stdout().flush()?;
+    Ok(())
+}
+
+fn base64(data: &[u8]) -> String {
+    const CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
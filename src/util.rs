@@ -1,12 +1,17 @@
 
 use thiserror::Error;
 use crate::svn::{self, LogPath, FromPath, LogEntry};
+use crate::issues;
 use colored::*;
-use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
 use std::sync::OnceLock;
-use std::path::PathBuf;
-use std::fs::{create_dir, rename};
+use std::path::{Path, PathBuf};
+use std::fs::{create_dir, rename, File};
+use std::process::Command;
+use std::io::Write;
 use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
 
 #[derive(Error, Debug)]
 pub enum SvError {
@@ -14,6 +19,27 @@ pub enum SvError {
     General(String),
     #[error("{}", String::from_utf8_lossy(&.0.stderr))]
     SvnError(std::process::Output),
+    //  Exit with the given status without displaying an error message.
+    #[error("exit status {0}")]
+    Exit(i32),
+    //  The command was stopped by Ctrl-C.
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+//  Remove passwords from svn command lines and output
+//  and credentials embedded in URLs.
+pub fn scrub_secrets(text: &str) -> String {
+    static PASSWORD: OnceLock<Regex> = OnceLock::new();
+    static URL_CREDS: OnceLock<Regex> = OnceLock::new();
+    let password = PASSWORD.get_or_init(|| {
+        Regex::new(r"(?i)(password[-_a-z]*[=: ]\s*)\S+").expect("Error parsing password regex")
+    });
+    let url_creds = URL_CREDS.get_or_init(|| {
+        Regex::new(r"([a-z][-+.a-z]*://)[^/@\s]+@").expect("Error parsing url credentials regex")
+    });
+    let text = password.replace_all(text, "${1}********");
+    url_creds.replace_all(&text, "${1}********@").into_owned()
 }
 
 pub fn join_paths<S, T>(base: S, leaf: T) -> String
@@ -29,6 +55,27 @@ T: AsRef<str>,
     path
 }
 
+//  Convert a glob pattern to a regular expression.
+//  `*` and `?` do not match a slash while `**` matches anything.
+//  A pattern without a slash is matched against the file name only.
+pub fn glob_regex(glob: &str) -> Result<Regex> {
+    let mut pattern = String::from(if glob.contains('/') { "^" } else { "(^|/)" });
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).map_err(|e| SvError::General(format!("Invalid pattern '{}': {}", glob, e)).into())
+}
+
 //  We create a .svu directory in the top directory of the working copy
 //  This gives sv commands a place to store data
 //  This will throw an error of the directory cannot be resloved.
@@ -49,6 +96,44 @@ pub fn data_directory() -> Result<PathBuf> {
     Ok(path)
 }
 
+//  The data directory of the working copy at wc_root, if it has one,
+//  without running svn or creating or renaming the directory.
+//  Used by `svu prompt`.
+pub fn existing_data_directory(wc_root: &Path) -> Option<PathBuf> {
+    [".svu", ".sv"].iter().map(|name| wc_root.join(name)).find(|path| path.is_dir())
+}
+
+//  Write a data file as json.  The data is written to a temporary file
+//  that then replaces the original so that an interrupted write can never
+//  leave a truncated data file behind.
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, data: &T) -> Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp_path = path.with_file_name(name);
+    serde_json::to_writer_pretty(File::create(&tmp_path)?, data)?;
+    rename(&tmp_path, path)?;
+    Ok(())
+}
+
+//  Returns true if a path relative to the working copy root refers
+//  to our data directory or something within it.  These must never
+//  be treated as part of the user's working copy changes.
+pub fn is_data_directory_path(path: &str) -> bool {
+    let first = path.trim_start_matches("./").split(['/', '\\']).next().unwrap_or("");
+    first == ".svu" || first == ".sv"
+}
+
+//  Returns true if a path relative to the current directory is the
+//  data directory at the root of a working copy.
+pub fn is_data_directory(path: &Path) -> bool {
+    let is_named = path.file_name().is_some_and(|name| name == ".svu" || name == ".sv");
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    is_named && parent.join(".svn").is_dir()
+}
+
 pub fn formatted_log_path(log_path: &LogPath) -> String {
     let color = match log_path.action.as_str() {
         "D"  => "red",
@@ -102,8 +187,20 @@ pub fn parse_svn_date_opt(opt_date_str: Option<String>) -> DateTime<Local> {
     }
 }
 
+fn date_format() -> &'static OnceLock<String> {
+    static DATE_FORMAT: OnceLock<String> = OnceLock::new();
+    &DATE_FORMAT
+}
+
+//  Set the format used by display_svn_date().
+//  The format must have been validated.
+pub fn set_date_format(format: &str) {
+    let _ = date_format().set(format.to_string());
+}
+
 pub fn display_svn_date(date: &DateTime<Local>) -> String {
-    date.format("%Y-%m-%d").to_string()
+    let format = date_format().get().map(|f| f.as_str()).unwrap_or("%Y-%m-%d");
+    date.format(format).to_string()
 }
 
 pub fn display_svn_time(date: &DateTime<Local>) -> String {
@@ -152,8 +249,154 @@ pub fn divider(len: usize) -> String {
     "-".repeat(len)
 }
 
+//  Return the width of the terminal, or 80 if it cannot be determined.
+pub fn terminal_width() -> usize {
+    match terminal_size::terminal_size() {
+        Some((terminal_size::Width(w), _)) => w as usize,
+        None => 80,
+    }
+}
+
+//  Return the number of columns that text occupies on the
+//  terminal, ignoring any color escape sequences.
+pub fn display_width(text: &str) -> usize {
+    strip_ansi(text).chars().count()
+}
+
+//  Remove any color escape sequences from text.
+pub fn strip_ansi(text: &str) -> String {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    let ansi = ANSI.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-9;]*m").expect("Error parsing ansi escape regex")
+    });
+    ansi.replace_all(text, "").to_string()
+}
+
+//  Reflow commit message lines so that none are longer than `width`.
+//  Lines are only split, never joined.  Continuation lines of list
+//  items (- * + 1. 1)) are indented to align with the item text.
+pub fn wrap_message(lines: &[String], width: usize) -> Vec<String> {
+    static LEADER: OnceLock<Regex> = OnceLock::new();
+    let leader = LEADER.get_or_init(|| {
+        Regex::new(r"^\s*(?:(?:[-*+]|\d+[.)])\s+)?").expect("Error parsing list leader regex")
+    });
+    let width = width.max(1);
+    let mut result = Vec::new();
+
+    for line in lines {
+        let line = line.trim_end();
+        if line.chars().count() <= width {
+            result.push(line.to_string());
+            continue;
+        }
+        let lead = leader.find(line).map(|m| m.as_str()).unwrap_or("");
+        let lead_len = lead.chars().count();
+        let indent = if lead_len < width / 2 { " ".repeat(lead_len) } else { "".to_string() };
+        let mut current = lead.to_string();
+        let mut current_len = lead_len;
+        let mut empty = true;
+
+        for word in line[lead.len()..].split_whitespace() {
+            let word_len = word.chars().count();
+            if !empty && current_len + 1 + word_len > width {
+                result.push(current);
+                current = indent.clone();
+                current_len = indent.len();
+                empty = true;
+            }
+            if !empty {
+                current.push(' ');
+                current_len += 1;
+            }
+            current.push_str(word);
+            current_len += word_len;
+            empty = false;
+        }
+        result.push(current);
+    }
+    result
+}
+
+//  When ASCII mode is on, unicode characters in our output
+//  are replaced with ASCII equivalents.
+fn ascii_mode() -> &'static OnceLock<bool> {
+    static ASCII: OnceLock<bool> = OnceLock::new();
+    &ASCII
+}
+
+pub fn set_ascii(ascii: bool) {
+    let _ = ascii_mode().set(ascii);
+}
+
+pub fn is_ascii() -> bool {
+    ascii_mode().get().copied().unwrap_or(false)
+}
+
+pub fn ellipsis() -> &'static str {
+    if is_ascii() { "..." } else { "\u{2026}" }
+}
+
+//  Marks an item that does not exist.
+pub fn missing_mark() -> &'static str {
+    if is_ascii() { "x" } else { "\u{2717}" }
+}
+
+//  Return the subject (first line) of a commit message truncated to `width`
+//  characters.  An ellipsis is added if the subject was truncated or if
+//  the message contains more text after the subject.
+pub fn message_subject(lines: &[String], width: usize) -> String {
+    let subject = lines.first().map(|s| s.trim_end()).unwrap_or("");
+    let more = lines.iter().skip(1).any(|l| !l.trim().is_empty());
+    let len = subject.chars().count();
+
+    if len <= width && !more {
+        subject.to_string()
+    } else if len + 1 + ellipsis().chars().count() <= width {
+        format!("{} {}", subject, ellipsis())
+    } else {
+        let keep = width.saturating_sub(ellipsis().chars().count());
+        format!("{}{}", subject.chars().take(keep).collect::<String>(), ellipsis())
+    }
+}
+
+//  How the message is rendered by show_commit()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageStyle {
+    Hidden,
+    Full,
+    Wrapped(usize),
+    Subject(usize),
+}
+
+impl MessageStyle {
+    //  Determine the style from the --wrap and --subject-only options.
+    //  A wrap width of zero means the width of the terminal.
+    pub fn from_options(wrap: Option<usize>, subject_only: bool) -> Self {
+        let width = match wrap {
+            Some(0) | None => terminal_width(),
+            Some(cols)     => cols,
+        };
+        if subject_only {
+            MessageStyle::Subject(width)
+        } else if wrap.is_some() {
+            MessageStyle::Wrapped(width)
+        } else {
+            MessageStyle::Full
+        }
+    }
+
+    pub fn render(&self, lines: &[String]) -> Vec<String> {
+        match self {
+            MessageStyle::Hidden         => vec![],
+            MessageStyle::Full           => lines.to_vec(),
+            MessageStyle::Wrapped(width) => wrap_message(lines, *width),
+            MessageStyle::Subject(width) => vec![message_subject(lines, *width)],
+        }
+    }
+}
+
 //  Print formatted commit info to stdout.
-pub fn show_commit(log_entry: &LogEntry, show_msg: bool, show_paths: bool) {
+pub fn show_commit(log_entry: &LogEntry, msg_style: MessageStyle, show_paths: bool) {
     let divider = divider(70);
     println!("{}", divider);
     println!("Commit: {}", log_entry.revision.yellow());
@@ -161,10 +404,14 @@ pub fn show_commit(log_entry: &LogEntry, show_msg: bool, show_paths: bool) {
     println!("Date  : {}", display_svn_datetime(&log_entry.date).magenta());
     println!("{}", divider);
 
-    if show_msg {
-        for line in &log_entry.msg {
-            println!(" {}", line);
-        }
+    //  Each message line is indented by one space
+    let msg_style = match msg_style {
+        MessageStyle::Wrapped(width) => MessageStyle::Wrapped(width.saturating_sub(1)),
+        MessageStyle::Subject(width) => MessageStyle::Subject(width.saturating_sub(1)),
+        style                        => style,
+    };
+    for line in msg_style.render(&log_entry.msg) {
+        println!(" {}", issues::decorate(&line));
     }
     println!();
 
@@ -207,6 +454,10 @@ pub fn show_commit(log_entry: &LogEntry, show_msg: bool, show_paths: bool) {
 }
 
 pub fn print_diff_line(line: &str) {
+    println!("{}", colored_diff_line(line));
+}
+
+pub fn colored_diff_line(line: &str) -> ColoredString {
     let color = if line.starts_with("---") || line.starts_with("+++") { "blue" }
            else if line.starts_with("Index:") || line.starts_with("==========") { "yellow" }
            else if line.starts_with("Property changes on:") { "magenta" }
@@ -215,5 +466,110 @@ pub fn print_diff_line(line: &str) {
            else if line.starts_with('-') { "red" }
            else { "white" };
 
-    println!("{}", line.color(color));
+    line.color(color)
+}
+
+//  Parse an age such as 90d, 12w, 6months or 1y.
+//  A number without units is a number of days.
+//  Months are considered to be 30 days and years 365 days.
+pub fn parse_age(arg: &str) -> Result<Duration> {
+    let invalid = || SvError::General(
+        format!("Invalid age '{}', expected a number followed by d, w, m or y (eg. 90d)", arg)
+    );
+    let too_large = || SvError::General(format!("The age '{}' is too large", arg));
+    let re = Regex::new(r"^(\d+)\s*(d|days?|w|weeks?|m|months?|y|years?)?$")?;
+    let caps = re.captures(arg.trim()).ok_or_else(invalid)?;
+    let num = caps[1].parse::<i64>().map_err(|_| too_large())?;
+    let days = match caps.get(2).map(|m| &m.as_str()[..1]) {
+        Some("w") => num.checked_mul(7),
+        Some("m") => num.checked_mul(30),
+        Some("y") => num.checked_mul(365),
+        _         => Some(num),
+    };
+    //  Duration::days panics beyond the number of days that fit in
+    //  milliseconds, and the age must be usable to compute a past date.
+    days.filter(|d| *d <= i64::MAX / 86_400_000)
+        .map(Duration::days)
+        .filter(|age| Local::now().checked_sub_signed(*age).is_some())
+        .ok_or_else(|| too_large().into())
+}
+
+//  Describe the age of a date in days.
+pub fn display_age(date: &DateTime<Local>) -> String {
+    match (Local::now() - *date).num_days() {
+        0 => "today".to_string(),
+        1 => "1 day old".to_string(),
+        n => format!("{} days old", n),
+    }
+}
+
+//  Display a size in bytes using the largest unit that keeps it above 1.
+pub fn display_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["KB", "MB", "GB", "TB", "PB"];
+    if size < 1024 {
+        return format!("{} B", size);
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+//  Split a command from an environment variable into
+//  the program and its leading arguments.
+pub fn command_from_env(vars: &[&str]) -> Option<Command> {
+    let value = vars.iter().find_map(|v| std::env::var(v).ok().filter(|s| !s.trim().is_empty()))?;
+    command_from_str(&value)
+}
+
+//  Split a configured command into the program and its leading arguments.
+pub fn command_from_str(value: &str) -> Option<Command> {
+    let mut words = value.split_whitespace();
+    let mut cmd = Command::new(words.next()?);
+    cmd.args(words);
+    Some(cmd)
+}
+
+//  Run check every interval seconds until interrupted.
+//  Failures are reported but do not stop polling,
+//  since anything missed is picked up by the next check.
+pub fn poll<F>(interval: u64, mut check: F) -> Result<()>
+where
+    F: FnMut() -> Result<()>,
+{
+    loop {
+        if let Err(e) = check() {
+            if crate::interrupt::interrupted() {
+                return Err(e);
+            }
+            eprintln!("{} {}", "error:".red(), e);
+        }
+        std::thread::sleep(std::time::Duration::from_secs(interval));
+    }
+}
+
+//  Open a file in the editor given by $VISUAL or $EDITOR (default vi).
+//  Returns an error if the editor fails so that the edit is not used.
+pub fn edit_file(file: &Path) -> Result<()> {
+    let mut cmd = command_from_env(&["VISUAL", "EDITOR"]).unwrap_or(Command::new("vi"));
+    let status = cmd.arg(file).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(SvError::General("The editor exited with an error".to_string()).into())
+    }
+}
+
+//  Ask the user a yes/no question.
+//  Returns true if the user answers yes.
+pub fn confirm(prompt: &str) -> Result<bool> {
+    let mut line = String::new();
+    print!("{} [y/N]: ", prompt);
+    std::io::stdout().flush()?;
+    std::io::stdin().read_line(&mut line)?;
+    let answer = line.trim().to_lowercase();
+    Ok(answer == "y" || answer == "yes")
 }